@@ -1,6 +1,8 @@
+use crate::git::compat_map::{CompatMap, CompatObjectId};
 use crate::git::loose_object::LooseObject;
 /// CLI formatters that reuse TUI formatting logic for consistent output
 use crate::git::pack::{Object, ObjectHeader};
+use crate::git::submodule::GitlinkTarget;
 use crate::tui::model::PackObject;
 use crate::tui::widget::loose_obj_details::LooseObjectWidget;
 use crate::tui::widget::pack_obj_details::PackObjectWidget;
@@ -12,33 +14,27 @@ pub struct CliPackFormatter;
 pub struct CliLooseFormatter;
 
 impl CliPackFormatter {
-    /// Format a complete pack file with header and all objects
-    #[must_use]
-    pub fn format_pack_file(header: &crate::git::pack::Header, objects: &[Object]) -> String {
-        let mut output = String::new();
-
-        // Format pack header using educational content system
-        Self::format_pack_header(&mut output, header);
-        writeln!(&mut output)
-            .expect("fmt::Write for String should not fail for in-memory formatting");
-
-        // Resolve delta chains so each object shows its real git object ID
-        let resolved = crate::git::pack::resolve_objects(objects);
-
-        // Format each object using TUI formatters
-        for (i, object) in objects.iter().enumerate() {
-            if i > 0 {
-                writeln!(&mut output, "{}", "═".repeat(80))
-                    .expect("fmt::Write for String should not fail for in-memory formatting");
-            }
-            Self::format_pack_object(&mut output, object, i + 1, resolved[i].as_ref());
+    /// Format the `index`th object of a pack listing, preceded by a
+    /// separator unless it is the first one
+    pub(crate) fn format_pack_entry(
+        output: &mut String,
+        object: &Object,
+        index: usize,
+        sha1: Option<&str>,
+        compat_map: Option<&CompatMap>,
+    ) {
+        if index > 1 {
+            writeln!(output, "{}", "═".repeat(80))
+                .expect("fmt::Write for String should not fail for in-memory formatting");
         }
-
-        output
+        let compat_id = sha1
+            .zip(compat_map)
+            .and_then(|(sha1, map)| map.compat_id(sha1));
+        Self::format_pack_object(output, object, index, sha1, compat_id);
     }
 
     /// Format pack file header information reusing educational content
-    fn format_pack_header(output: &mut String, header: &crate::git::pack::Header) {
+    pub(crate) fn format_pack_header(output: &mut String, header: &crate::git::pack::Header) {
         // Use the educational content system for pack header preview
         let educational_provider = crate::educational_content::EducationalContent::new();
         let header_preview = educational_provider.get_pack_preview(header);
@@ -59,7 +55,8 @@ impl CliPackFormatter {
         output: &mut String,
         object: &Object,
         index: usize,
-        resolved: Option<&crate::git::pack::ResolvedObject>,
+        sha1: Option<&str>,
+        compat_id: Option<CompatObjectId>,
     ) {
         writeln!(output).expect("fmt::Write for String should not fail for in-memory formatting");
         writeln!(output, "\x1b[1mOBJECT #{index}\x1b[0m")
@@ -69,7 +66,7 @@ impl CliPackFormatter {
         writeln!(output).expect("fmt::Write for String should not fail for in-memory formatting");
 
         // Create a PackObject from the Object (similar to what TUI loaders do)
-        let pack_obj = Self::create_pack_object_from_object(object, index, resolved);
+        let pack_obj = Self::create_pack_object_from_object(object, index, sha1, compat_id);
 
         // Use the TUI formatter to generate rich content
         let mut widget = PackObjectWidget::new(pack_obj);
@@ -195,16 +192,17 @@ impl CliPackFormatter {
     }
 
     /// Create a `PackObject` from an Object (similar to TUI loader logic)
-    fn create_pack_object_from_object(
+    pub(crate) fn create_pack_object_from_object(
         object: &Object,
         index: usize,
-        resolved: Option<&crate::git::pack::ResolvedObject>,
+        sha1: Option<&str>,
+        compat_id: Option<CompatObjectId>,
     ) -> PackObject {
         let obj_type = object.header.obj_type();
         let size = object.header.uncompressed_data_size();
 
         // Real git object ID from delta resolution; None if unresolvable
-        let sha1 = resolved.map(|r| r.sha1.clone());
+        let sha1 = sha1.map(str::to_string);
 
         // Extract base info for delta objects
         let base_info = match &object.header {
@@ -222,16 +220,26 @@ impl CliPackFormatter {
             obj_type: obj_type.to_string(),
             size: u32::try_from(size).unwrap_or(u32::MAX),
             sha1,
+            compat_id,
             base_info,
             object_data: Some(object.clone()),
+            source: None,
         }
     }
 }
 
 impl CliLooseFormatter {
     /// Format a loose object with rich formatting using TUI formatters
+    ///
+    /// The gitlinks of a tree are followed by the submodule commits
+    /// `gitlinks` resolved them to, and the object ID by `compat_id` when
+    /// the repository has a compatibility format.
     #[must_use]
-    pub fn format_loose_object(loose_obj: &LooseObject) -> String {
+    pub fn format_loose_object(
+        loose_obj: &LooseObject,
+        gitlinks: Vec<GitlinkTarget>,
+        compat_id: Option<CompatObjectId>,
+    ) -> String {
         let mut output = String::new();
 
         // Format loose object header
@@ -243,7 +251,8 @@ impl CliLooseFormatter {
             .expect("fmt::Write for String should not fail for in-memory formatting");
 
         // Use the TUI formatter to generate rich content
-        let widget = LooseObjectWidget::new(loose_obj.clone());
+        let widget =
+            LooseObjectWidget::with_gitlinks(loose_obj.clone(), gitlinks).with_compat_id(compat_id);
         let formatted_text = widget.text();
 
         // Convert ratatui Text to ANSI colored string
@@ -1,6 +1,7 @@
+use crate::git::object_format::ObjectFormat;
 use clap::{Parser, Subcommand};
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub mod formatters;
 
@@ -41,8 +42,8 @@ pub fn safe_println(content: &str) -> Result<(), String> {
 
 /// Determine if a string looks like a git object hash
 fn is_likely_hash(input: &str) -> bool {
-    // Must be 4-40 characters and all hex
-    if input.len() < 4 || input.len() > 40 {
+    // Must be 4 characters up to a full SHA-256 hash, and all hex
+    if input.len() < 4 || input.len() > ObjectFormat::Sha256.hex_len() {
         return false;
     }
 
@@ -56,6 +57,56 @@ fn is_likely_path(input: &str) -> bool {
     input.contains('/') || input.contains('\\') || input.contains('.')
 }
 
+/// Print every ref with its storage, value and, for symrefs, resolution
+fn print_refs(ref_store: &crate::git::refs::RefStore) -> Result<(), String> {
+    use crate::git::refs::RefStorage;
+
+    if ref_store.is_empty() {
+        return safe_println("No refs found");
+    }
+
+    let name_width = ref_store
+        .iter()
+        .map(|reference| reference.name.len())
+        .max()
+        .unwrap_or_default();
+    let mut broken = 0;
+    for reference in ref_store.iter() {
+        let mut line = format!(
+            "{:<name_width$}  {:<12}  {}",
+            reference.name, reference.storage, reference.target
+        );
+        if let Some(peeled) = &reference.peeled {
+            line.push_str(&format!(" (peeled: {peeled})"));
+        }
+        if reference.is_symbolic() {
+            let resolution = ref_store.resolve(&reference.name);
+            if resolution.is_broken() {
+                broken += 1;
+                line.push_str(&format!(" \x1b[31m→ {}\x1b[39m", resolution.end));
+            } else {
+                line.push_str(&format!(" → {}", resolution.end));
+            }
+        }
+        safe_println(&line)?;
+    }
+
+    let count = |storage: RefStorage| {
+        ref_store
+            .iter()
+            .filter(|reference| reference.storage == storage)
+            .count()
+    };
+    safe_println("")?;
+    safe_println(&format!(
+        "{} refs: {} loose, {} packed, {} loose shadowing packed; {broken} broken symrefs",
+        ref_store.len(),
+        count(RefStorage::Loose),
+        count(RefStorage::Packed),
+        count(RefStorage::LooseOverPacked),
+    ))
+}
+
 #[derive(Parser)]
 #[command(name = "git-plumber")]
 #[command(about = "Explorer for git internals, the plumbing", long_about = None)]
@@ -106,17 +157,78 @@ pub enum Commands {
         object_type: String,
     },
 
+    /// List refs: loose files, packed-refs and where symrefs lead
+    Refs,
+
+    /// Show the reflog of a ref as a timeline
+    Reflog {
+        /// Ref name, full (refs/heads/main) or short (main, origin/main)
+        #[arg(default_value = "HEAD")]
+        ref_name: String,
+    },
+
+    /// Show the commit-graph's changed-path Bloom filters
+    Bloom {
+        /// Path to ask every commit's filter about
+        path: Option<String>,
+
+        /// Recompute the filters from tree diffs and report false negatives
+        #[arg(long)]
+        verify: bool,
+    },
+
     /// View an object or file with detailed formatting
     View {
-        /// Object hash (4-40 hex chars) or file path to view
+        /// Object hash (4-64 hex chars) or file path to view
         #[arg(
             required = true,
-            help = "Object hash (4-40 characters) or path to file"
+            help = "Object hash (4-64 characters) or path to file"
         )]
         target: String,
     },
 }
 
+/// Show an existing file, with the viewer its name or location calls for
+fn view_path(plumber: &crate::GitPlumber, path: &Path) -> Result<(), String> {
+    let file_name = path.file_name().and_then(|s| s.to_str());
+    if crate::git::worktree::Worktree::is_admin_dir(path) {
+        plumber.view_worktree(path)
+    } else if path.extension().and_then(|s| s.to_str()) == Some("pack") {
+        plumber.parse_pack_file_rich(path)
+    } else if file_name.is_some_and(|name| {
+        name == "multi-pack-index"
+            || crate::git::pack::MultiPackIndexChain::is_layer_file_name(name)
+    }) {
+        plumber.view_multi_pack_index(path)
+    } else if file_name == Some(crate::git::pack::MultiPackIndexChain::CHAIN_FILE_NAME) {
+        plumber.view_multi_pack_index_chain(path)
+    } else if file_name.is_some_and(crate::git::index::GitIndex::is_index_file_name) {
+        plumber.view_git_index(path)
+    } else if file_name == Some("packed-refs") {
+        plumber.view_packed_refs(path)
+    } else if file_name.is_some_and(|name| {
+        name == "commit-graph"
+            || crate::git::commit_graph::CommitGraphChain::is_layer_file_name(name)
+    }) {
+        plumber.view_commit_graph(path)
+    } else if file_name == Some(crate::git::commit_graph::CommitGraphChain::CHAIN_FILE_NAME) {
+        plumber.view_commit_graph_chain(path)
+    } else if crate::git::refs::ReftableStack::is_table_path(path) {
+        plumber.view_reftable(path)
+    } else if file_name == Some(crate::git::refs::ReftableStack::TABLES_LIST) {
+        plumber.view_reftable_stack(path)
+    } else if crate::git::refs::Reflog::ref_for_log_path(path).is_some() {
+        plumber.view_reflog_file(path)
+    } else if file_name == Some(crate::git::compat_map::LooseObjectIdx::FILE) {
+        plumber.view_loose_object_idx(path)
+    } else if path.extension().and_then(|s| s.to_str()) == Some("bitmap") {
+        plumber.view_pack_bitmap(path)
+    } else {
+        // Try to parse as loose object file
+        plumber.view_file_as_object(path)
+    }
+}
+
 /// Run the CLI application
 ///
 /// # Errors
@@ -201,6 +313,15 @@ pub fn run() -> Result<(), String> {
                                     midx_path.display()
                                 ))?;
                             }
+                            if let Ok(Some(chain_group)) =
+                                plumber.get_multi_pack_index_chain_group()
+                            {
+                                safe_println(&format!(
+                                    "Multi-pack-index chain: {} ({} layers)",
+                                    chain_group.chain_file.display(),
+                                    chain_group.layer_files.len()
+                                ))?;
+                            }
                             Ok(())
                         }
                         Err(e) => Err(format!("Error listing pack files: {e}")),
@@ -267,6 +388,13 @@ pub fn run() -> Result<(), String> {
                     if let Some(midx_path) = plumber.get_multi_pack_index() {
                         safe_println(&format!("  Multi-pack-index: {}", midx_path.display()))?;
                     }
+                    if let Ok(Some(chain_group)) = plumber.get_multi_pack_index_chain_group() {
+                        safe_println(&format!(
+                            "  Multi-pack-index chain: {} ({} layers)",
+                            chain_group.chain_file.display(),
+                            chain_group.layer_files.len()
+                        ))?;
+                    }
 
                     safe_println("")?;
 
@@ -291,24 +419,21 @@ pub fn run() -> Result<(), String> {
                 }
             }
         }
+        Some(Commands::Refs) => match plumber.load_ref_store() {
+            Ok(ref_store) => print_refs(&ref_store),
+            Err(e) => Err(format!("Error loading refs: {e}")),
+        },
+        Some(Commands::Reflog { ref_name }) => plumber.view_reflog(ref_name),
+        Some(Commands::Bloom { path, verify }) => {
+            plumber.view_commit_graph_bloom(path.as_deref(), *verify)
+        }
         Some(Commands::View { target }) => {
             // Determine if target is a hash or path
             if is_likely_path(target) && !is_likely_hash(target) {
                 // Treat as file path
                 let path = PathBuf::from(target);
                 if path.exists() {
-                    // Check if it's a pack file or other git object file
-                    if path.extension().and_then(|s| s.to_str()) == Some("pack") {
-                        plumber.parse_pack_file_rich(&path)
-                    } else if path.file_name().and_then(|s| s.to_str()) == Some("multi-pack-index")
-                    {
-                        plumber.view_multi_pack_index(&path)
-                    } else if path.extension().and_then(|s| s.to_str()) == Some("bitmap") {
-                        plumber.view_pack_bitmap(&path)
-                    } else {
-                        // Try to parse as loose object file
-                        plumber.view_file_as_object(&path)
-                    }
+                    view_path(&plumber, &path)
                 } else {
                     Err(format!("File not found: {}", path.display()))
                 }
@@ -319,26 +444,16 @@ pub fn run() -> Result<(), String> {
                 // Ambiguous - try both approaches
                 let path = PathBuf::from(target);
                 if path.exists() {
-                    // File exists, treat as path
-                    if path.extension().and_then(|s| s.to_str()) == Some("pack") {
-                        plumber.parse_pack_file_rich(&path)
-                    } else if path.file_name().and_then(|s| s.to_str()) == Some("multi-pack-index")
-                    {
-                        plumber.view_multi_pack_index(&path)
-                    } else if path.extension().and_then(|s| s.to_str()) == Some("bitmap") {
-                        plumber.view_pack_bitmap(&path)
-                    } else {
-                        plumber.view_file_as_object(&path)
-                    }
+                    view_path(&plumber, &path)
                 } else if target.chars().all(|c| c.is_ascii_hexdigit()) {
                     // Looks like hex but too short or too long
                     if target.len() < 4 {
                         Err(format!(
                             "Hash too short: '{target}'. Git object hashes must be at least 4 characters long."
                         ))
-                    } else if target.len() > 40 {
+                    } else if target.len() > ObjectFormat::Sha256.hex_len() {
                         Err(format!(
-                            "Hash too long: '{target}'. Git object hashes must be at most 40 characters long."
+                            "Hash too long: '{target}'. Git object hashes must be at most 64 characters long."
                         ))
                     } else {
                         // Valid length hex but object not found
@@ -346,7 +461,7 @@ pub fn run() -> Result<(), String> {
                     }
                 } else {
                     Err(format!(
-                        "Invalid target: '{target}' is neither a valid file path nor object hash (hashes must be 4-40 hex characters)"
+                        "Invalid target: '{target}' is neither a valid file path nor object hash (hashes must be 4-64 hex characters)"
                     ))
                 }
             }
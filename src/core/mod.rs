@@ -1,21 +1,61 @@
+use crate::git::compat_map::{CompatMap, LooseObjectIdx};
+use crate::git::object_database::{ObjectDatabase, ObjectDatabases};
+use crate::git::object_format::ObjectFormat;
+use crate::git::refs::{RefStore, Reflog};
 use crate::git::repository::{Repository, RepositoryError};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Main application struct that handles shared logic
 pub struct GitPlumber {
     repo_path: PathBuf,
     repository: Option<Repository>,
+    /// Object databases of this repository and of the ones around it
+    /// (submodules, alternates), shared by every lookup
+    object_databases: ObjectDatabases,
 }
 
 impl GitPlumber {
-    /// Create a new `GitPlumber` instance
+    /// Create a new `GitPlumber` instance for the repository containing
+    /// `repo_path`
+    ///
+    /// Once discovered, the repository path is its work tree (or its git
+    /// directory when bare), whichever subdirectory was given.
     pub fn new(repo_path: impl AsRef<Path>) -> Self {
-        let repo_path = repo_path.as_ref().to_path_buf();
         let repository = Repository::new(&repo_path).ok();
+        let repo_path = repository.as_ref().map_or_else(
+            || repo_path.as_ref().to_path_buf(),
+            |repository| repository.get_path().to_path_buf(),
+        );
 
         Self {
             repo_path,
             repository,
+            object_databases: ObjectDatabases::default(),
+        }
+    }
+
+    /// Open an object directory on its own, such as an alternate, with the
+    /// directory around it standing in for its repository
+    #[must_use]
+    pub fn for_objects_dir(objects_dir: &Path) -> Self {
+        let repository = Repository::for_objects_dir(objects_dir);
+        Self {
+            repo_path: repository.get_path().to_path_buf(),
+            repository: Some(repository),
+            object_databases: ObjectDatabases::default(),
+        }
+    }
+
+    /// Open the repository whose git directory is `git_dir`, such as a
+    /// submodule's, ignoring `GIT_DIR` and the other discovery variables
+    #[must_use]
+    pub fn for_git_dir(git_dir: &Path) -> Self {
+        let repository = Repository::from_git_dir(git_dir);
+        Self {
+            repo_path: repository.get_path().to_path_buf(),
+            repository: Some(repository),
+            object_databases: ObjectDatabases::default(),
         }
     }
 
@@ -25,12 +65,139 @@ impl GitPlumber {
         &self.repo_path
     }
 
+    /// Get the git directory, falling back to `<repo>/.git` when no
+    /// repository was found
+    #[must_use]
+    pub fn get_git_dir(&self) -> PathBuf {
+        self.repository.as_ref().map_or_else(
+            || self.repo_path.join(Repository::DOT_GIT),
+            |repository| repository.git_dir().to_path_buf(),
+        )
+    }
+
+    /// The object database of the repository, opened on first use and
+    /// shared until [`Self::refresh_object_databases`]
+    #[must_use]
+    pub fn object_database(&self) -> Arc<ObjectDatabase> {
+        self.repository.as_ref().map_or_else(
+            || self.object_databases.for_git_dir(&self.get_git_dir()),
+            |repository| self.object_databases.for_repository(repository),
+        )
+    }
+
+    /// The object database of the repository whose git directory is
+    /// `git_dir`, such as a worktree's or a submodule's, opened on first
+    /// use and shared like [`Self::object_database`]
+    #[must_use]
+    pub fn object_database_for(&self, git_dir: &Path) -> Arc<ObjectDatabase> {
+        self.object_databases.for_git_dir(git_dir)
+    }
+
+    /// Forget the opened object databases, so that packs written or
+    /// removed since are seen
+    pub fn refresh_object_databases(&self) {
+        self.object_databases.clear();
+    }
+
+    /// Get the directory shared by all worktrees, holding refs and
+    /// packed-refs
+    #[must_use]
+    pub fn get_common_dir(&self) -> PathBuf {
+        self.repository.as_ref().map_or_else(
+            || self.get_git_dir(),
+            |repository| repository.common_dir().to_path_buf(),
+        )
+    }
+
+    /// Get the object store directory
+    #[must_use]
+    pub fn get_objects_dir(&self) -> PathBuf {
+        self.repository.as_ref().map_or_else(
+            || self.get_git_dir().join("objects"),
+            |repository| repository.objects_dir().to_path_buf(),
+        )
+    }
+
     /// Get access to the repository if it exists
     #[must_use]
     pub const fn get_repository(&self) -> Option<&Repository> {
         self.repository.as_ref()
     }
 
+    /// Get the hash function naming the repository's objects, SHA-1 when
+    /// no repository was found
+    #[must_use]
+    pub fn get_object_format(&self) -> ObjectFormat {
+        self.repository
+            .as_ref()
+            .map(Repository::object_format)
+            .unwrap_or_default()
+    }
+
+    /// The object format of a pack file: from its name when it has the
+    /// usual `pack-<checksum>.pack` one, else the repository's
+    fn pack_object_format(&self, path: &Path) -> ObjectFormat {
+        ObjectFormat::from_pack_name(path).unwrap_or_else(|| self.get_object_format())
+    }
+
+    /// Read a loose object map, `objects/loose-object-idx`, with the
+    /// repository's object and compatibility formats
+    ///
+    /// A repository that no longer sets `extensions.compatObjectFormat` is
+    /// assumed to have translated to the other hash function.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read or
+    /// parsed.
+    pub fn load_loose_object_idx(&self, path: &Path) -> Result<LooseObjectIdx, String> {
+        let object_format = self.get_object_format();
+        let compat_format = self
+            .repository
+            .as_ref()
+            .and_then(Repository::compat_object_format)
+            .or_else(|| {
+                ObjectFormat::ALL
+                    .into_iter()
+                    .find(|&format| format != object_format)
+            })
+            .unwrap_or_default();
+        LooseObjectIdx::load_file(path, object_format, compat_format)
+            .map_err(|e| format!("Error loading loose object map: {e}"))
+    }
+
+    /// View a loose object map, `objects/loose-object-idx`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file cannot be read or parsed
+    /// - The formatting operations fail
+    pub fn view_loose_object_idx(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::loose_object_idx_details::formatters::LooseObjectIdxFormatter;
+
+        let loose_object_idx = self.load_loose_object_idx(path)?;
+        let formatted_text = LooseObjectIdxFormatter::new(&loose_object_idx).generate_content();
+        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+        crate::cli::safe_print(&colored_text)?;
+        Ok(())
+    }
+
+    /// The translation between the repository's object IDs and their IDs
+    /// in `extensions.compatObjectFormat`, if it sets one
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - A loose object map or pack index cannot be read
+    /// - A loose object map is malformed
+    pub fn load_compat_map(&self) -> Result<Option<CompatMap>, RepositoryError> {
+        self.repository
+            .as_ref()
+            .map_or(Ok(None), Repository::load_compat_map)
+    }
+
     /// List all pack files in the repository
     ///
     /// # Errors
@@ -94,14 +261,25 @@ impl GitPlumber {
             .and_then(Repository::get_multi_pack_index_bitmap)
     }
 
-    /// List all head refs (local branches) in the repository
+    /// Get the path to the single-file commit-graph, if the repository has one
+    #[must_use]
+    pub fn get_commit_graph(&self) -> Option<PathBuf> {
+        self.repository
+            .as_ref()
+            .and_then(Repository::get_commit_graph)
+    }
+
+    /// List the files of the split commit-graph, if the repository has one
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - The path is not a valid git repository
-    /// - File system operations fail when reading the refs/heads directory
-    pub fn list_head_refs(&self) -> Result<Vec<PathBuf>, RepositoryError> {
+    /// - The commit-graphs directory cannot be read or its chain file is
+    ///   malformed
+    pub fn get_commit_graph_chain_group(
+        &self,
+    ) -> Result<Option<crate::git::repository::CommitGraphChainGroup>, RepositoryError> {
         self.repository.as_ref().map_or_else(
             || {
                 Err(RepositoryError::NotGitRepository(format!(
@@ -109,18 +287,18 @@ impl GitPlumber {
                     self.repo_path.display()
                 )))
             },
-            Repository::list_head_refs,
+            Repository::get_commit_graph_chain_group,
         )
     }
 
-    /// List all remote refs in the repository
+    /// List the linked worktrees of the repository
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - The path is not a valid git repository
-    /// - File system operations fail when reading the refs/remotes directory
-    pub fn list_remote_refs(&self) -> Result<Vec<(String, Vec<PathBuf>)>, RepositoryError> {
+    /// - The worktrees directory cannot be read
+    pub fn list_worktrees(&self) -> Result<Vec<crate::git::worktree::Worktree>, RepositoryError> {
         self.repository.as_ref().map_or_else(
             || {
                 Err(RepositoryError::NotGitRepository(format!(
@@ -128,18 +306,62 @@ impl GitPlumber {
                     self.repo_path.display()
                 )))
             },
-            Repository::list_remote_refs,
+            Repository::list_worktrees,
         )
     }
 
-    /// List all tag refs in the repository
+    /// List the submodules of the repository
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - The path is not a valid git repository
-    /// - File system operations fail when reading the refs/tags directory
-    pub fn list_tag_refs(&self) -> Result<Vec<PathBuf>, RepositoryError> {
+    /// - The modules directory cannot be read
+    pub fn list_submodules(
+        &self,
+    ) -> Result<Vec<crate::git::submodule::Submodule>, RepositoryError> {
+        self.repository.as_ref().map_or_else(
+            || {
+                Err(RepositoryError::NotGitRepository(format!(
+                    "{} is not a git repository",
+                    self.repo_path.display()
+                )))
+            },
+            Repository::list_submodules,
+        )
+    }
+
+    /// List the alternate object directories of the repository
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - An alternates file cannot be read
+    pub fn list_alternates(
+        &self,
+    ) -> Result<Vec<crate::git::alternates::Alternate>, RepositoryError> {
+        self.repository.as_ref().map_or_else(
+            || {
+                Err(RepositoryError::NotGitRepository(format!(
+                    "{} is not a git repository",
+                    self.repo_path.display()
+                )))
+            },
+            Repository::list_alternates,
+        )
+    }
+
+    /// List the submodules of the repository, nested ones included
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - A modules directory cannot be read
+    pub fn list_all_submodules(
+        &self,
+    ) -> Result<Vec<crate::git::submodule::Submodule>, RepositoryError> {
         self.repository.as_ref().map_or_else(
             || {
                 Err(RepositoryError::NotGitRepository(format!(
@@ -147,18 +369,147 @@ impl GitPlumber {
                     self.repo_path.display()
                 )))
             },
-            Repository::list_tag_refs,
+            Repository::list_all_submodules,
         )
     }
 
-    /// Check if the repository has stash refs
+    /// Resolve the gitlinks of a tree object in the submodules checked out
+    /// at their paths, none when the path is not a git repository
+    #[must_use]
+    pub fn resolve_gitlinks(
+        &self,
+        object: &crate::git::loose_object::LooseObject,
+    ) -> Vec<crate::git::submodule::GitlinkTarget> {
+        self.repository
+            .as_ref()
+            .map(|repo| repo.resolve_gitlinks(object, &self.object_databases))
+            .unwrap_or_default()
+    }
+
+    /// List the layers of the incremental multi-pack-index, if the
+    /// repository has one
     ///
     /// # Errors
     ///
     /// This function will return an error if:
     /// - The path is not a valid git repository
-    /// - File system operations fail when checking for stash refs
+    /// - The multi-pack-index.d directory cannot be read or its chain file
+    ///   is malformed
+    pub fn get_multi_pack_index_chain_group(
+        &self,
+    ) -> Result<Option<crate::git::repository::MultiPackIndexChainGroup>, RepositoryError> {
+        self.repository.as_ref().map_or_else(
+            || {
+                Err(RepositoryError::NotGitRepository(format!(
+                    "{} is not a git repository",
+                    self.repo_path.display()
+                )))
+            },
+            Repository::get_multi_pack_index_chain_group,
+        )
+    }
+
+    /// Load every ref of the repository: loose refs, packed refs and symrefs
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - The refs directory or packed-refs file cannot be read
+    pub fn load_ref_store(&self) -> Result<RefStore, RepositoryError> {
+        self.repository.as_ref().map_or_else(
+            || {
+                Err(RepositoryError::NotGitRepository(format!(
+                    "{} is not a git repository",
+                    self.repo_path.display()
+                )))
+            },
+            Repository::load_ref_store,
+        )
+    }
+
+    /// List all head refs (local branches) in the repository, as the paths
+    /// of their loose files, which packed refs don't have on disk
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - The refs directory or packed-refs file cannot be read
+    #[deprecated(note = "use `load_ref_store` and `RefStore::refs_under(\"refs/heads/\")`")]
+    pub fn list_head_refs(&self) -> Result<Vec<PathBuf>, RepositoryError> {
+        let ref_store = self.load_ref_store()?;
+        Ok(Self::loose_paths_under(&ref_store, "refs/heads/"))
+    }
+
+    /// List all remote refs grouped by remote name, as the paths of their
+    /// loose files, which packed refs don't have on disk
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - The refs directory or packed-refs file cannot be read
+    #[deprecated(note = "use `load_ref_store` and `RefStore::refs_under(\"refs/remotes/\")`")]
+    pub fn list_remote_refs(&self) -> Result<Vec<(String, Vec<PathBuf>)>, RepositoryError> {
+        let ref_store = self.load_ref_store()?;
+        let mut remotes: Vec<(String, Vec<PathBuf>)> = Vec::new();
+        // Refs come sorted by name, so each remote's refs are adjacent
+        for reference in ref_store.refs_under("refs/remotes/") {
+            let Some((remote, _)) = reference.name["refs/remotes/".len()..].split_once('/') else {
+                continue;
+            };
+            let path = ref_store.loose_path(&reference.name);
+            match remotes.last_mut() {
+                Some((name, paths)) if name == remote => paths.push(path),
+                _ => remotes.push((remote.to_string(), vec![path])),
+            }
+        }
+        Ok(remotes)
+    }
+
+    /// List all tag refs in the repository, as the paths of their loose
+    /// files, which packed refs don't have on disk
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - The refs directory or packed-refs file cannot be read
+    #[deprecated(note = "use `load_ref_store` and `RefStore::refs_under(\"refs/tags/\")`")]
+    pub fn list_tag_refs(&self) -> Result<Vec<PathBuf>, RepositoryError> {
+        let ref_store = self.load_ref_store()?;
+        Ok(Self::loose_paths_under(&ref_store, "refs/tags/"))
+    }
+
+    /// Check if the repository has a stash ref
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - The refs directory or packed-refs file cannot be read
+    #[deprecated(note = "use `load_ref_store` and `RefStore::get(\"refs/stash\")`")]
     pub fn has_stash_ref(&self) -> Result<bool, RepositoryError> {
+        Ok(self.load_ref_store()?.get("refs/stash").is_some())
+    }
+
+    /// Loose file paths of the refs under `prefix`
+    fn loose_paths_under(ref_store: &RefStore, prefix: &str) -> Vec<PathBuf> {
+        ref_store
+            .refs_under(prefix)
+            .map(|reference| ref_store.loose_path(&reference.name))
+            .collect()
+    }
+
+    /// Load the reflog of a ref, given as a full or short name
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - No ref by that name has a reflog, or it cannot be read or parsed
+    pub fn load_reflog(&self, name: &str) -> Result<Reflog, RepositoryError> {
         self.repository.as_ref().map_or_else(
             || {
                 Err(RepositoryError::NotGitRepository(format!(
@@ -166,7 +517,7 @@ impl GitPlumber {
                     self.repo_path.display()
                 )))
             },
-            Repository::has_stash_ref,
+            |repository| repository.load_reflog(name),
         )
     }
 
@@ -245,6 +596,7 @@ impl GitPlumber {
     pub fn parse_pack_file(&self, path: &Path) -> Result<(), String> {
         // Read the pack file
         let pack_data = std::fs::read(path).map_err(|e| format!("Error reading file: {e}"))?;
+        let object_format = self.pack_object_format(path);
 
         // Parse the pack file
         match crate::git::pack::Header::parse(&pack_data) {
@@ -253,7 +605,8 @@ impl GitPlumber {
                 crate::cli::safe_println(&format!("Number of objects: {}", header.object_count))?;
                 let mut remaining_data = objects_data;
                 for i in 0..header.object_count {
-                    match crate::git::pack::Object::parse(remaining_data) {
+                    match crate::git::pack::Object::parse_with_format(remaining_data, object_format)
+                    {
                         Ok((new_remaining_data, object)) => {
                             crate::cli::safe_println(&format!("{object}"))?;
                             remaining_data = new_remaining_data;
@@ -282,36 +635,64 @@ impl GitPlumber {
     /// - Parsing or display formatting operations fail
     pub fn parse_pack_file_rich(&self, path: &Path) -> Result<(), String> {
         use crate::cli::formatters::CliPackFormatter;
+        use std::fmt::Write;
 
-        // Read the pack file
-        let pack_data = std::fs::read(path).map_err(|e| format!("Error reading file: {e}"))?;
+        // Print one entry at a time instead of holding every parsed object
+        // in memory. Without an index, the entries are found by walking the
+        // pack and named by resolving their deltas.
+        let reader = match crate::git::pack::PackReader::open(path) {
+            Ok(reader) => reader,
+            Err(_) => {
+                crate::git::pack::PackReader::open_unindexed(path, self.pack_object_format(path))
+                    .map_err(|e| format!("Error parsing pack file: {e}"))?
+            }
+        };
+        let names: Vec<(u64, Option<String>)> = if reader.index().is_some() {
+            reader
+                .entries()
+                .map(|entry| (entry.offset, Some(hex::encode(entry.object_id))))
+                .collect()
+        } else {
+            let offsets = reader.entry_offsets();
+            let resolved = crate::git::pack::resolve_objects(&reader, &offsets);
+            offsets
+                .into_iter()
+                .zip(resolved)
+                .map(|(offset, resolved)| (offset, resolved.map(|resolved| resolved.sha1)))
+                .collect()
+        };
 
-        // Parse the pack file header
-        match crate::git::pack::Header::parse(&pack_data) {
-            Ok((mut remaining_data, header)) => {
-                let mut objects = Vec::new();
+        let compat_map = self.load_compat_map().ok().flatten();
 
-                // Parse all objects
-                for _i in 0..header.object_count {
-                    match crate::git::pack::Object::parse(remaining_data) {
-                        Ok((new_remaining_data, object)) => {
-                            objects.push(object);
-                            remaining_data = new_remaining_data;
-                        }
-                        Err(e) => {
-                            return Err(format!("Error parsing object: {e}"));
-                        }
-                    }
-                }
+        let mut output = String::new();
+        CliPackFormatter::format_pack_header(&mut output, reader.header());
+        writeln!(&mut output)
+            .expect("fmt::Write for String should not fail for in-memory formatting");
+        crate::cli::safe_print(&output)?;
 
-                // Format and display the rich output
-                let formatted_output = CliPackFormatter::format_pack_file(&header, &objects);
-                crate::cli::safe_print(&formatted_output)?;
+        for (position, (offset, sha1)) in names.iter().enumerate() {
+            let object = reader
+                .read_entry_at(*offset)
+                .map_err(|e| format!("Error parsing object: {e}"))?;
 
-                Ok(())
-            }
-            Err(e) => Err(format!("Error parsing pack file: {e}")),
+            let mut output = String::new();
+            CliPackFormatter::format_pack_entry(
+                &mut output,
+                &object,
+                position + 1,
+                sha1.as_deref(),
+                compat_map.as_ref(),
+            );
+            crate::cli::safe_print(&output)?;
+        }
+        if names.len() < reader.object_count() {
+            return Err(format!(
+                "Error parsing object #{}: corrupt entry",
+                names.len() + 1
+            ));
         }
+
+        Ok(())
     }
 
     /// View a file as a loose object with rich formatting
@@ -333,7 +714,16 @@ impl GitPlumber {
             },
             |repo| match repo.read_loose_object(path) {
                 Ok(loose_obj) => {
-                    let formatted_output = CliLooseFormatter::format_loose_object(&loose_obj);
+                    let compat_id = repo
+                        .load_compat_map()
+                        .ok()
+                        .flatten()
+                        .and_then(|map| map.compat_id(&loose_obj.object_id));
+                    let formatted_output = CliLooseFormatter::format_loose_object(
+                        &loose_obj,
+                        repo.resolve_gitlinks(&loose_obj, &self.object_databases),
+                        compat_id,
+                    );
                     crate::cli::safe_print(&formatted_output)?;
                     Ok(())
                 }
@@ -368,6 +758,327 @@ impl GitPlumber {
         }
     }
 
+    /// View a commit-graph file, or one layer of a split chain, with rich
+    /// formatting
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file cannot be read
+    /// - The file is not a valid commit-graph
+    /// - The formatting operations fail
+    pub fn view_commit_graph(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::git::commit_graph::CommitGraphChain;
+        use crate::tui::widget::commit_graph_details::formatters::CommitGraphFormatter;
+
+        let data =
+            std::fs::read(path).map_err(|e| format!("Error reading commit-graph file: {e}"))?;
+        match crate::git::commit_graph::CommitGraph::parse(&data) {
+            Ok((_, commit_graph)) => {
+                // Layers of a split chain number their commits after the layers below
+                let first_position = CommitGraphChain::layer_first_position(path).unwrap_or(0);
+                let formatted_text = CommitGraphFormatter::new(&commit_graph)
+                    .with_first_position(first_position)
+                    .generate_content();
+                let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+                crate::cli::safe_print(&colored_text)?;
+                Ok(())
+            }
+            Err(e) => Err(format!("Error parsing commit-graph: {e:?}")),
+        }
+    }
+
+    /// Show the changed-path Bloom filters of the repository's commit-graph,
+    /// optionally answering them for a path and recomputing them from tree
+    /// diffs
+    ///
+    /// Every layer of a split chain is shown in turn.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The repository has no commit-graph, or it cannot be read or parsed
+    /// - No commit-graph file has Bloom filter chunks
+    /// - The formatting operations fail
+    pub fn view_commit_graph_bloom(&self, path: Option<&str>, verify: bool) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::git::commit_graph::{CommitGraph, CommitGraphChain};
+        use crate::tui::widget::commit_graph_details::formatters::BloomFormatter;
+        use ratatui::text::{Line, Text};
+
+        // (label, graph, global position of its first commit)
+        let graphs: Vec<(String, CommitGraph, usize)> = if let Some(file) = self.get_commit_graph()
+        {
+            let data = std::fs::read(&file)
+                .map_err(|e| format!("Error reading commit-graph file: {e}"))?;
+            let (_, graph) = CommitGraph::parse(&data)
+                .map_err(|e| format!("Error parsing commit-graph: {e:?}"))?;
+            vec![("commit-graph".to_string(), graph, 0)]
+        } else if let Some(group) = self
+            .get_commit_graph_chain_group()
+            .map_err(|e| format!("Error listing commit-graph chain: {e}"))?
+        {
+            let chain = CommitGraphChain::load(&group.chain_file)
+                .map_err(|e| format!("Error loading commit-graph chain: {e}"))?;
+            chain
+                .layers
+                .into_iter()
+                .enumerate()
+                .map(|(index, layer)| {
+                    (
+                        format!("layer {index}: graph-{}.graph", layer.hash),
+                        layer.graph,
+                        layer.first_position,
+                    )
+                })
+                .collect()
+        } else {
+            return Err("The repository has no commit-graph; write one with \
+                 `git commit-graph write --reachable --changed-paths`"
+                .to_string());
+        };
+        if graphs
+            .iter()
+            .all(|(_, graph, _)| graph.bloom_filters.is_none())
+        {
+            return Err("The commit-graph has no Bloom filters; rewrite it with \
+                 `git commit-graph write --reachable --changed-paths`"
+                .to_string());
+        }
+
+        let database = verify.then(|| self.object_database());
+        let mut lines = Vec::new();
+        for (label, graph, first_position) in &graphs {
+            if graphs.len() > 1 {
+                lines.push(Line::from(format!("═══ {label} ═══")));
+                lines.push(Line::from(""));
+            }
+            let Some(formatter) = BloomFormatter::new(graph, *first_position) else {
+                lines.push(Line::from("No Bloom filter chunks in this file."));
+                lines.push(Line::from(""));
+                continue;
+            };
+            formatter.format_bloom_overview(&mut lines);
+            if let Some(path) = path {
+                formatter.format_query(path, &mut lines);
+            }
+            if let (Some(database), Some(filters)) = (&database, &graph.bloom_filters) {
+                let checks = filters.verify(graph, *first_position, database);
+                formatter.format_verification(&checks, &mut lines);
+            }
+        }
+
+        let colored_text = CliPackFormatter::text_to_ansi_string(&Text::from(lines));
+        crate::cli::safe_print(&colored_text)
+    }
+
+    /// View a split commit-graph chain with all of its layers
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The chain file or one of its layers cannot be read or parsed
+    /// - A layer listed in the chain is missing
+    /// - The formatting operations fail
+    pub fn view_commit_graph_chain(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::commit_graph_chain_details::formatters::CommitGraphChainFormatter;
+
+        let chain = crate::git::commit_graph::CommitGraphChain::load(path)
+            .map_err(|e| format!("Error loading commit-graph chain: {e}"))?;
+        let formatted_text = CommitGraphChainFormatter::new(&chain).generate_content();
+        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+        crate::cli::safe_print(&colored_text)?;
+        Ok(())
+    }
+
+    /// View one table of a reftable stack with rich formatting
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file cannot be read
+    /// - The file is not a valid reftable
+    /// - The formatting operations fail
+    pub fn view_reftable(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::reftable_details::formatters::ReftableFormatter;
+
+        let data = std::fs::read(path).map_err(|e| format!("Error reading reftable: {e}"))?;
+        match crate::git::refs::Reftable::parse(&data) {
+            Ok((_, reftable)) => {
+                let formatted_text = ReftableFormatter::new(&reftable).generate_content();
+                let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+                crate::cli::safe_print(&colored_text)?;
+                Ok(())
+            }
+            Err(e) => Err(format!("Error parsing reftable: {e:?}")),
+        }
+    }
+
+    /// View a reftable stack, given its `tables.list`, with the merged refs
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The list or one of its tables cannot be read or parsed
+    /// - A table listed in the stack is missing
+    /// - The formatting operations fail
+    pub fn view_reftable_stack(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::reftable_stack_details::formatters::ReftableStackFormatter;
+
+        let stack = crate::git::refs::ReftableStack::load_list(path)
+            .map_err(|e| format!("Error loading reftable stack: {e}"))?;
+        let formatted_text = ReftableStackFormatter::new(&stack).generate_content();
+        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+        crate::cli::safe_print(&colored_text)?;
+        Ok(())
+    }
+
+    /// View an incremental multi-pack-index, given its chain file, with every
+    /// layer and the objects across them
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The chain file or one of its layers cannot be read or parsed
+    /// - A layer listed in the chain is missing
+    /// - The formatting operations fail
+    pub fn view_multi_pack_index_chain(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::multi_pack_index_chain_details::formatters::MultiPackIndexChainFormatter;
+
+        let chain = crate::git::pack::MultiPackIndexChain::load(path)
+            .map_err(|e| format!("Error loading multi-pack-index chain: {e}"))?;
+        let formatted_text = MultiPackIndexChainFormatter::new(&chain).generate_content();
+        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+        crate::cli::safe_print(&colored_text)?;
+        Ok(())
+    }
+
+    /// View a linked worktree, given its `worktrees/<name>` directory, with
+    /// its checkout, state, resolved HEAD and index
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The directory cannot be read or its refs cannot be loaded
+    /// - The formatting operations fail
+    pub fn view_worktree(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::worktree_details::formatters::WorktreeFormatter;
+
+        let details =
+            crate::git::worktree::WorktreeDetails::load(path, &self.object_database_for(path))
+                .map_err(|e| format!("Error loading worktree: {e}"))?;
+        let formatted_text = WorktreeFormatter::new(&details).generate_content();
+        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+        crate::cli::safe_print(&colored_text)?;
+        Ok(())
+    }
+
+    /// View a Git index file (.git/index or a shared index) with rich formatting
+    ///
+    /// A split index is merged with the shared index found next to it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file cannot be read
+    /// - The file is not a valid index file
+    /// - The formatting operations fail
+    pub fn view_git_index(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::git_index_details::formatters::GitIndexFormatter;
+
+        let data = std::fs::read(path).map_err(|e| format!("Error reading index file: {e}"))?;
+        let object_format = path.parent().map_or_else(Default::default, |git_dir| {
+            crate::git::repository::Repository::from_git_dir(git_dir).object_format()
+        });
+        match crate::git::index::GitIndex::parse_with_format(&data, object_format) {
+            Ok((_, mut git_index)) => {
+                if let Some(git_dir) = path.parent() {
+                    git_index.resolve_repository_data(git_dir, &self.object_database_for(git_dir));
+                }
+                let formatted_text = GitIndexFormatter::new(&git_index).generate_content();
+                let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+                crate::cli::safe_print(&colored_text)?;
+                Ok(())
+            }
+            Err(e) => Err(format!("Error parsing index: {e:?}")),
+        }
+    }
+
+    /// View a packed-refs file with rich formatting
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file cannot be read
+    /// - The file is not a valid packed-refs file
+    /// - The formatting operations fail
+    pub fn view_packed_refs(&self, path: &Path) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::packed_refs_details::formatters::PackedRefsFormatter;
+
+        let data =
+            std::fs::read(path).map_err(|e| format!("Error reading packed-refs file: {e}"))?;
+        match crate::git::refs::PackedRefs::parse(&data) {
+            Ok((_, mut packed_refs)) => {
+                if let Some(git_dir) = path.parent() {
+                    packed_refs.resolve_loose_refs(git_dir);
+                }
+                let formatted_text = PackedRefsFormatter::new(&packed_refs).generate_content();
+                let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+                crate::cli::safe_print(&colored_text)?;
+                Ok(())
+            }
+            Err(e) => Err(format!("Error parsing packed-refs: {e:?}")),
+        }
+    }
+
+    /// View the reflog of a ref, given as a full or short name, as a timeline
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The path is not a valid git repository
+    /// - No ref by that name has a reflog, or it cannot be read or parsed
+    /// - The formatting operations fail
+    pub fn view_reflog(&self, name: &str) -> Result<(), String> {
+        let reflog = self
+            .load_reflog(name)
+            .map_err(|e| format!("Error loading reflog: {e}"))?;
+        Self::print_reflog(&reflog)
+    }
+
+    /// View a reflog file under `.git/logs` as a timeline
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file is not under the logs directory of a git directory
+    /// - The file cannot be read or parsed
+    /// - The formatting operations fail
+    pub fn view_reflog_file(&self, path: &Path) -> Result<(), String> {
+        let (git_dir, ref_name) = Reflog::ref_for_log_path(path)
+            .ok_or_else(|| format!("{} is not a reflog file", path.display()))?;
+        let reflog =
+            Reflog::load(&git_dir, &ref_name).map_err(|e| format!("Error loading reflog: {e}"))?;
+        Self::print_reflog(&reflog)
+    }
+
+    fn print_reflog(reflog: &Reflog) -> Result<(), String> {
+        use crate::cli::formatters::CliPackFormatter;
+        use crate::tui::widget::reflog_details::formatters::ReflogFormatter;
+
+        let formatted_text = ReflogFormatter::new(reflog).generate_content();
+        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+        crate::cli::safe_print(&colored_text)
+    }
+
     /// View a pack bitmap file with rich formatting
     ///
     /// # Errors
@@ -394,6 +1105,11 @@ impl GitPlumber {
 
     /// View an object by hash with rich formatting
     ///
+    /// Objects the repository doesn't store itself are looked up in its
+    /// alternate object directories, in the order git searches them. In a
+    /// repository with a compatibility format, a hash that names no stored
+    /// object is looked up as a compat ID.
+    ///
     /// # Errors
     ///
     /// This function will return an error if:
@@ -401,68 +1117,42 @@ impl GitPlumber {
     /// - Multiple objects match a partial hash (disambiguation needed)
     /// - The formatting operations fail
     pub fn view_object_by_hash(&self, hash: &str) -> Result<(), String> {
-        use crate::cli::formatters::{CliLooseFormatter, CliPackFormatter};
-        use std::fmt::Write;
-
         match self.repository.as_ref() {
-            Some(_repo) => {
-                // First try as loose object
-                if let Ok(loose_obj) = self.find_loose_object_by_partial_hash(hash) {
-                    let formatted_output = CliLooseFormatter::format_loose_object(&loose_obj);
-                    crate::cli::safe_print(&formatted_output)?;
+            Some(repo) => {
+                // A broken map only costs the compat IDs
+                let compat_map = repo.load_compat_map().ok().flatten();
+                if self.view_object_in_any_store(repo, hash, compat_map.as_ref())? {
                     return Ok(());
                 }
 
-                // If not found in loose objects, search pack files
-                if let Ok(pack_obj) = self.find_pack_object_by_partial_hash(hash) {
-                    // Format pack object using existing formatter - create single object "pack file"
-                    if let Some(ref object_data) = pack_obj.object_data {
-                        let mut output = String::new();
-                        writeln!(&mut output, "\x1b[1mPACK OBJECT (found by hash)\x1b[0m").expect(
-                            "fmt::Write for String should not fail for in-memory formatting",
-                        );
-                        writeln!(&mut output, "{}", "─".repeat(50)).expect(
-                            "fmt::Write for String should not fail for in-memory formatting",
-                        );
-                        writeln!(&mut output).expect(
-                            "fmt::Write for String should not fail for in-memory formatting",
-                        );
-
-                        // Create a PackObject from the found object and format it
-                        let formatted_pack_obj = crate::tui::model::PackObject {
-                            index: pack_obj.index,
-                            obj_type: pack_obj.obj_type.clone(),
-                            size: pack_obj.size,
-                            sha1: pack_obj.sha1.clone(),
-                            base_info: pack_obj.base_info.clone(),
-                            object_data: Some(object_data.clone()),
-                        };
-
-                        let mut widget =
-                            crate::tui::widget::pack_obj_details::PackObjectWidget::new(
-                                formatted_pack_obj,
-                            );
-                        let formatted_text = widget.text();
-
-                        // Convert ratatui Text to ANSI colored string (reuse formatter logic)
-                        let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
-                        output.push_str(&colored_text);
-
-                        crate::cli::safe_print(&output)?;
-                    } else {
-                        // Fallback to basic info if no object data
-                        crate::cli::safe_println("Pack Object (found by hash):")?;
+                let Some(map) = compat_map.as_ref() else {
+                    return Err(format!("Object not found: {hash}"));
+                };
+                match map.find_by_compat_prefix(hash).as_slice() {
+                    [] => Err(format!("Object not found: {hash}")),
+                    [(compat, stored)] => {
                         crate::cli::safe_println(&format!(
-                            "SHA1: {}",
-                            pack_obj.sha1.as_deref().unwrap_or("unknown")
+                            "{} {compat} is stored as {} {stored}\n",
+                            map.compat_format(),
+                            map.object_format()
                         ))?;
-                        crate::cli::safe_println(&format!("Type: {}", pack_obj.obj_type))?;
-                        crate::cli::safe_println(&format!("Size: {} bytes", pack_obj.size))?;
+                        if self.view_object_in_any_store(repo, stored, Some(map))? {
+                            Ok(())
+                        } else {
+                            Err(format!("Object not found: {stored}"))
+                        }
+                    }
+                    matches => {
+                        let mut error_msg =
+                            format!("Multiple {} IDs match '{hash}':\n", map.compat_format());
+                        for (compat, stored) in matches {
+                            use std::fmt::Write;
+                            writeln!(&mut error_msg, "  {compat} (stored as {stored})")
+                                .expect("Writing to string should not fail");
+                        }
+                        Err(error_msg)
                     }
-                    return Ok(());
                 }
-
-                Err(format!("Object not found: {hash}"))
             }
             None => Err(format!(
                 "Not a git repository: {}",
@@ -471,139 +1161,109 @@ impl GitPlumber {
         }
     }
 
-    /// Find a loose object by partial hash (4-40 characters)
+    /// View an object by hash if the repository or one of its alternates
+    /// stores it, returning whether it was found
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - No matching objects are found
-    /// - Multiple objects match the partial hash
-    fn find_loose_object_by_partial_hash(
+    /// - Multiple objects match a partial hash
+    /// - The object cannot be read
+    /// - The formatting operations fail
+    fn view_object_in_any_store(
         &self,
-        partial_hash: &str,
-    ) -> Result<crate::git::loose_object::LooseObject, String> {
-        // For full hash (40 chars), use direct lookup
-        if partial_hash.len() == 40 {
-            return self
-                .repository
-                .as_ref()
-                .expect("Repository should be available for hash lookup")
-                .read_loose_object_by_hash(partial_hash)
-                .map_err(|e| format!("Object not found: {e}"));
-        }
+        repo: &Repository,
+        hash: &str,
+        compat_map: Option<&CompatMap>,
+    ) -> Result<bool, String> {
+        use crate::cli::formatters::{CliLooseFormatter, CliPackFormatter};
+        use crate::git::object_database::{ObjectDatabaseError, ObjectLocation};
+        use std::fmt::Write;
 
-        // For partial hash, we need to search all loose objects
-        match self.list_parsed_loose_objects(10000) {
-            // Large limit for comprehensive search
-            Ok(objects) => {
-                let matches: Vec<_> = objects
-                    .into_iter()
-                    .filter(|obj| obj.object_id.starts_with(partial_hash))
-                    .collect();
-
-                match matches.len() {
-                    0 => Err(format!("No loose objects found matching: {partial_hash}")),
-                    1 => Ok(matches
-                        .into_iter()
-                        .next()
-                        .expect("Should have exactly one match")),
-                    _ => {
-                        let mut error_msg = format!("Multiple objects match '{partial_hash}':\n");
-                        for obj in matches {
-                            use std::fmt::Write;
-                            writeln!(&mut error_msg, "  {} ({})", obj.object_id, obj.object_type)
-                                .expect("Writing to string should not fail");
-                        }
-                        Err(error_msg)
-                    }
+        let database = self.object_database();
+        let oid = match database.resolve_prefix(hash) {
+            Ok(oid) => oid,
+            Err(ObjectDatabaseError::NotFound(_) | ObjectDatabaseError::InvalidPrefix(_)) => {
+                return Ok(false);
+            }
+            Err(ObjectDatabaseError::Ambiguous { prefix, candidates }) => {
+                let mut error_msg = format!("Multiple objects match '{prefix}':\n");
+                for candidate in candidates {
+                    let object_type = candidate
+                        .object_type
+                        .map_or_else(|| "unknown".to_string(), |obj_type| obj_type.to_string());
+                    writeln!(&mut error_msg, "  {} ({object_type})", candidate.object_id)
+                        .expect("Writing to string should not fail");
                 }
+                return Err(error_msg);
             }
-            Err(e) => Err(format!("Error searching loose objects: {e}")),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        // Objects borrowed through objects/info/alternates
+        if let Some(objects_dir) = database.objects_dir_of(&oid)
+            && objects_dir != repo.objects_dir()
+        {
+            crate::cli::safe_println(&format!(
+                "Borrowed from alternate object directory {}\n",
+                objects_dir.display()
+            ))?;
         }
-    }
 
-    /// Find a pack object by partial hash (4-40 characters)
-    ///
-    /// # Errors
-    ///
-    /// This function will return an error if:
-    /// - No matching objects are found
-    /// - Multiple objects match the partial hash
-    /// - Pack files cannot be read or parsed
-    fn find_pack_object_by_partial_hash(
-        &self,
-        partial_hash: &str,
-    ) -> Result<crate::tui::model::PackObject, String> {
-        use crate::git::pack::{Header, Object};
-
-        // Get all pack files
-        let pack_files = self
-            .list_pack_files()
-            .map_err(|e| format!("Error listing pack files: {e}"))?;
-
-        let mut matches = Vec::new();
-
-        // Search through each pack file
-        for pack_path in pack_files {
-            let pack_data =
-                std::fs::read(&pack_path).map_err(|e| format!("Error reading pack file: {e}"))?;
-
-            if let Ok((mut remaining_data, header)) = Header::parse(&pack_data) {
-                // Parse all objects in this pack file
-                let mut objects = Vec::new();
-                for _ in 0..header.object_count {
-                    match Object::parse(remaining_data) {
-                        Ok((new_remaining_data, object)) => {
-                            objects.push(object);
-                            remaining_data = new_remaining_data;
-                        }
-                        Err(_) => break,
-                    }
-                }
+        let object_id = hex::encode(&oid);
+        let compat_id = compat_map.and_then(|map| map.compat_id(&object_id));
+        crate::cli::safe_println(&format!(
+            "{object_id}, abbreviated {}\n",
+            database.abbreviate(&oid)
+        ))?;
 
-                // Resolve delta chains to get real git object IDs, so
-                // deltified objects are findable by their actual hash
-                let resolved = crate::git::pack::resolve_objects(&objects);
-
-                for (index, (object, resolved)) in objects.into_iter().zip(resolved).enumerate() {
-                    let Some(resolved) = resolved else { continue };
-
-                    if resolved.sha1.starts_with(partial_hash) {
-                        let pack_obj = crate::tui::model::PackObject {
-                            index: index + 1,
-                            obj_type: object.header.obj_type().to_string(),
-                            size: u32::try_from(object.header.uncompressed_data_size())
-                                .unwrap_or(u32::MAX),
-                            sha1: Some(resolved.sha1),
-                            base_info: None, // TODO: Add delta info if needed
-                            object_data: Some(object),
-                        };
-                        matches.push(pack_obj);
-                    }
-                }
-            }
-        }
+        if let Some(ObjectLocation::Packed { .. }) = database.locate(&oid) {
+            let entry = database
+                .read_pack_entry(&oid)
+                .map_err(|e| format!("Error reading pack object: {e}"))?;
 
-        match matches.len() {
-            0 => Err(format!("No pack objects found matching: {partial_hash}")),
-            1 => Ok(matches
-                .into_iter()
-                .next()
-                .expect("Should have exactly one match")),
-            _ => {
-                let mut error_msg = format!("Multiple pack objects match '{partial_hash}':\n");
-                for obj in matches {
-                    use std::fmt::Write;
-                    writeln!(
-                        &mut error_msg,
-                        "  {} ({})",
-                        obj.sha1.as_deref().unwrap_or("unknown"),
-                        obj.obj_type
-                    )
-                    .expect("Writing to string should not fail");
-                }
-                Err(error_msg)
-            }
+            let mut output = String::new();
+            writeln!(&mut output, "\x1b[1mPACK OBJECT (found by hash)\x1b[0m")
+                .expect("fmt::Write for String should not fail for in-memory formatting");
+            writeln!(&mut output, "{}", "─".repeat(50))
+                .expect("fmt::Write for String should not fail for in-memory formatting");
+            writeln!(
+                &mut output,
+                "Stored in {} at offset {}, object {} in pack order",
+                entry.pack,
+                entry.offset,
+                entry.position + 1
+            )
+            .expect("fmt::Write for String should not fail for in-memory formatting");
+            writeln!(&mut output)
+                .expect("fmt::Write for String should not fail for in-memory formatting");
+
+            let pack_obj = CliPackFormatter::create_pack_object_from_object(
+                &entry.object,
+                // Numbered from 1 like the pack view's object list
+                entry.position + 1,
+                Some(&object_id),
+                compat_id,
+            );
+            let mut widget = crate::tui::widget::pack_obj_details::PackObjectWidget::new(pack_obj);
+            let formatted_text = widget.text();
+
+            // Convert ratatui Text to ANSI colored string (reuse formatter logic)
+            let colored_text = CliPackFormatter::text_to_ansi_string(&formatted_text);
+            output.push_str(&colored_text);
+
+            crate::cli::safe_print(&output)?;
+        } else {
+            let loose_obj = database
+                .read(&oid)
+                .map_err(|e| format!("Object not found: {e}"))?;
+            let formatted_output = CliLooseFormatter::format_loose_object(
+                &loose_obj,
+                repo.resolve_gitlinks(&loose_obj, &self.object_databases),
+                compat_id,
+            );
+            crate::cli::safe_print(&formatted_output)?;
         }
+        Ok(true)
     }
 }
@@ -71,6 +71,9 @@ impl EducationalContent {
              │ Index Checksum  │ 20 bytes (SHA-1 of all index data)\n\
              └─────────────────┘\n\
              \n\
+             In SHA-256 repositories (extensions.objectFormat = sha256)\n\
+             object names and both checksums take 32 bytes instead of 20.\n\
+             \n\
              The fan-out table enables binary search optimization:\n\
              - Entry N contains count of objects with first byte ≤ N\n\
              - Allows quick range determination for binary search\n\
@@ -249,6 +252,18 @@ impl EducationalContent {
             ),
         );
 
+        // Alternates educational content
+        content_map.insert(
+            "Alternates".to_string(),
+            Text::from(
+                "ALTERNATES\n\nObject directories this repository borrows objects from,\n\
+             listed in .git/objects/info/alternates by `git clone --reference`\n\
+             or `--shared`. Lookups that miss the repository's own objects\n\
+             search them in order; git never writes to them, and an alternate\n\
+             can list alternates of its own.",
+            ),
+        );
+
         Self { content_map }
     }
 
@@ -0,0 +1,241 @@
+//! Alternate object stores: the `objects/info/alternates` file.
+//!
+//! `git clone --reference` and `--shared` leave most objects in another
+//! repository's object directory, listed one per line in
+//! `objects/info/alternates`. Relative entries are resolved against the
+//! object directory whose file lists them, and an alternate's own
+//! alternates are followed too, up to git's nesting limit.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AlternatesError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// One object directory lent to the repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alternate {
+    /// The object directory, with relative entries resolved
+    pub path: PathBuf,
+    /// The line as written in the alternates file
+    pub entry: String,
+    /// The alternates file listing it
+    pub source: PathBuf,
+    /// 1 for the repository's own alternates, 2 for theirs, and so on
+    pub depth: usize,
+    /// Whether the directory exists; git skips the entry when it doesn't
+    pub exists: bool,
+}
+
+impl Alternate {
+    /// Where an object directory lists its alternates
+    pub const FILE: &str = "info/alternates";
+    /// Alternates nested deeper than this are ignored, as git does
+    pub const MAX_DEPTH: usize = 5;
+
+    /// Every alternate of the object directory `objects_dir`, recursively,
+    /// in the order git searches them
+    ///
+    /// Entries naming `objects_dir` itself or an object directory already
+    /// listed are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an alternates file exists but can't be read.
+    pub fn list(objects_dir: &Path) -> Result<Vec<Self>, AlternatesError> {
+        let mut alternates = Vec::new();
+        let mut seen = vec![Self::identity(objects_dir)];
+        Self::collect(objects_dir, 1, &mut seen, &mut alternates)?;
+        Ok(alternates)
+    }
+
+    fn collect(
+        objects_dir: &Path,
+        depth: usize,
+        seen: &mut Vec<PathBuf>,
+        alternates: &mut Vec<Self>,
+    ) -> Result<(), AlternatesError> {
+        if depth > Self::MAX_DEPTH {
+            return Ok(());
+        }
+        let source = objects_dir.join(Self::FILE);
+        if !source.is_file() {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(&source)?;
+        for entry in Self::parse(&content) {
+            let path = normalize(&objects_dir.join(&entry));
+            let identity = Self::identity(&path);
+            if seen.contains(&identity) {
+                continue;
+            }
+            seen.push(identity);
+
+            let exists = path.is_dir();
+            alternates.push(Self {
+                path: path.clone(),
+                entry,
+                source: source.clone(),
+                depth,
+                exists,
+            });
+            if exists {
+                Self::collect(&path, depth + 1, seen, alternates)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The entries of an alternates file: one path per line, skipping
+    /// blank lines and `#` comments, with C-style quoting undone
+    #[must_use]
+    pub fn parse(content: &str) -> Vec<String> {
+        content
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.strip_prefix('"')
+                    .and_then(|quoted| quoted.strip_suffix('"'))
+                    .map_or_else(|| line.to_string(), unquote)
+            })
+            .collect()
+    }
+
+    /// A path to compare object directories by, following symlinks where
+    /// the directory exists
+    fn identity(path: &Path) -> PathBuf {
+        fs::canonicalize(path).unwrap_or_else(|_| normalize(path))
+    }
+}
+
+/// Undo C-style quoting: backslash escapes and three-digit octal bytes
+fn unquote(quoted: &str) -> String {
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut chars = quoted.bytes().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'v') => bytes.push(0x0b),
+            Some(digit @ b'0'..=b'3') => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    if let Some(next @ b'0'..=b'7') = chars.peek().copied() {
+                        value = value * 8 + (next - b'0');
+                        chars.next();
+                    }
+                }
+                bytes.push(value);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Drop `.` components and fold `..` into their parent without touching
+/// the filesystem, as git does for alternate paths
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_alternates(objects_dir: &Path, content: &str) {
+        fs::create_dir_all(objects_dir.join("info")).unwrap();
+        fs::write(objects_dir.join(Alternate::FILE), content).unwrap();
+    }
+
+    #[test]
+    fn test_parse_alternates_file() {
+        assert_eq!(
+            Alternate::parse(
+                "# shared\n/srv/a/objects\n\n../../b/objects\n\"/srv/with\\\"quote\\040\"\n"
+            ),
+            ["/srv/a/objects", "../../b/objects", "/srv/with\"quote "]
+        );
+    }
+
+    #[test]
+    fn test_list_relative_and_recursive_alternates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let objects_dir = temp_dir.path().join("clone/.git/objects");
+        let reference = temp_dir.path().join("reference/.git/objects");
+        let shared = temp_dir.path().join("shared/objects");
+        fs::create_dir_all(&objects_dir).unwrap();
+        fs::create_dir_all(&shared).unwrap();
+        write_alternates(
+            &objects_dir,
+            &format!(
+                "../../../reference/.git/objects\n{}\n/nonexistent/objects\n",
+                shared.display()
+            ),
+        );
+        // The reference lists the shared store again and the clone itself
+        write_alternates(
+            &reference,
+            &format!("../../../shared/objects\n{}\n", objects_dir.display()),
+        );
+
+        let alternates = Alternate::list(&objects_dir).unwrap();
+        let paths: Vec<_> = alternates.iter().map(|a| a.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                reference.clone(),
+                shared.clone(),
+                PathBuf::from("/nonexistent/objects")
+            ]
+        );
+        assert_eq!(alternates[0].entry, "../../../reference/.git/objects");
+        assert_eq!(alternates[0].depth, 1);
+        assert_eq!(alternates[1].depth, 2);
+        assert_eq!(alternates[1].source, reference.join(Alternate::FILE));
+        assert!(alternates[1].exists);
+        assert!(!alternates[2].exists);
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let stores: Vec<_> = (0..=Alternate::MAX_DEPTH + 1)
+            .map(|i| temp_dir.path().join(format!("store{i}")))
+            .collect();
+        for pair in stores.windows(2) {
+            fs::create_dir_all(&pair[1]).unwrap();
+            write_alternates(&pair[0], &format!("{}\n", pair[1].display()));
+        }
+
+        let alternates = Alternate::list(&stores[0]).unwrap();
+        assert_eq!(alternates.len(), Alternate::MAX_DEPTH);
+        assert_eq!(alternates.last().unwrap().depth, Alternate::MAX_DEPTH);
+    }
+}
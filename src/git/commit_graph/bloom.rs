@@ -0,0 +1,619 @@
+use crate::git::loose_object::{ParsedContent, TreeEntry, TreeEntryType};
+use crate::git::object_database::ObjectDatabase;
+use nom::{
+    IResult, Parser,
+    error::{Error, ErrorKind},
+    multi::count,
+    number::complete::be_u32,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::CommitGraph;
+
+/// Size of the BDAT header: hash version, number of hashes, bits per entry
+const BDAT_HEADER_SIZE: usize = 12;
+/// Seeds of the two murmur3 hashes every Bloom key is derived from
+const SEED_0: u32 = 0x293a_e76f;
+const SEED_1: u32 = 0x7e64_6e2c;
+/// Most bits a path may set: each is a murmur3-derived position, so more
+/// add nothing but work
+const MAX_NUM_HASHES: u32 = 32;
+/// Most filter bits per changed path: even at [`MAX_CHANGED_PATHS`] this
+/// keeps a recomputed filter to a few KiB
+const MAX_BITS_PER_ENTRY: u32 = 64;
+/// Changed paths above which git stores a "too large" filter instead
+pub const MAX_CHANGED_PATHS: usize = 512;
+/// Byte of the one-byte filter git stores when a commit changes too many
+/// paths: every bit set, so every query answers "maybe"
+const TRUNCATED_LARGE_FILTER: u8 = 0xff;
+
+/// Filter settings from the BDAT chunk header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BloomSettings {
+    /// 1: murmur3 over sign-extended bytes (what git wrote before 2.42, a
+    /// bug for paths with non-ASCII bytes); 2: murmur3 over unsigned bytes
+    pub hash_version: u32,
+    /// Bits set per path (7 by default)
+    pub num_hashes: u32,
+    /// Filter bits allotted per changed path (10 by default)
+    pub bits_per_entry: u32,
+}
+
+/// What a Bloom filter says about a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloomAnswer {
+    /// Every bit of the path is set: the commit may have changed it
+    Maybe,
+    /// A bit is clear: the commit certainly didn't change the path, so
+    /// `git log -- <path>` can skip diffing it
+    DefinitelyNot,
+    /// The commit has no filter (zero-length), so it must be diffed
+    NotComputed,
+}
+
+/// Changed-path Bloom filters from the BIDX and BDAT chunks
+///
+/// Each commit gets a filter of the paths it changed relative to its first
+/// parent, including every leading directory of a changed file. A path is
+/// hashed into `num_hashes` bit positions; if any is clear the commit
+/// certainly didn't touch the path, which lets `git log -- <path>` skip most
+/// tree diffs.
+#[derive(Debug, Clone)]
+pub struct BloomFilters {
+    pub settings: BloomSettings,
+    /// End offset of each commit's filter in [`BloomFilters::data`], in
+    /// OIDL order (BIDX chunk); a filter starts where the previous one ends
+    pub index: Vec<u32>,
+    /// Filter data after the BDAT header
+    pub data: Vec<u8>,
+}
+
+/// Outcome of recomputing one commit's filter from its tree diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BloomCheck {
+    /// The recomputed filter is the stored one, bit for bit
+    Identical { changed_paths: usize },
+    /// The filters differ, but the stored one answers "maybe" for every
+    /// changed path
+    Differs { changed_paths: usize },
+    /// Changed paths the stored filter answers "definitely not" for: history
+    /// queries for them would wrongly skip this commit
+    FalseNegatives(Vec<String>),
+    /// The stored filter is empty: git diffs this commit on every query
+    NotComputed,
+    /// The filter couldn't be recomputed
+    Skipped(String),
+}
+
+impl BloomFilters {
+    /// Parse the BIDX and BDAT chunks of a commit-graph with `total_commits`
+    /// commits
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if BIDX doesn't have one entry per commit,
+    /// its offsets decrease or run past BDAT, or the BDAT header names an
+    /// unknown hash version or a number of hashes or bits per entry outside
+    /// `1..=32` and `1..=64`.
+    pub fn parse<'a>(
+        bidx: &'a [u8],
+        bdat: &'a [u8],
+        total_commits: usize,
+    ) -> IResult<&'a [u8], Self> {
+        if bidx.len() != total_commits * 4 {
+            return Err(nom::Err::Error(Error::new(bidx, ErrorKind::LengthValue)));
+        }
+        let (_, index) = count(be_u32, total_commits).parse(bidx)?;
+
+        let (data, hash_version) = be_u32(bdat)?;
+        let (data, num_hashes) = be_u32(data)?;
+        let (data, bits_per_entry) = be_u32(data)?;
+        if hash_version != 1 && hash_version != 2 {
+            return Err(nom::Err::Error(Error::new(bdat, ErrorKind::Tag)));
+        }
+        if !(1..=MAX_NUM_HASHES).contains(&num_hashes)
+            || !(1..=MAX_BITS_PER_ENTRY).contains(&bits_per_entry)
+        {
+            return Err(nom::Err::Error(Error::new(bdat, ErrorKind::Verify)));
+        }
+
+        if index.windows(2).any(|w| w[0] > w[1])
+            || index.last().is_some_and(|&end| end as usize > data.len())
+        {
+            return Err(nom::Err::Error(Error::new(bidx, ErrorKind::Verify)));
+        }
+
+        Ok((
+            &data[data.len()..],
+            Self {
+                settings: BloomSettings {
+                    hash_version,
+                    num_hashes,
+                    bits_per_entry,
+                },
+                index,
+                data: data.to_vec(),
+            },
+        ))
+    }
+
+    /// Size in bytes of the BDAT chunk these filters were read from
+    #[must_use]
+    pub const fn bdat_size(&self) -> usize {
+        BDAT_HEADER_SIZE + self.data.len()
+    }
+
+    /// Filter bytes of the commit at `position`
+    #[must_use]
+    pub fn filter(&self, position: usize) -> Option<&[u8]> {
+        let end = *self.index.get(position)? as usize;
+        let start = match position {
+            0 => 0,
+            _ => self.index[position - 1] as usize,
+        };
+        self.data.get(start..end)
+    }
+
+    /// Whether the commit at `position` changed too many paths to get a
+    /// real filter
+    #[must_use]
+    pub fn is_truncated_large(&self, position: usize) -> bool {
+        self.filter(position) == Some(&[TRUNCATED_LARGE_FILTER])
+    }
+
+    /// Ask the filter of the commit at `position` whether it may have
+    /// changed `path`
+    ///
+    /// Like `git log -- <path>`, the path and each of its leading
+    /// directories are looked up, and the answer is "maybe" only if all of
+    /// them are.
+    #[must_use]
+    pub fn query(&self, position: usize, path: &str) -> Option<BloomAnswer> {
+        let filter = self.filter(position)?;
+        if filter.is_empty() {
+            return Some(BloomAnswer::NotComputed);
+        }
+
+        let path = normalize_path(path);
+        let mut keys = vec![path];
+        keys.extend(path.match_indices('/').map(|(i, _)| &path[..i]));
+        let maybe = keys
+            .iter()
+            .all(|key| filter_contains(filter, &self.key_hashes(key)));
+        Some(if maybe {
+            BloomAnswer::Maybe
+        } else {
+            BloomAnswer::DefinitelyNot
+        })
+    }
+
+    /// Bit positions a path sets, before reducing them modulo the filter
+    /// size: `num_hashes` values combined from two seeded murmur3 hashes
+    #[must_use]
+    pub fn key_hashes(&self, path: &str) -> Vec<u32> {
+        let sign_extend = self.settings.hash_version == 1;
+        let hash0 = murmur3_seeded(SEED_0, path.as_bytes(), sign_extend);
+        let hash1 = murmur3_seeded(SEED_1, path.as_bytes(), sign_extend);
+        (0..self.settings.num_hashes)
+            .map(|i| hash0.wrapping_add(i.wrapping_mul(hash1)))
+            .collect()
+    }
+
+    /// Build the filter git would store for a set of changed paths
+    #[must_use]
+    pub fn compute_filter(&self, paths: &BTreeSet<String>) -> Vec<u8> {
+        if paths.len() > MAX_CHANGED_PATHS {
+            return vec![TRUNCATED_LARGE_FILTER];
+        }
+
+        let bits = paths.len() * self.settings.bits_per_entry as usize;
+        let mut filter = vec![0u8; bits.div_ceil(8).max(1)];
+        let filter_bits = filter.len() as u64 * 8;
+        for path in paths {
+            for hash in self.key_hashes(path) {
+                let bit = (u64::from(hash) % filter_bits) as usize;
+                filter[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+        filter
+    }
+
+    /// Recompute the filter of every commit whose first parent is in the
+    /// same file from the diff of their root trees, and compare it with
+    /// the stored one
+    ///
+    /// `first_position` is the global position of the file's first commit,
+    /// nonzero for the upper layers of a split chain.
+    #[must_use]
+    pub fn verify(
+        &self,
+        graph: &CommitGraph,
+        first_position: usize,
+        database: &ObjectDatabase,
+    ) -> Vec<BloomCheck> {
+        (0..graph.commit_count())
+            .map(|position| self.verify_commit(graph, first_position, database, position))
+            .collect()
+    }
+
+    fn verify_commit(
+        &self,
+        graph: &CommitGraph,
+        first_position: usize,
+        database: &ObjectDatabase,
+        position: usize,
+    ) -> BloomCheck {
+        let Some(stored) = self.filter(position) else {
+            return BloomCheck::Skipped("no BIDX entry".to_string());
+        };
+        if stored.is_empty() {
+            return BloomCheck::NotComputed;
+        }
+
+        let commit = &graph.commits[position];
+        let parent_tree = match graph.parent_positions(position).unwrap_or_default().first() {
+            None => None,
+            Some(&parent) => match (parent as usize)
+                .checked_sub(first_position)
+                .and_then(|local| graph.commits.get(local))
+            {
+                Some(parent_commit) => Some(parent_commit.root_tree.as_slice()),
+                None => {
+                    return BloomCheck::Skipped("first parent is in a base layer".to_string());
+                }
+            },
+        };
+
+        let paths = match changed_paths(database, parent_tree, &commit.root_tree) {
+            Ok(paths) => paths,
+            Err(e) => return BloomCheck::Skipped(e),
+        };
+
+        // A truncated filter answers "maybe" for everything
+        let false_negatives: Vec<String> = if stored == [TRUNCATED_LARGE_FILTER] {
+            Vec::new()
+        } else {
+            paths
+                .iter()
+                .filter(|path| !filter_contains(stored, &self.key_hashes(path)))
+                .cloned()
+                .collect()
+        };
+        if !false_negatives.is_empty() {
+            return BloomCheck::FalseNegatives(false_negatives);
+        }
+
+        if self.compute_filter(&paths) == stored {
+            BloomCheck::Identical {
+                changed_paths: paths.len(),
+            }
+        } else {
+            BloomCheck::Differs {
+                changed_paths: paths.len(),
+            }
+        }
+    }
+}
+
+/// Strip what git strips from a pathspec before looking it up: leading
+/// `./` and `/`, and trailing slashes
+#[must_use]
+pub fn normalize_path(path: &str) -> &str {
+    let mut path = path.trim();
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest;
+    }
+    path.trim_start_matches('/').trim_end_matches('/')
+}
+
+/// Whether every bit position of a key is set in a non-empty filter
+fn filter_contains(filter: &[u8], hashes: &[u32]) -> bool {
+    let filter_bits = filter.len() as u64 * 8;
+    hashes.iter().all(|&hash| {
+        let bit = (u64::from(hash) % filter_bits) as usize;
+        filter[bit / 8] & (1 << (bit % 8)) != 0
+    })
+}
+
+/// 32-bit murmur3 as git's bloom.c computes it. With `sign_extend`, bytes
+/// are widened as signed chars, reproducing version 1 filters.
+fn murmur3_seeded(seed: u32, data: &[u8], sign_extend: bool) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let widen = |byte: u8| {
+        if sign_extend {
+            i32::from(byte.cast_signed()).cast_unsigned()
+        } else {
+            u32::from(byte)
+        }
+    };
+
+    let mut hash = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k = widen(block[0])
+            | (widen(block[1]) << 8)
+            | (widen(block[2]) << 16)
+            | (widen(block[3]) << 24);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+    }
+
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, &byte) in tail.iter().enumerate().rev() {
+            k ^= widen(byte) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
+
+/// Paths a commit changed relative to its first parent, as git puts them
+/// in its filter: every changed file plus each of its leading directories
+///
+/// # Errors
+///
+/// Returns an error if a tree can't be read from the object database.
+pub fn changed_paths(
+    database: &ObjectDatabase,
+    parent_tree: Option<&[u8]>,
+    tree: &[u8],
+) -> Result<BTreeSet<String>, String> {
+    let mut files = Vec::new();
+    diff_trees(database, parent_tree, Some(tree), "", &mut files)?;
+
+    let mut paths = BTreeSet::new();
+    for file in files {
+        paths.extend(file.match_indices('/').map(|(i, _)| file[..i].to_string()));
+        paths.insert(file);
+    }
+    Ok(paths)
+}
+
+/// Collect the files that differ between two trees, recursing into
+/// subtrees like `git diff-tree -r`
+fn diff_trees(
+    database: &ObjectDatabase,
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+    prefix: &str,
+    files: &mut Vec<String>,
+) -> Result<(), String> {
+    if old == new {
+        return Ok(());
+    }
+    let old_entries = old.map(|oid| read_tree(database, oid)).transpose()?;
+    let new_entries = new.map(|oid| read_tree(database, oid)).transpose()?;
+
+    let mut names: BTreeMap<&str, (Option<&TreeEntry>, Option<&TreeEntry>)> = BTreeMap::new();
+    for entry in old_entries.iter().flatten() {
+        names.entry(&entry.name).or_default().0 = Some(entry);
+    }
+    for entry in new_entries.iter().flatten() {
+        names.entry(&entry.name).or_default().1 = Some(entry);
+    }
+
+    for (name, (old_entry, new_entry)) in names {
+        let path = format!("{prefix}{name}");
+        let subtree = |entry: Option<&TreeEntry>| -> Result<Option<Vec<u8>>, String> {
+            entry
+                .filter(|entry| entry.object_type == TreeEntryType::Tree)
+                .map(|entry| hex::decode(&entry.sha1).map_err(|e| e.to_string()))
+                .transpose()
+        };
+        let (old_tree, new_tree) = (subtree(old_entry)?, subtree(new_entry)?);
+        let old_file = old_entry.filter(|entry| entry.object_type != TreeEntryType::Tree);
+        let new_file = new_entry.filter(|entry| entry.object_type != TreeEntryType::Tree);
+
+        if old_tree.is_some() || new_tree.is_some() {
+            diff_trees(
+                database,
+                old_tree.as_deref(),
+                new_tree.as_deref(),
+                &format!("{path}/"),
+                files,
+            )?;
+        }
+        let file_changed = match (old_file, new_file) {
+            (None, None) => false,
+            (Some(old_file), Some(new_file)) => {
+                old_file.sha1 != new_file.sha1 || old_file.mode != new_file.mode
+            }
+            _ => true,
+        };
+        if file_changed {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn read_tree(database: &ObjectDatabase, oid: &[u8]) -> Result<Vec<TreeEntry>, String> {
+    let object = database.read(oid).map_err(|e| e.to_string())?;
+    match object.parsed_content {
+        Some(ParsedContent::Tree(tree)) => Ok(tree.entries),
+        _ => Err(format!(
+            "{} is a {}, not a tree",
+            object.object_id, object.object_type
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::object_format::ObjectFormat;
+    use crate::git::pack::ObjectType;
+    use crate::git::pack::resolver::object_id;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+    use std::path::Path;
+
+    fn filters(hash_version: u32) -> BloomFilters {
+        BloomFilters {
+            settings: BloomSettings {
+                hash_version,
+                num_hashes: 7,
+                bits_per_entry: 10,
+            },
+            index: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_murmur3() {
+        // Reference values of 32-bit murmur3, also used by git's t0095
+        assert_eq!(murmur3_seeded(0, b"", false), 0);
+        assert_eq!(murmur3_seeded(0, b"Hello world!", false), 0x627b_0c2c);
+        assert_eq!(
+            murmur3_seeded(0, b"The quick brown fox jumps over the lazy dog", false),
+            0x2e4f_f723
+        );
+        // Version 1 only differs for bytes with the high bit set
+        assert_eq!(
+            murmur3_seeded(0, b"Hello world!", true),
+            murmur3_seeded(0, b"Hello world!", false)
+        );
+        assert_ne!(
+            murmur3_seeded(0, "caf\u{e9}".as_bytes(), true),
+            murmur3_seeded(0, "caf\u{e9}".as_bytes(), false)
+        );
+    }
+
+    #[test]
+    fn test_parse_and_query() {
+        let filters = filters(1);
+        let changed: BTreeSet<String> = ["src", "src/main.rs"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let filter = filters.compute_filter(&changed);
+        assert_eq!(filter.len(), 3); // 2 paths * 10 bits, rounded up to bytes
+
+        // Commit 0 has the computed filter, commit 1 an empty one
+        let mut bidx = be_u32_bytes(filter.len() as u32);
+        bidx.extend(be_u32_bytes(filter.len() as u32));
+        let mut bdat = Vec::new();
+        for value in [1u32, 7, 10] {
+            bdat.extend(be_u32_bytes(value));
+        }
+        bdat.extend(&filter);
+
+        let (_, parsed) = BloomFilters::parse(&bidx, &bdat, 2).unwrap();
+        assert_eq!(parsed.settings, filters.settings);
+        assert_eq!(parsed.filter(0), Some(filter.as_slice()));
+        assert_eq!(parsed.query(0, "src/main.rs"), Some(BloomAnswer::Maybe));
+        assert_eq!(parsed.query(0, "./src/"), Some(BloomAnswer::Maybe));
+        assert_eq!(parsed.query(1, "src"), Some(BloomAnswer::NotComputed));
+        assert_eq!(parsed.query(2, "src"), None);
+
+        // Offsets past the data, or too few entries, are rejected
+        assert!(BloomFilters::parse(&be_u32_bytes(99), &bdat, 1).is_err());
+        assert!(BloomFilters::parse(&bidx, &bdat, 3).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_absurd_settings() {
+        let bidx = be_u32_bytes(0);
+        for (num_hashes, bits_per_entry) in [(u32::MAX, 10), (0, 10), (7, u32::MAX), (7, 0)] {
+            let mut bdat = Vec::new();
+            for value in [2, num_hashes, bits_per_entry] {
+                bdat.extend(be_u32_bytes(value));
+            }
+            let Err(nom::Err::Error(error)) = BloomFilters::parse(&bidx, &bdat, 1) else {
+                panic!("accepted {num_hashes} hashes and {bits_per_entry} bits per entry");
+            };
+            assert_eq!(error.code, ErrorKind::Verify);
+        }
+    }
+
+    #[test]
+    fn test_truncated_large_filter() {
+        let filters = filters(2);
+        let changed: BTreeSet<String> = (0..=MAX_CHANGED_PATHS).map(|i| format!("f{i}")).collect();
+        assert_eq!(filters.compute_filter(&changed), vec![0xff]);
+        assert_eq!(filters.compute_filter(&BTreeSet::new()), vec![0]);
+    }
+
+    /// Write a loose object and return its binary ID
+    fn write_object(git_dir: &Path, object_type: ObjectType, content: &[u8]) -> Vec<u8> {
+        let oid = object_id(ObjectFormat::Sha1, object_type, content);
+        let mut raw = format!("{object_type} {}\0", content.len()).into_bytes();
+        raw.extend_from_slice(content);
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw).unwrap();
+
+        let object_dir = git_dir.join("objects").join(&oid[..2]);
+        std::fs::create_dir_all(&object_dir).unwrap();
+        std::fs::write(object_dir.join(&oid[2..]), encoder.finish().unwrap()).unwrap();
+        hex::decode(oid).unwrap()
+    }
+
+    fn write_tree(git_dir: &Path, entries: &[(&str, &str, &[u8])]) -> Vec<u8> {
+        let mut content = Vec::new();
+        for (mode, name, oid) in entries {
+            content.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+            content.extend_from_slice(oid);
+        }
+        write_object(git_dir, ObjectType::Tree, &content)
+    }
+
+    #[test]
+    fn test_changed_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let old_blob = write_object(&git_dir, ObjectType::Blob, b"old\n");
+        let new_blob = write_object(&git_dir, ObjectType::Blob, b"new\n");
+
+        let old_src = write_tree(&git_dir, &[("100644", "main.rs", &old_blob)]);
+        let new_src = write_tree(
+            &git_dir,
+            &[
+                ("100644", "lib.rs", &old_blob),
+                ("100644", "main.rs", &new_blob),
+            ],
+        );
+        let old_root = write_tree(
+            &git_dir,
+            &[("100644", "README", &old_blob), ("40000", "src", &old_src)],
+        );
+        let new_root = write_tree(
+            &git_dir,
+            &[("100644", "README", &old_blob), ("40000", "src", &new_src)],
+        );
+
+        let database = ObjectDatabase::open(&git_dir);
+        let paths = changed_paths(&database, Some(&old_root), &new_root).unwrap();
+        assert_eq!(
+            paths.into_iter().collect::<Vec<_>>(),
+            ["src", "src/lib.rs", "src/main.rs"]
+        );
+
+        // A root commit changes every path of its tree
+        let paths = changed_paths(&database, None, &old_root).unwrap();
+        assert_eq!(
+            paths.into_iter().collect::<Vec<_>>(),
+            ["README", "src", "src/main.rs"]
+        );
+        assert!(changed_paths(&database, None, &[0xab; 20]).is_err());
+    }
+
+    fn be_u32_bytes(value: u32) -> Vec<u8> {
+        value.to_be_bytes().to_vec()
+    }
+}
@@ -0,0 +1,438 @@
+use nom::{
+    IResult,
+    error::{Error, ErrorKind},
+};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::CommitGraph;
+
+#[derive(Debug, Error)]
+pub enum CommitGraphChainError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid commit-graph-chain file: {0}")]
+    InvalidChain(String),
+
+    #[error("Missing commit-graph layer: {0}")]
+    MissingLayer(String),
+
+    #[error("Invalid commit-graph layer {hash}: {reason}")]
+    InvalidLayer { hash: String, reason: String },
+}
+
+/// How a layer's BASE chunk compares with the layers below it in the chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaseCheck {
+    /// The BASE chunk lists exactly the layers below, in chain order
+    Matches,
+    /// The layer lists a different number of base graphs than there are
+    /// layers below it
+    CountMismatch { expected: usize, found: usize },
+    /// The base graph at `index` is not the chain's layer at that position
+    Mismatch {
+        index: usize,
+        expected: String,
+        found: String,
+    },
+}
+
+/// One layer of a split commit-graph: a `graph-<hash>.graph` file
+#[derive(Debug, Clone)]
+pub struct CommitGraphLayer {
+    /// Hex checksum listed in the chain file, which also names the file
+    pub hash: String,
+    pub path: PathBuf,
+    pub graph: CommitGraph,
+    /// Global graph position of this layer's first commit, i.e. the number
+    /// of commits in the layers below
+    pub first_position: usize,
+    pub base_check: BaseCheck,
+}
+
+impl CommitGraphLayer {
+    /// Global graph positions of this layer's commits
+    #[must_use]
+    pub const fn positions(&self) -> Range<usize> {
+        self.first_position..self.first_position + self.graph.commit_count()
+    }
+
+    /// Whether the file's trailing checksum is the hash the chain lists
+    #[must_use]
+    pub fn checksum_matches_name(&self) -> bool {
+        hex::encode(&self.graph.checksum) == self.hash
+    }
+
+    /// Whether the layer fits where the chain puts it
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.base_check == BaseCheck::Matches && self.checksum_matches_name()
+    }
+}
+
+/// A split commit-graph (objects/info/commit-graphs)
+///
+/// `git commit-graph write --split` adds commits in a new layer on top of
+/// the existing ones instead of rewriting one big file, merging layers only
+/// when they grow out of proportion. The `commit-graph-chain` file lists
+/// the layers' checksums, base layer first; each layer names the layers
+/// below it in its BASE chunk. Graph positions are global: the commits of
+/// a layer are numbered after all the commits of the layers below, so a
+/// parent position can point into any lower layer.
+#[derive(Debug, Clone)]
+pub struct CommitGraphChain {
+    /// Path of the `commit-graph-chain` file
+    pub path: PathBuf,
+    /// Layers in chain order, base layer first
+    pub layers: Vec<CommitGraphLayer>,
+}
+
+impl CommitGraphChain {
+    /// Directory of a split commit-graph, under objects/info
+    pub const DIR_NAME: &str = "commit-graphs";
+    /// File listing the layers of the chain
+    pub const CHAIN_FILE_NAME: &str = "commit-graph-chain";
+
+    /// Parse a chain file: one hex layer checksum per line, base layer first
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if a line is not a hex object ID.
+    pub fn parse_chain(input: &[u8]) -> IResult<&[u8], Vec<String>> {
+        let mut hashes = Vec::new();
+        for raw_line in input.split(|&b| b == b'\n') {
+            if raw_line.is_empty() {
+                continue;
+            }
+            let hash = std::str::from_utf8(raw_line)
+                .ok()
+                .filter(|line| crate::git::refs::is_object_id(line))
+                .ok_or_else(|| nom::Err::Error(Error::new(raw_line, ErrorKind::HexDigit)))?;
+            hashes.push(hash.to_ascii_lowercase());
+        }
+        Ok((&input[input.len()..], hashes))
+    }
+
+    /// Path of the layer with the given checksum in a commit-graphs directory
+    #[must_use]
+    pub fn layer_path(dir: &Path, hash: &str) -> PathBuf {
+        dir.join(format!("graph-{hash}.graph"))
+    }
+
+    /// Whether a file name has the shape of a layer file, `graph-<hash>.graph`
+    #[must_use]
+    pub fn is_layer_file_name(name: &str) -> bool {
+        name.strip_prefix("graph-")
+            .and_then(|rest| rest.strip_suffix(".graph"))
+            .is_some_and(crate::git::refs::is_object_id)
+    }
+
+    /// Chain file next to a layer file, if the layer sits in a
+    /// commit-graphs directory that has one
+    #[must_use]
+    pub fn chain_path_for_layer(layer_path: &Path) -> Option<PathBuf> {
+        let dir = layer_path.parent()?;
+        if dir.file_name()? != Self::DIR_NAME {
+            return None;
+        }
+        let chain_path = dir.join(Self::CHAIN_FILE_NAME);
+        chain_path.is_file().then_some(chain_path)
+    }
+
+    /// Global position of the first commit of the layer stored at
+    /// `layer_path`, or None when the layer is not part of a loadable chain
+    #[must_use]
+    pub fn layer_first_position(layer_path: &Path) -> Option<usize> {
+        let chain = Self::load(&Self::chain_path_for_layer(layer_path)?).ok()?;
+        let index = chain.layer_index_of(layer_path)?;
+        Some(chain.layers[index].first_position)
+    }
+
+    /// Read a chain file and every layer it lists
+    ///
+    /// Base lists that disagree with the chain are recorded in each layer's
+    /// [`BaseCheck`] rather than rejected, so they can be shown.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The chain file or a layer file cannot be read
+    /// - The chain file is malformed
+    /// - A listed layer is missing
+    /// - A layer is not a valid commit-graph, or has a parent position past
+    ///   the commits of its own and lower layers
+    pub fn load(chain_path: &Path) -> Result<Self, CommitGraphChainError> {
+        let data = std::fs::read(chain_path)?;
+        let (_, hashes) = Self::parse_chain(&data)
+            .map_err(|e| CommitGraphChainError::InvalidChain(format!("{e:?}")))?;
+        let dir = chain_path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut layers: Vec<CommitGraphLayer> = Vec::with_capacity(hashes.len());
+        let mut first_position = 0;
+        for (index, hash) in hashes.iter().enumerate() {
+            let path = Self::layer_path(dir, hash);
+            if !path.is_file() {
+                return Err(CommitGraphChainError::MissingLayer(hash.clone()));
+            }
+            let layer_data = std::fs::read(&path)?;
+            let (_, graph) = CommitGraph::parse(&layer_data).map_err(|e| {
+                CommitGraphChainError::InvalidLayer {
+                    hash: hash.clone(),
+                    reason: format!("{e:?}"),
+                }
+            })?;
+
+            let positions_end = first_position + graph.commit_count();
+            let parents_in_range = (0..graph.commit_count()).all(|position| {
+                graph.parent_positions(position).is_some_and(|parents| {
+                    parents
+                        .iter()
+                        .all(|&parent| (parent as usize) < positions_end)
+                })
+            });
+            if !parents_in_range {
+                return Err(CommitGraphChainError::InvalidLayer {
+                    hash: hash.clone(),
+                    reason: "parent position beyond this layer".to_string(),
+                });
+            }
+
+            let base_check = Self::check_bases(&graph, &hashes[..index]);
+            layers.push(CommitGraphLayer {
+                hash: hash.clone(),
+                path,
+                graph,
+                first_position,
+                base_check,
+            });
+            first_position = positions_end;
+        }
+
+        Ok(Self {
+            path: chain_path.to_path_buf(),
+            layers,
+        })
+    }
+
+    /// Compare a layer's BASE chunk with the hashes of the layers below it
+    fn check_bases(graph: &CommitGraph, expected: &[String]) -> BaseCheck {
+        if graph.base_graphs.len() != expected.len() {
+            return BaseCheck::CountMismatch {
+                expected: expected.len(),
+                found: graph.base_graphs.len(),
+            };
+        }
+        graph
+            .base_graphs
+            .iter()
+            .map(hex::encode)
+            .zip(expected)
+            .enumerate()
+            .find(|(_, (found, expected))| found != *expected)
+            .map_or(BaseCheck::Matches, |(index, (found, expected))| {
+                BaseCheck::Mismatch {
+                    index,
+                    expected: expected.clone(),
+                    found,
+                }
+            })
+    }
+
+    /// Total number of commits across all layers
+    #[must_use]
+    pub fn commit_count(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.positions().end)
+    }
+
+    /// Whether every layer's base list and checksum match the chain
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.layers.iter().all(CommitGraphLayer::is_consistent)
+    }
+
+    /// Index of the layer stored at `path`
+    #[must_use]
+    pub fn layer_index_of(&self, path: &Path) -> Option<usize> {
+        let name = path.file_name()?;
+        self.layers
+            .iter()
+            .position(|layer| layer.path.file_name() == Some(name))
+    }
+
+    /// Layer index and position within that layer of a global graph position
+    #[must_use]
+    pub fn locate(&self, global_position: usize) -> Option<(usize, usize)> {
+        self.layers
+            .iter()
+            .position(|layer| layer.positions().contains(&global_position))
+            .map(|index| (index, global_position - self.layers[index].first_position))
+    }
+
+    /// Hex commit ID at a global graph position
+    #[must_use]
+    pub fn oid_hex_at(&self, global_position: usize) -> Option<String> {
+        let (layer, local) = self.locate(global_position)?;
+        self.layers[layer].graph.oid_hex_at(local)
+    }
+
+    /// Layer index and global graph position of a commit by its binary
+    /// object ID, searching from the top layer down like git does
+    #[must_use]
+    pub fn position_of(&self, oid: &[u8]) -> Option<(usize, usize)> {
+        self.layers
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, layer)| {
+                layer
+                    .graph
+                    .position_of(oid)
+                    .map(|local| (index, layer.first_position + local))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{assemble_graph, cdat_entry};
+    use super::*;
+
+    const HASH_LEN: usize = 20;
+
+    /// A layer whose commits have the given first OID bytes and parents,
+    /// with a checksum made of `checksum_byte`
+    fn layer_data(
+        first_bytes: &[u8],
+        parents: &[(u32, u32)],
+        bases: &[[u8; HASH_LEN]],
+        checksum_byte: u8,
+    ) -> Vec<u8> {
+        let mut oidl = Vec::new();
+        let mut oidf = Vec::new();
+        for &first_byte in first_bytes {
+            let mut oid = [0x22u8; HASH_LEN];
+            oid[0] = first_byte;
+            oidl.extend_from_slice(&oid);
+        }
+        for byte in 0..=255u8 {
+            let cumulative = first_bytes.iter().filter(|&&b| b <= byte).count() as u32;
+            oidf.extend_from_slice(&cumulative.to_be_bytes());
+        }
+        let cdat = parents
+            .iter()
+            .flat_map(|&parents| cdat_entry(HASH_LEN, parents, 1, 1))
+            .collect();
+
+        let mut chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+        ];
+        if !bases.is_empty() {
+            chunks.push((CommitGraph::CHUNK_BASE, bases.concat()));
+        }
+        let mut data = assemble_graph(1, bases.len() as u8, &chunks, HASH_LEN);
+        let checksum_start = data.len() - HASH_LEN;
+        data[checksum_start..].fill(checksum_byte);
+        data
+    }
+
+    /// Write a two-layer chain: two commits in the base layer, one on top
+    /// whose parent is the base layer's second commit
+    fn write_chain(dir: &Path, top_bases: &[[u8; HASH_LEN]]) -> PathBuf {
+        let none = CommitGraph::PARENT_NONE;
+        let base_hash = hex::encode([0xa0; HASH_LEN]);
+        let top_hash = hex::encode([0xa1; HASH_LEN]);
+        std::fs::write(
+            CommitGraphChain::layer_path(dir, &base_hash),
+            layer_data(&[0x10, 0x30], &[(none, none), (0, none)], &[], 0xa0),
+        )
+        .unwrap();
+        std::fs::write(
+            CommitGraphChain::layer_path(dir, &top_hash),
+            layer_data(&[0x20], &[(1, none)], top_bases, 0xa1),
+        )
+        .unwrap();
+
+        let chain_path = dir.join(CommitGraphChain::CHAIN_FILE_NAME);
+        std::fs::write(&chain_path, format!("{base_hash}\n{top_hash}\n")).unwrap();
+        chain_path
+    }
+
+    #[test]
+    fn test_load_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xa0; HASH_LEN]]);
+        let chain = CommitGraphChain::load(&chain_path).unwrap();
+
+        assert_eq!(chain.layers.len(), 2);
+        assert_eq!(chain.commit_count(), 3);
+        assert!(chain.is_consistent());
+        assert_eq!(chain.layers[1].positions(), 2..3);
+
+        // The top layer's commit is global position 2; its parent, global
+        // position 1, is the second commit of the base layer
+        let mut oid = [0x22u8; HASH_LEN];
+        oid[0] = 0x20;
+        assert_eq!(chain.position_of(&oid), Some((1, 2)));
+        assert_eq!(chain.locate(1), Some((0, 1)));
+        assert_eq!(chain.layers[1].graph.parent_positions(0), Some(vec![1]));
+        assert!(chain.oid_hex_at(1).unwrap().starts_with("30"));
+        assert_eq!(chain.locate(3), None);
+
+        let top_path = chain.layers[1].path.clone();
+        assert_eq!(chain.layer_index_of(&top_path), Some(1));
+        assert!(CommitGraphChain::is_layer_file_name(
+            &top_path.file_name().unwrap().to_string_lossy()
+        ));
+    }
+
+    #[test]
+    fn test_base_list_checked_against_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xee; HASH_LEN]]);
+        let chain = CommitGraphChain::load(&chain_path).unwrap();
+        assert!(!chain.is_consistent());
+        assert_eq!(
+            chain.layers[1].base_check,
+            BaseCheck::Mismatch {
+                index: 0,
+                expected: hex::encode([0xa0; HASH_LEN]),
+                found: hex::encode([0xee; HASH_LEN]),
+            }
+        );
+
+        let chain_path = write_chain(temp_dir.path(), &[[0xa0; HASH_LEN], [0xa0; HASH_LEN]]);
+        let chain = CommitGraphChain::load(&chain_path).unwrap();
+        assert_eq!(
+            chain.layers[1].base_check,
+            BaseCheck::CountMismatch {
+                expected: 1,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_layer_and_bad_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xa0; HASH_LEN]]);
+        std::fs::remove_file(CommitGraphChain::layer_path(
+            temp_dir.path(),
+            &hex::encode([0xa0; HASH_LEN]),
+        ))
+        .unwrap();
+        assert!(matches!(
+            CommitGraphChain::load(&chain_path),
+            Err(CommitGraphChainError::MissingLayer(_))
+        ));
+
+        std::fs::write(&chain_path, "not-a-hash\n").unwrap();
+        assert!(matches!(
+            CommitGraphChain::load(&chain_path),
+            Err(CommitGraphChainError::InvalidChain(_))
+        ));
+    }
+}
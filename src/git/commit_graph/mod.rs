@@ -0,0 +1,1020 @@
+use nom::{
+    IResult, Parser,
+    bytes::complete::take,
+    error::{Error, ErrorKind},
+    multi::count,
+    number::complete::{be_u8, be_u32, be_u64},
+};
+use std::fmt;
+
+pub mod bloom;
+pub mod chain;
+
+pub use bloom::{BloomAnswer, BloomCheck, BloomFilters, BloomSettings};
+pub use chain::{BaseCheck, CommitGraphChain, CommitGraphChainError, CommitGraphLayer};
+
+/// Size of the fixed commit-graph header in bytes
+const HEADER_SIZE: usize = 8;
+/// Size of a single chunk lookup table entry (4-byte ID + 8-byte offset)
+const CHUNK_LOOKUP_ENTRY_SIZE: usize = 12;
+/// Bytes of a CDAT entry after the root tree ID: two parent positions,
+/// then the topological level and commit time packed into 8 bytes
+const COMMIT_DATA_FIXED_SIZE: usize = 16;
+
+/// A single entry of the commit-graph chunk lookup table
+///
+/// Same layout as in the multi-pack-index: a four-character chunk ID and
+/// an absolute file offset, with sizes derived from consecutive offsets.
+#[derive(Debug, Clone)]
+pub struct ChunkEntry {
+    /// Four-character chunk identifier (e.g. 0x43444154 = "CDAT")
+    pub id: u32,
+    /// Absolute offset of the chunk from the start of the file
+    pub offset: u64,
+    /// Chunk size in bytes (next entry's offset minus this entry's offset)
+    pub size: u64,
+}
+
+impl ChunkEntry {
+    /// Get the chunk ID as a four-character string (e.g. "CDAT")
+    ///
+    /// Non-printable bytes are rendered as '?' so unknown IDs stay displayable.
+    #[must_use]
+    pub fn id_str(&self) -> String {
+        self.id
+            .to_be_bytes()
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '?' })
+            .collect()
+    }
+
+    /// Whether this chunk ID is defined by the commit-graph format
+    #[must_use]
+    pub const fn is_known(&self) -> bool {
+        matches!(
+            self.id,
+            CommitGraph::CHUNK_OIDF
+                | CommitGraph::CHUNK_OIDL
+                | CommitGraph::CHUNK_CDAT
+                | CommitGraph::CHUNK_GDA2
+                | CommitGraph::CHUNK_GDO2
+                | CommitGraph::CHUNK_EDGE
+                | CommitGraph::CHUNK_BIDX
+                | CommitGraph::CHUNK_BDAT
+                | CommitGraph::CHUNK_BASE
+        )
+    }
+
+    /// Short human-readable description of the chunk's purpose
+    #[must_use]
+    pub const fn description(&self) -> &'static str {
+        match self.id {
+            CommitGraph::CHUNK_OIDF => "OID fanout table (required)",
+            CommitGraph::CHUNK_OIDL => "OID lookup table (required)",
+            CommitGraph::CHUNK_CDAT => "Commit data (required)",
+            CommitGraph::CHUNK_GDA2 => "Generation data (optional)",
+            CommitGraph::CHUNK_GDO2 => "Generation data overflow (optional)",
+            CommitGraph::CHUNK_EDGE => "Extra edges for octopus merges (optional)",
+            CommitGraph::CHUNK_BIDX => "Bloom filter index (optional)",
+            CommitGraph::CHUNK_BDAT => "Bloom filter data (optional)",
+            CommitGraph::CHUNK_BASE => "Base graphs (split commit-graph)",
+            _ => "Unknown chunk",
+        }
+    }
+}
+
+/// One commit's entry of the CDAT chunk
+#[derive(Debug, Clone)]
+pub struct CommitData {
+    /// Object ID of the commit's root tree
+    pub root_tree: Vec<u8>,
+    /// Graph position of the first parent, or [`CommitGraph::PARENT_NONE`]
+    pub parent1: u32,
+    /// Graph position of the second parent, [`CommitGraph::PARENT_NONE`],
+    /// or, with [`CommitGraph::EXTRA_EDGES_NEEDED`] set, the index in the
+    /// EDGE chunk where the list of the second and later parents starts
+    pub parent2: u32,
+    /// Topological level (generation number v1): 1 for a root commit,
+    /// otherwise one more than the highest level among the parents
+    pub topological_level: u32,
+    /// Committer time in seconds since the epoch (34 bits)
+    pub commit_time: u64,
+}
+
+impl CommitData {
+    /// Whether the commit has more than two parents, listed in EDGE
+    #[must_use]
+    pub const fn is_octopus(&self) -> bool {
+        self.parent2 != CommitGraph::PARENT_NONE
+            && self.parent2 & CommitGraph::EXTRA_EDGES_NEEDED != 0
+    }
+}
+
+/// Represents a Git commit-graph file (objects/info/commit-graph)
+///
+/// The commit-graph caches what history walks need from every commit: its
+/// root tree, its parents as positions in this file, its commit time and a
+/// generation number. With it, `git log --graph`, `merge-base` and
+/// reachability checks avoid inflating commit objects one by one. Like the
+/// multi-pack-index, the file is chunk-based: a lookup table of
+/// four-character chunk IDs and absolute offsets describes where each data
+/// table lives.
+#[derive(Debug, Clone)]
+pub struct CommitGraph {
+    /// File format version (always 1)
+    pub version: u8,
+    /// Hash function identifier (1 for SHA-1, 2 for SHA-256)
+    pub hash_version: u8,
+    /// Number of chunks in the chunk lookup table (excluding the terminator)
+    pub chunk_count: u8,
+    /// Number of commit-graph files below this one in a split chain (zero
+    /// for a standalone `commit-graph`)
+    pub base_graph_count: u8,
+    /// Chunk lookup table entries (without the terminating entry), with sizes
+    /// computed from consecutive offsets
+    pub chunks: Vec<ChunkEntry>,
+    /// Fan-out table from the OIDF chunk: entry i counts commits whose first
+    /// OID byte is <= i; entry 255 is the total commit count
+    pub fan_out: [u32; 256],
+    /// Commit IDs from the OIDL chunk in lexicographic order (hash-length
+    /// bytes each); a commit's index here is its graph position
+    pub commit_ids: Vec<Vec<u8>>,
+    /// Per-commit data from the CDAT chunk, in OIDL order
+    pub commits: Vec<CommitData>,
+    /// Parent positions from the EDGE chunk; the last parent of each
+    /// commit's list has [`CommitGraph::LAST_EDGE`] set
+    pub extra_edges: Vec<u32>,
+    /// Corrected commit date offsets from the GDA2 chunk, in OIDL order;
+    /// with [`CommitGraph::GENERATION_OVERFLOW`] set, the low 31 bits index
+    /// the GDO2 chunk instead
+    pub generation_data: Option<Vec<u32>>,
+    /// Offsets too large for GDA2, from the GDO2 chunk
+    pub generation_overflow: Option<Vec<u64>>,
+    /// Checksums of the base graphs from the BASE chunk, bottom layer first
+    pub base_graphs: Vec<Vec<u8>>,
+    /// Changed-path Bloom filters from the BIDX and BDAT chunks, present
+    /// when the file was written with `--changed-paths`
+    pub bloom_filters: Option<BloomFilters>,
+    /// SHA-1/SHA-256 checksum of all the above content
+    pub checksum: Vec<u8>,
+    /// Raw data for debugging/display purposes
+    pub raw_data: Vec<u8>,
+}
+
+impl CommitGraph {
+    /// Magic signature for commit-graph files: "CGPH"
+    pub const SIGNATURE: u32 = 0x4347_5048;
+    /// The only version git writes
+    pub const VERSION: u8 = 1;
+
+    /// Chunk ID "OIDF": 256-entry commit ID fanout table
+    pub const CHUNK_OIDF: u32 = 0x4f49_4446;
+    /// Chunk ID "OIDL": commit ID lookup table
+    pub const CHUNK_OIDL: u32 = 0x4f49_444c;
+    /// Chunk ID "CDAT": root tree, parents, topological level and commit time
+    pub const CHUNK_CDAT: u32 = 0x4344_4154;
+    /// Chunk ID "GDA2": corrected commit date offsets (generation number v2)
+    pub const CHUNK_GDA2: u32 = 0x4744_4132;
+    /// Chunk ID "GDO2": 8-byte generation data overflow
+    pub const CHUNK_GDO2: u32 = 0x4744_4f32;
+    /// Chunk ID "EDGE": parent lists of octopus merges
+    pub const CHUNK_EDGE: u32 = 0x4544_4745;
+    /// Chunk ID "BIDX": changed-path Bloom filter index
+    pub const CHUNK_BIDX: u32 = 0x4249_4458;
+    /// Chunk ID "BDAT": changed-path Bloom filter data
+    pub const CHUNK_BDAT: u32 = 0x4244_4154;
+    /// Chunk ID "BASE": checksums of the base graphs of a split chain
+    pub const CHUNK_BASE: u32 = 0x4241_5345;
+
+    /// Parent position meaning "no parent"
+    pub const PARENT_NONE: u32 = 0x7000_0000;
+    /// Flag on the second parent: the parents continue in the EDGE chunk
+    pub const EXTRA_EDGES_NEEDED: u32 = 0x8000_0000;
+    /// Flag on an EDGE entry: last parent of the commit
+    pub const LAST_EDGE: u32 = 0x8000_0000;
+    /// Flag on a GDA2 entry: the value lives in the GDO2 chunk
+    pub const GENERATION_OVERFLOW: u32 = 0x8000_0000;
+
+    /// Git directory of a commit-graph file or split layer, which live
+    /// under `objects/info`
+    #[must_use]
+    pub fn git_dir_of(path: &std::path::Path) -> Option<std::path::PathBuf> {
+        path.ancestors()
+            .find(|dir| dir.file_name().is_some_and(|name| name == "objects"))
+            .and_then(std::path::Path::parent)
+            .map(std::path::Path::to_path_buf)
+    }
+
+    /// Parse a commit-graph file from raw bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if the input is not a valid commit-graph:
+    /// wrong "CGPH" signature, unsupported version or hash version, a
+    /// malformed chunk lookup table, a missing required chunk, or chunk
+    /// contents inconsistent with each other (parents, EDGE/GDO2 indexes or
+    /// Bloom filter offsets out of range).
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let original_input = input;
+
+        let (input, (version, hash_version, chunk_count, base_graph_count)) =
+            Self::parse_header(input)?;
+
+        // Chunk lookup table: (chunk_count + 1) entries, the last is a
+        // terminator with ID 0 whose offset marks the end of chunk data
+        let (_, toc) = count(Self::parse_toc_entry, chunk_count as usize + 1).parse(input)?;
+
+        let Some(&(terminator_id, trailer_offset)) = toc.last() else {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::LengthValue)));
+        };
+        if terminator_id != 0 {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+
+        let expected_first_offset =
+            (HEADER_SIZE + (chunk_count as usize + 1) * CHUNK_LOOKUP_ENTRY_SIZE) as u64;
+        if let Some(&(_, first_offset)) = toc.first()
+            && first_offset != expected_first_offset
+        {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+        }
+        if toc.windows(2).any(|w| w[0].1 > w[1].1) {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+        }
+        if trailer_offset > original_input.len() as u64 {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::LengthValue)));
+        }
+
+        let chunks: Vec<ChunkEntry> = toc
+            .windows(2)
+            .map(|w| ChunkEntry {
+                id: w[0].0,
+                offset: w[0].1,
+                size: w[1].1 - w[0].1,
+            })
+            .collect();
+
+        // Slice out each known chunk from the original buffer (offsets are
+        // absolute, so sequential parsing doesn't apply past this point)
+        let chunk_slice = |id: u32| -> Option<&[u8]> {
+            chunks.iter().find(|c| c.id == id).map(|c| {
+                let start = c.offset as usize;
+                let end = (c.offset + c.size) as usize;
+                &original_input[start..end]
+            })
+        };
+        let required_chunk = |id: u32| -> Result<&[u8], nom::Err<Error<&[u8]>>> {
+            chunk_slice(id).ok_or(nom::Err::Error(Error::new(original_input, ErrorKind::Tag)))
+        };
+
+        let hash_len = match hash_version {
+            1 => 20, // SHA-1
+            2 => 32, // SHA-256
+            _ => unreachable!("validated in parse_header"),
+        };
+
+        let fan_out = Self::parse_oidf(required_chunk(Self::CHUNK_OIDF)?)?;
+        let total_commits = fan_out[255] as usize;
+        let commit_ids =
+            Self::parse_oidl(required_chunk(Self::CHUNK_OIDL)?, total_commits, hash_len)?;
+        let commits = Self::parse_cdat(required_chunk(Self::CHUNK_CDAT)?, total_commits, hash_len)?;
+        let extra_edges = chunk_slice(Self::CHUNK_EDGE)
+            .map(Self::parse_edge)
+            .transpose()?
+            .unwrap_or_default();
+        let generation_data = chunk_slice(Self::CHUNK_GDA2)
+            .map(|data| Self::parse_gda2(data, total_commits))
+            .transpose()?;
+        let generation_overflow = chunk_slice(Self::CHUNK_GDO2)
+            .map(Self::parse_gdo2)
+            .transpose()?;
+        // Like git, ignore Bloom data unless both chunks are present
+        let bloom_filters = match (chunk_slice(Self::CHUNK_BIDX), chunk_slice(Self::CHUNK_BDAT)) {
+            (Some(bidx), Some(bdat)) => Some(BloomFilters::parse(bidx, bdat, total_commits)?.1),
+            _ => None,
+        };
+        let base_graphs = if base_graph_count == 0 {
+            Vec::new()
+        } else {
+            Self::parse_base(
+                required_chunk(Self::CHUNK_BASE)?,
+                base_graph_count as usize,
+                hash_len,
+            )?
+        };
+
+        let graph = Self {
+            version,
+            hash_version,
+            chunk_count,
+            base_graph_count,
+            chunks,
+            fan_out,
+            commit_ids,
+            commits,
+            extra_edges,
+            generation_data,
+            generation_overflow,
+            base_graphs,
+            bloom_filters,
+            checksum: Vec::new(),
+            raw_data: Vec::new(),
+        };
+        if !graph.references_are_valid() {
+            return Err(nom::Err::Error(Error::new(
+                original_input,
+                ErrorKind::Verify,
+            )));
+        }
+
+        // Trailer checksum sits at the terminator offset
+        let trailer_start = trailer_offset as usize;
+        let (remaining, checksum_bytes) = take(hash_len)(&original_input[trailer_start..])?;
+
+        let consumed = original_input.len() - remaining.len();
+        Ok((
+            remaining,
+            Self {
+                checksum: checksum_bytes.to_vec(),
+                raw_data: original_input[..consumed].to_vec(),
+                ..graph
+            },
+        ))
+    }
+
+    /// Parse the 8-byte header (signature, version, hash version, chunk
+    /// count, base graph count)
+    fn parse_header(input: &[u8]) -> IResult<&[u8], (u8, u8, u8, u8)> {
+        // Magic signature: "CGPH" (0x43475048)
+        let (input, signature) = be_u32(input)?;
+        if signature != Self::SIGNATURE {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+
+        let (input, version) = be_u8(input)?;
+        if version != Self::VERSION {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+
+        // Hash version
+        let (input, hash_version) = be_u8(input)?;
+        if hash_version != 1 && hash_version != 2 {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+
+        let (input, chunk_count) = be_u8(input)?;
+        let (input, base_graph_count) = be_u8(input)?;
+
+        Ok((
+            input,
+            (version, hash_version, chunk_count, base_graph_count),
+        ))
+    }
+
+    /// Parse one chunk lookup table entry (4-byte ID + 8-byte offset)
+    fn parse_toc_entry(input: &[u8]) -> IResult<&[u8], (u32, u64)> {
+        let (input, id) = be_u32(input)?;
+        let (input, offset) = be_u64(input)?;
+        Ok((input, (id, offset)))
+    }
+
+    /// Parse the OIDF chunk: 256-entry monotonic fan-out table
+    fn parse_oidf(data: &[u8]) -> Result<[u32; 256], nom::Err<Error<&[u8]>>> {
+        let (_, fan_out_vec) = count(be_u32, 256).parse(data)?;
+
+        let mut fan_out = [0u32; 256];
+        for (i, &value) in fan_out_vec.iter().enumerate() {
+            fan_out[i] = value;
+        }
+
+        for i in 1..256 {
+            if fan_out[i] < fan_out[i - 1] {
+                return Err(nom::Err::Error(Error::new(data, ErrorKind::Verify)));
+            }
+        }
+
+        Ok(fan_out)
+    }
+
+    /// Parse the OIDL chunk: commit IDs in lexicographic order
+    fn parse_oidl(
+        data: &[u8],
+        total_commits: usize,
+        hash_len: usize,
+    ) -> Result<Vec<Vec<u8>>, nom::Err<Error<&[u8]>>> {
+        if data.len() != total_commits * hash_len {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        Ok(data.chunks_exact(hash_len).map(<[u8]>::to_vec).collect())
+    }
+
+    /// Parse the CDAT chunk: root tree, two parent positions and the packed
+    /// topological level / commit time of each commit
+    fn parse_cdat(
+        data: &[u8],
+        total_commits: usize,
+        hash_len: usize,
+    ) -> Result<Vec<CommitData>, nom::Err<Error<&[u8]>>> {
+        if data.len() != total_commits * (hash_len + COMMIT_DATA_FIXED_SIZE) {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        let (_, commits) = count(
+            |input| {
+                let (input, root_tree) = take::<_, _, Error<&[u8]>>(hash_len)(input)?;
+                let (input, parent1) = be_u32(input)?;
+                let (input, parent2) = be_u32(input)?;
+                // Upper 30 bits: topological level; lower 2 bits: the top of
+                // the 34-bit commit time, whose low 32 bits follow
+                let (input, level_and_time_high) = be_u32(input)?;
+                let (input, time_low) = be_u32(input)?;
+                Ok((
+                    input,
+                    CommitData {
+                        root_tree: root_tree.to_vec(),
+                        parent1,
+                        parent2,
+                        topological_level: level_and_time_high >> 2,
+                        commit_time: (u64::from(level_and_time_high & 0x3) << 32)
+                            | u64::from(time_low),
+                    },
+                ))
+            },
+            total_commits,
+        )
+        .parse(data)?;
+
+        Ok(commits)
+    }
+
+    /// Parse the EDGE chunk: 4-byte parent positions
+    fn parse_edge(data: &[u8]) -> Result<Vec<u32>, nom::Err<Error<&[u8]>>> {
+        if !data.len().is_multiple_of(4) {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        let (_, edges) = count(be_u32, data.len() / 4).parse(data)?;
+        Ok(edges)
+    }
+
+    /// Parse the GDA2 chunk: one 4-byte corrected commit date offset per commit
+    fn parse_gda2(data: &[u8], total_commits: usize) -> Result<Vec<u32>, nom::Err<Error<&[u8]>>> {
+        if data.len() != total_commits * 4 {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        let (_, offsets) = count(be_u32, total_commits).parse(data)?;
+        Ok(offsets)
+    }
+
+    /// Parse the GDO2 chunk: 8-byte overflowing generation offsets
+    fn parse_gdo2(data: &[u8]) -> Result<Vec<u64>, nom::Err<Error<&[u8]>>> {
+        if !data.len().is_multiple_of(8) {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        let (_, offsets) = count(be_u64, data.len() / 8).parse(data)?;
+        Ok(offsets)
+    }
+
+    /// Parse the BASE chunk: the checksum of each base graph, bottom first
+    fn parse_base(
+        data: &[u8],
+        base_graph_count: usize,
+        hash_len: usize,
+    ) -> Result<Vec<Vec<u8>>, nom::Err<Error<&[u8]>>> {
+        if data.len() != base_graph_count * hash_len {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        Ok(data.chunks_exact(hash_len).map(<[u8]>::to_vec).collect())
+    }
+
+    /// Check that every parent list ends inside EDGE and every GDA2
+    /// overflow lands inside GDO2. Parent positions are only checked against
+    /// this file's commit count when it has no base graphs: in a split
+    /// chain, positions count the commits of the base layers first.
+    fn references_are_valid(&self) -> bool {
+        let commit_count = self.commit_count();
+        let total_positions = if self.base_graph_count == 0 {
+            Some(commit_count)
+        } else {
+            None
+        };
+        let position_is_valid =
+            |position: u32| total_positions.is_none_or(|total| (position as usize) < total);
+
+        for (index, commit) in self.commits.iter().enumerate() {
+            if commit.parent1 != Self::PARENT_NONE && !position_is_valid(commit.parent1) {
+                return false;
+            }
+            let Some(parents) = self.parent_positions(index) else {
+                return false;
+            };
+            if !parents.iter().all(|&position| position_is_valid(position)) {
+                return false;
+            }
+        }
+
+        let overflow_count = self.generation_overflow.as_ref().map_or(0, Vec::len);
+        self.generation_data.iter().flatten().all(|&value| {
+            value & Self::GENERATION_OVERFLOW == 0
+                || ((value & !Self::GENERATION_OVERFLOW) as usize) < overflow_count
+        })
+    }
+
+    /// Get the total number of commits in this file
+    #[must_use]
+    pub const fn commit_count(&self) -> usize {
+        self.commit_ids.len()
+    }
+
+    /// Get the hash function name as a string
+    #[must_use]
+    pub const fn hash_function_name(&self) -> &'static str {
+        match self.hash_version {
+            1 => "SHA-1",
+            2 => "SHA-256",
+            _ => "Unknown",
+        }
+    }
+
+    /// Get the checksum size based on hash function
+    #[must_use]
+    pub const fn checksum_size(&self) -> usize {
+        match self.hash_version {
+            1 => 20, // SHA-1
+            2 => 32, // SHA-256
+            _ => 0,
+        }
+    }
+
+    /// Get the hex representation of the commit ID at the given graph position
+    #[must_use]
+    pub fn oid_hex_at(&self, position: usize) -> Option<String> {
+        self.commit_ids.get(position).map(hex::encode)
+    }
+
+    /// Find the graph position of a commit by its binary object ID, using
+    /// the fan-out table to narrow the binary search
+    #[must_use]
+    pub fn position_of(&self, oid: &[u8]) -> Option<usize> {
+        let first_byte = *oid.first()? as usize;
+        let start = if first_byte == 0 {
+            0
+        } else {
+            self.fan_out[first_byte - 1] as usize
+        };
+        let end = self.fan_out[first_byte] as usize;
+        self.commit_ids
+            .get(start..end)?
+            .binary_search_by(|candidate| candidate.as_slice().cmp(oid))
+            .ok()
+            .map(|i| start + i)
+    }
+
+    /// Graph positions of all parents of the commit at `position`, following
+    /// the EDGE chunk for octopus merges
+    ///
+    /// Returns None if the commit doesn't exist or its EDGE list runs past
+    /// the end of the chunk without a terminating entry.
+    #[must_use]
+    pub fn parent_positions(&self, position: usize) -> Option<Vec<u32>> {
+        let commit = self.commits.get(position)?;
+        let mut parents = Vec::new();
+        if commit.parent1 != Self::PARENT_NONE {
+            parents.push(commit.parent1);
+        }
+        if commit.parent2 == Self::PARENT_NONE {
+            return Some(parents);
+        }
+        if !commit.is_octopus() {
+            parents.push(commit.parent2);
+            return Some(parents);
+        }
+
+        let start = (commit.parent2 & !Self::EXTRA_EDGES_NEEDED) as usize;
+        for &edge in self.extra_edges.get(start..)? {
+            parents.push(edge & !Self::LAST_EDGE);
+            if edge & Self::LAST_EDGE != 0 {
+                return Some(parents);
+            }
+        }
+        None
+    }
+
+    /// Corrected commit date (generation number v2) of the commit at
+    /// `position`: its commit time plus the GDA2 offset, resolving GDO2
+    /// overflows. None when the file has no GDA2 chunk.
+    #[must_use]
+    pub fn corrected_commit_date(&self, position: usize) -> Option<u64> {
+        let commit = self.commits.get(position)?;
+        let value = *self.generation_data.as_ref()?.get(position)?;
+        let offset = if value & Self::GENERATION_OVERFLOW == 0 {
+            u64::from(value)
+        } else {
+            let index = (value & !Self::GENERATION_OVERFLOW) as usize;
+            *self.generation_overflow.as_ref()?.get(index)?
+        };
+        Some(commit.commit_time + offset)
+    }
+
+    /// Number of GDA2 entries that redirect into GDO2
+    #[must_use]
+    pub fn generation_overflow_count(&self) -> usize {
+        self.generation_data
+            .iter()
+            .flatten()
+            .filter(|&&value| value & Self::GENERATION_OVERFLOW != 0)
+            .count()
+    }
+
+    /// Number of commits with more than two parents
+    #[must_use]
+    pub fn octopus_count(&self) -> usize {
+        self.commits
+            .iter()
+            .filter(|commit| commit.is_octopus())
+            .count()
+    }
+
+    /// Find a chunk lookup table entry by chunk ID
+    #[must_use]
+    pub fn chunk_by_id(&self, id: u32) -> Option<&ChunkEntry> {
+        self.chunks.iter().find(|c| c.id == id)
+    }
+}
+
+impl fmt::Display for CommitGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Commit Graph (version {})", self.version)?;
+        writeln!(
+            f,
+            "Hash function: {} (ID: {})",
+            self.hash_function_name(),
+            self.hash_version
+        )?;
+        writeln!(f, "Base graphs: {}", self.base_graph_count)?;
+        writeln!(f, "Total commits: {}", self.commit_count())?;
+        writeln!(f, "Checksum: {}", hex::encode(&self.checksum))?;
+
+        writeln!(f, "\nChunks:")?;
+        for chunk in &self.chunks {
+            writeln!(
+                f,
+                "  {} at offset {} ({} bytes) — {}",
+                chunk.id_str(),
+                chunk.offset,
+                chunk.size,
+                chunk.description()
+            )?;
+        }
+
+        writeln!(f, "\nSample commits (OID → parents):")?;
+        let commit_sample = std::cmp::min(10, self.commit_count());
+        for position in 0..commit_sample {
+            if let (Some(oid), Some(parents)) =
+                (self.oid_hex_at(position), self.parent_positions(position))
+            {
+                let parents: Vec<String> = parents.iter().map(u32::to_string).collect();
+                writeln!(f, "  {oid} → [{}]", parents.join(", "))?;
+            }
+        }
+        if self.commit_count() > commit_sample {
+            writeln!(
+                f,
+                "  ... ({} more commits)",
+                self.commit_count() - commit_sample
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assemble a commit-graph from raw chunk payloads, computing the chunk
+    /// lookup table offsets automatically
+    pub(super) fn assemble_graph(
+        hash_version: u8,
+        base_graph_count: u8,
+        chunks: &[(u32, Vec<u8>)],
+        checksum_len: usize,
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        // Header
+        data.extend_from_slice(&CommitGraph::SIGNATURE.to_be_bytes());
+        data.push(CommitGraph::VERSION);
+        data.push(hash_version);
+        data.push(chunks.len() as u8);
+        data.push(base_graph_count);
+
+        // Chunk lookup table ((chunks + 1 terminator) * 12 bytes)
+        let mut offset = (HEADER_SIZE + (chunks.len() + 1) * CHUNK_LOOKUP_ENTRY_SIZE) as u64;
+        for (id, payload) in chunks {
+            data.extend_from_slice(&id.to_be_bytes());
+            data.extend_from_slice(&offset.to_be_bytes());
+            offset += payload.len() as u64;
+        }
+        data.extend_from_slice(&0u32.to_be_bytes()); // terminator ID
+        data.extend_from_slice(&offset.to_be_bytes()); // trailer offset
+
+        for (_, payload) in chunks {
+            data.extend_from_slice(payload);
+        }
+
+        // Trailer checksum (dummy)
+        data.extend_from_slice(&vec![0xcc; checksum_len]);
+
+        data
+    }
+
+    /// Synthesize commit IDs with increasing first bytes and the matching fanout
+    fn build_oids(num_commits: usize, hash_len: usize) -> (Vec<u8>, Vec<u8>) {
+        let mut oidl = Vec::new();
+        for i in 0..num_commits {
+            let mut oid = vec![0x11u8; hash_len];
+            oid[0] = i as u8;
+            oidl.extend_from_slice(&oid);
+        }
+
+        let mut oidf = Vec::new();
+        for first_byte in 0..256 {
+            let cumulative = std::cmp::min(first_byte + 1, num_commits) as u32;
+            oidf.extend_from_slice(&cumulative.to_be_bytes());
+        }
+
+        (oidf, oidl)
+    }
+
+    /// One CDAT entry
+    pub(super) fn cdat_entry(
+        hash_len: usize,
+        parents: (u32, u32),
+        level: u32,
+        time: u64,
+    ) -> Vec<u8> {
+        let mut entry = vec![0xaa; hash_len];
+        entry.extend_from_slice(&parents.0.to_be_bytes());
+        entry.extend_from_slice(&parents.1.to_be_bytes());
+        entry.extend_from_slice(&((level << 2) | (time >> 32) as u32).to_be_bytes());
+        entry.extend_from_slice(&(time as u32).to_be_bytes());
+        entry
+    }
+
+    fn be_u32s(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    /// A linear history of three commits, then an octopus merge of all of them
+    fn create_test_graph_data(with_generation_data: bool) -> Vec<u8> {
+        let hash_len = 20;
+        let none = CommitGraph::PARENT_NONE;
+        let (oidf, oidl) = build_oids(4, hash_len);
+
+        let mut cdat = Vec::new();
+        cdat.extend(cdat_entry(hash_len, (none, none), 1, 1_000));
+        cdat.extend(cdat_entry(hash_len, (0, none), 2, 2_000));
+        cdat.extend(cdat_entry(hash_len, (1, none), 3, 0x1_0000_0000));
+        cdat.extend(cdat_entry(
+            hash_len,
+            (2, CommitGraph::EXTRA_EDGES_NEEDED),
+            4,
+            0x1_0000_0010,
+        ));
+        let edge = be_u32s(&[1, CommitGraph::LAST_EDGE]);
+
+        let mut chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+        ];
+        if with_generation_data {
+            chunks.push((
+                CommitGraph::CHUNK_GDA2,
+                be_u32s(&[0, 5, 0, CommitGraph::GENERATION_OVERFLOW]),
+            ));
+            chunks.push((
+                CommitGraph::CHUNK_GDO2,
+                0x2_0000_0000u64.to_be_bytes().to_vec(),
+            ));
+        }
+        chunks.push((CommitGraph::CHUNK_EDGE, edge));
+
+        assemble_graph(1, 0, &chunks, hash_len)
+    }
+
+    #[test]
+    fn test_parse_commit_data() {
+        let data = create_test_graph_data(false);
+        let (remaining, graph) = CommitGraph::parse(&data).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(graph.version, 1);
+        assert_eq!(graph.hash_function_name(), "SHA-1");
+        assert_eq!(graph.commit_count(), 4);
+        assert_eq!(graph.checksum, vec![0xcc; 20]);
+        assert_eq!(graph.raw_data.len(), data.len());
+
+        let root = &graph.commits[0];
+        assert_eq!(root.root_tree, vec![0xaa; 20]);
+        assert_eq!(root.topological_level, 1);
+        assert_eq!(root.commit_time, 1_000);
+        assert_eq!(graph.parent_positions(0), Some(vec![]));
+        assert_eq!(graph.parent_positions(1), Some(vec![0]));
+
+        // 34-bit commit time
+        assert_eq!(graph.commits[2].commit_time, 0x1_0000_0000);
+        assert_eq!(graph.commits[2].topological_level, 3);
+
+        assert_eq!(graph.corrected_commit_date(0), None);
+    }
+
+    #[test]
+    fn test_octopus_parents_from_edge_chunk() {
+        let data = create_test_graph_data(false);
+        let (_, graph) = CommitGraph::parse(&data).unwrap();
+
+        assert!(graph.commits[3].is_octopus());
+        assert_eq!(graph.octopus_count(), 1);
+        assert_eq!(graph.parent_positions(3), Some(vec![2, 1, 0]));
+    }
+
+    #[test]
+    fn test_generation_data_with_overflow() {
+        let data = create_test_graph_data(true);
+        let (_, graph) = CommitGraph::parse(&data).unwrap();
+
+        assert_eq!(graph.corrected_commit_date(0), Some(1_000));
+        assert_eq!(graph.corrected_commit_date(1), Some(2_005));
+        assert_eq!(graph.generation_overflow_count(), 1);
+        assert_eq!(
+            graph.corrected_commit_date(3),
+            Some(0x1_0000_0010 + 0x2_0000_0000)
+        );
+    }
+
+    #[test]
+    fn test_position_of() {
+        let data = create_test_graph_data(false);
+        let (_, graph) = CommitGraph::parse(&data).unwrap();
+
+        let mut oid = vec![0x11u8; 20];
+        oid[0] = 2;
+        assert_eq!(graph.position_of(&oid), Some(2));
+        oid[0] = 9;
+        assert_eq!(graph.position_of(&oid), None);
+    }
+
+    #[test]
+    fn test_unterminated_edge_list() {
+        let hash_len = 20;
+        let none = CommitGraph::PARENT_NONE;
+        let (oidf, oidl) = build_oids(2, hash_len);
+        let mut cdat = cdat_entry(hash_len, (none, none), 1, 1);
+        cdat.extend(cdat_entry(
+            hash_len,
+            (0, CommitGraph::EXTRA_EDGES_NEEDED),
+            2,
+            2,
+        ));
+        let chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+            // No entry has LAST_EDGE set
+            (CommitGraph::CHUNK_EDGE, be_u32s(&[0])),
+        ];
+        let data = assemble_graph(1, 0, &chunks, hash_len);
+
+        assert!(CommitGraph::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_parent_out_of_range() {
+        let hash_len = 20;
+        let (oidf, oidl) = build_oids(1, hash_len);
+        let cdat = cdat_entry(hash_len, (5, CommitGraph::PARENT_NONE), 1, 1);
+        let chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+        ];
+
+        assert!(CommitGraph::parse(&assemble_graph(1, 0, &chunks, hash_len)).is_err());
+        // With a base graph, positions may point into the layers below
+        let mut layered_chunks = chunks;
+        layered_chunks.push((CommitGraph::CHUNK_BASE, vec![0xbb; hash_len]));
+        assert!(CommitGraph::parse(&assemble_graph(1, 1, &layered_chunks, hash_len)).is_ok());
+    }
+
+    #[test]
+    fn test_base_chunk() {
+        let hash_len = 20;
+        let (oidf, oidl) = build_oids(1, hash_len);
+        let cdat = cdat_entry(hash_len, (0, CommitGraph::PARENT_NONE), 1, 1);
+        let mut base = vec![0xb0; hash_len];
+        base.extend(vec![0xb1; hash_len]);
+        let mut chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+        ];
+
+        // A layer with base graphs must list them
+        assert!(CommitGraph::parse(&assemble_graph(1, 2, &chunks, hash_len)).is_err());
+
+        chunks.push((CommitGraph::CHUNK_BASE, base));
+        let (_, graph) = CommitGraph::parse(&assemble_graph(1, 2, &chunks, hash_len)).unwrap();
+        assert_eq!(graph.base_graphs, vec![vec![0xb0; 20], vec![0xb1; 20]]);
+
+        // The BASE chunk holds exactly one checksum per base graph
+        assert!(CommitGraph::parse(&assemble_graph(1, 3, &chunks, hash_len)).is_err());
+    }
+
+    #[test]
+    fn test_generation_overflow_out_of_range() {
+        let hash_len = 20;
+        let (oidf, oidl) = build_oids(1, hash_len);
+        let cdat = cdat_entry(
+            hash_len,
+            (CommitGraph::PARENT_NONE, CommitGraph::PARENT_NONE),
+            1,
+            1,
+        );
+        let chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+            (
+                CommitGraph::CHUNK_GDA2,
+                be_u32s(&[CommitGraph::GENERATION_OVERFLOW]),
+            ),
+        ];
+
+        assert!(CommitGraph::parse(&assemble_graph(1, 0, &chunks, hash_len)).is_err());
+    }
+
+    #[test]
+    fn test_missing_required_chunk() {
+        let hash_len = 20;
+        let (oidf, oidl) = build_oids(1, hash_len);
+        let chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+        ];
+
+        assert!(CommitGraph::parse(&assemble_graph(1, 0, &chunks, hash_len)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let mut data = create_test_graph_data(false);
+        data[0] = 0x00;
+        assert!(CommitGraph::parse(&data).is_err());
+
+        let mut data = create_test_graph_data(false);
+        data[4] = 2; // version
+        assert!(CommitGraph::parse(&data).is_err());
+
+        let mut data = create_test_graph_data(false);
+        data[5] = 3; // hash version
+        assert!(CommitGraph::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_cdat_size_mismatch() {
+        let hash_len = 20;
+        let (oidf, oidl) = build_oids(2, hash_len);
+        let cdat = cdat_entry(
+            hash_len,
+            (CommitGraph::PARENT_NONE, CommitGraph::PARENT_NONE),
+            1,
+            1,
+        );
+        let chunks = vec![
+            (CommitGraph::CHUNK_OIDF, oidf),
+            (CommitGraph::CHUNK_OIDL, oidl),
+            (CommitGraph::CHUNK_CDAT, cdat),
+        ];
+
+        assert!(CommitGraph::parse(&assemble_graph(1, 0, &chunks, hash_len)).is_err());
+    }
+
+    #[test]
+    fn test_display_format() {
+        let data = create_test_graph_data(true);
+        let (_, graph) = CommitGraph::parse(&data).unwrap();
+
+        let display_str = format!("{graph}");
+        assert!(display_str.contains("Commit Graph (version 1)"));
+        assert!(display_str.contains("Total commits: 4"));
+        assert!(display_str.contains("CDAT"));
+        assert!(display_str.contains("[2, 1, 0]"));
+    }
+}
@@ -0,0 +1,347 @@
+//! Compatibility object maps: each object's name in a second hash function.
+//!
+//! A repository setting `extensions.compatObjectFormat` names its objects
+//! in both SHA-1 and SHA-256 while storing them under one of the two. Git
+//! records the translation next to the objects: loose objects in
+//! `objects/loose-object-idx`, one `<stored name> <compat name>` pair per
+//! line, and packed objects in the second set of tables of a version-3
+//! `.idx`.
+
+use crate::git::object_format::ObjectFormat;
+use crate::git::pack::PackIndex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CompatMapError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Missing '# loose-object-idx' header")]
+    MissingHeader,
+
+    #[error("Invalid loose object map entry on line {line}: {content}")]
+    InvalidEntry { line: usize, content: String },
+}
+
+/// An object's name in the compatibility format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatObjectId {
+    pub object_format: ObjectFormat,
+    pub id: String,
+}
+
+/// The `objects/loose-object-idx` file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LooseObjectIdx {
+    /// Format of the names objects are stored under
+    pub object_format: ObjectFormat,
+    /// Format of the names they are translated to
+    pub compat_format: ObjectFormat,
+    /// `(stored name, compat name)` pairs in file order
+    pub entries: Vec<(String, String)>,
+}
+
+impl LooseObjectIdx {
+    /// Where an object directory keeps its loose object map
+    pub const FILE: &str = "loose-object-idx";
+    /// First line of the file
+    pub const HEADER: &str = "# loose-object-idx";
+
+    /// Parse the map of a repository storing objects in `object_format`
+    /// and translating them to `compat_format`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header is missing, or a line is not two
+    /// hex names of the expected lengths separated by a space.
+    pub fn parse(
+        content: &str,
+        object_format: ObjectFormat,
+        compat_format: ObjectFormat,
+    ) -> Result<Self, CompatMapError> {
+        let mut lines = content.lines();
+        if lines.next() != Some(Self::HEADER) {
+            return Err(CompatMapError::MissingHeader);
+        }
+
+        let is_name = |name: &str, format: ObjectFormat| {
+            name.len() == format.hex_len() && name.bytes().all(|byte| byte.is_ascii_hexdigit())
+        };
+        let entries = lines
+            .enumerate()
+            .map(|(i, line)| {
+                line.split_once(' ')
+                    .filter(|(stored, compat)| {
+                        is_name(stored, object_format) && is_name(compat, compat_format)
+                    })
+                    .map(|(stored, compat)| (stored.to_lowercase(), compat.to_lowercase()))
+                    .ok_or_else(|| CompatMapError::InvalidEntry {
+                        line: i + 2,
+                        content: line.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            object_format,
+            compat_format,
+            entries,
+        })
+    }
+
+    /// Read a loose object map file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed.
+    pub fn load_file(
+        path: &Path,
+        object_format: ObjectFormat,
+        compat_format: ObjectFormat,
+    ) -> Result<Self, CompatMapError> {
+        Self::parse(&fs::read_to_string(path)?, object_format, compat_format)
+    }
+
+    /// Read the map of the object directory `objects_dir`, if it has one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but can't be read or parsed.
+    pub fn load(
+        objects_dir: &Path,
+        object_format: ObjectFormat,
+        compat_format: ObjectFormat,
+    ) -> Result<Option<Self>, CompatMapError> {
+        let path = objects_dir.join(Self::FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Self::load_file(&path, object_format, compat_format).map(Some)
+    }
+}
+
+/// Translation between stored and compat names, in both directions
+#[derive(Debug, Clone)]
+pub struct CompatMap {
+    object_format: ObjectFormat,
+    compat_format: ObjectFormat,
+    to_compat: HashMap<String, String>,
+    to_storage: HashMap<String, String>,
+}
+
+impl CompatMap {
+    #[must_use]
+    pub fn new(object_format: ObjectFormat, compat_format: ObjectFormat) -> Self {
+        Self {
+            object_format,
+            compat_format,
+            to_compat: HashMap::new(),
+            to_storage: HashMap::new(),
+        }
+    }
+
+    /// Every mapping of an object directory: its loose object map and the
+    /// compat tables of its version-3 pack indexes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the loose object map or a pack index can't be
+    /// read, or the loose object map can't be parsed.
+    pub fn load(
+        objects_dir: &Path,
+        object_format: ObjectFormat,
+        compat_format: ObjectFormat,
+    ) -> Result<Self, CompatMapError> {
+        let mut map = Self::new(object_format, compat_format);
+        if let Some(loose) = LooseObjectIdx::load(objects_dir, object_format, compat_format)? {
+            map.add_loose_object_idx(&loose);
+        }
+
+        let pack_dir = objects_dir.join("pack");
+        if pack_dir.is_dir() {
+            for entry in fs::read_dir(pack_dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|ext| ext != "idx") {
+                    continue;
+                }
+                let data = fs::read(&path)?;
+                // Only version-3 indexes carry compat names; skip the
+                // others without parsing them
+                if data.get(4..8) != Some(&3u32.to_be_bytes()[..]) {
+                    continue;
+                }
+                if let Ok((_, index)) = PackIndex::parse(&data) {
+                    map.add_pack_index(&index);
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// Record that the object stored as `stored` is named `compat` in the
+    /// compatibility format
+    pub fn insert(&mut self, stored: String, compat: String) {
+        self.to_storage.insert(compat.clone(), stored.clone());
+        self.to_compat.insert(stored, compat);
+    }
+
+    /// Add every mapping of another map, such as an alternate's
+    pub fn merge(&mut self, other: Self) {
+        self.to_compat.extend(other.to_compat);
+        self.to_storage.extend(other.to_storage);
+    }
+
+    pub fn add_loose_object_idx(&mut self, loose: &LooseObjectIdx) {
+        for (stored, compat) in &loose.entries {
+            self.insert(stored.clone(), compat.clone());
+        }
+    }
+
+    /// Add the compat names of a version-3 index, when they are in this
+    /// map's compatibility format
+    pub fn add_pack_index(&mut self, index: &PackIndex) {
+        let Some(compat) = &index.compat else {
+            return;
+        };
+        if index.object_format != self.object_format || compat.object_format != self.compat_format {
+            return;
+        }
+        for (stored, compat) in index.object_names.iter().zip(&compat.object_names) {
+            self.insert(hex::encode(stored), hex::encode(compat));
+        }
+    }
+
+    #[must_use]
+    pub const fn object_format(&self) -> ObjectFormat {
+        self.object_format
+    }
+
+    #[must_use]
+    pub const fn compat_format(&self) -> ObjectFormat {
+        self.compat_format
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.to_compat.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.to_compat.is_empty()
+    }
+
+    /// The compat name of the object stored as `stored`
+    #[must_use]
+    pub fn compat_id(&self, stored: &str) -> Option<CompatObjectId> {
+        self.to_compat
+            .get(&stored.to_lowercase())
+            .map(|id| CompatObjectId {
+                object_format: self.compat_format,
+                id: id.clone(),
+            })
+    }
+
+    /// The stored name of the object named `compat` in the compatibility
+    /// format
+    #[must_use]
+    pub fn storage_id(&self, compat: &str) -> Option<&str> {
+        self.to_storage
+            .get(&compat.to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// `(compat name, stored name)` of every object whose compat name
+    /// starts with `prefix`, sorted by compat name
+    #[must_use]
+    pub fn find_by_compat_prefix(&self, prefix: &str) -> Vec<(&str, &str)> {
+        let prefix = prefix.to_lowercase();
+        let mut matches: Vec<_> = self
+            .to_storage
+            .iter()
+            .filter(|(compat, _)| compat.starts_with(&prefix))
+            .map(|(compat, stored)| (compat.as_str(), stored.as_str()))
+            .collect();
+        matches.sort_unstable();
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORED: &str = "473a0f4c3be8a93681a267e3b1e9a7dcda1185436fe141f7749120a303721813";
+    const COMPAT: &str = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391";
+
+    #[test]
+    fn test_parse_loose_object_idx() {
+        let content = format!("# loose-object-idx\n{STORED} {COMPAT}\n");
+        let loose =
+            LooseObjectIdx::parse(&content, ObjectFormat::Sha256, ObjectFormat::Sha1).unwrap();
+        assert_eq!(loose.entries, [(STORED.to_string(), COMPAT.to_string())]);
+
+        assert!(matches!(
+            LooseObjectIdx::parse(
+                &format!("{STORED} {COMPAT}\n"),
+                ObjectFormat::Sha256,
+                ObjectFormat::Sha1
+            ),
+            Err(CompatMapError::MissingHeader)
+        ));
+        // Names in the wrong order don't match the formats' lengths
+        assert!(matches!(
+            LooseObjectIdx::parse(
+                &format!("# loose-object-idx\n{COMPAT} {STORED}\n"),
+                ObjectFormat::Sha256,
+                ObjectFormat::Sha1
+            ),
+            Err(CompatMapError::InvalidEntry { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_map_translates_both_ways() {
+        let mut map = CompatMap::new(ObjectFormat::Sha256, ObjectFormat::Sha1);
+        map.insert(STORED.to_string(), COMPAT.to_string());
+
+        assert_eq!(
+            map.compat_id(STORED),
+            Some(CompatObjectId {
+                object_format: ObjectFormat::Sha1,
+                id: COMPAT.to_string()
+            })
+        );
+        assert_eq!(map.storage_id(&COMPAT.to_uppercase()), Some(STORED));
+        assert_eq!(map.find_by_compat_prefix("e69d"), [(COMPAT, STORED)]);
+        assert!(map.find_by_compat_prefix("e69e").is_empty());
+    }
+
+    #[test]
+    fn test_load_from_objects_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let objects_dir = temp_dir.path();
+        fs::create_dir_all(objects_dir.join("pack")).unwrap();
+        fs::write(
+            objects_dir.join(LooseObjectIdx::FILE),
+            format!("# loose-object-idx\n{STORED} {COMPAT}\n"),
+        )
+        .unwrap();
+        // A version-2 index has nothing to add
+        fs::write(
+            objects_dir.join("pack/pack-a.idx"),
+            [0xff, 0x74, 0x4f, 0x63, 0, 0, 0, 2],
+        )
+        .unwrap();
+
+        let map = CompatMap::load(objects_dir, ObjectFormat::Sha256, ObjectFormat::Sha1).unwrap();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.storage_id(COMPAT), Some(STORED));
+
+        let empty = tempfile::tempdir().unwrap();
+        let map = CompatMap::load(empty.path(), ObjectFormat::Sha256, ObjectFormat::Sha1).unwrap();
+        assert!(map.is_empty());
+    }
+}
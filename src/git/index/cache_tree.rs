@@ -0,0 +1,321 @@
+use crate::git::object_database::{ObjectDatabase, ObjectLocation};
+use crate::git::pack::ObjectType;
+use nom::{
+    IResult,
+    bytes::complete::take,
+    error::{Error, ErrorKind},
+};
+use std::fmt;
+
+use super::{GitIndex, IndexEntry};
+
+/// Outcome of checking one cache-tree node against the object database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheTreeStatus {
+    /// Invalidated node: there is no tree to check
+    Invalidated,
+    /// The tree exists as a loose object
+    Loose,
+    /// The tree exists in the named pack
+    Packed { pack: String },
+    /// The object exists but is not a tree
+    WrongType(String),
+    /// The object is in neither loose storage nor any pack
+    Missing,
+    /// The node claims a different number of entries than the index holds
+    /// under its path
+    EntryCountMismatch { expected: usize },
+}
+
+impl CacheTreeStatus {
+    /// Whether this status points at a corrupt cache
+    #[must_use]
+    pub const fn is_error(&self) -> bool {
+        matches!(
+            self,
+            Self::WrongType(_) | Self::Missing | Self::EntryCountMismatch { .. }
+        )
+    }
+}
+
+impl fmt::Display for CacheTreeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalidated => write!(f, "invalidated"),
+            Self::Loose => write!(f, "ok (loose)"),
+            // "pack-" plus the first 8 hex digits tells packs apart well enough
+            Self::Packed { pack } => write!(f, "ok ({})", &pack[..pack.len().min(13)]),
+            Self::WrongType(obj_type) => write!(f, "not a tree: {obj_type}"),
+            Self::Missing => write!(f, "missing object"),
+            Self::EntryCountMismatch { expected } => {
+                write!(f, "entry count mismatch: index has {expected}")
+            }
+        }
+    }
+}
+
+/// One directory of the cache tree, in depth-first order
+#[derive(Debug, Clone)]
+pub struct CacheTreeNode {
+    /// Path relative to the repository root ("" for the root directory)
+    pub path: String,
+    /// Nesting depth (0 for the root)
+    pub depth: usize,
+    /// Number of index entries covered by this tree; -1 when invalidated
+    pub entry_count: i32,
+    /// Number of subtrees that follow this node
+    pub subtree_count: usize,
+    /// Tree object ID, present only for valid nodes
+    pub oid: Option<Vec<u8>>,
+    /// Result of [`CacheTree::verify`], None until verified
+    pub status: Option<CacheTreeStatus>,
+}
+
+impl CacheTreeNode {
+    /// Whether the cached tree can be reused (`git commit` won't rehash it)
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.entry_count >= 0
+    }
+
+    /// Hex object ID of the cached tree, if valid
+    #[must_use]
+    pub fn oid_hex(&self) -> Option<String> {
+        self.oid.as_ref().map(hex::encode)
+    }
+}
+
+/// The cache-tree extension ("TREE")
+///
+/// Caches the tree object ID of every directory whose index entries haven't
+/// changed since the last tree was written, so `git commit` and
+/// `git write-tree` only rehash the directories that did change.
+#[derive(Debug, Clone, Default)]
+pub struct CacheTree {
+    /// Directories in depth-first order, starting with the root
+    pub nodes: Vec<CacheTreeNode>,
+}
+
+impl CacheTree {
+    /// Extension signature
+    pub const SIGNATURE: [u8; 4] = *b"TREE";
+
+    /// Parse the payload of a cache-tree extension
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if a node header is malformed (missing
+    /// terminator, non-numeric counts), an object ID is truncated, or the
+    /// subtree counts don't match the data.
+    pub fn parse(input: &[u8], hash_size: usize) -> IResult<&[u8], Self> {
+        let mut nodes = Vec::new();
+        let mut input = input;
+        if !input.is_empty() {
+            input = Self::parse_node(input, hash_size, "", 0, &mut nodes)?.0;
+        }
+        if !input.is_empty() {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Eof)));
+        }
+        Ok((input, Self { nodes }))
+    }
+
+    /// Parse a node and, recursively, its subtrees
+    fn parse_node<'a>(
+        input: &'a [u8],
+        hash_size: usize,
+        parent: &str,
+        depth: usize,
+        nodes: &mut Vec<CacheTreeNode>,
+    ) -> IResult<&'a [u8], ()> {
+        let (input, name) = GitIndex::parse_nul_terminated(input)?;
+        let Some(space) = input.iter().position(|&b| b == b' ') else {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Char)));
+        };
+        let Some(newline) = input.iter().position(|&b| b == b'\n') else {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Char)));
+        };
+        if newline < space {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Char)));
+        }
+        let entry_count: i32 = Self::parse_decimal(input, &input[..space])?;
+        let subtree_count: usize = Self::parse_decimal(input, &input[space + 1..newline])?;
+        let mut input = &input[newline + 1..];
+
+        let oid = if entry_count >= 0 {
+            let (rest, oid) = take(hash_size)(input)?;
+            input = rest;
+            Some(oid.to_vec())
+        } else {
+            None
+        };
+
+        let name = String::from_utf8_lossy(name);
+        let path = match (parent.is_empty(), depth) {
+            (_, 0) => String::new(),
+            (true, _) => name.into_owned(),
+            (false, _) => format!("{parent}/{name}"),
+        };
+        nodes.push(CacheTreeNode {
+            path: path.clone(),
+            depth,
+            entry_count,
+            subtree_count,
+            oid,
+            status: None,
+        });
+
+        for _ in 0..subtree_count {
+            input = Self::parse_node(input, hash_size, &path, depth + 1, nodes)?.0;
+        }
+        Ok((input, ()))
+    }
+
+    fn parse_decimal<'a, T: std::str::FromStr>(
+        input: &'a [u8],
+        digits: &[u8],
+    ) -> Result<T, nom::Err<Error<&'a [u8]>>> {
+        std::str::from_utf8(digits)
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Digit)))
+    }
+
+    /// Number of nodes that are still valid
+    #[must_use]
+    pub fn valid_count(&self) -> usize {
+        self.nodes.iter().filter(|node| node.is_valid()).count()
+    }
+
+    /// Number of nodes whose verification found a problem
+    #[must_use]
+    pub fn error_count(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| node.status.as_ref().is_some_and(CacheTreeStatus::is_error))
+            .count()
+    }
+
+    /// Check every node against the index entries and the object database
+    ///
+    /// Valid nodes must cover as many entries as the index holds under their
+    /// path, and their tree must exist as a tree object, either loose or in
+    /// one of the repository's packs.
+    pub fn verify(&mut self, database: &ObjectDatabase, entries: &[IndexEntry]) {
+        for node in &mut self.nodes {
+            let Some(oid) = &node.oid else {
+                node.status = Some(CacheTreeStatus::Invalidated);
+                continue;
+            };
+
+            let expected = Self::entries_under(entries, &node.path);
+            if usize::try_from(node.entry_count).ok() != Some(expected) {
+                node.status = Some(CacheTreeStatus::EntryCountMismatch { expected });
+                continue;
+            }
+
+            node.status = Some(Self::locate_tree(database, oid));
+        }
+    }
+
+    /// Number of index entries inside a directory ("" for the whole index)
+    ///
+    /// Entries are sorted by path, so everything under `dir/` sits between
+    /// `dir/` and `dir0` (`'0'` follows `'/'`).
+    fn entries_under(entries: &[IndexEntry], path: &str) -> usize {
+        if path.is_empty() {
+            return entries.len();
+        }
+        let start = format!("{path}/");
+        let end = format!("{path}0");
+        let lower = entries.partition_point(|entry| entry.path.as_str() < start.as_str());
+        let upper = entries.partition_point(|entry| entry.path.as_str() < end.as_str());
+        upper - lower
+    }
+
+    fn locate_tree(database: &ObjectDatabase, oid: &[u8]) -> CacheTreeStatus {
+        let Some(location) = database.locate(oid) else {
+            return CacheTreeStatus::Missing;
+        };
+        match database.read_type(oid) {
+            Ok(ObjectType::Tree) => match location {
+                ObjectLocation::Loose(_) => CacheTreeStatus::Loose,
+                ObjectLocation::Packed { pack, .. } => CacheTreeStatus::Packed { pack },
+            },
+            Ok(obj_type) => CacheTreeStatus::WrongType(obj_type.to_string()),
+            Err(e) => CacheTreeStatus::WrongType(format!("unreadable ({e})")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::object_format::ObjectFormat;
+
+    fn node(name: &str, entry_count: i32, subtree_count: usize, oid_byte: u8) -> Vec<u8> {
+        let mut data = name.as_bytes().to_vec();
+        data.push(0);
+        data.extend_from_slice(format!("{entry_count} {subtree_count}\n").as_bytes());
+        if entry_count >= 0 {
+            data.extend_from_slice(&[oid_byte; 20]);
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_hierarchy() {
+        let mut data = node("", 5, 2, 0xaa);
+        data.extend(node("src", 3, 1, 0xbb));
+        data.extend(node("git", -1, 0, 0));
+        data.extend(node("docs", 1, 0, 0xcc));
+
+        let (remaining, cache_tree) = CacheTree::parse(&data, 20).unwrap();
+        assert!(remaining.is_empty());
+        let paths: Vec<_> = cache_tree.nodes.iter().map(|n| n.path.as_str()).collect();
+        assert_eq!(paths, ["", "src", "src/git", "docs"]);
+        assert_eq!(cache_tree.nodes[2].depth, 2);
+        assert!(!cache_tree.nodes[2].is_valid());
+        assert_eq!(cache_tree.nodes[2].oid, None);
+        assert_eq!(cache_tree.nodes[3].oid_hex(), Some("cc".repeat(20)));
+        assert_eq!(cache_tree.valid_count(), 3);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        // Declares a subtree that isn't there
+        assert!(CacheTree::parse(&node("", 1, 1, 0xaa), 20).is_err());
+        // Non-numeric count
+        assert!(CacheTree::parse(b"\0x 0\n", 20).is_err());
+        // Truncated object ID
+        let mut data = node("", 1, 0, 0xaa);
+        data.pop();
+        assert!(CacheTree::parse(&data, 20).is_err());
+        // Trailing garbage
+        let mut data = node("", -1, 0, 0);
+        data.push(b'x');
+        assert!(CacheTree::parse(&data, 20).is_err());
+    }
+
+    #[test]
+    fn test_verify_entry_counts_and_missing_objects() {
+        let mut data = node("", 3, 1, 0xaa);
+        data.extend(node("src", 1, 0, 0xbb));
+        let (_, mut cache_tree) = CacheTree::parse(&data, 20).unwrap();
+
+        let index_data = crate::git::index::tests::finish(
+            crate::git::index::tests::create_test_index(2, &["a", "src/x", "src/y"], 20),
+            20,
+        );
+        let (_, index) = GitIndex::parse_with_format(&index_data, ObjectFormat::Sha1).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let database = ObjectDatabase::open(&temp_dir.path().join(".git"));
+        cache_tree.verify(&database, &index.entries);
+        assert_eq!(cache_tree.nodes[0].status, Some(CacheTreeStatus::Missing));
+        assert_eq!(
+            cache_tree.nodes[1].status,
+            Some(CacheTreeStatus::EntryCountMismatch { expected: 2 })
+        );
+        assert_eq!(cache_tree.error_count(), 2);
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+use super::IndexEntry;
+
+/// The three sides of a conflicted path, stored as index stages 1-3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    /// Stage 1: the common ancestor
+    Base,
+    /// Stage 2: HEAD, the branch being merged into
+    Ours,
+    /// Stage 3: the branch being merged in
+    Theirs,
+}
+
+impl ConflictSide {
+    /// All sides in stage order
+    pub const ALL: [Self; 3] = [Self::Base, Self::Ours, Self::Theirs];
+
+    /// Index stage number (1-3)
+    #[must_use]
+    pub const fn stage(self) -> u16 {
+        match self {
+            Self::Base => 1,
+            Self::Ours => 2,
+            Self::Theirs => 3,
+        }
+    }
+}
+
+impl fmt::Display for ConflictSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Base => "base",
+            Self::Ours => "ours",
+            Self::Theirs => "theirs",
+        })
+    }
+}
+
+/// An unmerged path: its stage 1-3 entries grouped together
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// Conflicted path
+    pub path: String,
+    /// Entries for stages 1, 2 and 3; None where the side has no version
+    pub stages: [Option<IndexEntry>; 3],
+}
+
+impl Conflict {
+    /// Group the stage 1-3 entries of an index by path, in index order
+    ///
+    /// Entries are sorted by path and then by stage, so the stages of a
+    /// path are always adjacent.
+    #[must_use]
+    pub fn group<'a>(entries: impl IntoIterator<Item = &'a IndexEntry>) -> Vec<Self> {
+        let mut conflicts: Vec<Self> = Vec::new();
+        for entry in entries {
+            let stage = entry.stage();
+            if stage == 0 {
+                continue;
+            }
+            if conflicts.last().is_none_or(|last| last.path != entry.path) {
+                conflicts.push(Self {
+                    path: entry.path.clone(),
+                    stages: [None, None, None],
+                });
+            }
+            if let Some(last) = conflicts.last_mut() {
+                last.stages[usize::from(stage) - 1] = Some(entry.clone());
+            }
+        }
+        conflicts
+    }
+
+    /// Entry for one side of the conflict
+    #[must_use]
+    pub fn side(&self, side: ConflictSide) -> Option<&IndexEntry> {
+        self.stages[usize::from(side.stage()) - 1].as_ref()
+    }
+
+    /// Short description in `git status` terms ("both modified", ...)
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match (
+            self.stages[0].is_some(),
+            self.stages[1].is_some(),
+            self.stages[2].is_some(),
+        ) {
+            (true, true, true) => "both modified",
+            (false, true, true) => "both added",
+            (true, false, true) => "deleted by us",
+            (true, true, false) => "deleted by them",
+            (false, true, false) => "added by us",
+            (false, false, true) => "added by them",
+            (true, false, false) => "both deleted",
+            (false, false, false) => "unknown",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::index::GitIndex;
+    use crate::git::index::tests::{entry_fixed_part, finish};
+    use crate::git::object_format::ObjectFormat;
+
+    fn create_conflicted_index(entries: &[(&str, u16)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&GitIndex::SIGNATURE.to_be_bytes());
+        data.extend_from_slice(&2u32.to_be_bytes());
+        data.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for (i, (path, stage)) in entries.iter().enumerate() {
+            let flags = (stage << 12) | path.len() as u16;
+            let mut entry = entry_fixed_part(i as u8 + 1, 20, flags);
+            entry.extend_from_slice(path.as_bytes());
+            let padded = (entry.len() + 8) & !7;
+            entry.resize(padded, 0);
+            data.extend_from_slice(&entry);
+        }
+        finish(data, 20)
+    }
+
+    #[test]
+    fn test_group_conflicts() {
+        let data = create_conflicted_index(&[
+            ("a.txt", 0),
+            ("b.txt", 1),
+            ("b.txt", 2),
+            ("b.txt", 3),
+            ("c.txt", 2),
+            ("c.txt", 3),
+            ("d.txt", 1),
+            ("d.txt", 2),
+            ("e.txt", 0),
+        ]);
+        let (_, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+        let conflicts = index.conflicts();
+
+        assert_eq!(conflicts.len(), 3);
+        assert_eq!(conflicts[0].path, "b.txt");
+        assert_eq!(conflicts[0].kind(), "both modified");
+        assert_eq!(
+            conflicts[0].side(ConflictSide::Theirs).unwrap().object_id,
+            vec![4; 20]
+        );
+        assert_eq!(conflicts[1].kind(), "both added");
+        assert!(conflicts[1].side(ConflictSide::Base).is_none());
+        assert_eq!(conflicts[2].path, "d.txt");
+        assert_eq!(conflicts[2].kind(), "deleted by them");
+    }
+
+    #[test]
+    fn test_no_conflicts() {
+        let data = create_conflicted_index(&[("a.txt", 0), ("b.txt", 0)]);
+        let (_, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+        assert!(index.conflicts().is_empty());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::git::pack::bitmap::EwahBitmap;
+use nom::{
+    IResult,
+    error::{Error, ErrorKind},
+    number::complete::{be_u32, be_u64},
+};
+
+use super::GitIndex;
+
+/// Where the file system monitor picks up from on the next query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsmonitorToken {
+    /// Version 1: nanoseconds since the Unix epoch
+    Timestamp(u64),
+    /// Version 2: opaque token defined by the monitor (e.g. a Watchman clock)
+    Opaque(String),
+}
+
+/// The file system monitor extension ("FSMN", `core.fsmonitor`)
+///
+/// Records how far git has synced with the file system monitor and which
+/// entries the monitor has not vouched for. Entries not marked dirty are
+/// trusted to be unchanged, so `git status` skips their `lstat()`.
+#[derive(Debug, Clone)]
+pub struct FsmonitorExtension {
+    /// Extension version (1 or 2)
+    pub version: u32,
+    /// Point in time (or monitor token) the data reflects
+    pub token: FsmonitorToken,
+    /// Size in bytes of the serialized bitmap
+    pub bitmap_size: u32,
+    /// Bit n set: index entry n is dirty (not `CE_FSMONITOR_VALID`)
+    pub dirty_bitmap: EwahBitmap,
+}
+
+impl FsmonitorExtension {
+    /// Extension signature
+    pub const SIGNATURE: [u8; 4] = *b"FSMN";
+
+    /// Parse the payload of a file system monitor extension
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if the version is not 1 or 2, the token is
+    /// not NUL-terminated, or the bitmap is truncated or doesn't match its
+    /// declared size.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, version) = be_u32(input)?;
+        let (input, token) = match version {
+            1 => {
+                let (input, nanoseconds) = be_u64(input)?;
+                (input, FsmonitorToken::Timestamp(nanoseconds))
+            }
+            2 => {
+                let (input, token) = GitIndex::parse_nul_terminated(input)?;
+                (
+                    input,
+                    FsmonitorToken::Opaque(String::from_utf8_lossy(token).into_owned()),
+                )
+            }
+            _ => return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag))),
+        };
+
+        let (input, bitmap_size) = be_u32(input)?;
+        let (rest, dirty_bitmap) = EwahBitmap::parse(input)?;
+        if dirty_bitmap.compressed_byte_size() != bitmap_size as usize {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::LengthValue)));
+        }
+
+        Ok((
+            rest,
+            Self {
+                version,
+                token,
+                bitmap_size,
+                dirty_bitmap,
+            },
+        ))
+    }
+
+    /// Positions of the index entries the monitor has not vouched for
+    #[must_use]
+    pub fn dirty_positions(&self) -> Vec<u64> {
+        self.dirty_bitmap.set_bit_positions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::pack::bitmap::tests::ewah_bytes_with_bits;
+
+    #[test]
+    fn test_parse_v2_token() {
+        let bitmap = ewah_bytes_with_bits(4, &[1, 3]);
+        let mut data = 2u32.to_be_bytes().to_vec();
+        data.extend_from_slice(b"1:1700000000:42\0");
+        data.extend_from_slice(&(bitmap.len() as u32).to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        let (remaining, fsmonitor) = FsmonitorExtension::parse(&data).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(fsmonitor.version, 2);
+        assert_eq!(
+            fsmonitor.token,
+            FsmonitorToken::Opaque("1:1700000000:42".into())
+        );
+        assert_eq!(fsmonitor.dirty_positions(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_parse_v1_timestamp() {
+        let bitmap = ewah_bytes_with_bits(2, &[]);
+        let mut data = 1u32.to_be_bytes().to_vec();
+        data.extend_from_slice(&1_700_000_000_000_000_000u64.to_be_bytes());
+        data.extend_from_slice(&(bitmap.len() as u32).to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        let (_, fsmonitor) = FsmonitorExtension::parse(&data).unwrap();
+        assert_eq!(
+            fsmonitor.token,
+            FsmonitorToken::Timestamp(1_700_000_000_000_000_000)
+        );
+        assert!(fsmonitor.dirty_positions().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_version_and_size() {
+        let mut data = 3u32.to_be_bytes().to_vec();
+        data.extend_from_slice(&[0; 32]);
+        assert!(FsmonitorExtension::parse(&data).is_err());
+
+        let bitmap = ewah_bytes_with_bits(2, &[]);
+        let mut data = 2u32.to_be_bytes().to_vec();
+        data.extend_from_slice(b"t\0");
+        data.extend_from_slice(&99u32.to_be_bytes());
+        data.extend_from_slice(&bitmap);
+        assert!(FsmonitorExtension::parse(&data).is_err());
+    }
+}
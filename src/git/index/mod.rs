@@ -0,0 +1,1055 @@
+use crate::git::loose_object::LooseObject;
+use crate::git::object_database::ObjectDatabase;
+use crate::git::object_format::ObjectFormat;
+use nom::{
+    IResult,
+    bytes::complete::take,
+    error::{Error, ErrorKind},
+    number::complete::{be_u16, be_u32},
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+pub mod cache_tree;
+pub mod conflict;
+pub mod fsmonitor;
+pub mod resolve_undo;
+pub mod sparse;
+pub mod split;
+pub mod untracked_cache;
+
+pub use cache_tree::{CacheTree, CacheTreeNode, CacheTreeStatus};
+pub use conflict::{Conflict, ConflictSide};
+pub use fsmonitor::{FsmonitorExtension, FsmonitorToken};
+pub use resolve_undo::{ResolveUndo, ResolveUndoEntry};
+pub use sparse::{ConeMembership, SparseCheckout, SparseDirectory, TreeNode};
+pub use split::{EntrySource, LinkExtension, MergedEntry, SplitIndex, SplitIndexError};
+pub use untracked_cache::{UntrackedCache, UntrackedDirectory};
+
+/// Size of the fixed index header ("DIRC" + version + entry count)
+const HEADER_SIZE: usize = 12;
+/// Size of the stat data fields that open every entry (10 × 4 bytes)
+const STAT_DATA_SIZE: usize = 40;
+
+/// A timestamp as stored in the index stat data (seconds + nanoseconds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexTime {
+    /// Seconds since the Unix epoch (truncated to 32 bits)
+    pub seconds: u32,
+    /// Nanosecond fraction (0 when the platform does not provide it)
+    pub nanoseconds: u32,
+}
+
+/// A single cache entry of the index: one tracked path at one stage
+///
+/// The stat data is a snapshot of `lstat()` taken when git last hashed the
+/// working tree file. `git status` compares it against the current file to
+/// decide whether the file needs rehashing at all.
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    /// Byte offset of the entry from the start of the index file
+    pub offset: usize,
+    /// Last metadata change time of the working tree file
+    pub ctime: IndexTime,
+    /// Last data modification time of the working tree file
+    pub mtime: IndexTime,
+    /// Device number of the working tree file
+    pub dev: u32,
+    /// Inode number of the working tree file
+    pub ino: u32,
+    /// Object type and unix permissions (e.g. 0o100644)
+    pub mode: u32,
+    /// Owner user ID
+    pub uid: u32,
+    /// Owner group ID
+    pub gid: u32,
+    /// On-disk file size, truncated to 32 bits
+    pub size: u32,
+    /// Object ID of the staged blob (or commit, for gitlinks)
+    pub object_id: Vec<u8>,
+    /// 16-bit flags: assume-valid, extended, 2-bit stage, 12-bit name length
+    pub flags: u16,
+    /// Extended flags (version 3+ only, present when the extended bit is set):
+    /// skip-worktree and intent-to-add
+    pub extended_flags: Option<u16>,
+    /// Full path relative to the repository root
+    pub path: String,
+    /// Version 4 only: how many bytes of the previous entry's path were
+    /// dropped before appending this entry's suffix
+    pub prefix_strip_len: Option<usize>,
+    /// Size of the entry on disk, including padding
+    pub size_on_disk: usize,
+}
+
+impl IndexEntry {
+    /// Flag bit: git should assume the file is unchanged (`update-index --assume-unchanged`)
+    pub const FLAG_ASSUME_VALID: u16 = 0x8000;
+    /// Flag bit: an extended flags field follows (version 3+)
+    pub const FLAG_EXTENDED: u16 = 0x4000;
+    /// Mask of the 2-bit merge stage
+    pub const FLAG_STAGE_MASK: u16 = 0x3000;
+    /// Mask of the 12-bit path length (0xFFF means "4095 or longer")
+    pub const FLAG_NAME_MASK: u16 = 0x0fff;
+
+    /// Extended flag bit: path lies outside the sparse checkout
+    pub const EXT_FLAG_SKIP_WORKTREE: u16 = 0x4000;
+    /// Extended flag bit: path was added with `git add -N`
+    pub const EXT_FLAG_INTENT_TO_ADD: u16 = 0x2000;
+
+    /// Merge stage: 0 for a normal entry, 1-3 for base/ours/theirs in a conflict
+    #[must_use]
+    pub const fn stage(&self) -> u8 {
+        ((self.flags & Self::FLAG_STAGE_MASK) >> 12) as u8
+    }
+
+    /// Path length as recorded in the flags (capped at 0xFFF)
+    #[must_use]
+    pub const fn name_length(&self) -> u16 {
+        self.flags & Self::FLAG_NAME_MASK
+    }
+
+    #[must_use]
+    pub const fn is_assume_valid(&self) -> bool {
+        self.flags & Self::FLAG_ASSUME_VALID != 0
+    }
+
+    #[must_use]
+    pub const fn is_extended(&self) -> bool {
+        self.flags & Self::FLAG_EXTENDED != 0
+    }
+
+    #[must_use]
+    pub const fn is_skip_worktree(&self) -> bool {
+        matches!(self.extended_flags, Some(flags) if flags & Self::EXT_FLAG_SKIP_WORKTREE != 0)
+    }
+
+    #[must_use]
+    pub const fn is_intent_to_add(&self) -> bool {
+        matches!(self.extended_flags, Some(flags) if flags & Self::EXT_FLAG_INTENT_TO_ADD != 0)
+    }
+
+    /// Whether this is a sparse directory entry (`index.sparse`): a whole
+    /// directory outside the sparse-checkout cone, stored as its tree
+    #[must_use]
+    pub fn is_sparse_directory(&self) -> bool {
+        self.mode == 0o040_000 && self.path.ends_with('/')
+    }
+
+    /// Object type encoded in the top 4 bits of the mode
+    #[must_use]
+    pub const fn object_type_name(&self) -> &'static str {
+        match self.mode >> 12 {
+            0b1000 => "regular file",
+            0b1010 => "symlink",
+            0b1110 => "gitlink",
+            0b0100 => "directory",
+            _ => "unknown",
+        }
+    }
+
+    /// Hex representation of the staged object ID
+    #[must_use]
+    pub fn oid_hex(&self) -> String {
+        hex::encode(&self.object_id)
+    }
+}
+
+/// A raw index extension block
+///
+/// Extensions follow the entries. Each starts with a four-byte signature and
+/// a 32-bit size. A signature starting with an uppercase letter is optional:
+/// git ignores it if unknown. Anything else must be understood or the index
+/// is rejected.
+#[derive(Debug, Clone)]
+pub struct IndexExtension {
+    /// Four-byte extension signature (e.g. b"TREE")
+    pub signature: [u8; 4],
+    /// Byte offset of the extension header from the start of the file
+    pub offset: usize,
+    /// Extension payload, without the 8-byte header
+    pub data: Vec<u8>,
+}
+
+impl IndexExtension {
+    /// Get the signature as a four-character string
+    ///
+    /// Non-printable bytes are rendered as '?' so unknown signatures stay displayable.
+    #[must_use]
+    pub fn signature_str(&self) -> String {
+        self.signature
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '?' })
+            .collect()
+    }
+
+    /// Whether git may ignore this extension when it doesn't understand it
+    #[must_use]
+    pub const fn is_optional(&self) -> bool {
+        self.signature[0].is_ascii_uppercase()
+    }
+
+    /// Payload size in bytes as recorded in the extension header
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Short human-readable description of the extension's purpose
+    #[must_use]
+    pub const fn description(&self) -> &'static str {
+        match &self.signature {
+            b"TREE" => "Cache tree",
+            b"REUC" => "Resolve undo",
+            b"link" => "Split index link",
+            b"UNTR" => "Untracked cache",
+            b"FSMN" => "File system monitor cache",
+            b"EOIE" => "End of index entry",
+            b"IEOT" => "Index entry offset table",
+            b"sdir" => "Sparse directory entries",
+            _ => "Unknown extension",
+        }
+    }
+}
+
+/// Represents the Git index file (.git/index), also called the staging area
+/// or the directory cache ("DIRC")
+///
+/// The index lists every tracked path with the object ID that the next
+/// commit will record for it, plus cached file-system stat data that lets
+/// git skip rehashing unchanged files.
+#[derive(Debug, Clone)]
+pub struct GitIndex {
+    /// Index format version (2, 3 or 4)
+    pub version: u32,
+    /// Number of entries declared in the header
+    pub entry_count: u32,
+    /// Cache entries, sorted by path and then by stage
+    pub entries: Vec<IndexEntry>,
+    /// Extension blocks in file order
+    pub extensions: Vec<IndexExtension>,
+    /// Decoded split index link extension, if present
+    pub link: Option<LinkExtension>,
+    /// Decoded cache-tree extension (None if absent or undecodable)
+    pub cache_tree: Option<CacheTree>,
+    /// Decoded untracked cache extension (None if absent or undecodable)
+    pub untracked_cache: Option<UntrackedCache>,
+    /// Decoded file system monitor extension (None if absent or undecodable)
+    pub fsmonitor: Option<FsmonitorExtension>,
+    /// Decoded resolve-undo extension (None if absent or undecodable)
+    pub resolve_undo: Option<ResolveUndo>,
+    /// Result of merging with the shared index, filled in by
+    /// [`GitIndex::resolve_split_index`]
+    pub split_index: Option<Result<SplitIndex, String>>,
+    /// Blobs of the conflicted stages keyed by hex object ID, filled in
+    /// by [`GitIndex::resolve_conflict_blobs`]
+    pub conflict_blobs: HashMap<String, Result<LooseObject, String>>,
+    /// Patterns of `info/sparse-checkout`, filled in by
+    /// [`GitIndex::resolve_sparse_directories`]
+    pub sparse_checkout: Option<SparseCheckout>,
+    /// Sparse directory entries with their trees opened, filled in by
+    /// [`GitIndex::resolve_sparse_directories`]
+    pub sparse_directories: Vec<SparseDirectory>,
+    /// Trailing checksum over all preceding bytes (all zeros with `index.skipHash`)
+    pub checksum: Vec<u8>,
+    /// Object ID and checksum length in bytes: 20 (SHA-1) or 32 (SHA-256)
+    pub hash_size: usize,
+    /// Raw data for debugging/display purposes
+    pub raw_data: Vec<u8>,
+}
+
+impl GitIndex {
+    /// Magic signature for index files: "DIRC" (directory cache)
+    pub const SIGNATURE: u32 = 0x4449_5243;
+    /// Supported format versions
+    pub const VERSIONS: [u32; 3] = [2, 3, 4];
+    /// Signature of the (empty) extension marking a sparse index
+    pub const SPARSE_DIRECTORIES_SIGNATURE: [u8; 4] = *b"sdir";
+
+    /// Whether a file name inside a git directory denotes an index file:
+    /// `index` itself or a split index's `sharedindex.<oid>`
+    #[must_use]
+    pub fn is_index_file_name(name: &str) -> bool {
+        name == "index" || name.starts_with("sharedindex.")
+    }
+
+    /// Parse an index file whose object IDs use `object_format`
+    ///
+    /// The header does not record the hash function, so it has to come
+    /// from the repository the index belongs to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if the input is not a valid index file:
+    /// wrong "DIRC" signature, unsupported version, an entry count the
+    /// file can't hold, truncated entries, a version 4 prefix longer than
+    /// the previous path, or extension sizes that overrun the trailing
+    /// checksum.
+    pub fn parse_with_format(input: &[u8], object_format: ObjectFormat) -> IResult<&[u8], Self> {
+        Self::parse_with_hash_size(input, object_format.raw_len())
+    }
+
+    fn parse_with_hash_size(input: &[u8], hash_size: usize) -> IResult<&[u8], Self> {
+        let original_input = input;
+
+        let (mut input, (version, entry_count)) = Self::parse_header(input)?;
+
+        // Every entry takes at least its stat data, object ID and flags;
+        // reject absurd counts before allocating for them
+        if entry_count as usize > input.len() / (STAT_DATA_SIZE + hash_size + 2) {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::TooLarge)));
+        }
+        let mut entries = Vec::with_capacity(entry_count as usize);
+        let mut previous_path: Vec<u8> = Vec::new();
+        for _ in 0..entry_count {
+            let offset = original_input.len() - input.len();
+            let (rest, entry) =
+                Self::parse_entry(input, offset, version, hash_size, &mut previous_path)?;
+            entries.push(entry);
+            input = rest;
+        }
+
+        // Extensions run until only the trailing checksum is left
+        let mut extensions = Vec::new();
+        while input.len() > hash_size {
+            let offset = original_input.len() - input.len();
+            let (rest, signature) = take(4usize)(input)?;
+            let (rest, size) = be_u32(rest)?;
+            if rest.len() < size as usize + hash_size {
+                return Err(nom::Err::Error(Error::new(input, ErrorKind::LengthValue)));
+            }
+            let (rest, data) = take(size as usize)(rest)?;
+
+            let mut signature_bytes = [0u8; 4];
+            signature_bytes.copy_from_slice(signature);
+            extensions.push(IndexExtension {
+                signature: signature_bytes,
+                offset,
+                data: data.to_vec(),
+            });
+            input = rest;
+        }
+
+        let link = match extensions
+            .iter()
+            .find(|ext| ext.signature == LinkExtension::SIGNATURE)
+        {
+            Some(ext) => match LinkExtension::parse(&ext.data, hash_size) {
+                Ok((_, link)) => Some(link),
+                Err(_) => {
+                    let at = &original_input[ext.offset..];
+                    return Err(nom::Err::Error(Error::new(at, ErrorKind::Verify)));
+                }
+            },
+            None => None,
+        };
+
+        // Optional extensions: git ignores them when they don't decode, and so do we
+        let cache_tree = extensions
+            .iter()
+            .find(|ext| ext.signature == CacheTree::SIGNATURE)
+            .and_then(|ext| CacheTree::parse(&ext.data, hash_size).ok())
+            .map(|(_, cache_tree)| cache_tree);
+        let untracked_cache = extensions
+            .iter()
+            .find(|ext| ext.signature == UntrackedCache::SIGNATURE)
+            .and_then(|ext| UntrackedCache::parse(&ext.data, hash_size).ok())
+            .map(|(_, cache)| cache);
+        let fsmonitor = extensions
+            .iter()
+            .find(|ext| ext.signature == FsmonitorExtension::SIGNATURE)
+            .and_then(|ext| FsmonitorExtension::parse(&ext.data).ok())
+            .map(|(_, fsmonitor)| fsmonitor);
+        let resolve_undo = extensions
+            .iter()
+            .find(|ext| ext.signature == ResolveUndo::SIGNATURE)
+            .and_then(|ext| ResolveUndo::parse(&ext.data, hash_size).ok())
+            .map(|(_, resolve_undo)| resolve_undo);
+
+        let (input, checksum_bytes) = take(hash_size)(input)?;
+        let checksum = checksum_bytes.to_vec();
+
+        let consumed = original_input.len() - input.len();
+        let raw_data = original_input[..consumed].to_vec();
+
+        Ok((
+            input,
+            Self {
+                version,
+                entry_count,
+                entries,
+                extensions,
+                link,
+                cache_tree,
+                untracked_cache,
+                fsmonitor,
+                resolve_undo,
+                split_index: None,
+                conflict_blobs: HashMap::new(),
+                sparse_checkout: None,
+                sparse_directories: Vec::new(),
+                checksum,
+                hash_size,
+                raw_data,
+            },
+        ))
+    }
+
+    /// Parse the 12-byte header (signature, version, entry count)
+    fn parse_header(input: &[u8]) -> IResult<&[u8], (u32, u32)> {
+        let (input, signature) = be_u32(input)?;
+        if signature != Self::SIGNATURE {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+
+        let (input, version) = be_u32(input)?;
+        if !Self::VERSIONS.contains(&version) {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+
+        let (input, entry_count) = be_u32(input)?;
+
+        Ok((input, (version, entry_count)))
+    }
+
+    /// Parse one cache entry
+    ///
+    /// `previous_path` carries the last entry's path for version 4 prefix
+    /// compression and is updated to this entry's path.
+    fn parse_entry<'a>(
+        input: &'a [u8],
+        offset: usize,
+        version: u32,
+        hash_size: usize,
+        previous_path: &mut Vec<u8>,
+    ) -> IResult<&'a [u8], IndexEntry> {
+        let entry_start = input;
+
+        let (input, ctime_seconds) = be_u32(input)?;
+        let (input, ctime_nanoseconds) = be_u32(input)?;
+        let (input, mtime_seconds) = be_u32(input)?;
+        let (input, mtime_nanoseconds) = be_u32(input)?;
+        let (input, dev) = be_u32(input)?;
+        let (input, ino) = be_u32(input)?;
+        let (input, mode) = be_u32(input)?;
+        let (input, uid) = be_u32(input)?;
+        let (input, gid) = be_u32(input)?;
+        let (input, size) = be_u32(input)?;
+        let (input, object_id) = take(hash_size)(input)?;
+        let (input, flags) = be_u16(input)?;
+
+        // The extended flag is reserved in version 2
+        let (input, extended_flags) = if flags & IndexEntry::FLAG_EXTENDED == 0 {
+            (input, None)
+        } else if version < 3 {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+        } else {
+            let (input, extended) = be_u16(input)?;
+            (input, Some(extended))
+        };
+
+        let (input, path, prefix_strip_len) = if version == 4 {
+            // Version 4: varint of bytes to drop from the previous path,
+            // then the NUL-terminated suffix; no padding
+            let (input, strip_len) = Self::parse_offset_varint(input)?;
+            let Some(keep_len) = previous_path.len().checked_sub(strip_len) else {
+                return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+            };
+            let (input, suffix) = Self::parse_nul_terminated(input)?;
+
+            let mut path = previous_path[..keep_len].to_vec();
+            path.extend_from_slice(suffix);
+            (input, path, Some(strip_len))
+        } else {
+            // Versions 2 and 3: NUL-terminated path, then 1-8 NUL bytes of
+            // padding so the entry length is a multiple of 8
+            let (_, path) = Self::parse_nul_terminated(input)?;
+            let fixed_size = entry_start.len() - input.len();
+            let padded_size = (fixed_size + path.len() + 8) & !7;
+            let (input, _) = take(padded_size - fixed_size)(input)?;
+            (input, path.to_vec(), None)
+        };
+
+        previous_path.clone_from(&path);
+
+        Ok((
+            input,
+            IndexEntry {
+                offset,
+                ctime: IndexTime {
+                    seconds: ctime_seconds,
+                    nanoseconds: ctime_nanoseconds,
+                },
+                mtime: IndexTime {
+                    seconds: mtime_seconds,
+                    nanoseconds: mtime_nanoseconds,
+                },
+                dev,
+                ino,
+                mode,
+                uid,
+                gid,
+                size,
+                object_id: object_id.to_vec(),
+                flags,
+                extended_flags,
+                path: String::from_utf8_lossy(&path).into_owned(),
+                prefix_strip_len,
+                size_on_disk: entry_start.len() - input.len(),
+            },
+        ))
+    }
+
+    /// Parse a NUL-terminated byte string, consuming the terminator
+    pub(crate) fn parse_nul_terminated(input: &[u8]) -> IResult<&[u8], &[u8]> {
+        let Some(nul_pos) = input.iter().position(|&b| b == 0) else {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Eof)));
+        };
+        Ok((&input[nul_pos + 1..], &input[..nul_pos]))
+    }
+
+    /// Parse git's "offset" varint: big-endian 7-bit groups where every
+    /// continuation adds one before shifting, so each length has exactly
+    /// one encoding
+    pub(crate) fn parse_offset_varint(input: &[u8]) -> IResult<&[u8], usize> {
+        let mut input = input;
+        let Some((&first, rest)) = input.split_first() else {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Eof)));
+        };
+        input = rest;
+
+        let mut byte = first;
+        let mut value = usize::from(byte & 0x7f);
+        while byte & 0x80 != 0 {
+            let Some((&next, rest)) = input.split_first() else {
+                return Err(nom::Err::Error(Error::new(input, ErrorKind::Eof)));
+            };
+            input = rest;
+            byte = next;
+            value = value
+                .checked_add(1)
+                .and_then(|v| v.checked_mul(128))
+                .map(|v| v | usize::from(byte & 0x7f))
+                .ok_or(nom::Err::Error(Error::new(input, ErrorKind::TooLarge)))?;
+        }
+
+        Ok((input, value))
+    }
+
+    /// Number of bytes taken by the stat data and object ID of every entry
+    #[must_use]
+    pub const fn fixed_entry_size(&self) -> usize {
+        STAT_DATA_SIZE + self.hash_size + 2
+    }
+
+    /// Size of the fixed header in bytes
+    #[must_use]
+    pub const fn header_size() -> usize {
+        HEADER_SIZE
+    }
+
+    /// Get the number of parsed entries
+    #[must_use]
+    pub const fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Byte offset where the extensions (or the checksum, if none) start
+    #[must_use]
+    pub fn entries_end_offset(&self) -> usize {
+        self.entries
+            .last()
+            .map_or(HEADER_SIZE, |entry| entry.offset + entry.size_on_disk)
+    }
+
+    /// Find an extension block by its signature
+    #[must_use]
+    pub fn extension(&self, signature: &[u8; 4]) -> Option<&IndexExtension> {
+        self.extensions
+            .iter()
+            .find(|ext| &ext.signature == signature)
+    }
+
+    /// Whether this index is the split part of a split index (`core.splitIndex`)
+    #[must_use]
+    pub fn is_split(&self) -> bool {
+        self.link
+            .as_ref()
+            .is_some_and(|link| link.shared_index_file_name().is_some())
+    }
+
+    /// Fill in everything that needs the surrounding repository: the merged
+    /// split index, the blobs of conflicted stages, the cache-tree checks and
+    /// the trees behind sparse directory entries, read from `database`
+    pub fn resolve_repository_data(&mut self, git_dir: &Path, database: &ObjectDatabase) {
+        self.resolve_split_index(git_dir);
+        self.resolve_conflict_blobs(database);
+        self.verify_cache_tree(database);
+        self.resolve_sparse_directories(git_dir, database);
+    }
+
+    /// Whether this is a sparse index (`index.sparse=true`), which may hold
+    /// sparse directory entries
+    #[must_use]
+    pub fn is_sparse(&self) -> bool {
+        self.extension(&Self::SPARSE_DIRECTORIES_SIGNATURE)
+            .is_some()
+    }
+
+    /// Read the sparse-checkout patterns from `git_dir` and open the tree of
+    /// every sparse directory entry
+    pub fn resolve_sparse_directories(&mut self, git_dir: &Path, database: &ObjectDatabase) {
+        self.sparse_checkout = SparseCheckout::load(git_dir);
+        let sparse_checkout = self.sparse_checkout.as_ref();
+        self.sparse_directories = match &self.split_index {
+            Some(Ok(split_index)) => {
+                let entries: Vec<IndexEntry> = split_index
+                    .entries
+                    .iter()
+                    .map(|merged| merged.entry.clone())
+                    .collect();
+                SparseDirectory::collect(&entries, sparse_checkout, database)
+            }
+            _ => SparseDirectory::collect(&self.entries, sparse_checkout, database),
+        };
+    }
+
+    /// Load the shared index this index links to from `git_dir` and store
+    /// the merge result (or the reason it failed) in `split_index`
+    ///
+    /// Does nothing for indexes without a link extension.
+    pub fn resolve_split_index(&mut self, git_dir: &Path) {
+        self.split_index = SplitIndex::load(git_dir, self)
+            .map_err(|e| e.to_string())
+            .transpose();
+    }
+
+    /// Unmerged paths with their stage 1-3 entries grouped together
+    ///
+    /// Uses the merged entries when this is a resolved split index.
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        match &self.split_index {
+            Some(Ok(split_index)) => {
+                Conflict::group(split_index.entries.iter().map(|merged| &merged.entry))
+            }
+            _ => Conflict::group(&self.entries),
+        }
+    }
+
+    /// Read the blobs of every conflicted stage into `conflict_blobs`
+    ///
+    /// Objects that can't be read are recorded with the reason.
+    pub fn resolve_conflict_blobs(&mut self, database: &ObjectDatabase) {
+        let mut blobs = HashMap::new();
+        for conflict in self.conflicts() {
+            for entry in conflict.stages.iter().flatten() {
+                blobs
+                    .entry(entry.oid_hex())
+                    .or_insert_with(|| database.read(&entry.object_id).map_err(|e| e.to_string()));
+            }
+        }
+        self.conflict_blobs = blobs;
+    }
+
+    /// Check the cache tree against the index entries and the object
+    /// database, storing the result in each node's `status`
+    ///
+    /// Uses the merged entries when this is a resolved split index.
+    pub fn verify_cache_tree(&mut self, database: &ObjectDatabase) {
+        let Some(mut cache_tree) = self.cache_tree.take() else {
+            return;
+        };
+        match &self.split_index {
+            Some(Ok(split_index)) => {
+                let entries: Vec<IndexEntry> = split_index
+                    .entries
+                    .iter()
+                    .map(|merged| merged.entry.clone())
+                    .collect();
+                cache_tree.verify(database, &entries);
+            }
+            _ => cache_tree.verify(database, &self.entries),
+        }
+        self.cache_tree = Some(cache_tree);
+    }
+
+    /// Object format of the index's object IDs
+    #[must_use]
+    pub fn object_format(&self) -> ObjectFormat {
+        ObjectFormat::from_raw_len(self.hash_size).unwrap_or_default()
+    }
+
+    /// Get the hash function name matching the detected object ID size
+    #[must_use]
+    pub const fn hash_function_name(&self) -> &'static str {
+        match self.hash_size {
+            20 => "SHA-1",
+            32 => "SHA-256",
+            _ => "Unknown",
+        }
+    }
+
+    /// True when the trailing checksum was skipped (`index.skipHash=true`)
+    #[must_use]
+    pub fn is_checksum_skipped(&self) -> bool {
+        self.checksum.iter().all(|&b| b == 0)
+    }
+}
+
+impl fmt::Display for GitIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Git Index (version {})", self.version)?;
+        writeln!(f, "Hash function: {}", self.hash_function_name())?;
+        writeln!(f, "Entries: {}", self.entry_count())?;
+        writeln!(f, "Checksum: {}", hex::encode(&self.checksum))?;
+        let conflicts = self.conflicts();
+        if !conflicts.is_empty() {
+            writeln!(f, "Unmerged paths: {}", conflicts.len())?;
+        }
+
+        writeln!(f, "\nSample entries (mode, object ID, stage, path):")?;
+        let entry_sample = std::cmp::min(10, self.entry_count());
+        for entry in &self.entries[..entry_sample] {
+            writeln!(
+                f,
+                "  {:06o} {} {} {}",
+                entry.mode,
+                entry.oid_hex(),
+                entry.stage(),
+                entry.path
+            )?;
+        }
+        if self.entry_count() > entry_sample {
+            writeln!(
+                f,
+                "  ... ({} more entries)",
+                self.entry_count() - entry_sample
+            )?;
+        }
+
+        if !self.extensions.is_empty() {
+            writeln!(f, "\nExtensions:")?;
+            for ext in &self.extensions {
+                writeln!(
+                    f,
+                    "  {} at offset {} ({} bytes) — {}",
+                    ext.signature_str(),
+                    ext.offset,
+                    ext.size(),
+                    ext.description()
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialize one entry's stat data, object ID and flags
+    pub(super) fn entry_fixed_part(oid_byte: u8, hash_size: usize, flags: u16) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in [1u32, 2, 3, 4, 5, 6, 0o100_644, 1000, 1000, 42] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(&vec![oid_byte; hash_size]);
+        data.extend_from_slice(&flags.to_be_bytes());
+        data
+    }
+
+    /// Encode a length the way git's `encode_varint` does
+    pub(super) fn offset_varint(mut value: usize) -> Vec<u8> {
+        let mut bytes = vec![(value & 0x7f) as u8];
+        value >>= 7;
+        while value != 0 {
+            value -= 1;
+            bytes.push(0x80 | (value & 0x7f) as u8);
+            value >>= 7;
+        }
+        bytes.reverse();
+        bytes
+    }
+
+    /// Build an index holding the given paths (stage 0, no extended flags)
+    pub(super) fn create_test_index(version: u32, paths: &[&str], hash_size: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&GitIndex::SIGNATURE.to_be_bytes());
+        data.extend_from_slice(&version.to_be_bytes());
+        data.extend_from_slice(&(paths.len() as u32).to_be_bytes());
+
+        let mut previous: &str = "";
+        for (i, path) in paths.iter().enumerate() {
+            let flags = path.len().min(0xfff) as u16;
+            let mut entry = entry_fixed_part(i as u8 + 1, hash_size, flags);
+            if version == 4 {
+                let common = previous
+                    .bytes()
+                    .zip(path.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                entry.extend_from_slice(&offset_varint(previous.len() - common));
+                entry.extend_from_slice(&path.as_bytes()[common..]);
+                entry.push(0);
+            } else {
+                entry.extend_from_slice(path.as_bytes());
+                let padded = (entry.len() + 8) & !7;
+                entry.resize(padded, 0);
+            }
+            data.extend_from_slice(&entry);
+            previous = path;
+        }
+
+        data
+    }
+
+    pub(super) fn finish(mut data: Vec<u8>, hash_size: usize) -> Vec<u8> {
+        data.extend_from_slice(&vec![0xcc; hash_size]);
+        data
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let data = create_test_index(2, &[], 20);
+        let (remaining, (version, entry_count)) = GitIndex::parse_header(&data).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(version, 2);
+        assert_eq!(entry_count, 0);
+    }
+
+    #[test]
+    fn test_parse_v2_index() {
+        let data = finish(create_test_index(2, &["README.md", "src/main.rs"], 20), 20);
+        let (remaining, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(index.version, 2);
+        assert_eq!(index.entry_count(), 2);
+        assert_eq!(index.hash_size, 20);
+        assert_eq!(index.hash_function_name(), "SHA-1");
+        assert_eq!(index.checksum, vec![0xcc; 20]);
+        assert_eq!(index.raw_data.len(), data.len());
+
+        let first = &index.entries[0];
+        assert_eq!(first.path, "README.md");
+        assert_eq!(first.offset, 12);
+        assert_eq!(first.ctime.seconds, 1);
+        assert_eq!(first.mtime.nanoseconds, 4);
+        assert_eq!(first.mode, 0o100_644);
+        assert_eq!(first.object_type_name(), "regular file");
+        assert_eq!(first.size, 42);
+        assert_eq!(first.object_id, vec![1; 20]);
+        assert_eq!(first.stage(), 0);
+        assert_eq!(first.name_length(), 9);
+        // 62 fixed bytes + 9 path bytes, padded to a multiple of 8
+        assert_eq!(first.size_on_disk, 72);
+
+        assert_eq!(index.entries[1].path, "src/main.rs");
+        assert_eq!(index.entries[1].offset, 12 + 72);
+        assert!(index.entries[1].prefix_strip_len.is_none());
+    }
+
+    #[test]
+    fn test_padding_when_path_fills_the_block() {
+        // 62 + 2 = 64 is already aligned, so a full 8 NUL bytes follow
+        let data = finish(create_test_index(2, &["ab"], 20), 20);
+        let (_, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+
+        assert_eq!(index.entries[0].size_on_disk, 72);
+    }
+
+    #[test]
+    fn test_parse_v3_extended_flags() {
+        let mut data = create_test_index(3, &[], 20);
+        data[8..12].copy_from_slice(&1u32.to_be_bytes());
+        let flags = IndexEntry::FLAG_EXTENDED | 4;
+        let mut entry = entry_fixed_part(7, 20, flags);
+        entry.extend_from_slice(
+            &(IndexEntry::EXT_FLAG_SKIP_WORKTREE | IndexEntry::EXT_FLAG_INTENT_TO_ADD)
+                .to_be_bytes(),
+        );
+        entry.extend_from_slice(b"docs");
+        let padded = (entry.len() + 8) & !7;
+        entry.resize(padded, 0);
+        data.extend_from_slice(&entry);
+        let data = finish(data, 20);
+
+        let (remaining, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+        assert!(remaining.is_empty());
+
+        let entry = &index.entries[0];
+        assert!(entry.is_extended());
+        assert!(entry.is_skip_worktree());
+        assert!(entry.is_intent_to_add());
+        assert_eq!(entry.path, "docs");
+        assert_eq!(entry.size_on_disk, 72);
+    }
+
+    #[test]
+    fn test_extended_flag_rejected_in_v2() {
+        let mut data = create_test_index(2, &[], 20);
+        data[8..12].copy_from_slice(&1u32.to_be_bytes());
+        let mut entry = entry_fixed_part(7, 20, IndexEntry::FLAG_EXTENDED | 1);
+        entry.extend_from_slice(&[0, 0]);
+        entry.extend_from_slice(b"a\0\0\0\0\0");
+        data.extend_from_slice(&entry);
+        let data = finish(data, 20);
+
+        assert!(GitIndex::parse_with_format(&data, ObjectFormat::Sha1).is_err());
+    }
+
+    #[test]
+    fn test_parse_v4_prefix_compression() {
+        let paths = [
+            "src/git/index.rs",
+            "src/git/mod.rs",
+            "src/main.rs",
+            "tests/a.rs",
+        ];
+        let data = finish(create_test_index(4, &paths, 20), 20);
+        let (remaining, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(index.version, 4);
+        let parsed: Vec<&str> = index.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(parsed, paths);
+
+        assert_eq!(index.entries[0].prefix_strip_len, Some(0));
+        // "src/git/index.rs" -> "src/git/mod.rs" drops "index.rs"
+        assert_eq!(index.entries[1].prefix_strip_len, Some(8));
+        // v4 entries are not padded: 62 fixed + 1 varint + "mod.rs\0"
+        assert_eq!(index.entries[1].size_on_disk, 62 + 1 + 7);
+    }
+
+    #[test]
+    fn test_v4_strip_longer_than_previous_path() {
+        let mut data = create_test_index(4, &[], 20);
+        data[8..12].copy_from_slice(&1u32.to_be_bytes());
+        let mut entry = entry_fixed_part(1, 20, 1);
+        entry.push(5); // strip 5 bytes from an empty previous path
+        entry.extend_from_slice(b"a\0");
+        data.extend_from_slice(&entry);
+        let data = finish(data, 20);
+
+        assert!(GitIndex::parse_with_format(&data, ObjectFormat::Sha1).is_err());
+    }
+
+    #[test]
+    fn test_offset_varint() {
+        for value in [0usize, 1, 127, 128, 255, 16_511, 16_512, 1 << 20] {
+            let encoded = offset_varint(value);
+            let (rest, decoded) = GitIndex::parse_offset_varint(&encoded).unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(decoded, value, "round trip of {value}");
+        }
+        // 0x80 0x00 is git's encoding of 128, not 0
+        assert_eq!(GitIndex::parse_offset_varint(&[0x80, 0x00]).unwrap().1, 128);
+    }
+
+    #[test]
+    fn test_parse_extensions() {
+        let mut data = create_test_index(2, &["a"], 20);
+        data.extend_from_slice(b"TREE");
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        data.extend_from_slice(b"sdir");
+        data.extend_from_slice(&0u32.to_be_bytes());
+        let data = finish(data, 20);
+
+        let (remaining, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(index.extensions.len(), 2);
+
+        let tree = index.extension(b"TREE").unwrap();
+        assert_eq!(tree.signature_str(), "TREE");
+        assert_eq!(tree.offset, index.entries_end_offset());
+        assert_eq!(tree.data, vec![1, 2, 3]);
+        assert!(tree.is_optional());
+        assert_eq!(tree.description(), "Cache tree");
+
+        let sdir = index.extension(b"sdir").unwrap();
+        assert!(!sdir.is_optional());
+        assert_eq!(sdir.size(), 0);
+    }
+
+    #[test]
+    fn test_extension_overrunning_checksum() {
+        let mut data = create_test_index(2, &["a"], 20);
+        data.extend_from_slice(b"TREE");
+        data.extend_from_slice(&100u32.to_be_bytes());
+        let data = finish(data, 20);
+
+        assert!(GitIndex::parse_with_format(&data, ObjectFormat::Sha1).is_err());
+    }
+
+    #[test]
+    fn test_parse_sha256_index() {
+        let data = finish(create_test_index(2, &["file.txt"], 32), 32);
+        let (remaining, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha256).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(index.hash_size, 32);
+        assert_eq!(index.hash_function_name(), "SHA-256");
+        assert_eq!(index.entries[0].object_id.len(), 32);
+        assert_eq!(index.entries[0].path, "file.txt");
+    }
+
+    #[test]
+    fn test_parse_with_format() {
+        let data = finish(create_test_index(2, &["file.txt"], 32), 32);
+        let (_, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha256).unwrap();
+        assert_eq!(index.object_format(), ObjectFormat::Sha256);
+        assert_eq!(index.entries[0].path, "file.txt");
+
+        assert!(GitIndex::parse_with_format(&data, ObjectFormat::Sha1).is_err());
+    }
+
+    #[test]
+    fn test_invalid_signature_and_version() {
+        let mut data = finish(create_test_index(2, &[], 20), 20);
+        data[0] = b'X';
+        assert!(GitIndex::parse_with_format(&data, ObjectFormat::Sha1).is_err());
+
+        let data = finish(create_test_index(5, &[], 20), 20);
+        assert!(GitIndex::parse_with_format(&data, ObjectFormat::Sha1).is_err());
+    }
+
+    #[test]
+    fn test_entry_count_larger_than_the_file() {
+        let mut data = b"DIRC".to_vec();
+        data.extend_from_slice(&2u32.to_be_bytes());
+        data.extend_from_slice(&u32::MAX.to_be_bytes());
+        data.extend_from_slice(&[0; 20]);
+
+        assert!(matches!(
+            GitIndex::parse_with_format(&data, ObjectFormat::Sha1),
+            Err(nom::Err::Error(Error {
+                code: ErrorKind::TooLarge,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_index() {
+        let data = finish(create_test_index(2, &["README.md"], 20), 20);
+
+        assert!(GitIndex::parse_with_format(&data[..40], ObjectFormat::Sha1).is_err());
+    }
+
+    #[test]
+    fn test_display_format() {
+        let data = finish(create_test_index(2, &["README.md"], 20), 20);
+        let (_, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+
+        let display_str = format!("{index}");
+        assert!(display_str.contains("Git Index (version 2)"));
+        assert!(display_str.contains("Entries: 1"));
+        assert!(display_str.contains("100644"));
+        assert!(display_str.contains("README.md"));
+    }
+}
@@ -0,0 +1,152 @@
+use nom::{
+    IResult,
+    bytes::complete::take,
+    error::{Error, ErrorKind},
+};
+
+use super::GitIndex;
+
+/// The stages a resolved conflict had, as recorded in the resolve-undo extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveUndoEntry {
+    /// Path of the resolved file
+    pub path: String,
+    /// Modes of stages 1 (base), 2 (ours) and 3 (theirs); 0 if the stage was absent
+    pub modes: [u32; 3],
+    /// Object IDs of the stages, present exactly where the mode is nonzero
+    pub object_ids: [Option<Vec<u8>>; 3],
+}
+
+impl ResolveUndoEntry {
+    /// Hex object ID of a stage (1-3), if that stage was present
+    #[must_use]
+    pub fn stage_oid_hex(&self, stage: usize) -> Option<String> {
+        self.object_ids
+            .get(stage.checked_sub(1)?)?
+            .as_ref()
+            .map(hex::encode)
+    }
+}
+
+/// The resolve-undo extension ("REUC")
+///
+/// When `git add` resolves a conflicted path, the stage 1-3 entries it drops
+/// are remembered here so `git checkout -m <path>` (or `git update-index
+/// --unresolve`) can bring the conflict back.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveUndo {
+    /// Recorded paths in file order
+    pub entries: Vec<ResolveUndoEntry>,
+}
+
+impl ResolveUndo {
+    /// Extension signature
+    pub const SIGNATURE: [u8; 4] = *b"REUC";
+
+    /// Parse the payload of a resolve-undo extension
+    ///
+    /// Each record is a NUL-terminated path, three NUL-terminated ASCII
+    /// octal modes and one object ID per nonzero mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if a path or mode is not NUL-terminated, a
+    /// mode is not valid octal, or an object ID is truncated.
+    pub fn parse(mut input: &[u8], hash_size: usize) -> IResult<&[u8], Self> {
+        let mut entries = Vec::new();
+
+        while !input.is_empty() {
+            let (rest, path) = GitIndex::parse_nul_terminated(input)?;
+            let mut rest = rest;
+
+            let mut modes = [0u32; 3];
+            for mode in &mut modes {
+                let (after, digits) = GitIndex::parse_nul_terminated(rest)?;
+                *mode = std::str::from_utf8(digits)
+                    .ok()
+                    .and_then(|digits| u32::from_str_radix(digits, 8).ok())
+                    .ok_or_else(|| nom::Err::Error(Error::new(rest, ErrorKind::Digit)))?;
+                rest = after;
+            }
+
+            let mut object_ids: [Option<Vec<u8>>; 3] = [None, None, None];
+            for (object_id, &mode) in object_ids.iter_mut().zip(&modes) {
+                if mode != 0 {
+                    let (after, oid) = take(hash_size)(rest)?;
+                    *object_id = Some(oid.to_vec());
+                    rest = after;
+                }
+            }
+
+            entries.push(ResolveUndoEntry {
+                path: String::from_utf8_lossy(path).into_owned(),
+                modes,
+                object_ids,
+            });
+            input = rest;
+        }
+
+        Ok((input, Self { entries }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(path: &str, modes: [u32; 3], oid_byte: u8, hash_size: usize) -> Vec<u8> {
+        let mut data = path.as_bytes().to_vec();
+        data.push(0);
+        for mode in modes {
+            data.extend_from_slice(format!("{mode:o}").as_bytes());
+            data.push(0);
+        }
+        for mode in modes {
+            if mode != 0 {
+                data.extend_from_slice(&vec![oid_byte; hash_size]);
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let mut data = record("a.txt", [0o100_644; 3], 0xaa, 20);
+        data.extend(record("b.txt", [0, 0o100_644, 0o100_755], 0xbb, 20));
+
+        let (remaining, reuc) = ResolveUndo::parse(&data, 20).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(reuc.entries.len(), 2);
+
+        let first = &reuc.entries[0];
+        assert_eq!(first.path, "a.txt");
+        assert_eq!(first.modes, [0o100_644; 3]);
+        assert_eq!(first.stage_oid_hex(1), Some("aa".repeat(20)));
+
+        let second = &reuc.entries[1];
+        assert_eq!(second.modes, [0, 0o100_644, 0o100_755]);
+        assert_eq!(second.object_ids[0], None);
+        assert_eq!(second.stage_oid_hex(1), None);
+        assert_eq!(second.stage_oid_hex(3), Some("bb".repeat(20)));
+        assert_eq!(second.stage_oid_hex(4), None);
+    }
+
+    #[test]
+    fn test_parse_sha256() {
+        let data = record("x", [0o120_000, 0, 0], 0x11, 32);
+        let (_, reuc) = ResolveUndo::parse(&data, 32).unwrap();
+        assert_eq!(reuc.entries[0].object_ids[0], Some(vec![0x11; 32]));
+    }
+
+    #[test]
+    fn test_invalid_records() {
+        // Not octal
+        assert!(ResolveUndo::parse(b"a\x00100644\x009\x000\x00", 20).is_err());
+        // Object ID truncated
+        let mut data = record("a", [0o100_644, 0, 0], 0xaa, 20);
+        data.truncate(data.len() - 1);
+        assert!(ResolveUndo::parse(&data, 20).is_err());
+        // Missing terminator
+        assert!(ResolveUndo::parse(b"a\x00100644", 20).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::git::loose_object::{ParsedContent, TreeEntryType};
+use crate::git::object_database::ObjectDatabase;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+
+use super::IndexEntry;
+
+/// How deep the trees behind sparse directory entries are opened
+pub const MAX_TREE_DEPTH: usize = 3;
+/// Tree entries read per tree; the rest are only counted
+pub const MAX_TREE_ENTRIES: usize = 100;
+
+/// Where a path stands relative to the sparse-checkout cone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConeMembership {
+    /// Everything below the directory is checked out
+    InCone,
+    /// Only the files directly inside the directory are checked out
+    ParentOnly,
+    /// The path is not checked out
+    OutOfCone,
+}
+
+impl fmt::Display for ConeMembership {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::InCone => "in cone",
+            Self::ParentOnly => "cone parent",
+            Self::OutOfCone => "out of cone",
+        })
+    }
+}
+
+/// The patterns of `.git/info/sparse-checkout`
+///
+/// In cone mode the file only contains patterns of a few fixed shapes:
+/// `/*` and `!/*/` for the root files, `/dir/` for a directory included
+/// recursively, and `!/dir/*/` to narrow `dir` down to its direct files.
+#[derive(Debug, Clone, Default)]
+pub struct SparseCheckout {
+    /// Pattern lines, without comments and blank lines
+    pub patterns: Vec<String>,
+    /// Whether every pattern has one of the cone-mode shapes
+    pub cone: bool,
+    /// Directories included with everything below them
+    pub recursive: BTreeSet<String>,
+    /// Directories whose direct files are included, but not their subdirectories
+    pub parents: BTreeSet<String>,
+}
+
+impl SparseCheckout {
+    /// Parse the contents of a sparse-checkout file
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let patterns: Vec<String> = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+
+        let mut cone = true;
+        let mut recursive = BTreeSet::new();
+        let mut parents = BTreeSet::new();
+        for pattern in &patterns {
+            if pattern == "/*" || pattern == "!/*/" {
+                continue;
+            }
+            if let Some(dir) = pattern
+                .strip_prefix("!/")
+                .and_then(|rest| rest.strip_suffix("/*/"))
+            {
+                recursive.remove(dir);
+                parents.insert(dir.to_string());
+            } else if let Some(dir) = pattern
+                .strip_prefix('/')
+                .and_then(|rest| rest.strip_suffix('/'))
+                .filter(|dir| !dir.is_empty() && !dir.contains('*'))
+            {
+                if !parents.contains(dir) {
+                    recursive.insert(dir.to_string());
+                }
+            } else {
+                cone = false;
+            }
+        }
+
+        Self {
+            patterns,
+            cone,
+            recursive,
+            parents,
+        }
+    }
+
+    /// Read `info/sparse-checkout` from a git directory, if there is one
+    #[must_use]
+    pub fn load(git_dir: &Path) -> Option<Self> {
+        std::fs::read_to_string(git_dir.join("info").join("sparse-checkout"))
+            .ok()
+            .map(|content| Self::parse(&content))
+    }
+
+    /// Cone membership of a directory ("a/b", no trailing slash)
+    ///
+    /// Returns None when the patterns are not in cone mode.
+    #[must_use]
+    pub fn directory_membership(&self, dir: &str) -> Option<ConeMembership> {
+        if !self.cone {
+            return None;
+        }
+        let dir = dir.trim_end_matches('/');
+        let in_recursive = self.recursive.iter().any(|included| {
+            dir == included
+                || dir
+                    .strip_prefix(included.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        });
+        Some(if in_recursive {
+            ConeMembership::InCone
+        } else if self.parents.contains(dir) {
+            ConeMembership::ParentOnly
+        } else {
+            ConeMembership::OutOfCone
+        })
+    }
+
+    /// Whether a file is checked out under the cone patterns
+    ///
+    /// Returns None when the patterns are not in cone mode.
+    #[must_use]
+    pub fn file_in_cone(&self, path: &str) -> Option<bool> {
+        let parent = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+        if parent.is_empty() {
+            return self.cone.then_some(true);
+        }
+        self.directory_membership(parent)
+            .map(|membership| membership != ConeMembership::OutOfCone)
+    }
+}
+
+/// An entry of a tree opened from the object database
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    pub name: String,
+    pub mode: String,
+    /// Hex object ID
+    pub oid: String,
+    pub is_tree: bool,
+    /// Subtree contents: None when not opened (depth limit) or not a tree
+    pub children: Option<Result<Vec<TreeNode>, String>>,
+}
+
+/// A sparse directory entry: a directory outside the sparse-checkout cone
+/// collapsed into a single index entry pointing at its tree
+#[derive(Debug, Clone)]
+pub struct SparseDirectory {
+    /// Directory path with its trailing slash, as stored in the index
+    pub path: String,
+    /// Hex object ID of the tree
+    pub tree_oid: String,
+    /// Cone membership according to `info/sparse-checkout` (None if not in cone mode)
+    pub membership: Option<ConeMembership>,
+    /// Tree contents read from the object database
+    pub tree: Result<Vec<TreeNode>, String>,
+    /// Entries of the top-level tree beyond [`MAX_TREE_ENTRIES`]
+    pub omitted_entries: usize,
+}
+
+impl SparseDirectory {
+    /// Build the sparse directory view of every sparse directory entry
+    #[must_use]
+    pub fn collect(
+        entries: &[IndexEntry],
+        sparse_checkout: Option<&SparseCheckout>,
+        database: &ObjectDatabase,
+    ) -> Vec<Self> {
+        entries
+            .iter()
+            .filter(|entry| entry.is_sparse_directory())
+            .map(|entry| {
+                let (tree, omitted_entries) = match open_tree(database, &entry.object_id, 1) {
+                    Ok((nodes, omitted)) => (Ok(nodes), omitted),
+                    Err(e) => (Err(e), 0),
+                };
+                Self {
+                    path: entry.path.clone(),
+                    tree_oid: entry.oid_hex(),
+                    membership: sparse_checkout
+                        .and_then(|sparse| sparse.directory_membership(&entry.path)),
+                    tree,
+                    omitted_entries,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Read a tree and, down to [`MAX_TREE_DEPTH`], its subtrees
+fn open_tree(
+    database: &ObjectDatabase,
+    oid: &[u8],
+    depth: usize,
+) -> Result<(Vec<TreeNode>, usize), String> {
+    let object = database.read(oid).map_err(|e| e.to_string())?;
+    let Some(ParsedContent::Tree(tree)) = object.parsed_content else {
+        return Err(format!(
+            "{} is a {}, not a tree",
+            object.object_id, object.object_type
+        ));
+    };
+
+    let omitted = tree.entries.len().saturating_sub(MAX_TREE_ENTRIES);
+    let nodes = tree
+        .entries
+        .into_iter()
+        .take(MAX_TREE_ENTRIES)
+        .map(|entry| {
+            let is_tree = entry.object_type == TreeEntryType::Tree;
+            let children = (is_tree && depth < MAX_TREE_DEPTH).then(|| {
+                hex::decode(&entry.sha1)
+                    .map_err(|e| e.to_string())
+                    .and_then(|child| open_tree(database, &child, depth + 1))
+                    .map(|(children, _)| children)
+            });
+            TreeNode {
+                name: entry.name,
+                mode: entry.mode,
+                oid: entry.sha1,
+                is_tree,
+                children,
+            }
+        })
+        .collect();
+    Ok((nodes, omitted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONE: &str = "/*\n!/*/\n/docs/\n!/docs/*/\n/docs/guide/\n/src/\n";
+
+    #[test]
+    fn test_parse_cone_patterns() {
+        let sparse = SparseCheckout::parse(CONE);
+        assert!(sparse.cone);
+        assert_eq!(sparse.patterns.len(), 6);
+        assert!(sparse.recursive.contains("src"));
+        assert!(sparse.recursive.contains("docs/guide"));
+        assert!(sparse.parents.contains("docs"));
+        assert!(!sparse.recursive.contains("docs"));
+    }
+
+    #[test]
+    fn test_directory_membership() {
+        let sparse = SparseCheckout::parse(CONE);
+        assert_eq!(
+            sparse.directory_membership("src/deep/dir/"),
+            Some(ConeMembership::InCone)
+        );
+        assert_eq!(
+            sparse.directory_membership("docs"),
+            Some(ConeMembership::ParentOnly)
+        );
+        assert_eq!(
+            sparse.directory_membership("docs/api/"),
+            Some(ConeMembership::OutOfCone)
+        );
+        assert_eq!(
+            sparse.directory_membership("srcs/"),
+            Some(ConeMembership::OutOfCone)
+        );
+
+        assert_eq!(sparse.file_in_cone("README.md"), Some(true));
+        assert_eq!(sparse.file_in_cone("docs/index.md"), Some(true));
+        assert_eq!(sparse.file_in_cone("docs/api/x.md"), Some(false));
+        assert_eq!(sparse.file_in_cone("src/a/b.rs"), Some(true));
+    }
+
+    #[test]
+    fn test_non_cone_patterns() {
+        let sparse = SparseCheckout::parse("# comment\n*.md\n/src/\n");
+        assert!(!sparse.cone);
+        assert_eq!(sparse.patterns, ["*.md", "/src/"]);
+        assert_eq!(sparse.directory_membership("src/"), None);
+        assert_eq!(sparse.file_in_cone("a.md"), None);
+    }
+}
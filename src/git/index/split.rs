@@ -0,0 +1,488 @@
+use crate::git::pack::bitmap::EwahBitmap;
+use nom::{
+    IResult,
+    bytes::complete::take,
+    error::{Error, ErrorKind},
+};
+use std::fmt;
+use std::path::Path;
+use thiserror::Error;
+
+use super::{GitIndex, IndexEntry};
+
+/// The split index "link" extension
+///
+/// With `core.splitIndex` the index is split in two: a large, rarely
+/// rewritten shared index (`.git/sharedindex.<oid>`) and the small
+/// `.git/index` holding only the changes on top of it. The link extension
+/// names the shared index and records which of its entries were deleted
+/// or replaced.
+#[derive(Debug, Clone)]
+pub struct LinkExtension {
+    /// Object ID (checksum) of the shared index file; all zeros means none
+    pub shared_index_oid: Vec<u8>,
+    /// Bit i set: shared index entry i is removed
+    pub delete_bitmap: Option<EwahBitmap>,
+    /// Bit i set: shared index entry i is replaced by the next nameless
+    /// entry of the split index
+    pub replace_bitmap: Option<EwahBitmap>,
+}
+
+impl LinkExtension {
+    /// Extension signature
+    pub const SIGNATURE: [u8; 4] = *b"link";
+
+    /// Parse the payload of a link extension
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if the payload is shorter than an object ID,
+    /// either EWAH bitmap is truncated, or bytes are left after the replace
+    /// bitmap.
+    pub fn parse(input: &[u8], hash_size: usize) -> IResult<&[u8], Self> {
+        let (input, oid) = take(hash_size)(input)?;
+
+        // A bare object ID means nothing was deleted or replaced
+        if input.is_empty() {
+            return Ok((
+                input,
+                Self {
+                    shared_index_oid: oid.to_vec(),
+                    delete_bitmap: None,
+                    replace_bitmap: None,
+                },
+            ));
+        }
+
+        let (input, delete_bitmap) = EwahBitmap::parse(input)?;
+        let (input, replace_bitmap) = EwahBitmap::parse(input)?;
+        if !input.is_empty() {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Eof)));
+        }
+
+        Ok((
+            input,
+            Self {
+                shared_index_oid: oid.to_vec(),
+                delete_bitmap: Some(delete_bitmap),
+                replace_bitmap: Some(replace_bitmap),
+            },
+        ))
+    }
+
+    /// Hex representation of the shared index object ID
+    #[must_use]
+    pub fn shared_index_oid_hex(&self) -> String {
+        hex::encode(&self.shared_index_oid)
+    }
+
+    /// File name of the shared index, or `None` when the link points nowhere
+    #[must_use]
+    pub fn shared_index_file_name(&self) -> Option<String> {
+        if self.shared_index_oid.iter().all(|&b| b == 0) {
+            None
+        } else {
+            Some(format!("sharedindex.{}", self.shared_index_oid_hex()))
+        }
+    }
+
+    /// Shared index positions marked as deleted
+    #[must_use]
+    pub fn deleted_positions(&self) -> Vec<u64> {
+        self.delete_bitmap
+            .as_ref()
+            .map(EwahBitmap::set_bit_positions)
+            .unwrap_or_default()
+    }
+
+    /// Shared index positions marked as replaced
+    #[must_use]
+    pub fn replaced_positions(&self) -> Vec<u64> {
+        self.replace_bitmap
+            .as_ref()
+            .map(EwahBitmap::set_bit_positions)
+            .unwrap_or_default()
+    }
+}
+
+/// Where an entry of the merged index came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntrySource {
+    /// Taken unchanged from the shared index
+    Shared { position: usize },
+    /// A shared index entry whose data was replaced by a split index entry
+    Replaced {
+        shared_position: usize,
+        split_position: usize,
+    },
+    /// Added by the split index (`.git/index`)
+    Split { position: usize },
+}
+
+impl fmt::Display for EntrySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Shared { position } => write!(f, "shared #{position}"),
+            Self::Replaced {
+                shared_position,
+                split_position,
+            } => write!(
+                f,
+                "split #{split_position} replaces shared #{shared_position}"
+            ),
+            Self::Split { position } => write!(f, "split #{position}"),
+        }
+    }
+}
+
+/// One entry of the merged index together with its origin
+#[derive(Debug, Clone)]
+pub struct MergedEntry {
+    pub entry: IndexEntry,
+    pub source: EntrySource,
+}
+
+#[derive(Debug, Error)]
+pub enum SplitIndexError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    #[error("Shared index checksum {found} does not match the link extension ({expected})")]
+    ChecksumMismatch { expected: String, found: String },
+
+    #[error("Position {position} is outside the shared index ({entry_count} entries)")]
+    PositionOutOfRange { position: u64, entry_count: usize },
+
+    #[error("Shared index entry {0} is marked as both replaced and deleted")]
+    ReplacedAndDeleted(u64),
+
+    #[error("More replacements than split index entries ({0})")]
+    TooManyReplacements(usize),
+
+    #[error("Split index entry {0} should have a zero-length name (it replaces a shared entry)")]
+    ReplacementHasName(usize),
+
+    #[error("Split index entry {0} should have a name (it is added on top of the shared index)")]
+    AdditionWithoutName(usize),
+}
+
+/// A split index merged with its shared index, as git sees it in memory
+#[derive(Debug, Clone)]
+pub struct SplitIndex {
+    /// File name of the shared index (`sharedindex.<oid>`)
+    pub shared_index_name: String,
+    /// The parsed shared index file
+    pub shared_index: Box<GitIndex>,
+    /// Final entries after applying replacements, deletions and additions
+    pub entries: Vec<MergedEntry>,
+    /// Number of shared entries removed through the delete bitmap
+    pub deleted_count: usize,
+    /// Number of shared entries overwritten through the replace bitmap
+    pub replaced_count: usize,
+    /// Number of entries the split index adds or overrides by path
+    pub added_count: usize,
+}
+
+impl SplitIndex {
+    /// Read the shared index referenced by `split`'s link extension from
+    /// `git_dir` and merge the two
+    ///
+    /// Returns `Ok(None)` when the index has no link extension or the link
+    /// does not name a shared index.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SplitIndexError` if the shared index cannot be read or
+    /// parsed, its checksum differs from the one in the link extension, or
+    /// the bitmaps are inconsistent with the entries of both files.
+    pub fn load(git_dir: &Path, split: &GitIndex) -> Result<Option<Self>, SplitIndexError> {
+        let Some(link) = &split.link else {
+            return Ok(None);
+        };
+        let Some(shared_index_name) = link.shared_index_file_name() else {
+            return Ok(None);
+        };
+
+        let data = std::fs::read(git_dir.join(&shared_index_name))?;
+        // The shared index names objects with the same hash function
+        let (_, shared_index) = GitIndex::parse_with_format(&data, split.object_format())
+            .map_err(|e| SplitIndexError::ParseError(format!("{e:?}")))?;
+
+        if shared_index.checksum != link.shared_index_oid {
+            return Err(SplitIndexError::ChecksumMismatch {
+                expected: link.shared_index_oid_hex(),
+                found: hex::encode(&shared_index.checksum),
+            });
+        }
+
+        Self::merge(split, link, shared_index, shared_index_name).map(Some)
+    }
+
+    /// Apply a split index on top of its shared index
+    ///
+    /// Mirrors git's `merge_base_index()`: replacements consume the leading
+    /// nameless split entries in order, deletions are dropped, and every
+    /// remaining split entry is inserted by path and stage, overriding a
+    /// shared entry with the same path and stage.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SplitIndexError` if a bitmap position lies outside the
+    /// shared index, an entry is both replaced and deleted, or the nameless
+    /// replacement entries don't line up with the replace bitmap.
+    pub fn merge(
+        split: &GitIndex,
+        link: &LinkExtension,
+        shared_index: GitIndex,
+        shared_index_name: String,
+    ) -> Result<Self, SplitIndexError> {
+        let shared_count = shared_index.entries.len();
+        let mut slots: Vec<Option<MergedEntry>> = shared_index
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| {
+                Some(MergedEntry {
+                    entry: entry.clone(),
+                    source: EntrySource::Shared { position },
+                })
+            })
+            .collect();
+
+        let check_range = |position: u64| {
+            usize::try_from(position)
+                .ok()
+                .filter(|&p| p < shared_count)
+                .ok_or(SplitIndexError::PositionOutOfRange {
+                    position,
+                    entry_count: shared_count,
+                })
+        };
+
+        let replaced = link.replaced_positions();
+        for (split_position, &position) in replaced.iter().enumerate() {
+            let shared_position = check_range(position)?;
+            let Some(source_entry) = split.entries.get(split_position) else {
+                return Err(SplitIndexError::TooManyReplacements(split.entries.len()));
+            };
+            if !source_entry.path.is_empty() {
+                return Err(SplitIndexError::ReplacementHasName(split_position));
+            }
+            if let Some(merged) = &mut slots[shared_position] {
+                let path = std::mem::take(&mut merged.entry.path);
+                merged.entry = source_entry.clone();
+                merged.entry.path = path;
+                merged.source = EntrySource::Replaced {
+                    shared_position,
+                    split_position,
+                };
+            }
+        }
+
+        let deleted = link.deleted_positions();
+        for &position in &deleted {
+            let shared_position = check_range(position)?;
+            if replaced.contains(&position) {
+                return Err(SplitIndexError::ReplacedAndDeleted(position));
+            }
+            slots[shared_position] = None;
+        }
+
+        let mut entries: Vec<MergedEntry> = slots.into_iter().flatten().collect();
+
+        for (position, entry) in split.entries.iter().enumerate().skip(replaced.len()) {
+            if entry.path.is_empty() {
+                return Err(SplitIndexError::AdditionWithoutName(position));
+            }
+            let merged = MergedEntry {
+                entry: entry.clone(),
+                source: EntrySource::Split { position },
+            };
+            let key = (entry.path.as_str(), entry.stage());
+            match entries.binary_search_by(|e| (e.entry.path.as_str(), e.entry.stage()).cmp(&key)) {
+                Ok(existing) => entries[existing] = merged,
+                Err(insert_at) => {
+                    // A merged (stage 0) entry resolves any conflict stages
+                    if entry.stage() == 0 {
+                        while entries
+                            .get(insert_at)
+                            .is_some_and(|e| e.entry.path == entry.path)
+                        {
+                            entries.remove(insert_at);
+                        }
+                    }
+                    entries.insert(insert_at, merged);
+                }
+            }
+        }
+
+        Ok(Self {
+            shared_index_name,
+            shared_index: Box::new(shared_index),
+            entries,
+            deleted_count: deleted.len(),
+            replaced_count: replaced.len(),
+            added_count: split.entries.len() - replaced.len(),
+        })
+    }
+
+    /// Number of merged entries that still come unchanged from the shared index
+    #[must_use]
+    pub fn unchanged_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.source, EntrySource::Shared { .. }))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::index::tests::{create_test_index, finish};
+    use crate::git::object_format::ObjectFormat;
+    use crate::git::pack::bitmap::tests::ewah_bytes_with_bits;
+
+    fn link_payload(oid_byte: u8, deleted: &[u64], replaced: &[u64]) -> Vec<u8> {
+        let mut data = vec![oid_byte; 20];
+        data.extend_from_slice(&ewah_bytes_with_bits(8, deleted));
+        data.extend_from_slice(&ewah_bytes_with_bits(8, replaced));
+        data
+    }
+
+    fn parse_index(paths: &[&str]) -> GitIndex {
+        let data = finish(create_test_index(2, paths, 20), 20);
+        GitIndex::parse_with_format(&data, ObjectFormat::Sha1)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_parse_link_extension() {
+        let payload = link_payload(0xab, &[1], &[0, 2]);
+        let (remaining, link) = LinkExtension::parse(&payload, 20).unwrap();
+
+        assert!(remaining.is_empty());
+        assert_eq!(link.deleted_positions(), vec![1]);
+        assert_eq!(link.replaced_positions(), vec![0, 2]);
+        assert_eq!(
+            link.shared_index_file_name(),
+            Some(format!("sharedindex.{}", "ab".repeat(20)))
+        );
+    }
+
+    #[test]
+    fn test_parse_link_extension_without_bitmaps() {
+        let (_, link) = LinkExtension::parse(&[0u8; 20], 20).unwrap();
+
+        assert!(link.delete_bitmap.is_none());
+        assert!(link.deleted_positions().is_empty());
+        assert_eq!(link.shared_index_file_name(), None);
+    }
+
+    #[test]
+    fn test_link_extension_trailing_garbage() {
+        let mut payload = link_payload(1, &[], &[]);
+        payload.push(0);
+
+        assert!(LinkExtension::parse(&payload, 20).is_err());
+    }
+
+    #[test]
+    fn test_link_decoded_by_index_parser() {
+        let mut data = create_test_index(2, &["a"], 20);
+        let payload = link_payload(7, &[], &[]);
+        data.extend_from_slice(&LinkExtension::SIGNATURE);
+        data.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        data.extend_from_slice(&payload);
+        let data = finish(data, 20);
+
+        let (_, index) = GitIndex::parse_with_format(&data, ObjectFormat::Sha1).unwrap();
+        let link = index.link.as_ref().unwrap();
+        assert_eq!(link.shared_index_oid, vec![7; 20]);
+    }
+
+    #[test]
+    fn test_merge_replace_delete_add() {
+        let shared = parse_index(&["a", "b", "c"]);
+        // One replacement for "a", then "bb" added; "c" deleted
+        let split = parse_index(&["", "bb"]);
+        let (_, link) = LinkExtension::parse(&link_payload(1, &[2], &[0]), 20).unwrap();
+
+        let merged = SplitIndex::merge(&split, &link, shared, "sharedindex.x".into()).unwrap();
+        let paths: Vec<&str> = merged
+            .entries
+            .iter()
+            .map(|e| e.entry.path.as_str())
+            .collect();
+        assert_eq!(paths, ["a", "b", "bb"]);
+
+        assert_eq!(
+            merged.entries[0].source,
+            EntrySource::Replaced {
+                shared_position: 0,
+                split_position: 0
+            }
+        );
+        // Replacement data comes from the split entry
+        assert_eq!(merged.entries[0].entry.object_id, vec![1; 20]);
+        assert_eq!(
+            merged.entries[1].source,
+            EntrySource::Shared { position: 1 }
+        );
+        assert_eq!(merged.entries[2].source, EntrySource::Split { position: 1 });
+        assert_eq!(merged.deleted_count, 1);
+        assert_eq!(merged.replaced_count, 1);
+        assert_eq!(merged.added_count, 1);
+        assert_eq!(merged.unchanged_count(), 1);
+    }
+
+    #[test]
+    fn test_merge_addition_overrides_same_path() {
+        let shared = parse_index(&["a", "b"]);
+        let split = parse_index(&["b"]);
+        let (_, link) = LinkExtension::parse(&link_payload(1, &[], &[]), 20).unwrap();
+
+        let merged = SplitIndex::merge(&split, &link, shared, String::new()).unwrap();
+        assert_eq!(merged.entries.len(), 2);
+        assert_eq!(merged.entries[1].source, EntrySource::Split { position: 0 });
+    }
+
+    #[test]
+    fn test_merge_rejects_inconsistent_bitmaps() {
+        let (_, link) = LinkExtension::parse(&link_payload(1, &[5], &[]), 20).unwrap();
+        let result =
+            SplitIndex::merge(&parse_index(&[]), &link, parse_index(&["a"]), String::new());
+        assert!(matches!(
+            result,
+            Err(SplitIndexError::PositionOutOfRange { position: 5, .. })
+        ));
+
+        let (_, link) = LinkExtension::parse(&link_payload(1, &[0], &[0]), 20).unwrap();
+        let result = SplitIndex::merge(
+            &parse_index(&[""]),
+            &link,
+            parse_index(&["a"]),
+            String::new(),
+        );
+        assert!(matches!(
+            result,
+            Err(SplitIndexError::ReplacedAndDeleted(0))
+        ));
+
+        let (_, link) = LinkExtension::parse(&link_payload(1, &[], &[0]), 20).unwrap();
+        let result = SplitIndex::merge(
+            &parse_index(&["x"]),
+            &link,
+            parse_index(&["a"]),
+            String::new(),
+        );
+        assert!(matches!(
+            result,
+            Err(SplitIndexError::ReplacementHasName(0))
+        ));
+    }
+}
@@ -0,0 +1,426 @@
+use crate::git::pack::bitmap::EwahBitmap;
+use nom::{
+    IResult,
+    bytes::complete::take,
+    error::{Error, ErrorKind},
+    number::complete::be_u32,
+};
+
+use super::{GitIndex, IndexTime};
+
+/// Stat data as stored by the untracked cache: like an index entry's stat
+/// data, but without the mode field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachedStat {
+    pub ctime: IndexTime,
+    pub mtime: IndexTime,
+    pub dev: u32,
+    pub ino: u32,
+    pub uid: u32,
+    pub gid: u32,
+    pub size: u32,
+}
+
+impl CachedStat {
+    /// Size of the on-disk representation
+    pub const SIZE: usize = 36;
+
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (input, ctime_seconds) = be_u32(input)?;
+        let (input, ctime_nanoseconds) = be_u32(input)?;
+        let (input, mtime_seconds) = be_u32(input)?;
+        let (input, mtime_nanoseconds) = be_u32(input)?;
+        let (input, dev) = be_u32(input)?;
+        let (input, ino) = be_u32(input)?;
+        let (input, uid) = be_u32(input)?;
+        let (input, gid) = be_u32(input)?;
+        let (input, size) = be_u32(input)?;
+
+        Ok((
+            input,
+            Self {
+                ctime: IndexTime {
+                    seconds: ctime_seconds,
+                    nanoseconds: ctime_nanoseconds,
+                },
+                mtime: IndexTime {
+                    seconds: mtime_seconds,
+                    nanoseconds: mtime_nanoseconds,
+                },
+                dev,
+                ino,
+                uid,
+                gid,
+                size,
+            },
+        ))
+    }
+}
+
+/// Stat data and content hash of an exclude file, as last seen by git
+#[derive(Debug, Clone)]
+pub struct ExcludeFileState {
+    pub stat: CachedStat,
+    /// Object ID of the file contents; all zeros when the file did not exist
+    pub oid: Vec<u8>,
+}
+
+impl ExcludeFileState {
+    /// Whether the file existed when the cache was written
+    #[must_use]
+    pub fn exists(&self) -> bool {
+        self.oid.iter().any(|&b| b != 0)
+    }
+}
+
+/// One directory block of the untracked cache, in depth-first order
+#[derive(Debug, Clone)]
+pub struct UntrackedDirectory {
+    /// Path relative to the repository root ("" for the root directory)
+    pub path: String,
+    /// Nesting depth (0 for the root directory)
+    pub depth: usize,
+    /// Untracked files and directories found during the last scan
+    /// (directories end with '/')
+    pub untracked: Vec<String>,
+    /// Number of subdirectory blocks that follow this one
+    pub subdirectory_count: usize,
+    /// Stat data of the directory itself; present when the cached listing
+    /// is valid
+    pub stat: Option<CachedStat>,
+    /// Hash of the directory's `.gitignore`, when one was read
+    pub exclude_oid: Option<Vec<u8>>,
+    /// Set when the directory was only checked for the existence of
+    /// untracked files, not fully listed
+    pub check_only: bool,
+}
+
+impl UntrackedDirectory {
+    /// Whether git may reuse the cached listing instead of calling
+    /// `opendir()` again, provided the directory's stat data still matches
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.stat.is_some()
+    }
+}
+
+/// The untracked cache extension ("UNTR", `core.untrackedCache`)
+///
+/// Remembers the untracked files of every directory together with the
+/// directory's stat data. When a directory's mtime hasn't changed, `git
+/// status` reuses the cached list instead of reading the directory again.
+#[derive(Debug, Clone)]
+pub struct UntrackedCache {
+    /// Environment the cache is valid for (location, system, ...)
+    pub identifiers: Vec<String>,
+    /// State of `$GIT_DIR/info/exclude`
+    pub info_exclude: ExcludeFileState,
+    /// State of `core.excludesFile`
+    pub excludes_file: ExcludeFileState,
+    /// `dir_struct` flags the cache was built with
+    pub dir_flags: u32,
+    /// Name of the per-directory exclude file (usually ".gitignore")
+    pub exclude_per_dir: String,
+    /// Directory blocks in depth-first order
+    pub directories: Vec<UntrackedDirectory>,
+    /// Which directories have a valid cached listing (and stat data)
+    pub valid_bitmap: Option<EwahBitmap>,
+    /// Which directories were scanned in check-only mode
+    pub check_only_bitmap: Option<EwahBitmap>,
+    /// Which directories have an exclude file hash
+    pub hash_valid_bitmap: Option<EwahBitmap>,
+}
+
+impl UntrackedCache {
+    /// Extension signature
+    pub const SIGNATURE: [u8; 4] = *b"UNTR";
+
+    /// `DIR_SHOW_OTHER_DIRECTORIES`: untracked directories are listed as a whole
+    pub const DIR_SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+    /// `DIR_HIDE_EMPTY_DIRECTORIES`: empty untracked directories are not listed
+    pub const DIR_HIDE_EMPTY_DIRECTORIES: u32 = 1 << 2;
+
+    /// Parse the payload of an untracked cache extension
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if a length, string, bitmap or stat/hash
+    /// array runs past the end of the payload, or the bitmaps refer to more
+    /// directories than the payload contains.
+    pub fn parse(input: &[u8], hash_size: usize) -> IResult<&[u8], Self> {
+        let (input, ident_len) = GitIndex::parse_offset_varint(input)?;
+        let (input, ident) = take(ident_len)(input)?;
+        let identifiers = ident
+            .split(|&b| b == 0)
+            .filter(|s| !s.is_empty())
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+            .collect();
+
+        let (input, info_exclude_stat) = CachedStat::parse(input)?;
+        let (input, excludes_file_stat) = CachedStat::parse(input)?;
+        let (input, dir_flags) = be_u32(input)?;
+        let (input, info_exclude_oid) = take(hash_size)(input)?;
+        let (input, excludes_file_oid) = take(hash_size)(input)?;
+        let (input, exclude_per_dir) = GitIndex::parse_nul_terminated(input)?;
+
+        let mut cache = Self {
+            identifiers,
+            info_exclude: ExcludeFileState {
+                stat: info_exclude_stat,
+                oid: info_exclude_oid.to_vec(),
+            },
+            excludes_file: ExcludeFileState {
+                stat: excludes_file_stat,
+                oid: excludes_file_oid.to_vec(),
+            },
+            dir_flags,
+            exclude_per_dir: String::from_utf8_lossy(exclude_per_dir).into_owned(),
+            directories: Vec::new(),
+            valid_bitmap: None,
+            check_only_bitmap: None,
+            hash_valid_bitmap: None,
+        };
+
+        let (input, directory_count) = GitIndex::parse_offset_varint(input)?;
+        if directory_count == 0 {
+            return Ok((input, cache));
+        }
+
+        let mut input = input;
+        Self::parse_directory(&mut input, "", 0, &mut cache.directories)?;
+        if cache.directories.len() != directory_count {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Count)));
+        }
+
+        let (input, valid_bitmap) = EwahBitmap::parse(input)?;
+        let (input, check_only_bitmap) = EwahBitmap::parse(input)?;
+        let (mut input, hash_valid_bitmap) = EwahBitmap::parse(input)?;
+
+        for position in valid_bitmap.set_bit_positions() {
+            let (rest, stat) = CachedStat::parse(input)?;
+            Self::directory_at(&mut cache.directories, position, input)?.stat = Some(stat);
+            input = rest;
+        }
+        for position in hash_valid_bitmap.set_bit_positions() {
+            let (rest, oid) = take(hash_size)(input)?;
+            Self::directory_at(&mut cache.directories, position, input)?.exclude_oid =
+                Some(oid.to_vec());
+            input = rest;
+        }
+        for position in check_only_bitmap.set_bit_positions() {
+            Self::directory_at(&mut cache.directories, position, input)?.check_only = true;
+        }
+
+        cache.valid_bitmap = Some(valid_bitmap);
+        cache.check_only_bitmap = Some(check_only_bitmap);
+        cache.hash_valid_bitmap = Some(hash_valid_bitmap);
+
+        // Trailing NUL guard written after the arrays
+        let (input, _) = take(1usize)(input)?;
+
+        Ok((input, cache))
+    }
+
+    /// Parse one directory block and, recursively, its subdirectory blocks
+    fn parse_directory<'a>(
+        input: &mut &'a [u8],
+        parent_path: &str,
+        depth: usize,
+        directories: &mut Vec<UntrackedDirectory>,
+    ) -> Result<(), nom::Err<Error<&'a [u8]>>> {
+        let (rest, untracked_count) = GitIndex::parse_offset_varint(input)?;
+        let (rest, subdirectory_count) = GitIndex::parse_offset_varint(rest)?;
+        let (mut rest, name) = GitIndex::parse_nul_terminated(rest)?;
+
+        // Every entry takes at least its NUL byte; reject absurd counts early
+        if untracked_count > rest.len() || subdirectory_count > rest.len() {
+            return Err(nom::Err::Error(Error::new(rest, ErrorKind::TooLarge)));
+        }
+
+        let mut untracked = Vec::with_capacity(untracked_count);
+        for _ in 0..untracked_count {
+            let (after, file_name) = GitIndex::parse_nul_terminated(rest)?;
+            untracked.push(String::from_utf8_lossy(file_name).into_owned());
+            rest = after;
+        }
+
+        let name = String::from_utf8_lossy(name);
+        let path = if parent_path.is_empty() {
+            name.into_owned()
+        } else {
+            format!("{parent_path}{name}")
+        };
+        // Children are stored by name only; keep a trailing '/' for joining
+        let child_prefix = if path.is_empty() || path.ends_with('/') {
+            path.clone()
+        } else {
+            format!("{path}/")
+        };
+
+        directories.push(UntrackedDirectory {
+            path,
+            depth,
+            untracked,
+            subdirectory_count,
+            stat: None,
+            exclude_oid: None,
+            check_only: false,
+        });
+
+        *input = rest;
+        for _ in 0..subdirectory_count {
+            Self::parse_directory(input, &child_prefix, depth + 1, directories)?;
+        }
+
+        Ok(())
+    }
+
+    fn directory_at<'d, 'a>(
+        directories: &'d mut [UntrackedDirectory],
+        position: u64,
+        input: &'a [u8],
+    ) -> Result<&'d mut UntrackedDirectory, nom::Err<Error<&'a [u8]>>> {
+        usize::try_from(position)
+            .ok()
+            .and_then(|p| directories.get_mut(p))
+            .ok_or(nom::Err::Error(Error::new(input, ErrorKind::Verify)))
+    }
+
+    /// Names of the set `dir_flags` bits
+    #[must_use]
+    pub fn dir_flag_names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.dir_flags & Self::DIR_SHOW_OTHER_DIRECTORIES != 0 {
+            names.push("SHOW_OTHER_DIRECTORIES");
+        }
+        if self.dir_flags & Self::DIR_HIDE_EMPTY_DIRECTORIES != 0 {
+            names.push("HIDE_EMPTY_DIRECTORIES");
+        }
+        names
+    }
+
+    /// Number of directories whose cached listing git can reuse
+    #[must_use]
+    pub fn valid_directory_count(&self) -> usize {
+        self.directories.iter().filter(|d| d.is_valid()).count()
+    }
+
+    /// Total number of untracked names across all directories
+    #[must_use]
+    pub fn untracked_count(&self) -> usize {
+        self.directories.iter().map(|d| d.untracked.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::index::tests::offset_varint;
+    use crate::git::pack::bitmap::tests::ewah_bytes_with_bits;
+
+    fn stat_bytes(mtime: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        for value in [1u32, 0, mtime, 0, 2, 3, 1000, 1000, 0] {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data
+    }
+
+    fn header_bytes() -> Vec<u8> {
+        let ident = b"Location /repo, system Linux\0";
+        let mut data = offset_varint(ident.len());
+        data.extend_from_slice(ident);
+        data.extend_from_slice(&stat_bytes(10));
+        data.extend_from_slice(&stat_bytes(0));
+        data.extend_from_slice(&6u32.to_be_bytes());
+        data.extend_from_slice(&[0x11; 20]);
+        data.extend_from_slice(&[0; 20]);
+        data.extend_from_slice(b".gitignore\0");
+        data
+    }
+
+    #[test]
+    fn test_parse_empty_untracked_cache() {
+        let mut data = header_bytes();
+        data.extend_from_slice(&offset_varint(0));
+
+        let (remaining, cache) = UntrackedCache::parse(&data, 20).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(cache.identifiers, vec!["Location /repo, system Linux"]);
+        assert_eq!(cache.info_exclude.stat.mtime.seconds, 10);
+        assert!(cache.info_exclude.exists());
+        assert!(!cache.excludes_file.exists());
+        assert_eq!(
+            cache.dir_flag_names(),
+            vec!["SHOW_OTHER_DIRECTORIES", "HIDE_EMPTY_DIRECTORIES"]
+        );
+        assert_eq!(cache.exclude_per_dir, ".gitignore");
+        assert!(cache.directories.is_empty());
+    }
+
+    #[test]
+    fn test_parse_directory_blocks() {
+        let mut data = header_bytes();
+        data.extend_from_slice(&offset_varint(3));
+        // root: one untracked file, two subdirectories
+        data.extend_from_slice(&[1, 2, 0]);
+        data.extend_from_slice(b"notes.txt\0");
+        // src: no untracked files, no subdirectories
+        data.extend_from_slice(&[0, 0]);
+        data.extend_from_slice(b"src\0");
+        // build: one untracked directory
+        data.extend_from_slice(&[1, 0]);
+        data.extend_from_slice(b"build\0");
+        data.extend_from_slice(b"out/\0");
+        // valid: root and src; check-only: build; hash: root
+        data.extend_from_slice(&ewah_bytes_with_bits(3, &[0, 1]));
+        data.extend_from_slice(&ewah_bytes_with_bits(3, &[2]));
+        data.extend_from_slice(&ewah_bytes_with_bits(3, &[0]));
+        data.extend_from_slice(&stat_bytes(100));
+        data.extend_from_slice(&stat_bytes(200));
+        data.extend_from_slice(&[0x22; 20]);
+        data.push(0);
+
+        let (remaining, cache) = UntrackedCache::parse(&data, 20).unwrap();
+        assert!(remaining.is_empty());
+
+        let paths: Vec<&str> = cache.directories.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(paths, ["", "src", "build"]);
+        assert_eq!(cache.directories[1].depth, 1);
+        assert_eq!(cache.directories[0].untracked, vec!["notes.txt"]);
+        assert_eq!(cache.directories[2].untracked, vec!["out/"]);
+
+        assert_eq!(cache.directories[1].stat.unwrap().mtime.seconds, 200);
+        assert!(!cache.directories[2].is_valid());
+        assert!(cache.directories[2].check_only);
+        assert_eq!(cache.directories[0].exclude_oid, Some(vec![0x22; 20]));
+        assert_eq!(cache.valid_directory_count(), 2);
+        assert_eq!(cache.untracked_count(), 2);
+    }
+
+    #[test]
+    fn test_directory_count_mismatch() {
+        let mut data = header_bytes();
+        data.extend_from_slice(&offset_varint(2));
+        data.extend_from_slice(&[0, 0, 0]);
+        data.extend_from_slice(&ewah_bytes_with_bits(1, &[]));
+        data.extend_from_slice(&ewah_bytes_with_bits(1, &[]));
+        data.extend_from_slice(&ewah_bytes_with_bits(1, &[]));
+        data.push(0);
+
+        assert!(UntrackedCache::parse(&data, 20).is_err());
+    }
+
+    #[test]
+    fn test_truncated_stat_array() {
+        let mut data = header_bytes();
+        data.extend_from_slice(&offset_varint(1));
+        data.extend_from_slice(&[0, 0, 0]);
+        data.extend_from_slice(&ewah_bytes_with_bits(1, &[0]));
+        data.extend_from_slice(&ewah_bytes_with_bits(1, &[]));
+        data.extend_from_slice(&ewah_bytes_with_bits(1, &[]));
+        data.extend_from_slice(&[0; 10]);
+
+        assert!(UntrackedCache::parse(&data, 20).is_err());
+    }
+}
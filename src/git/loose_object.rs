@@ -1,3 +1,4 @@
+use crate::git::object_format::ObjectFormat;
 use flate2::read::ZlibDecoder;
 use std::fs;
 use std::io::Read;
@@ -171,9 +172,10 @@ impl LooseObject {
             ));
         }
 
-        if filename.len() != 38 {
+        // The directory takes the first two hex digits of the object ID
+        if ObjectFormat::from_hex_len(filename.len() + 2).is_none() {
             return Err(LooseObjectError::InvalidFormat(
-                "Filename should be 38 characters".to_string(),
+                "Filename should be 38 (SHA-1) or 62 (SHA-256) characters".to_string(),
             ));
         }
 
@@ -216,23 +218,38 @@ impl LooseObject {
             )));
         }
 
+        Ok(Self::from_parts(object_type, content.to_vec(), object_id))
+    }
+
+    /// Build an object from its type and content, parsing the content the
+    /// same way as for a loose object file (used for objects read from packs)
+    ///
+    /// Tree entries are read with the hash length of `object_id`, falling
+    /// back to SHA-1 for IDs of any other length.
+    #[must_use]
+    pub fn from_parts(object_type: LooseObjectType, content: Vec<u8>, object_id: String) -> Self {
+        let object_format = ObjectFormat::from_hex_len(object_id.len()).unwrap_or_default();
+
         // Parse type-specific content
         let parsed_content = match object_type {
             LooseObjectType::Commit => {
-                Some(ParsedContent::Commit(Self::parse_commit_content(content)))
+                Some(ParsedContent::Commit(Self::parse_commit_content(&content)))
             }
-            LooseObjectType::Tree => Some(ParsedContent::Tree(Self::parse_tree_content(content))),
-            LooseObjectType::Blob => Some(ParsedContent::Blob(content.to_vec())),
-            LooseObjectType::Tag => Some(ParsedContent::Tag(Self::parse_tag_content(content))),
+            LooseObjectType::Tree => Some(ParsedContent::Tree(Self::parse_tree_content(
+                &content,
+                object_format,
+            ))),
+            LooseObjectType::Blob => Some(ParsedContent::Blob(content.clone())),
+            LooseObjectType::Tag => Some(ParsedContent::Tag(Self::parse_tag_content(&content))),
         };
 
-        Ok(Self {
+        Self {
             object_type,
-            size,
-            content: content.to_vec(),
+            size: content.len(),
+            content,
             object_id,
             parsed_content,
-        })
+        }
     }
 
     /// Parse commit object content
@@ -289,8 +306,10 @@ impl LooseObject {
         }
     }
 
-    /// Parse tree object content
-    fn parse_tree_content(content: &[u8]) -> TreeObject {
+    /// Parse tree object content, whose entries name objects by
+    /// `object_format` hashes
+    fn parse_tree_content(content: &[u8], object_format: ObjectFormat) -> TreeObject {
+        let hash_len = object_format.raw_len();
         let mut entries = Vec::new();
         let mut i = 0;
 
@@ -317,12 +336,12 @@ impl LooseObject {
             let name = String::from_utf8_lossy(&content[name_start..i]).to_string();
             i += 1; // Skip null
 
-            // Read SHA-1 (20 bytes)
-            if i + 20 > content.len() {
+            // Read the object ID (20 bytes for SHA-1, 32 for SHA-256)
+            if i + hash_len > content.len() {
                 break;
             }
-            let sha1 = hex::encode(&content[i..i + 20]);
-            i += 20;
+            let sha1 = hex::encode(&content[i..i + hash_len]);
+            i += hash_len;
 
             // Determine object type from mode
             let object_type = match mode.as_str() {
@@ -439,6 +458,33 @@ mod tests {
         assert_eq!(object_id, "abcdef1234567890123456789012345678901234");
     }
 
+    #[test]
+    fn test_extract_sha256_object_id() {
+        let object_id = "473a0f4c3be8a93681a267e3b1e9a7dcda1185436fe141f7749120a303721813";
+        let path = Path::new(".git/objects/47").join(&object_id[2..]);
+        assert_eq!(LooseObject::extract_object_id(&path).unwrap(), object_id);
+        assert!(LooseObject::extract_object_id(Path::new(".git/objects/47/3a0f4c")).is_err());
+    }
+
+    #[test]
+    fn test_parse_sha256_tree_content() {
+        let mut content = Vec::new();
+        content.extend_from_slice(b"100644 a.txt\0");
+        content.extend_from_slice(&[0xab; 32]);
+        content.extend_from_slice(b"40000 dir\0");
+        content.extend_from_slice(&[0xcd; 32]);
+
+        let object_id = "ef".repeat(32);
+        let tree = LooseObject::from_parts(LooseObjectType::Tree, content, object_id);
+        let Some(ParsedContent::Tree(tree)) = tree.parsed_content else {
+            panic!("expected a tree");
+        };
+        assert_eq!(tree.entries.len(), 2);
+        assert_eq!(tree.entries[0].sha1, "ab".repeat(32));
+        assert_eq!(tree.entries[1].name, "dir");
+        assert_eq!(tree.entries[1].sha1, "cd".repeat(32));
+    }
+
     #[test]
     fn test_parse_object_data() {
         let content = b"Hello, World!";
@@ -534,7 +580,7 @@ mod tests {
         content.extend_from_slice(b"100644 README.md\0");
         content.extend_from_slice(&[0xcd; 20]);
 
-        let tree = LooseObject::parse_tree_content(&content);
+        let tree = LooseObject::parse_tree_content(&content, ObjectFormat::Sha1);
         assert_eq!(tree.entries.len(), 2);
         assert_eq!(tree.entries[0].name, "src");
         assert_eq!(tree.entries[0].object_type, TreeEntryType::Tree);
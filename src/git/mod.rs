@@ -1,3 +1,12 @@
+pub mod alternates;
+pub mod commit_graph;
+pub mod compat_map;
+pub mod index;
 pub mod loose_object;
+pub mod object_database;
+pub mod object_format;
 pub mod pack;
+pub mod refs;
 pub mod repository;
+pub mod submodule;
+pub mod worktree;
@@ -0,0 +1,1196 @@
+//! Reads objects by ID from a repository's loose objects and packs.
+//!
+//! Packs are located through the multi-pack-index when one covers them,
+//! and through their own `.idx` otherwise, and read through a memory-mapped
+//! [`PackReader`]. Only the requested object (plus its delta bases) is
+//! inflated, so looking up a handful of objects stays cheap even in large
+//! packs. Opening a database parses those indexes, so callers share one per
+//! repository through [`ObjectDatabases`]. Abbreviated object IDs are
+//! resolved the same way, by binary search in the sorted object ID tables
+//! of the indexes.
+
+use crate::git::loose_object::{LooseObject, LooseObjectError, LooseObjectType};
+use crate::git::object_format::ObjectFormat;
+use crate::git::pack::{
+    MultiPackIndex, MultiPackIndexChain, Object, ObjectType, PackIndex, PackReader, PackReaderError,
+};
+use crate::git::repository::Repository;
+use flate2::read::ZlibDecoder;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use thiserror::Error;
+
+/// Most delta bases looked up outside their pack, in a loose object or
+/// another pack, while reading one object
+const MAX_EXTERNAL_BASES: usize = 1000;
+/// Enough for a loose object's `"<type> <size>\0"` header
+const MAX_LOOSE_HEADER_SIZE: u64 = 32;
+
+#[derive(Debug, Error)]
+pub enum ObjectDatabaseError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Object not found: {0}")]
+    NotFound(String),
+
+    #[error("Invalid object ID prefix: {0}")]
+    InvalidPrefix(String),
+
+    #[error("Ambiguous object ID prefix {prefix}: {} candidates", candidates.len())]
+    Ambiguous {
+        prefix: String,
+        /// Every matching object, sorted by ID
+        candidates: Vec<PrefixCandidate>,
+    },
+
+    #[error("Loose object error: {0}")]
+    LooseObjectError(#[from] LooseObjectError),
+
+    #[error("Corrupt pack entry in {pack} at offset {offset}: {reason}")]
+    CorruptPackEntry {
+        pack: String,
+        offset: u64,
+        reason: String,
+    },
+
+    #[error("Cannot read pack {pack}: {reason}")]
+    UnreadablePack { pack: String, reason: String },
+
+    #[error("Pack reader error: {0}")]
+    PackReaderError(#[from] PackReaderError),
+}
+
+/// Where an object is stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectLocation {
+    /// A zlib-compressed file under `objects/xx/`
+    Loose(PathBuf),
+    /// An entry in the named pack
+    Packed { pack: String, offset: u64 },
+}
+
+/// An object's type and size, read from its header alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectInfo {
+    /// The object's type, deltas resolved to their base's
+    pub object_type: ObjectType,
+    /// Size of the content, deltas applied
+    pub size: usize,
+}
+
+/// One of the objects an ambiguous prefix matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixCandidate {
+    /// The object's full ID, in hex
+    pub object_id: String,
+    /// The object's type, or `None` if it can't be read
+    pub object_type: Option<ObjectType>,
+}
+
+/// A packed object's entry as the pack stores it, before delta resolution
+#[derive(Debug, Clone)]
+pub struct PackEntry {
+    pub pack: String,
+    pub offset: u64,
+    /// Position of the entry in pack order, counting from 0
+    pub position: usize,
+    pub object: Object,
+}
+
+/// A pack, read through its own index unless a multi-pack-index covers it
+#[derive(Debug)]
+struct PackHandle {
+    name: String,
+    pack_file: PathBuf,
+    /// Whether a multi-pack-index locates this pack's objects, leaving its
+    /// reader unopened until one of them is read
+    in_multi_pack_index: bool,
+    reader: OnceLock<Result<PackReader, String>>,
+}
+
+impl PackHandle {
+    fn reader(&self) -> Result<&PackReader, ObjectDatabaseError> {
+        self.reader
+            .get_or_init(|| PackReader::open(&self.pack_file).map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|reason| ObjectDatabaseError::UnreadablePack {
+                pack: self.name.clone(),
+                reason: reason.clone(),
+            })
+    }
+
+    /// The pack's own index, when it is the one locating its objects
+    fn own_index(&self) -> Option<&PackIndex> {
+        if self.in_multi_pack_index {
+            return None;
+        }
+        self.reader().ok().and_then(PackReader::index)
+    }
+}
+
+/// Object databases opened so far, one per objects directory, shared by
+/// every lookup until [`Self::clear`] drops them
+#[derive(Debug, Default)]
+pub struct ObjectDatabases {
+    opened: Mutex<HashMap<PathBuf, Arc<ObjectDatabase>>>,
+}
+
+impl ObjectDatabases {
+    /// The object database of a discovered repository, opened on first use
+    #[must_use]
+    pub fn for_repository(&self, repository: &Repository) -> Arc<ObjectDatabase> {
+        let mut opened = self
+            .opened
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(
+            opened
+                .entry(repository.objects_dir().to_path_buf())
+                .or_insert_with(|| Arc::new(ObjectDatabase::for_repository(repository))),
+        )
+    }
+
+    /// The object database of the repository whose git directory is
+    /// `git_dir`, opened on first use
+    #[must_use]
+    pub fn for_git_dir(&self, git_dir: &Path) -> Arc<ObjectDatabase> {
+        self.for_repository(&Repository::from_git_dir(git_dir))
+    }
+
+    /// Drop every database, so that packs written or removed since they
+    /// were opened are seen
+    pub fn clear(&self) {
+        self.opened
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// The objects of one repository: loose objects and every indexed pack,
+/// then those of the alternate object directories it borrows from
+#[derive(Debug)]
+pub struct ObjectDatabase {
+    objects_dir: PathBuf,
+    object_format: ObjectFormat,
+    /// Multi-pack-indexes in search order: the layers of an incremental
+    /// chain top layer first, or the single `multi-pack-index`
+    multi_pack_indexes: Vec<MultiPackIndex>,
+    packs: Vec<PackHandle>,
+    /// Alternates in search order, already flattened, so they have none
+    /// of their own
+    alternates: Vec<Self>,
+}
+
+impl ObjectDatabase {
+    /// Shortest object ID prefix [`Self::resolve_prefix`] accepts, as git
+    pub const MIN_PREFIX_LEN: usize = 4;
+    /// Shortest abbreviation [`Self::abbreviate`] gives, git's fallback for
+    /// `core.abbrev`
+    pub const MIN_ABBREV_LEN: usize = 7;
+
+    /// Open the object database of the repository whose git directory is
+    /// `git_dir`
+    #[must_use]
+    pub fn open(git_dir: &Path) -> Self {
+        Self::for_repository(&Repository::from_git_dir(git_dir))
+    }
+
+    /// Open the object database of a discovered repository, which may keep
+    /// its objects outside its git directory
+    ///
+    /// Packs that no multi-pack-index covers and that have no readable
+    /// `.idx` are skipped: their objects can't be found without one.
+    #[must_use]
+    pub fn for_repository(repository: &Repository) -> Self {
+        let object_format = repository.object_format();
+        let mut database = Self::without_alternates(repository, object_format);
+        database.alternates = repository
+            .list_alternates()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|alternate| alternate.exists)
+            .map(|alternate| {
+                Self::without_alternates(
+                    &Repository::for_objects_dir(&alternate.path),
+                    object_format,
+                )
+            })
+            .collect();
+        database
+    }
+
+    fn without_alternates(repository: &Repository, object_format: ObjectFormat) -> Self {
+        let multi_pack_indexes = Self::load_multi_pack_indexes(repository);
+        let covered: HashSet<&str> = multi_pack_indexes
+            .iter()
+            .flat_map(|midx| midx.pack_names.iter().map(|name| pack_stem(name)))
+            .collect();
+
+        let groups = repository.list_pack_groups().unwrap_or_default();
+        let mut packs: Vec<PackHandle> = groups
+            .into_values()
+            .filter_map(|group| {
+                let pack_file = group.pack_file?;
+                let in_multi_pack_index = covered.contains(group.base_name.as_str());
+                let reader = OnceLock::new();
+                if !in_multi_pack_index {
+                    // Every lookup searches this pack's index
+                    reader
+                        .set(Ok(PackReader::open(&pack_file).ok()?))
+                        .expect("the reader was just created");
+                }
+                Some(PackHandle {
+                    name: group.base_name,
+                    pack_file,
+                    in_multi_pack_index,
+                    reader,
+                })
+            })
+            .collect();
+        packs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            objects_dir: repository.objects_dir().to_path_buf(),
+            object_format,
+            multi_pack_indexes,
+            packs,
+            alternates: Vec::new(),
+        }
+    }
+
+    /// The multi-pack-indexes git would use: an incremental chain takes
+    /// precedence over a single file, and one that can't be read is
+    /// ignored, leaving its packs to their own indexes
+    fn load_multi_pack_indexes(repository: &Repository) -> Vec<MultiPackIndex> {
+        if let Ok(Some(group)) = repository.get_multi_pack_index_chain_group()
+            && let Ok(chain) = MultiPackIndexChain::load(&group.chain_file)
+        {
+            return chain
+                .layers
+                .into_iter()
+                .rev()
+                .map(|layer| layer.midx)
+                .collect();
+        }
+        repository
+            .get_multi_pack_index()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| MultiPackIndex::parse(&data).ok().map(|(_, midx)| midx))
+            .into_iter()
+            .collect()
+    }
+
+    /// Whether the repository or one of its alternates stores an object
+    #[must_use]
+    pub fn contains(&self, oid: &[u8]) -> bool {
+        self.find_store(oid).is_some()
+    }
+
+    /// Find where an object is stored, preferring loose objects like git
+    /// does, and the repository's own objects over its alternates'
+    #[must_use]
+    pub fn locate(&self, oid: &[u8]) -> Option<ObjectLocation> {
+        self.find_store(oid).map(|(_, location)| location)
+    }
+
+    /// The object directory storing an object: the repository's own, or
+    /// that of one of its alternates
+    #[must_use]
+    pub fn objects_dir_of(&self, oid: &[u8]) -> Option<&Path> {
+        self.find_store(oid)
+            .map(|(store, _)| store.objects_dir.as_path())
+    }
+
+    /// The store holding an object, own objects first, with its location
+    /// there
+    fn find_store(&self, oid: &[u8]) -> Option<(&Self, ObjectLocation)> {
+        self.stores()
+            .find_map(|store| store.locate_own(oid).map(|location| (store, location)))
+    }
+
+    /// This store and its alternates, in search order
+    fn stores(&self) -> impl Iterator<Item = &Self> {
+        std::iter::once(self).chain(&self.alternates)
+    }
+
+    fn locate_own(&self, oid: &[u8]) -> Option<ObjectLocation> {
+        let hex = hex::encode(oid);
+        if hex.len() > 2 {
+            let loose_path = self.objects_dir.join(&hex[..2]).join(&hex[2..]);
+            if loose_path.is_file() {
+                return Some(ObjectLocation::Loose(loose_path));
+            }
+        }
+
+        // A multi-pack-index may still list packs since deleted
+        let in_multi_pack_index = self.multi_pack_indexes.iter().find_map(|midx| {
+            let (pack_id, offset) = midx.offset_at(midx.position_of(oid)?)?;
+            let pack = self.pack(pack_stem(midx.pack_name_for(pack_id)?)).ok()?;
+            Some(ObjectLocation::Packed {
+                pack: pack.name.clone(),
+                offset,
+            })
+        });
+        in_multi_pack_index.or_else(|| {
+            self.packs.iter().find_map(|pack| {
+                pack.own_index()?
+                    .lookup_object(oid)
+                    .map(|offset| ObjectLocation::Packed {
+                        pack: pack.name.clone(),
+                        offset,
+                    })
+            })
+        })
+    }
+
+    /// The full object ID of the one object whose hex ID starts with
+    /// `prefix`, searching loose objects, packs and alternates
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prefix isn't hex or is shorter than
+    /// [`Self::MIN_PREFIX_LEN`] or longer than an object ID, if no object
+    /// matches, or if several do.
+    pub fn resolve_prefix(&self, prefix: &str) -> Result<Vec<u8>, ObjectDatabaseError> {
+        let prefix = prefix.to_ascii_lowercase();
+        let hex_len = self.object_format.hex_len();
+        let oid_prefix = (prefix.len() >= Self::MIN_PREFIX_LEN && prefix.len() <= hex_len)
+            .then(|| OidPrefix::parse(&prefix))
+            .flatten()
+            .ok_or_else(|| ObjectDatabaseError::InvalidPrefix(prefix.clone()))?;
+
+        if prefix.len() == hex_len {
+            return if self.contains(&oid_prefix.bytes) {
+                Ok(oid_prefix.bytes)
+            } else {
+                Err(ObjectDatabaseError::NotFound(prefix))
+            };
+        }
+
+        let mut candidates = Vec::new();
+        for store in self.stores() {
+            store.collect_prefix_matches(&oid_prefix, &mut candidates);
+        }
+        candidates.sort();
+        candidates.dedup();
+
+        match candidates.len() {
+            0 => Err(ObjectDatabaseError::NotFound(prefix)),
+            1 => Ok(candidates.remove(0)),
+            _ => Err(ObjectDatabaseError::Ambiguous {
+                prefix,
+                candidates: candidates
+                    .iter()
+                    .map(|oid| PrefixCandidate {
+                        object_id: hex::encode(oid),
+                        object_type: self.read_type(oid).ok(),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Add this store's objects whose ID starts with `prefix`
+    ///
+    /// Loose objects are found by listing the one directory the first two
+    /// digits name, packed ones by binary search in the fan-out bucket of
+    /// their first byte.
+    fn collect_prefix_matches(&self, prefix: &OidPrefix, matches: &mut Vec<Vec<u8>>) {
+        let first_byte = prefix.bytes[0];
+        matches.extend(
+            self.loose_object_ids(first_byte)
+                .into_iter()
+                .filter(|oid| prefix.matches(oid)),
+        );
+        for (fan_out, names) in self.sorted_object_ids() {
+            matches.extend_from_slice(prefix_run(fan_out, names, prefix));
+        }
+    }
+
+    /// The shortest prefix of an object's hex ID that no other object in
+    /// the repository or its alternates shares, but never shorter than
+    /// [`Self::auto_abbrev_len`], as git abbreviates with `core.abbrev=auto`
+    #[must_use]
+    pub fn abbreviate(&self, oid: &[u8]) -> String {
+        let hex = hex::encode(oid);
+        let shared = self
+            .stores()
+            .map(|store| store.longest_shared_prefix(oid))
+            .max()
+            .unwrap_or(0);
+        let len = (shared + 1).max(self.auto_abbrev_len()).min(hex.len());
+        hex[..len].to_string()
+    }
+
+    /// The abbreviation length `core.abbrev=auto` starts from, grown with
+    /// the number of packed objects so that abbreviations rarely collide
+    #[must_use]
+    pub fn auto_abbrev_len(&self) -> usize {
+        let count: usize = self.stores().map(Self::packed_object_count).sum();
+        // About 2^bits objects expect their first collision among 2^(bits/2)
+        // of them, and each hex digit holds 4 bits
+        let bits = count.checked_ilog2().unwrap_or(0) as usize + 1;
+        bits.div_ceil(2)
+            .max(Self::MIN_ABBREV_LEN)
+            .min(self.object_format.hex_len())
+    }
+
+    /// Objects in this store's packs, not counting its alternates'
+    fn packed_object_count(&self) -> usize {
+        self.sorted_object_ids().map(|(_, names)| names.len()).sum()
+    }
+
+    /// Most hex digits `oid` shares with another object of this store
+    fn longest_shared_prefix(&self, oid: &[u8]) -> usize {
+        let Some(&first_byte) = oid.first() else {
+            return 0;
+        };
+        let loose = self
+            .loose_object_ids(first_byte)
+            .into_iter()
+            .filter(|other| other.as_slice() != oid)
+            .map(|other| common_hex_digits(oid, &other))
+            .max();
+        // In a sorted list, the objects sharing most with `oid` are next to
+        // where it is or would be
+        let packed = self.sorted_object_ids().filter_map(|(_, names)| {
+            let position = names.partition_point(|name| name.as_slice() < oid);
+            let after = names[position..].iter().find(|name| name.as_slice() != oid);
+            let before = position.checked_sub(1).map(|before| &names[before]);
+            [before, after]
+                .into_iter()
+                .flatten()
+                .map(|name| common_hex_digits(oid, name))
+                .max()
+        });
+        loose.into_iter().chain(packed).max().unwrap_or(0)
+    }
+
+    /// Object IDs of the loose objects under the `objects/xx/` directory
+    /// of this first byte
+    fn loose_object_ids(&self, first_byte: u8) -> Vec<Vec<u8>> {
+        let dir = format!("{first_byte:02x}");
+        let Ok(entries) = fs::read_dir(self.objects_dir.join(&dir)) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let name = name.to_str()?;
+                if name.len() + 2 != self.object_format.hex_len() {
+                    return None;
+                }
+                hex::decode(format!("{dir}{name}")).ok()
+            })
+            .collect()
+    }
+
+    /// The sorted object ID lists of this store, with their fan-out
+    /// tables: each multi-pack-index's, then each uncovered pack index's
+    fn sorted_object_ids(&self) -> impl Iterator<Item = (&[u32; 256], &[Vec<u8>])> {
+        let midx = self
+            .multi_pack_indexes
+            .iter()
+            .map(|midx| (&midx.fan_out, midx.object_ids.as_slice()));
+        let packs = self
+            .packs
+            .iter()
+            .filter_map(PackHandle::own_index)
+            .map(|index| (&index.fan_out, index.object_names.as_slice()));
+        midx.chain(packs)
+    }
+
+    /// Type and size of an object, read from its header: a loose object's
+    /// first bytes, or a pack entry's header and those of its delta bases
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object doesn't exist, or its loose file or
+    /// pack entry can't be read.
+    pub fn read_header(&self, oid: &[u8]) -> Result<ObjectInfo, ObjectDatabaseError> {
+        match self.find_store(oid) {
+            Some((_, ObjectLocation::Loose(path))) => Self::read_loose_header(&path),
+            Some((store, ObjectLocation::Packed { pack, offset })) => {
+                let pack = store.pack(&pack)?;
+                let reader = pack.reader()?;
+                let size = reader
+                    .object_size_at(offset)
+                    .map_err(|e| Self::pack_error(pack, e))?;
+                let object_type = store.packed_type(pack, offset, 0)?;
+                Ok(ObjectInfo { object_type, size })
+            }
+            None => Err(ObjectDatabaseError::NotFound(hex::encode(oid))),
+        }
+    }
+
+    /// Type of an object, without inflating packed data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object doesn't exist, or its loose file or
+    /// pack entry can't be read.
+    pub fn read_type(&self, oid: &[u8]) -> Result<ObjectType, ObjectDatabaseError> {
+        self.read_header(oid).map(|info| info.object_type)
+    }
+
+    /// Read an object, resolving delta chains for packed objects
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object doesn't exist, its loose file can't be
+    /// parsed, or its pack entry (or one of its delta bases) is corrupt.
+    pub fn read(&self, oid: &[u8]) -> Result<LooseObject, ObjectDatabaseError> {
+        match self.find_store(oid) {
+            Some((_, ObjectLocation::Loose(path))) => Ok(LooseObject::read_from_path(&path)?),
+            Some((store, ObjectLocation::Packed { pack, offset })) => {
+                let pack = store.pack(&pack)?;
+                let (obj_type, content) = store.read_packed(pack, offset, 0)?;
+                let object_type = match obj_type {
+                    ObjectType::Commit => LooseObjectType::Commit,
+                    ObjectType::Tree => LooseObjectType::Tree,
+                    ObjectType::Blob => LooseObjectType::Blob,
+                    ObjectType::Tag => LooseObjectType::Tag,
+                    other => {
+                        return Err(Self::corrupt(pack, offset, &format!("{other} base")));
+                    }
+                };
+                Ok(LooseObject::from_parts(
+                    object_type,
+                    content,
+                    hex::encode(oid),
+                ))
+            }
+            None => Err(ObjectDatabaseError::NotFound(hex::encode(oid))),
+        }
+    }
+
+    /// The pack entry of a packed object as stored: its header, and its
+    /// inflated data, which for a delta is the delta itself
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object isn't packed, or its entry is
+    /// corrupt.
+    pub fn read_pack_entry(&self, oid: &[u8]) -> Result<PackEntry, ObjectDatabaseError> {
+        let Some((store, ObjectLocation::Packed { pack, offset })) = self.find_store(oid) else {
+            return Err(ObjectDatabaseError::NotFound(hex::encode(oid)));
+        };
+        let pack = store.pack(&pack)?;
+        let reader = pack.reader()?;
+        let object = reader
+            .read_entry_at(offset)
+            .map_err(|e| Self::pack_error(pack, e))?;
+        let position = reader
+            .pack_position(offset)
+            .ok_or_else(|| Self::corrupt(pack, offset, "offset not in the pack index"))?;
+        Ok(PackEntry {
+            pack: pack.name.clone(),
+            offset,
+            position,
+            object,
+        })
+    }
+
+    fn pack(&self, name: &str) -> Result<&PackHandle, ObjectDatabaseError> {
+        self.packs
+            .iter()
+            .find(|pack| pack.name == name)
+            .ok_or_else(|| ObjectDatabaseError::NotFound(name.to_string()))
+    }
+
+    const fn object_type(object_type: &LooseObjectType) -> ObjectType {
+        match object_type {
+            LooseObjectType::Commit => ObjectType::Commit,
+            LooseObjectType::Tree => ObjectType::Tree,
+            LooseObjectType::Blob => ObjectType::Blob,
+            LooseObjectType::Tag => ObjectType::Tag,
+        }
+    }
+
+    /// Type and size from the `"<type> <size>\0"` header of a loose
+    /// object, inflating only its first bytes
+    fn read_loose_header(path: &Path) -> Result<ObjectInfo, ObjectDatabaseError> {
+        let mut prefix = Vec::new();
+        ZlibDecoder::new(File::open(path)?)
+            .take(MAX_LOOSE_HEADER_SIZE)
+            .read_to_end(&mut prefix)
+            .map_err(|e| LooseObjectError::DecompressionError(e.to_string()))?;
+        let header = prefix
+            .iter()
+            .position(|&byte| byte == 0)
+            .map(|end| String::from_utf8_lossy(&prefix[..end]).into_owned())
+            .ok_or_else(|| {
+                LooseObjectError::InvalidFormat("No null terminator found".to_string())
+            })?;
+        let (object_type, size) = header.split_once(' ').ok_or_else(|| {
+            LooseObjectError::InvalidFormat("Header should contain type and size".to_string())
+        })?;
+        Ok(ObjectInfo {
+            object_type: Self::object_type(&object_type.parse::<LooseObjectType>()?),
+            size: size
+                .parse()
+                .map_err(|_| LooseObjectError::InvalidFormat("Invalid size".to_string()))?,
+        })
+    }
+
+    /// Type of the object stored in this store under `oid`, the base of
+    /// a delta `depth` packs up
+    fn stored_type(&self, oid: &[u8], depth: usize) -> Result<ObjectType, ObjectDatabaseError> {
+        match self.locate_own(oid) {
+            Some(ObjectLocation::Loose(path)) => Ok(Self::read_loose_header(&path)?.object_type),
+            Some(ObjectLocation::Packed { pack, offset }) => {
+                self.packed_type(self.pack(&pack)?, offset, depth)
+            }
+            None => Err(ObjectDatabaseError::NotFound(hex::encode(oid))),
+        }
+    }
+
+    /// Type of the pack entry at `offset`, following its delta chain by
+    /// headers alone, out of the pack for a thin pack's base
+    fn packed_type(
+        &self,
+        pack: &PackHandle,
+        offset: u64,
+        depth: usize,
+    ) -> Result<ObjectType, ObjectDatabaseError> {
+        if depth > MAX_EXTERNAL_BASES {
+            return Err(Self::corrupt(pack, offset, "delta chain too long"));
+        }
+        pack.reader()?
+            .object_type_at_with(offset, |base| self.stored_type(base, depth + 1))
+            .map_err(|e| Self::name_pack(pack, e))
+    }
+
+    /// Type and content of the object stored in this store under `oid`,
+    /// the base of a delta `depth` packs up
+    fn read_stored(
+        &self,
+        oid: &[u8],
+        depth: usize,
+    ) -> Result<(ObjectType, Vec<u8>), ObjectDatabaseError> {
+        match self.locate_own(oid) {
+            Some(ObjectLocation::Loose(path)) => {
+                let object = LooseObject::read_from_path(&path)?;
+                Ok((Self::object_type(&object.object_type), object.content))
+            }
+            Some(ObjectLocation::Packed { pack, offset }) => {
+                self.read_packed(self.pack(&pack)?, offset, depth)
+            }
+            None => Err(ObjectDatabaseError::NotFound(hex::encode(oid))),
+        }
+    }
+
+    /// Read the pack entry at `offset`, applying its delta chain, whose
+    /// base may be out of the pack for a thin pack
+    fn read_packed(
+        &self,
+        pack: &PackHandle,
+        offset: u64,
+        depth: usize,
+    ) -> Result<(ObjectType, Vec<u8>), ObjectDatabaseError> {
+        if depth > MAX_EXTERNAL_BASES {
+            return Err(Self::corrupt(pack, offset, "delta chain too long"));
+        }
+        pack.reader()?
+            .read_object_at_with(offset, |base| self.read_stored(base, depth + 1))
+            .map_err(|e| Self::name_pack(pack, e))
+    }
+
+    /// A reader error, named after the pack it came from
+    fn pack_error(pack: &PackHandle, error: PackReaderError) -> ObjectDatabaseError {
+        match error {
+            PackReaderError::IoError(e) => ObjectDatabaseError::IoError(e),
+            PackReaderError::CorruptEntry { offset, reason } => {
+                Self::corrupt(pack, offset, &reason)
+            }
+            other => ObjectDatabaseError::UnreadablePack {
+                pack: pack.name.clone(),
+                reason: other.to_string(),
+            },
+        }
+    }
+
+    /// Name the pack in an error its reader raised
+    fn name_pack(pack: &PackHandle, error: ObjectDatabaseError) -> ObjectDatabaseError {
+        match error {
+            ObjectDatabaseError::PackReaderError(e) => Self::pack_error(pack, e),
+            other => other,
+        }
+    }
+
+    fn corrupt(pack: &PackHandle, offset: u64, reason: &str) -> ObjectDatabaseError {
+        ObjectDatabaseError::CorruptPackEntry {
+            pack: pack.name.clone(),
+            offset,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// A pack's name without extension, as a multi-pack-index lists it with
+/// its `.idx` (or, from older git, `.pack`) extension
+fn pack_stem(name: &str) -> &str {
+    name.strip_suffix(".idx")
+        .or_else(|| name.strip_suffix(".pack"))
+        .unwrap_or(name)
+}
+
+/// Positions of the sorted object names starting with `first_byte`
+fn fan_out_bucket(fan_out: &[u32; 256], first_byte: u8) -> Range<usize> {
+    let start = first_byte
+        .checked_sub(1)
+        .map_or(0, |previous| fan_out[usize::from(previous)] as usize);
+    start..fan_out[usize::from(first_byte)] as usize
+}
+
+/// An object ID prefix as bytes: the whole bytes of its hex digits, plus
+/// the high nibble of the next byte when there's an odd number of them
+#[derive(Debug)]
+struct OidPrefix {
+    bytes: Vec<u8>,
+    half_byte: Option<u8>,
+}
+
+impl OidPrefix {
+    /// Parse a non-empty prefix of hex digits
+    fn parse(hex_prefix: &str) -> Option<Self> {
+        let (whole, half) = hex_prefix.split_at(hex_prefix.len() & !1);
+        let bytes = hex::decode(whole).ok()?;
+        let half_byte = match half {
+            "" => None,
+            digit => Some(u8::from_str_radix(digit, 16).ok()? << 4),
+        };
+        (!bytes.is_empty()).then_some(Self { bytes, half_byte })
+    }
+
+    /// The smallest object ID with this prefix, truncated after it
+    fn lower_bound(&self) -> Vec<u8> {
+        let mut lower_bound = self.bytes.clone();
+        lower_bound.extend(self.half_byte);
+        lower_bound
+    }
+
+    fn matches(&self, oid: &[u8]) -> bool {
+        oid.starts_with(&self.bytes)
+            && self.half_byte.is_none_or(|half_byte| {
+                oid.get(self.bytes.len())
+                    .is_some_and(|byte| byte & 0xf0 == half_byte)
+            })
+    }
+}
+
+/// The run of sorted `names` starting with `prefix`, binary searched in
+/// the fan-out bucket of its first byte
+fn prefix_run<'a>(fan_out: &[u32; 256], names: &'a [Vec<u8>], prefix: &OidPrefix) -> &'a [Vec<u8>] {
+    let bucket = names
+        .get(fan_out_bucket(fan_out, prefix.bytes[0]))
+        .unwrap_or_default();
+    let lower_bound = prefix.lower_bound();
+    let start = bucket.partition_point(|name| name.as_slice() < lower_bound.as_slice());
+    let len = bucket[start..]
+        .iter()
+        .take_while(|name| prefix.matches(name))
+        .count();
+    &bucket[start..start + len]
+}
+
+/// Number of leading hex digits two object IDs share
+fn common_hex_digits(a: &[u8], b: &[u8]) -> usize {
+    let whole_bytes = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let half_byte = match (a.get(whole_bytes), b.get(whole_bytes)) {
+        (Some(a), Some(b)) if a >> 4 == b >> 4 => 1,
+        _ => 0,
+    };
+    whole_bytes * 2 + half_byte
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::object_format::ObjectFormat;
+    use crate::git::pack::resolver::object_id;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::fs;
+    use std::io::Write;
+
+    const BASE: &[u8] = b"the quick brown fox";
+    const RESULT: &[u8] = b"the quick brown fox jumps";
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Type/size header of a pack entry
+    fn entry_header(obj_type: u8, mut size: usize) -> Vec<u8> {
+        let mut header = vec![(obj_type << 4) | (size & 0x0f) as u8];
+        size >>= 4;
+        while size != 0 {
+            *header.last_mut().unwrap() |= 0x80;
+            header.push((size & 0x7f) as u8);
+            size >>= 7;
+        }
+        header
+    }
+
+    /// A pack holding `BASE` as a blob and `RESULT` as an `ofs_delta` on it,
+    /// plus a matching version 2 index
+    fn write_pack(pack_dir: &Path) -> (Vec<u8>, Vec<u8>) {
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&2u32.to_be_bytes());
+
+        let base_offset = pack.len() as u64;
+        pack.extend(entry_header(3, BASE.len()));
+        pack.extend(zlib(BASE));
+
+        let delta_offset = pack.len() as u64;
+        let delta = entry_delta();
+        pack.extend(entry_header(6, delta.len()));
+        pack.push((delta_offset - base_offset) as u8);
+        pack.extend(zlib(&delta));
+        pack.extend_from_slice(&[0; 20]);
+
+        let base_oid = hex::decode(object_id(ObjectFormat::Sha1, ObjectType::Blob, BASE)).unwrap();
+        let result_oid =
+            hex::decode(object_id(ObjectFormat::Sha1, ObjectType::Blob, RESULT)).unwrap();
+        let mut objects = [
+            (base_oid.clone(), base_offset),
+            (result_oid.clone(), delta_offset),
+        ];
+        objects.sort();
+
+        let mut idx = vec![0xff, 0x74, 0x4f, 0x63, 0, 0, 0, 2];
+        for byte in 0..=255u8 {
+            let count = objects.iter().filter(|(oid, _)| oid[0] <= byte).count();
+            idx.extend_from_slice(&(count as u32).to_be_bytes());
+        }
+        for (oid, _) in &objects {
+            idx.extend_from_slice(oid);
+        }
+        for _ in &objects {
+            idx.extend_from_slice(&0u32.to_be_bytes());
+        }
+        for (_, offset) in &objects {
+            idx.extend_from_slice(&(*offset as u32).to_be_bytes());
+        }
+        idx.extend_from_slice(&[0; 40]);
+
+        fs::create_dir_all(pack_dir).unwrap();
+        fs::write(pack_dir.join("pack-test.pack"), pack).unwrap();
+        fs::write(pack_dir.join("pack-test.idx"), idx).unwrap();
+        (base_oid, result_oid)
+    }
+
+    #[test]
+    fn test_read_packed_and_delta_objects() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let (base_oid, result_oid) = write_pack(&git_dir.join("objects/pack"));
+
+        let database = ObjectDatabase::open(&git_dir);
+        assert!(matches!(
+            database.locate(&result_oid),
+            Some(ObjectLocation::Packed { ref pack, .. }) if pack == "pack-test"
+        ));
+        assert_eq!(database.read_type(&result_oid).unwrap(), ObjectType::Blob);
+
+        let base = database.read(&base_oid).unwrap();
+        assert_eq!(base.content, BASE);
+        let result = database.read(&result_oid).unwrap();
+        assert_eq!(result.object_type, LooseObjectType::Blob);
+        assert_eq!(result.content, RESULT);
+        assert_eq!(result.object_id, hex::encode(&result_oid));
+    }
+
+    #[test]
+    fn test_read_objects_from_alternates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let reference = temp_dir.path().join("reference/.git");
+        let (base_oid, result_oid) = write_pack(&reference.join("objects/pack"));
+        let git_dir = temp_dir.path().join("clone/.git");
+        fs::create_dir_all(git_dir.join("objects/info")).unwrap();
+        fs::write(
+            git_dir.join("objects/info/alternates"),
+            "../../../reference/.git/objects\n",
+        )
+        .unwrap();
+
+        let database = ObjectDatabase::open(&git_dir);
+        assert!(matches!(
+            database.locate(&result_oid),
+            Some(ObjectLocation::Packed { ref pack, .. }) if pack == "pack-test"
+        ));
+        assert_eq!(database.read_type(&base_oid).unwrap(), ObjectType::Blob);
+        assert_eq!(database.read(&result_oid).unwrap().content, RESULT);
+    }
+
+    #[test]
+    fn test_read_loose_and_missing_objects() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let content = b"hello\n";
+        let oid = object_id(ObjectFormat::Sha1, ObjectType::Blob, content);
+        let object_dir = git_dir.join("objects").join(&oid[..2]);
+        fs::create_dir_all(&object_dir).unwrap();
+        let mut raw = format!("blob {}\0", content.len()).into_bytes();
+        raw.extend_from_slice(content);
+        fs::write(object_dir.join(&oid[2..]), zlib(&raw)).unwrap();
+
+        let database = ObjectDatabase::open(&git_dir);
+        let oid = hex::decode(oid).unwrap();
+        assert!(matches!(
+            database.locate(&oid),
+            Some(ObjectLocation::Loose(_))
+        ));
+        assert_eq!(database.read(&oid).unwrap().content, content);
+
+        let missing = [0xab; 20];
+        assert!(database.locate(&missing).is_none());
+        assert!(matches!(
+            database.read(&missing),
+            Err(ObjectDatabaseError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_contains_and_read_header() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let (base_oid, result_oid) = write_pack(&git_dir.join("objects/pack"));
+
+        let database = ObjectDatabase::open(&git_dir);
+        assert!(database.contains(&base_oid));
+        assert!(!database.contains(&[0xab; 20]));
+        assert_eq!(
+            database.read_header(&result_oid).unwrap(),
+            ObjectInfo {
+                object_type: ObjectType::Blob,
+                size: RESULT.len(),
+            }
+        );
+        assert_eq!(database.read_header(&base_oid).unwrap().size, BASE.len());
+
+        let entry = database.read_pack_entry(&result_oid).unwrap();
+        assert_eq!(entry.pack, "pack-test");
+        assert_eq!(entry.object.header.obj_type(), ObjectType::OfsDelta);
+        assert_eq!(entry.object.compressed_data, zlib(&entry_delta()));
+    }
+
+    #[test]
+    fn test_read_thin_pack_with_loose_base() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let base_oid = object_id(ObjectFormat::Sha1, ObjectType::Blob, BASE);
+        let object_dir = git_dir.join("objects").join(&base_oid[..2]);
+        fs::create_dir_all(&object_dir).unwrap();
+        let mut raw = format!("blob {}\0", BASE.len()).into_bytes();
+        raw.extend_from_slice(BASE);
+        fs::write(object_dir.join(&base_oid[2..]), zlib(&raw)).unwrap();
+
+        // A pack holding only `RESULT`, as a `ref_delta` on the loose base
+        let delta = entry_delta();
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&1u32.to_be_bytes());
+        pack.extend(entry_header(7, delta.len()));
+        pack.extend(hex::decode(&base_oid).unwrap());
+        pack.extend(zlib(&delta));
+        pack.extend_from_slice(&[0; 20]);
+
+        let result_oid =
+            hex::decode(object_id(ObjectFormat::Sha1, ObjectType::Blob, RESULT)).unwrap();
+        let mut idx = vec![0xff, 0x74, 0x4f, 0x63, 0, 0, 0, 2];
+        for byte in 0..=255u8 {
+            idx.extend_from_slice(&u32::from(result_oid[0] <= byte).to_be_bytes());
+        }
+        idx.extend_from_slice(&result_oid);
+        idx.extend_from_slice(&0u32.to_be_bytes());
+        idx.extend_from_slice(&12u32.to_be_bytes());
+        idx.extend_from_slice(&[0; 40]);
+        let pack_dir = git_dir.join("objects/pack");
+        fs::create_dir_all(&pack_dir).unwrap();
+        fs::write(pack_dir.join("pack-thin.pack"), pack).unwrap();
+        fs::write(pack_dir.join("pack-thin.idx"), idx).unwrap();
+
+        let database = ObjectDatabase::open(&git_dir);
+        assert_eq!(database.read(&result_oid).unwrap().content, RESULT);
+        assert_eq!(
+            database.read_header(&result_oid).unwrap(),
+            ObjectInfo {
+                object_type: ObjectType::Blob,
+                size: RESULT.len(),
+            }
+        );
+    }
+
+    /// The delta `write_pack` stores for `RESULT`
+    fn entry_delta() -> Vec<u8> {
+        let mut delta = vec![BASE.len() as u8, RESULT.len() as u8];
+        delta.extend_from_slice(&[0x91, 0x00, BASE.len() as u8]);
+        delta.push((RESULT.len() - BASE.len()) as u8);
+        delta.extend_from_slice(&RESULT[BASE.len()..]);
+        delta
+    }
+
+    #[test]
+    fn test_resolve_prefix() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let (_, result_oid) = write_pack(&git_dir.join("objects/pack"));
+        // Two loose objects sharing a prefix; resolving doesn't read them
+        let loose_dir = git_dir.join("objects/ab");
+        fs::create_dir_all(&loose_dir).unwrap();
+        fs::write(loose_dir.join(format!("cd{}", "0".repeat(36))), b"").unwrap();
+        fs::write(loose_dir.join(format!("cd{}", "1".repeat(36))), b"").unwrap();
+
+        let database = ObjectDatabase::open(&git_dir);
+        let result_hex = hex::encode(&result_oid);
+        assert_eq!(
+            database.resolve_prefix(&result_hex[..6]).unwrap(),
+            result_oid
+        );
+        assert_eq!(
+            database
+                .resolve_prefix(&result_hex[..8].to_ascii_uppercase())
+                .unwrap(),
+            result_oid
+        );
+        assert_eq!(database.resolve_prefix(&result_hex).unwrap(), result_oid);
+        assert_eq!(
+            database
+                .resolve_prefix(&format!("abcd{}", "1".repeat(30)))
+                .unwrap(),
+            hex::decode(format!("abcd{}", "1".repeat(36))).unwrap()
+        );
+
+        match database.resolve_prefix("abcd") {
+            Err(ObjectDatabaseError::Ambiguous { candidates, .. }) => assert_eq!(
+                candidates,
+                [
+                    // Empty files, so their types can't be read
+                    PrefixCandidate {
+                        object_id: format!("abcd{}", "0".repeat(36)),
+                        object_type: None,
+                    },
+                    PrefixCandidate {
+                        object_id: format!("abcd{}", "1".repeat(36)),
+                        object_type: None,
+                    },
+                ]
+            ),
+            other => panic!("expected ambiguity, got {other:?}"),
+        }
+        // Odd-length prefixes compare the high nibble of their last byte
+        assert_eq!(
+            database.resolve_prefix(&result_hex[..7]).unwrap(),
+            result_oid
+        );
+        assert_eq!(
+            database.resolve_prefix("abcd1").unwrap(),
+            hex::decode(format!("abcd{}", "1".repeat(36))).unwrap()
+        );
+        assert!(matches!(
+            database.resolve_prefix("abce"),
+            Err(ObjectDatabaseError::NotFound(_))
+        ));
+        assert!(matches!(
+            database.resolve_prefix("abc"),
+            Err(ObjectDatabaseError::InvalidPrefix(_))
+        ));
+        assert!(matches!(
+            database.resolve_prefix("abcz"),
+            Err(ObjectDatabaseError::InvalidPrefix(_))
+        ));
+    }
+
+    #[test]
+    fn test_abbreviate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let (base_oid, result_oid) = write_pack(&git_dir.join("objects/pack"));
+        // Two loose objects sharing 8 hex digits
+        let loose_dir = git_dir.join("objects/ab");
+        fs::create_dir_all(&loose_dir).unwrap();
+        fs::write(loose_dir.join(format!("cdef012{}", "0".repeat(31))), b"").unwrap();
+        fs::write(loose_dir.join(format!("cdef013{}", "0".repeat(31))), b"").unwrap();
+
+        let mut database = ObjectDatabase::open(&git_dir);
+        assert_eq!(database.auto_abbrev_len(), ObjectDatabase::MIN_ABBREV_LEN);
+        assert_eq!(
+            database.abbreviate(&result_oid),
+            hex::encode(&result_oid)[..ObjectDatabase::MIN_ABBREV_LEN]
+        );
+        let loose_oid = hex::decode(format!("abcdef012{}", "0".repeat(31))).unwrap();
+        assert_eq!(database.abbreviate(&loose_oid), "abcdef012");
+
+        // A packed object sharing 9 hex digits with `base_oid`
+        let mut neighbour = base_oid.clone();
+        neighbour[4] ^= 0x0f;
+        let reader = database.packs[0].reader.get_mut().unwrap();
+        let index = reader.as_mut().unwrap().index_mut();
+        index.object_names.push(neighbour);
+        index.object_names.sort();
+        assert_eq!(database.abbreviate(&base_oid), hex::encode(&base_oid)[..10]);
+    }
+
+    #[test]
+    fn test_locate_packs_through_multi_pack_index() {
+        use crate::git::pack::multi_pack_index::tests::{assemble_midx, build_pnam};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        let pack_dir = git_dir.join("objects/pack");
+        let (base_oid, result_oid) = write_pack(&pack_dir);
+
+        // A multi-pack-index covering the pack
+        let idx = fs::read(pack_dir.join("pack-test.idx")).unwrap();
+        let (_, index) = PackIndex::parse(&idx).unwrap();
+        let oidf: Vec<u8> = index.fan_out.iter().flat_map(|n| n.to_be_bytes()).collect();
+        let oidl = index.object_names.concat();
+        let ooff: Vec<u8> = index
+            .offsets
+            .iter()
+            .flat_map(|offset| [0u32.to_be_bytes(), offset.to_be_bytes()].concat())
+            .collect();
+        let midx = assemble_midx(
+            1,
+            1,
+            0,
+            1,
+            &[
+                (MultiPackIndex::CHUNK_PNAM, build_pnam(&["pack-test.idx"])),
+                (MultiPackIndex::CHUNK_OIDF, oidf),
+                (MultiPackIndex::CHUNK_OIDL, oidl),
+                (MultiPackIndex::CHUNK_OOFF, ooff),
+            ],
+            20,
+        );
+        fs::write(pack_dir.join("multi-pack-index"), midx).unwrap();
+
+        let database = ObjectDatabase::open(&git_dir);
+        assert!(matches!(
+            database.locate(&base_oid),
+            Some(ObjectLocation::Packed { ref pack, offset: 12 }) if pack == "pack-test"
+        ));
+        assert_eq!(
+            database
+                .resolve_prefix(&hex::encode(&result_oid)[..5])
+                .unwrap(),
+            result_oid
+        );
+        // Found without opening the pack's own index
+        assert!(database.packs[0].reader.get().is_none());
+        assert_eq!(database.read(&result_oid).unwrap().content, RESULT);
+        assert_eq!(database.read_pack_entry(&result_oid).unwrap().position, 1);
+    }
+}
@@ -0,0 +1,329 @@
+//! Object formats: the hash function naming a repository's objects.
+//!
+//! A repository created with `git init --object-format=sha256` records
+//! `extensions.objectFormat = sha256` in its config and names every object
+//! by a 32-byte SHA-256 digest instead of a 20-byte SHA-1 one. Loose object
+//! paths, pack index tables, ref-delta bases and trailing checksums all
+//! grow accordingly; nothing else about the on-disk formats changes.
+
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ObjectFormatError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Unsupported object format: {0}")]
+    Unsupported(String),
+}
+
+/// The hash function of a repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ObjectFormat {
+    #[default]
+    Sha1,
+    Sha256,
+}
+
+impl ObjectFormat {
+    pub const ALL: [Self; 2] = [Self::Sha1, Self::Sha256];
+
+    /// Length of an object ID in bytes
+    #[must_use]
+    pub const fn raw_len(self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+        }
+    }
+
+    /// Length of an object ID in hex digits
+    #[must_use]
+    pub const fn hex_len(self) -> usize {
+        self.raw_len() * 2
+    }
+
+    /// Name as written in `extensions.objectFormat`
+    #[must_use]
+    pub const fn config_name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "sha1",
+            Self::Sha256 => "sha256",
+        }
+    }
+
+    /// Hash function ID stored in MIDX, commit-graph and `.rev` headers
+    #[must_use]
+    pub const fn hash_function_id(self) -> u32 {
+        match self {
+            Self::Sha1 => 1,
+            Self::Sha256 => 2,
+        }
+    }
+
+    /// Four-character format ID stored in version 3 pack index headers
+    #[must_use]
+    pub const fn format_id(self) -> u32 {
+        u32::from_be_bytes(match self {
+            Self::Sha1 => *b"sha1",
+            Self::Sha256 => *b"s256",
+        })
+    }
+
+    #[must_use]
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| name.eq_ignore_ascii_case(format.config_name()))
+    }
+
+    #[must_use]
+    pub fn from_hash_function_id(id: u32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.hash_function_id() == id)
+    }
+
+    #[must_use]
+    pub fn from_format_id(id: u32) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.format_id() == id)
+    }
+
+    #[must_use]
+    pub fn from_raw_len(len: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.raw_len() == len)
+    }
+
+    #[must_use]
+    pub fn from_hex_len(len: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.hex_len() == len)
+    }
+
+    /// The format of a pack, index or other pack-side file from its name:
+    /// `git pack-objects` names them `pack-<checksum>.<ext>`
+    #[must_use]
+    pub fn from_pack_name(path: &Path) -> Option<Self> {
+        let checksum = path.file_stem()?.to_str()?.strip_prefix("pack-")?;
+        checksum
+            .bytes()
+            .all(|byte| byte.is_ascii_hexdigit())
+            .then(|| Self::from_hex_len(checksum.len()))
+            .flatten()
+    }
+
+    /// The format of the repository whose common directory is `common_dir`,
+    /// from `extensions.objectFormat` in its config
+    ///
+    /// A repository without a config, or without the extension, uses SHA-1.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config exists but can't be read, or names a
+    /// hash function git doesn't know.
+    pub fn for_repository(common_dir: &Path) -> Result<Self, ObjectFormatError> {
+        let config = common_dir.join("config");
+        if !config.is_file() {
+            return Ok(Self::Sha1);
+        }
+        Self::from_config(&fs::read_to_string(config)?)
+    }
+
+    /// The format named by `extensions.objectFormat` in config file content
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension names an unknown hash function.
+    pub fn from_config(content: &str) -> Result<Self, ObjectFormatError> {
+        Ok(Self::extension_from_config(content, "objectformat")?.unwrap_or_default())
+    }
+
+    /// The compatibility format of the repository whose common directory is
+    /// `common_dir`, from `extensions.compatObjectFormat` in its config
+    ///
+    /// A repository with a compatibility format keeps a second name for
+    /// every object, in that format, and translates between the two.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config exists but can't be read, or names a
+    /// hash function git doesn't know.
+    pub fn compat_for_repository(common_dir: &Path) -> Result<Option<Self>, ObjectFormatError> {
+        let config = common_dir.join("config");
+        if !config.is_file() {
+            return Ok(None);
+        }
+        Self::compat_from_config(&fs::read_to_string(config)?)
+    }
+
+    /// The format named by `extensions.compatObjectFormat` in config file
+    /// content, if any
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension names an unknown hash function.
+    pub fn compat_from_config(content: &str) -> Result<Option<Self>, ObjectFormatError> {
+        Self::extension_from_config(content, "compatobjectformat")
+    }
+
+    /// The hash function named by the last `key` in the `[extensions]`
+    /// sections of config file content
+    fn extension_from_config(content: &str, key: &str) -> Result<Option<Self>, ObjectFormatError> {
+        let mut in_extensions = false;
+        let mut format = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                // Section names are case-insensitive; subsections don't
+                // apply here
+                in_extensions = line
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .trim()
+                    .eq_ignore_ascii_case("extensions");
+                continue;
+            }
+            if !in_extensions {
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            if name.trim().eq_ignore_ascii_case(key) {
+                let value = value.trim().trim_matches('"');
+                format = Some(
+                    Self::from_config_name(value)
+                        .ok_or_else(|| ObjectFormatError::Unsupported(value.to_string()))?,
+                );
+            }
+        }
+        Ok(format)
+    }
+
+    /// Digest of arbitrary data, as used for trailing file checksums
+    #[must_use]
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => Sha1::digest(data).to_vec(),
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+        }
+    }
+
+    /// Object ID of content with type `obj_type`: the digest of
+    /// `"{type} {size}\0"` followed by the content
+    #[must_use]
+    pub fn object_id(self, obj_type: &str, content: &[u8]) -> Vec<u8> {
+        let header = format!("{obj_type} {}\0", content.len());
+        match self {
+            Self::Sha1 => Sha1::new()
+                .chain_update(header)
+                .chain_update(content)
+                .finalize()
+                .to_vec(),
+            Self::Sha256 => Sha256::new()
+                .chain_update(header)
+                .chain_update(content)
+                .finalize()
+                .to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for ObjectFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_config() {
+        assert_eq!(
+            ObjectFormat::from_config("[core]\n\trepositoryformatversion = 0\n").unwrap(),
+            ObjectFormat::Sha1
+        );
+        assert_eq!(
+            ObjectFormat::from_config(
+                "[core]\n\trepositoryformatversion = 1\n[Extensions]\n\tobjectFormat = sha256\n"
+            )
+            .unwrap(),
+            ObjectFormat::Sha256
+        );
+        // The key only counts inside [extensions]
+        assert_eq!(
+            ObjectFormat::from_config("[core]\n\tobjectformat = sha256\n").unwrap(),
+            ObjectFormat::Sha1
+        );
+        assert!(ObjectFormat::from_config("[extensions]\n\tobjectformat = md5\n").is_err());
+    }
+
+    #[test]
+    fn test_compat_format_from_config() {
+        let config = "[core]\n\trepositoryformatversion = 1\n\
+                      [extensions]\n\tobjectFormat = sha256\n\tcompatObjectFormat = sha1\n";
+        assert_eq!(
+            ObjectFormat::from_config(config).unwrap(),
+            ObjectFormat::Sha256
+        );
+        assert_eq!(
+            ObjectFormat::compat_from_config(config).unwrap(),
+            Some(ObjectFormat::Sha1)
+        );
+        assert_eq!(
+            ObjectFormat::compat_from_config("[extensions]\n\tobjectFormat = sha256\n").unwrap(),
+            None
+        );
+        assert_eq!(
+            ObjectFormat::from_format_id(0x7332_3536),
+            Some(ObjectFormat::Sha256)
+        );
+        assert_eq!(ObjectFormat::Sha1.format_id(), 0x7368_6131);
+    }
+
+    #[test]
+    fn test_object_ids() {
+        // `git hash-object --stdin` over empty input, in a SHA-1 and in a
+        // SHA-256 repository
+        assert_eq!(
+            hex::encode(ObjectFormat::Sha1.object_id("blob", b"")),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            hex::encode(ObjectFormat::Sha256.object_id("blob", b"")),
+            "473a0f4c3be8a93681a267e3b1e9a7dcda1185436fe141f7749120a303721813"
+        );
+        assert_eq!(ObjectFormat::from_hex_len(64), Some(ObjectFormat::Sha256));
+        assert_eq!(ObjectFormat::from_raw_len(20), Some(ObjectFormat::Sha1));
+        assert_eq!(
+            ObjectFormat::from_hash_function_id(2),
+            Some(ObjectFormat::Sha256)
+        );
+    }
+
+    #[test]
+    fn test_format_from_pack_name() {
+        let sha256 = format!("objects/pack/pack-{}.idx", "ab".repeat(32));
+        assert_eq!(
+            ObjectFormat::from_pack_name(Path::new(&sha256)),
+            Some(ObjectFormat::Sha256)
+        );
+        let sha1 = format!("pack-{}.pack", "ab".repeat(20));
+        assert_eq!(
+            ObjectFormat::from_pack_name(Path::new(&sha1)),
+            Some(ObjectFormat::Sha1)
+        );
+        assert_eq!(ObjectFormat::from_pack_name(Path::new("custom.pack")), None);
+    }
+}
@@ -87,6 +87,48 @@ impl EwahBitmap {
 
         bits
     }
+
+    /// Decode the positions of all set bits, in ascending order
+    ///
+    /// Bits are numbered least-significant first within each 64-bit word.
+    /// Positions at or beyond `bit_count` are dropped, as is any trailing
+    /// chunk whose literal count points past the stored words.
+    #[must_use]
+    pub fn set_bit_positions(&self) -> Vec<u64> {
+        let mut positions = Vec::new();
+        let mut word_position: u64 = 0;
+        let mut i = 0;
+
+        while i < self.words.len() {
+            let rlw = self.words[i];
+            let run_bit = rlw & 1;
+            let run_len = (rlw >> 1) & 0xFFFF_FFFF;
+            let literal_count = (rlw >> 33) as usize;
+
+            if run_bit == 1 {
+                positions.extend(word_position * 64..(word_position + run_len) * 64);
+            }
+            word_position += run_len;
+
+            let literals_end = i + 1 + literal_count;
+            if literals_end > self.words.len() {
+                break;
+            }
+            for &word in &self.words[i + 1..literals_end] {
+                let mut remaining = word;
+                while remaining != 0 {
+                    let bit = u64::from(remaining.trailing_zeros());
+                    positions.push(word_position * 64 + bit);
+                    remaining &= remaining - 1;
+                }
+                word_position += 1;
+            }
+            i = literals_end;
+        }
+
+        positions.retain(|&position| position < u64::from(self.bit_count));
+        positions
+    }
 }
 
 /// A single commit reachability bitmap entry in a .bitmap file
@@ -444,11 +486,11 @@ impl fmt::Display for PackBitmap {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     /// Serialize an EWAH bitmap holding the given literal words
-    fn ewah_bytes(bit_count: u32, literal_words: &[u64]) -> Vec<u8> {
+    pub(crate) fn ewah_bytes(bit_count: u32, literal_words: &[u64]) -> Vec<u8> {
         let mut data = Vec::new();
         data.extend_from_slice(&bit_count.to_be_bytes());
         // One RLW (no run, all literals) + the literal words
@@ -463,6 +505,15 @@ mod tests {
         data
     }
 
+    /// Serialize an EWAH bitmap of `bit_count` bits with the given positions set
+    pub(crate) fn ewah_bytes_with_bits(bit_count: u32, set_bits: &[u64]) -> Vec<u8> {
+        let mut literal_words = vec![0u64; (bit_count as usize).div_ceil(64)];
+        for &bit in set_bits {
+            literal_words[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        ewah_bytes(bit_count, &literal_words)
+    }
+
     fn create_test_bitmap_data(
         num_objects: u32,
         entry_count: u32,
@@ -673,6 +724,26 @@ mod tests {
         assert_eq!(ewah.compressed_byte_size(), 12 + 16);
     }
 
+    #[test]
+    fn test_ewah_set_bit_positions() {
+        // RLW with a run of 1 all-zeros word plus 2 literal words
+        let rlw: u64 = (2 << 33) | (1 << 1);
+        let mut data = Vec::new();
+        data.extend_from_slice(&190u32.to_be_bytes()); // bit count
+        data.extend_from_slice(&3u32.to_be_bytes()); // word count
+        data.extend_from_slice(&rlw.to_be_bytes());
+        data.extend_from_slice(&0b1001u64.to_be_bytes());
+        data.extend_from_slice(&(1u64 << 63 | 1 << 61).to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes()); // RLW position
+
+        let (_, ewah) = EwahBitmap::parse(&data).unwrap();
+        // Bit 191 lies past the declared bit count and is dropped
+        assert_eq!(ewah.set_bit_positions(), vec![64, 67, 189]);
+
+        let (_, ones) = EwahBitmap::parse(&ewah_bytes(3, &[0b111])).unwrap();
+        assert_eq!(ones.set_bit_positions(), vec![0, 1, 2]);
+    }
+
     #[test]
     fn test_display_format() {
         let data = create_test_bitmap_data(64, 3, PackBitmap::FLAG_FULL_DAG, 20);
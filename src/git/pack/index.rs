@@ -7,42 +7,108 @@ use nom::{
 };
 use std::fmt;
 
+use crate::git::object_format::ObjectFormat;
+
+/// Where a version-3 index keeps the tables of one object format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashTable {
+    pub object_format: ObjectFormat,
+    /// Length of the names in the sorted table: the shortest prefix that
+    /// keeps every object in the pack unambiguous
+    pub short_name_len: usize,
+    /// Offset of the tables from the start of the file
+    pub table_offset: u32,
+    /// For each sorted name, the object's position in pack order
+    pub pack_order: Vec<u32>,
+}
+
+/// Object names in the second hash function of a version-3 index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompatObjectNames {
+    pub object_format: ObjectFormat,
+    /// Full names, in the same order as [`PackIndex::object_names`]
+    pub object_names: Vec<Vec<u8>>,
+}
+
 /// Represents a Git pack index file (.idx)
 ///
 /// Pack index files provide efficient lookup into pack files by mapping
-/// object SHA-1 hashes to their byte offsets within the corresponding pack file.
+/// object IDs to their byte offsets within the corresponding pack file.
+///
+/// Version 3 indexes, written for repositories translating between SHA-1
+/// and SHA-256, are read into the same shape: names and CRC32 values in
+/// sorted order, with the fan-out table computed from the names. So are
+/// version 1 indexes, which have no CRC32 values.
 #[derive(Debug, Clone)]
 pub struct PackIndex {
-    /// Index file format version (should be 2)
+    /// Index file format version (1, 2 or 3)
     pub version: u32,
+    /// Hash function of the object names and checksums
+    pub object_format: ObjectFormat,
     /// Fan-out table: 256 entries indicating object count for each first byte
     pub fan_out: [u32; 256],
-    /// Sorted array of 20-byte SHA-1 object names
-    pub object_names: Vec<[u8; 20]>,
-    /// CRC32 checksums for packed object data (for integrity verification)
+    /// Sorted array of object names, 20 bytes (SHA-1) or 32 (SHA-256) each
+    pub object_names: Vec<Vec<u8>>,
+    /// CRC32 checksums for packed object data (for integrity verification),
+    /// empty for version 1
     pub crc32_checksums: Vec<u32>,
     /// 4-byte offsets into the pack file for each object
     pub offsets: Vec<u32>,
     /// Optional 8-byte offsets for large pack files (when 4-byte offset has MSB set)
     pub large_offsets: Option<Vec<u64>>,
-    /// SHA-1 checksum of the corresponding pack file
-    pub pack_checksum: [u8; 20],
-    /// SHA-1 checksum of all the index data above
-    pub index_checksum: [u8; 20],
+    /// Checksum of the corresponding pack file
+    pub pack_checksum: Vec<u8>,
+    /// Checksum of all the index data above
+    pub index_checksum: Vec<u8>,
     /// Raw data for debugging/display purposes
     pub raw_data: Vec<u8>,
+    /// Version 3 only: the tables of each object format, the pack's own
+    /// format first
+    pub hash_tables: Vec<HashTable>,
+    /// Version 3 only: each object's name in the second object format
+    pub compat: Option<CompatObjectNames>,
 }
 
 impl PackIndex {
     /// Parse a pack index file from raw bytes
     ///
+    /// The file does not record its hash function, so it is inferred from
+    /// the file size: with the object count from the fan-out table, only
+    /// one hash length accounts for every byte.
+    ///
+    /// Version-1 indexes have no header: a file that doesn't start with
+    /// the magic number is read as one.
+    ///
     /// # Errors
     ///
-    /// Returns a nom parse error if the input is not a valid version-2 pack
-    /// index: wrong magic number or version, a non-monotonic fan-out table,
-    /// or truncated data (fewer bytes than the fan-out table claims).
+    /// Returns a nom parse error if the input is not a valid version-1, 2 or
+    /// 3 pack index: wrong version, a non-monotonic fan-out table, or
+    /// truncated data (fewer bytes than the fan-out table or the header
+    /// claims).
     pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        match Self::peek_version(input) {
+            Some(3) => Self::parse_v3(input),
+            Some(_) => {
+                Self::parse_with_format(input, Self::detect_format(input).unwrap_or_default())
+            }
+            None => Self::parse_v1(input, Self::detect_v1_format(input).unwrap_or_default()),
+        }
+    }
+
+    /// Parse a pack index file whose object names use `object_format`
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error under the same conditions as [`Self::parse`].
+    pub fn parse_with_format(input: &[u8], object_format: ObjectFormat) -> IResult<&[u8], Self> {
+        match Self::peek_version(input) {
+            // A version-3 index names its formats itself
+            Some(3) => return Self::parse_v3(input),
+            None => return Self::parse_v1(input, object_format),
+            Some(_) => {}
+        }
         let original_input = input;
+        let hash_len = object_format.raw_len();
 
         // Parse header (magic + version)
         let (input, ()) = Self::parse_header(input)?;
@@ -53,8 +119,9 @@ impl PackIndex {
         // Get total object count from last fan-out entry
         let total_objects = fan_out[255] as usize;
 
-        // Parse object names (20 bytes each)
-        let (input, object_names) = count(Self::parse_object_name, total_objects).parse(input)?;
+        // Parse object names (one hash each)
+        let (input, object_names) =
+            count(Self::parse_object_name(hash_len), total_objects).parse(input)?;
 
         // Parse CRC32 table (4 bytes per object)
         let (input, crc32_checksums) = count(be_u32, total_objects).parse(input)?;
@@ -77,15 +144,11 @@ impl PackIndex {
             (input, None)
         };
 
-        // Parse pack checksum (20 bytes)
-        let (input, pack_checksum_bytes) = take(20usize)(input)?;
-        let mut pack_checksum = [0u8; 20];
-        pack_checksum.copy_from_slice(pack_checksum_bytes);
+        // Parse pack checksum (one hash)
+        let (input, pack_checksum) = Self::parse_object_name(hash_len)(input)?;
 
-        // Parse index checksum (20 bytes)
-        let (input, index_checksum_bytes) = take(20usize)(input)?;
-        let mut index_checksum = [0u8; 20];
-        index_checksum.copy_from_slice(index_checksum_bytes);
+        // Parse index checksum (one hash)
+        let (input, index_checksum) = Self::parse_object_name(hash_len)(input)?;
 
         // Calculate raw data size (everything we've consumed)
         let consumed = original_input.len() - input.len();
@@ -95,6 +158,7 @@ impl PackIndex {
             input,
             Self {
                 version: 2, // We only support version 2
+                object_format,
                 fan_out,
                 object_names,
                 crc32_checksums,
@@ -103,15 +167,197 @@ impl PackIndex {
                 pack_checksum,
                 index_checksum,
                 raw_data,
+                hash_tables: Vec::new(),
+                compat: None,
             },
         ))
     }
 
+    /// Parse a version-1 index
+    ///
+    /// Version 1 predates the header: the file starts with the fan-out
+    /// table, followed by one entry per object holding its 4-byte offset
+    /// then its name, and the two checksums. There are no CRC32 values, and
+    /// offsets are plain 32-bit values with no large offset table.
+    fn parse_v1(input: &[u8], object_format: ObjectFormat) -> IResult<&[u8], Self> {
+        let hash_len = object_format.raw_len();
+        let (rest, fan_out) = Self::parse_fan_out_table(input)?;
+        let (rest, entries) = count(
+            (be_u32, Self::parse_object_name(hash_len)),
+            fan_out[255] as usize,
+        )
+        .parse(rest)?;
+        let (rest, pack_checksum) = Self::parse_object_name(hash_len)(rest)?;
+        let (rest, index_checksum) = Self::parse_object_name(hash_len)(rest)?;
+        let (offsets, object_names) = entries.into_iter().unzip();
+
+        let consumed = input.len() - rest.len();
+        Ok((
+            rest,
+            Self {
+                version: 1,
+                object_format,
+                fan_out,
+                object_names,
+                crc32_checksums: Vec::new(),
+                offsets,
+                large_offsets: None,
+                pack_checksum,
+                index_checksum,
+                raw_data: input[..consumed].to_vec(),
+                hash_tables: Vec::new(),
+                compat: None,
+            },
+        ))
+    }
+
+    /// Parse a version-3 index
+    ///
+    /// The header lists each object format with the offset of its tables:
+    /// sorted shortened names, full names in pack order, and a map from
+    /// sorted to pack order. The first format's tables go on with CRC32
+    /// values in pack order, offsets in sorted order and large offsets. The
+    /// trailer, at an offset also given by the header, holds the pack and
+    /// index checksums.
+    fn parse_v3(input: &[u8]) -> IResult<&[u8], Self> {
+        fn verify(at: &[u8]) -> nom::Err<Error<&[u8]>> {
+            nom::Err::Error(Error::new(at, ErrorKind::Verify))
+        }
+
+        let (rest, _) = tag(&Self::MAGIC[..])(input)?;
+        let (rest, _version) = be_u32(rest)?;
+        let (rest, header_len) = be_u32(rest)?;
+        let (rest, total_objects) = be_u32(rest)?;
+        let (rest, format_count) = be_u32(rest)?;
+        let (rest, formats) = count((be_u32, be_u32, be_u32), format_count as usize).parse(rest)?;
+        let (_, trailer_offset) = be_u32(rest)?;
+        if (header_len as usize) > input.len() || formats.is_empty() {
+            return Err(verify(rest));
+        }
+        let total_objects = total_objects as usize;
+
+        // Names and pack order of each format
+        let mut hash_tables = Vec::with_capacity(formats.len());
+        let mut full_names = Vec::with_capacity(formats.len());
+        let mut tables_end = 0;
+        for (format_id, short_name_len, table_offset) in formats {
+            let object_format =
+                ObjectFormat::from_format_id(format_id).ok_or_else(|| verify(rest))?;
+            let short_name_len = short_name_len as usize;
+            if short_name_len > object_format.raw_len() {
+                return Err(verify(rest));
+            }
+            let tables = input
+                .get(table_offset as usize..)
+                .ok_or_else(|| verify(rest))?;
+            let (tables, short_names) = count(take(short_name_len), total_objects).parse(tables)?;
+            let (tables, names) = count(
+                Self::parse_object_name(object_format.raw_len()),
+                total_objects,
+            )
+            .parse(tables)?;
+            let (tables, pack_order) = count(be_u32, total_objects).parse(tables)?;
+
+            // Every sorted name must be the prefix of the full name it maps to
+            for (short_name, &position) in short_names.iter().zip(&pack_order) {
+                let full_name = names.get(position as usize).ok_or_else(|| verify(tables))?;
+                if !full_name.starts_with(short_name) {
+                    return Err(verify(tables));
+                }
+            }
+            if hash_tables.is_empty() {
+                tables_end = input.len() - tables.len();
+            }
+            hash_tables.push(HashTable {
+                object_format,
+                short_name_len,
+                table_offset,
+                pack_order,
+            });
+            full_names.push(names);
+        }
+
+        // The rest of the first format's tables
+        let main = &hash_tables[0];
+        let object_format = main.object_format;
+        let (tables, crc32_in_pack_order) =
+            count(be_u32, total_objects).parse(&input[tables_end..])?;
+        let (tables, offsets) = count(be_u32, total_objects).parse(tables)?;
+        let large_offset_count = offsets
+            .iter()
+            .filter(|&&offset| offset & 0x8000_0000 != 0)
+            .count();
+        let large_offsets = if large_offset_count > 0 {
+            Some(count(be_u64, large_offset_count).parse(tables)?.1)
+        } else {
+            None
+        };
+
+        let trailer = input
+            .get(trailer_offset as usize..)
+            .ok_or_else(|| verify(rest))?;
+        let (trailer, pack_checksum) = Self::parse_object_name(object_format.raw_len())(trailer)?;
+        let (remaining, index_checksum) =
+            Self::parse_object_name(object_format.raw_len())(trailer)?;
+
+        // Bring everything into sorted order of the first format
+        let in_sorted_order = |values: &[Vec<u8>]| -> Vec<Vec<u8>> {
+            main.pack_order
+                .iter()
+                .map(|&position| values[position as usize].clone())
+                .collect()
+        };
+        let object_names = in_sorted_order(&full_names[0]);
+        let crc32_checksums = main
+            .pack_order
+            .iter()
+            .map(|&position| crc32_in_pack_order[position as usize])
+            .collect();
+        let compat = hash_tables.get(1).map(|table| CompatObjectNames {
+            object_format: table.object_format,
+            object_names: in_sorted_order(&full_names[1]),
+        });
+
+        let mut fan_out = [0u32; 256];
+        for name in &object_names {
+            fan_out[usize::from(name.first().copied().unwrap_or_default())] += 1;
+        }
+        for i in 1..256 {
+            fan_out[i] += fan_out[i - 1];
+        }
+
+        let consumed = input.len() - remaining.len();
+        Ok((
+            remaining,
+            Self {
+                version: 3,
+                object_format,
+                fan_out,
+                object_names,
+                crc32_checksums,
+                offsets,
+                large_offsets,
+                pack_checksum,
+                index_checksum,
+                raw_data: input[..consumed].to_vec(),
+                hash_tables,
+                compat,
+            },
+        ))
+    }
+
+    /// Magic number of version 2 and later: \377tOc (0xff744f63)
+    const MAGIC: [u8; 4] = [0xff, 0x74, 0x4f, 0x63];
+
+    /// The version of an index starting with the magic number
+    fn peek_version(input: &[u8]) -> Option<u32> {
+        let version = input.strip_prefix(&Self::MAGIC[..])?.get(..4)?;
+        Some(u32::from_be_bytes(version.try_into().ok()?))
+    }
+
     /// Parse the index header (magic number + version)
     fn parse_header(input: &[u8]) -> IResult<&[u8], ()> {
-        // Magic number for version 2: \377tOc (0xff744f63)
-        let magic_bytes: &[u8] = &[0xff, 0x74, 0x4f, 0x63];
-        let (input, _) = tag(magic_bytes)(input)?;
+        let (input, _) = tag(&Self::MAGIC[..])(input)?;
 
         // Version number (should be 2)
         let (input, version) = be_u32(input)?;
@@ -143,12 +389,46 @@ impl PackIndex {
         Ok((input, fan_out))
     }
 
-    /// Parse a single 20-byte object name (SHA-1)
-    fn parse_object_name(input: &[u8]) -> IResult<&[u8], [u8; 20]> {
-        let (input, name_bytes) = take(20usize)(input)?;
-        let mut name = [0u8; 20];
-        name.copy_from_slice(name_bytes);
-        Ok((input, name))
+    /// Parse a single object name of `hash_len` bytes
+    fn parse_object_name(hash_len: usize) -> impl Fn(&[u8]) -> IResult<&[u8], Vec<u8>> {
+        move |input| {
+            let (input, name_bytes) = take(hash_len)(input)?;
+            Ok((input, name_bytes.to_vec()))
+        }
+    }
+
+    /// The object format whose layout matches the size of a version-2
+    /// index: header, fan-out, then per object a name, CRC32 and offset,
+    /// at most one large offset per object, and two trailing checksums
+    fn detect_format(input: &[u8]) -> Option<ObjectFormat> {
+        const FAN_OUT_END: usize = 8 + 256 * 4;
+        let total_objects = input
+            .get(FAN_OUT_END - 4..FAN_OUT_END)
+            .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+            .map(|bytes| u32::from_be_bytes(bytes) as usize)?;
+        ObjectFormat::ALL.into_iter().find(|format| {
+            let hash_len = format.raw_len();
+            let fixed = FAN_OUT_END + total_objects * (hash_len + 8) + 2 * hash_len;
+            input
+                .len()
+                .checked_sub(fixed)
+                .is_some_and(|large| large % 8 == 0 && large / 8 <= total_objects)
+        })
+    }
+
+    /// The object format whose layout matches the size of a version-1
+    /// index: fan-out, an offset and a name per object, and two trailing
+    /// checksums
+    fn detect_v1_format(input: &[u8]) -> Option<ObjectFormat> {
+        const FAN_OUT_END: usize = 256 * 4;
+        let total_objects = input
+            .get(FAN_OUT_END - 4..FAN_OUT_END)
+            .and_then(|bytes| <[u8; 4]>::try_from(bytes).ok())
+            .map(|bytes| u32::from_be_bytes(bytes) as usize)?;
+        ObjectFormat::ALL.into_iter().find(|format| {
+            let hash_len = format.raw_len();
+            input.len() == FAN_OUT_END + total_objects * (hash_len + 4) + 2 * hash_len
+        })
     }
 
     /// Get the total number of objects in this index
@@ -157,12 +437,12 @@ impl PackIndex {
         self.object_names.len()
     }
 
-    /// Look up an object by its SHA-1 hash
+    /// Look up an object by its ID
     /// Returns the offset in the pack file if found
     #[must_use]
-    pub fn lookup_object(&self, sha1: &[u8; 20]) -> Option<u64> {
+    pub fn lookup_object(&self, oid: &[u8]) -> Option<u64> {
         // Use fan-out table for efficient binary search
-        let first_byte = sha1[0] as usize;
+        let first_byte = *oid.first()? as usize;
 
         // Determine search range using fan-out table
         let start_idx = if first_byte == 0 {
@@ -176,7 +456,7 @@ impl PackIndex {
         let search_slice = &self.object_names[start_idx..end_idx];
 
         search_slice
-            .binary_search(sha1)
+            .binary_search_by(|name| name.as_slice().cmp(oid))
             .ok()
             .map(|relative_idx| self.get_object_offset(start_idx + relative_idx))
     }
@@ -209,14 +489,101 @@ impl PackIndex {
     pub fn get_object_crc32(&self, index: usize) -> Option<u32> {
         self.crc32_checksums.get(index).copied()
     }
+
+    /// The name in the index's second object format of the object at the
+    /// given index, for version-3 indexes that have one
+    #[must_use]
+    pub fn get_compat_object_name(&self, index: usize) -> Option<&[u8]> {
+        self.compat
+            .as_ref()?
+            .object_names
+            .get(index)
+            .map(Vec::as_slice)
+    }
+
+    /// 0-based file position of the full name of the object at the given
+    /// index
+    #[must_use]
+    pub fn object_name_position(&self, index: usize) -> u64 {
+        let hash_len = self.object_format.raw_len() as u64;
+        if self.version == 1 {
+            // Behind the entry's offset
+            return Self::v1_entry_position(hash_len, index) + 4;
+        }
+        match self.hash_tables.first() {
+            Some(table) => {
+                let (names_start, position) = self.v3_pack_order_position(table, index);
+                names_start + position * hash_len
+            }
+            None => Self::V2_NAMES_START + index as u64 * hash_len,
+        }
+    }
+
+    /// 0-based file position of the CRC32 of the object at the given
+    /// index, or `None` for version-1 indexes, which have no CRC32 table
+    #[must_use]
+    pub fn crc32_position(&self, index: usize) -> Option<u64> {
+        if self.version == 1 {
+            return None;
+        }
+        let objects = self.object_count() as u64;
+        let hash_len = self.object_format.raw_len() as u64;
+        Some(match self.hash_tables.first() {
+            Some(table) => {
+                let (names_start, position) = self.v3_pack_order_position(table, index);
+                // Past the full names and the order map
+                names_start + objects * (hash_len + 4) + position * 4
+            }
+            None => Self::V2_NAMES_START + objects * hash_len + index as u64 * 4,
+        })
+    }
+
+    /// 0-based file position of the offset of the object at the given index
+    #[must_use]
+    pub fn offset_position(&self, index: usize) -> u64 {
+        let objects = self.object_count() as u64;
+        let hash_len = self.object_format.raw_len() as u64;
+        if self.version == 1 {
+            return Self::v1_entry_position(hash_len, index);
+        }
+        match self.hash_tables.first() {
+            Some(table) => {
+                let (names_start, _) = self.v3_pack_order_position(table, index);
+                names_start + objects * (hash_len + 8) + index as u64 * 4
+            }
+            None => Self::V2_NAMES_START + objects * (hash_len + 4) + index as u64 * 4,
+        }
+    }
+
+    /// Version-2 header and fan-out table
+    const V2_NAMES_START: u64 = 8 + 256 * 4;
+
+    /// Start of the version-1 entry, offset then name, of the object at the
+    /// given index
+    const fn v1_entry_position(hash_len: u64, index: usize) -> u64 {
+        256 * 4 + index as u64 * (4 + hash_len)
+    }
+
+    /// Start of a version-3 format's full names, and the position in pack
+    /// order of the object at the given sorted index
+    fn v3_pack_order_position(&self, table: &HashTable, index: usize) -> (u64, u64) {
+        let names_start =
+            u64::from(table.table_offset) + (self.object_count() * table.short_name_len) as u64;
+        let position = table.pack_order.get(index).copied().unwrap_or_default();
+        (names_start, u64::from(position))
+    }
 }
 
 impl fmt::Display for PackIndex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Pack Index (version {})", self.version)?;
         writeln!(f, "Total objects: {}", self.object_count())?;
-        writeln!(f, "Pack checksum: {}", hex::encode(self.pack_checksum))?;
-        writeln!(f, "Index checksum: {}", hex::encode(self.index_checksum))?;
+        writeln!(f, "Object format: {}", self.object_format)?;
+        if let Some(ref compat) = self.compat {
+            writeln!(f, "Compatibility format: {}", compat.object_format)?;
+        }
+        writeln!(f, "Pack checksum: {}", hex::encode(&self.pack_checksum))?;
+        writeln!(f, "Index checksum: {}", hex::encode(&self.index_checksum))?;
 
         if let Some(ref large_offsets) = self.large_offsets {
             writeln!(f, "Large offsets: {} entries", large_offsets.len())?;
@@ -256,7 +623,7 @@ mod tests {
     fn test_parse_object_name() {
         let name_data = [0x01; 20]; // 20 bytes of 0x01
 
-        let (remaining, name) = PackIndex::parse_object_name(&name_data).unwrap();
+        let (remaining, name) = PackIndex::parse_object_name(20)(&name_data).unwrap();
         assert!(remaining.is_empty());
         assert_eq!(name, [0x01; 20]);
     }
@@ -283,20 +650,23 @@ mod tests {
             fan_out[i] = 1; // All subsequent entries have the same count
         }
 
-        let object_names = vec![[0x00; 20]]; // One object with all zeros
+        let object_names = vec![vec![0x00; 20]]; // One object with all zeros
         let crc32_checksums = vec![0x12345678];
         let offsets = vec![100]; // Offset 100 in pack file
 
         let index = PackIndex {
             version: 2,
+            object_format: ObjectFormat::Sha1,
             fan_out,
             object_names,
             crc32_checksums,
             offsets,
             large_offsets: None,
-            pack_checksum: [0; 20],
-            index_checksum: [0; 20],
+            pack_checksum: vec![0; 20],
+            index_checksum: vec![0; 20],
             raw_data: vec![],
+            hash_tables: Vec::new(),
+            compat: None,
         };
 
         // Should find the object
@@ -336,20 +706,29 @@ mod tests {
         let mut obj5 = [0u8; 20];
         obj5[0] = 0xff;
 
-        let object_names = vec![obj1, obj2, obj3, obj4, obj5];
+        let object_names = vec![
+            obj1.to_vec(),
+            obj2.to_vec(),
+            obj3.to_vec(),
+            obj4.to_vec(),
+            obj5.to_vec(),
+        ];
         let crc32_checksums = vec![0x11111111, 0x22222222, 0x33333333, 0x44444444, 0x55555555];
         let offsets = vec![100, 200, 300, 400, 500];
 
         let index = PackIndex {
             version: 2,
+            object_format: ObjectFormat::Sha1,
             fan_out,
             object_names,
             crc32_checksums,
             offsets,
             large_offsets: None,
-            pack_checksum: [0; 20],
-            index_checksum: [0; 20],
+            pack_checksum: vec![0; 20],
+            index_checksum: vec![0; 20],
             raw_data: vec![],
+            hash_tables: Vec::new(),
+            compat: None,
         };
 
         // Test lookups
@@ -379,25 +758,238 @@ mod tests {
             fan_out[i] = 1;
         }
 
-        let object_names = vec![[0x00; 20]];
+        let object_names = vec![vec![0x00; 20]];
         let crc32_checksums = vec![0x12345678];
         let offsets = vec![0x80000000]; // MSB set indicates large offset
         let large_offsets = Some(vec![0x123456789abcdef0]); // Large 8-byte offset
 
         let index = PackIndex {
             version: 2,
+            object_format: ObjectFormat::Sha1,
             fan_out,
             object_names,
             crc32_checksums,
             offsets,
             large_offsets,
-            pack_checksum: [0; 20],
-            index_checksum: [0; 20],
+            pack_checksum: vec![0; 20],
+            index_checksum: vec![0; 20],
             raw_data: vec![],
+            hash_tables: Vec::new(),
+            compat: None,
         };
 
         // Should return the large offset
         assert_eq!(index.get_object_offset(0), 0x123456789abcdef0);
         assert_eq!(index.lookup_object(&[0x00; 20]), Some(0x123456789abcdef0));
     }
+
+    /// A version-2 index holding one object named `name`, at offset 12
+    fn single_object_index(name: &[u8]) -> Vec<u8> {
+        let mut data = vec![0xff, 0x74, 0x4f, 0x63, 0, 0, 0, 2];
+        for byte in 0..=255u8 {
+            let count = u32::from(byte >= name[0]);
+            data.extend_from_slice(&count.to_be_bytes());
+        }
+        data.extend_from_slice(name);
+        data.extend_from_slice(&0x1234_5678u32.to_be_bytes());
+        data.extend_from_slice(&12u32.to_be_bytes());
+        data.extend_from_slice(&vec![0xaa; name.len()]);
+        data.extend_from_slice(&vec![0xbb; name.len()]);
+        data
+    }
+
+    #[test]
+    fn test_parse_detects_object_format() {
+        let sha256_name = [0x42; 32];
+        let data = single_object_index(&sha256_name);
+        let (remaining, index) = PackIndex::parse(&data).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(index.object_format, ObjectFormat::Sha256);
+        assert_eq!(index.object_names, [sha256_name.to_vec()]);
+        assert_eq!(index.pack_checksum, vec![0xaa; 32]);
+        assert_eq!(index.lookup_object(&sha256_name), Some(12));
+
+        let (_, index) = PackIndex::parse(&single_object_index(&[0x42; 20])).unwrap();
+        assert_eq!(index.object_format, ObjectFormat::Sha1);
+        assert_eq!(index.lookup_object(&[0x42; 20]), Some(12));
+    }
+
+    /// A version-3 index over objects given in pack order as
+    /// `(SHA-256 name, SHA-1 name, CRC32, offset)`, with 4-byte short names
+    fn v3_index(objects: &[([u8; 32], [u8; 20], u32, u32)]) -> Vec<u8> {
+        const SHORT: usize = 4;
+        let n = objects.len();
+        let by_name = |key: &dyn Fn(usize) -> Vec<u8>| {
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by_key(|&i| key(i));
+            order
+        };
+        let main_order = by_name(&|i| objects[i].0.to_vec());
+        let compat_order = by_name(&|i| objects[i].1.to_vec());
+
+        let header_len = 48;
+        let main_offset = header_len;
+        let compat_offset = main_offset + n * (SHORT + 32 + 4 + 4 + 4);
+        let trailer_offset = compat_offset + n * (SHORT + 20 + 4);
+
+        let mut data = vec![0xff, 0x74, 0x4f, 0x63, 0, 0, 0, 3];
+        for value in [header_len, n, 2] {
+            data.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        data.extend_from_slice(b"s256");
+        data.extend_from_slice(&(SHORT as u32).to_be_bytes());
+        data.extend_from_slice(&(main_offset as u32).to_be_bytes());
+        data.extend_from_slice(b"sha1");
+        data.extend_from_slice(&(SHORT as u32).to_be_bytes());
+        data.extend_from_slice(&(compat_offset as u32).to_be_bytes());
+        data.extend_from_slice(&(trailer_offset as u32).to_be_bytes());
+
+        for &i in &main_order {
+            data.extend_from_slice(&objects[i].0[..SHORT]);
+        }
+        for object in objects {
+            data.extend_from_slice(&object.0);
+        }
+        for &i in &main_order {
+            data.extend_from_slice(&(i as u32).to_be_bytes());
+        }
+        for object in objects {
+            data.extend_from_slice(&object.2.to_be_bytes());
+        }
+        for &i in &main_order {
+            data.extend_from_slice(&objects[i].3.to_be_bytes());
+        }
+
+        for &i in &compat_order {
+            data.extend_from_slice(&objects[i].1[..SHORT]);
+        }
+        for object in objects {
+            data.extend_from_slice(&object.1);
+        }
+        for &i in &compat_order {
+            data.extend_from_slice(&(i as u32).to_be_bytes());
+        }
+
+        data.extend_from_slice(&[0xaa; 32]);
+        data.extend_from_slice(&[0xbb; 32]);
+        data
+    }
+
+    #[test]
+    fn test_parse_v3_index() {
+        // Pack order differs from name order in both formats
+        let objects = [
+            ([0xcc; 32], [0x11; 20], 0xc0c0_c0c0, 12),
+            ([0x33; 32], [0xee; 20], 0x3030_3030, 140),
+        ];
+        let data = v3_index(&objects);
+        let (remaining, index) = PackIndex::parse(&data).unwrap();
+        assert!(remaining.is_empty());
+
+        assert_eq!(index.version, 3);
+        assert_eq!(index.object_format, ObjectFormat::Sha256);
+        assert_eq!(index.object_names, [vec![0x33; 32], vec![0xcc; 32]]);
+        assert_eq!(index.crc32_checksums, [0x3030_3030, 0xc0c0_c0c0]);
+        assert_eq!(index.fan_out[0x32], 0);
+        assert_eq!(index.fan_out[0x33], 1);
+        assert_eq!(index.fan_out[255], 2);
+        assert_eq!(index.lookup_object(&[0xcc; 32]), Some(12));
+        assert_eq!(index.pack_checksum, vec![0xaa; 32]);
+        assert_eq!(index.index_checksum, vec![0xbb; 32]);
+
+        // Compat names line up with the sorted SHA-256 names
+        let compat = index.compat.as_ref().unwrap();
+        assert_eq!(compat.object_format, ObjectFormat::Sha1);
+        assert_eq!(index.get_compat_object_name(0), Some(&[0xee; 20][..]));
+        assert_eq!(index.get_compat_object_name(1), Some(&[0x11; 20][..]));
+
+        // The first sorted object is second in pack order
+        assert_eq!(index.hash_tables[0].pack_order, [1, 0]);
+        let names_start = 48 + 2 * 4;
+        assert_eq!(index.object_name_position(0), names_start + 32);
+        assert_eq!(
+            &data[index.object_name_position(1) as usize..][..32],
+            &[0xcc; 32]
+        );
+        assert_eq!(
+            &data[index.crc32_position(0).unwrap() as usize..][..4],
+            &0x3030_3030u32.to_be_bytes()
+        );
+        assert_eq!(
+            &data[index.offset_position(1) as usize..][..4],
+            &12u32.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_v3_rejects_mismatched_short_names() {
+        let mut data = v3_index(&[([0xcc; 32], [0x11; 20], 0, 12)]);
+        // First byte of the sorted SHA-256 short name
+        data[48] = 0xcd;
+        assert!(PackIndex::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_v2_positions() {
+        let data = single_object_index(&[0x42; 20]);
+        let (_, index) = PackIndex::parse(&data).unwrap();
+        assert_eq!(index.object_name_position(0), 1032);
+        assert_eq!(index.crc32_position(0), Some(1052));
+        assert_eq!(index.offset_position(0), 1056);
+    }
+
+    /// A version-1 index over sorted `(name, offset)` entries
+    fn v1_index(entries: &[(&[u8], u32)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for byte in 0..=255u8 {
+            let count = entries.iter().filter(|(name, _)| name[0] <= byte).count() as u32;
+            data.extend_from_slice(&count.to_be_bytes());
+        }
+        for (name, offset) in entries {
+            data.extend_from_slice(&offset.to_be_bytes());
+            data.extend_from_slice(name);
+        }
+        let hash_len = entries.first().map_or(20, |(name, _)| name.len());
+        data.extend_from_slice(&vec![0xaa; hash_len]);
+        data.extend_from_slice(&vec![0xbb; hash_len]);
+        data
+    }
+
+    #[test]
+    fn test_parse_v1_index() {
+        let first = [0x01; 20];
+        let second = [0xab; 20];
+        // Version 1 has no large offset table: the MSB is part of the offset
+        let data = v1_index(&[(&first, 12), (&second, 0x8000_0010)]);
+        let (remaining, index) = PackIndex::parse(&data).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(index.version, 1);
+        assert_eq!(index.object_format, ObjectFormat::Sha1);
+        assert_eq!(index.object_names, [first.to_vec(), second.to_vec()]);
+        assert!(index.crc32_checksums.is_empty());
+        assert_eq!(index.get_object_crc32(0), None);
+        assert_eq!(index.large_offsets, None);
+        assert_eq!(index.lookup_object(&first), Some(12));
+        assert_eq!(index.lookup_object(&second), Some(0x8000_0010));
+        assert_eq!(index.pack_checksum, vec![0xaa; 20]);
+        assert_eq!(index.index_checksum, vec![0xbb; 20]);
+
+        assert_eq!(index.offset_position(1), 1048);
+        assert_eq!(index.object_name_position(1), 1052);
+        assert_eq!(index.crc32_position(1), None);
+
+        let (_, index) = PackIndex::parse(&v1_index(&[(&[0x42; 32], 12)])).unwrap();
+        assert_eq!(index.object_format, ObjectFormat::Sha256);
+        assert_eq!(index.lookup_object(&[0x42; 32]), Some(12));
+    }
+
+    #[test]
+    fn test_v1_rejects_truncated_entries() {
+        let data = v1_index(&[(&[0x01; 20], 12)]);
+        assert!(PackIndex::parse(&data[..1024 + 10]).is_err());
+        // With the magic number, an unknown version is still an error
+        let mut data = single_object_index(&[0x42; 20]);
+        data[7] = 4;
+        assert!(PackIndex::parse(&data).is_err());
+    }
 }
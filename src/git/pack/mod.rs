@@ -11,7 +11,9 @@ pub mod delta;
 pub mod index;
 pub mod mtimes;
 pub mod multi_pack_index;
+pub mod multi_pack_index_chain;
 pub mod object;
+pub mod reader;
 pub mod resolver;
 pub mod reverse_index;
 
@@ -20,8 +22,12 @@ pub use delta::{DeltaInstruction, parse_delta_instructions};
 pub use index::PackIndex;
 pub use mtimes::PackMtimes;
 pub use multi_pack_index::MultiPackIndex;
+pub use multi_pack_index_chain::{
+    ChainObjectLocation, MultiPackIndexChain, MultiPackIndexChainError, MultiPackIndexLayer,
+};
 pub use object::{Object, ObjectHeader, ObjectType};
-pub use resolver::{ResolvedObject, resolve_objects};
+pub use reader::{DeltaBase, PackEntryLocation, PackReader, PackReaderError};
+pub use resolver::{ResolvedObject, resolve_objects, resolve_objects_with_cache_limit};
 pub use reverse_index::PackReverseIndex;
 
 use thiserror::Error;
@@ -124,8 +124,8 @@ pub struct MultiPackIndex {
     pub hash_function_id: u8,
     /// Number of chunks in the chunk lookup table (excluding the terminator)
     pub chunk_count: u8,
-    /// Number of base multi-pack-index files (non-zero only for incremental
-    /// chains, which this parser rejects)
+    /// Number of base multi-pack-index files: the layers below this one in
+    /// an incremental chain (multi-pack-index.d/), zero for a standalone file
     pub base_midx_count: u8,
     /// Number of pack files covered by this multi-pack-index
     pub num_packs: u32,
@@ -146,6 +146,8 @@ pub struct MultiPackIndex {
     pub large_offsets: Option<Vec<u64>>,
     /// Reverse index from the RIDX chunk: positions sorted by pseudo-pack order
     pub reverse_index: Option<Vec<u32>>,
+    /// Checksums of the base layers from the BASE chunk, bottom first
+    pub base_midxs: Vec<Vec<u8>>,
     /// SHA-1/SHA-256 checksum of all the above content
     pub checksum: Vec<u8>,
     /// Raw data for debugging/display purposes
@@ -180,9 +182,10 @@ impl MultiPackIndex {
     /// # Errors
     ///
     /// Returns a nom parse error if the input is not a valid multi-pack-index:
-    /// wrong "MIDX" signature, unsupported version or hash function ID, an
-    /// incremental file (non-zero base count), a malformed chunk lookup table,
-    /// a missing required chunk, or chunk contents inconsistent with the header.
+    /// wrong "MIDX" signature, unsupported version or hash function ID, a
+    /// malformed chunk lookup table, a missing required chunk (including
+    /// BASE when the header counts base files), or chunk contents
+    /// inconsistent with the header.
     pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
         let original_input = input;
 
@@ -258,6 +261,15 @@ impl MultiPackIndex {
         let reverse_index = chunk_slice(Self::CHUNK_RIDX)
             .map(|data| Self::parse_ridx(data, total_objects))
             .transpose()?;
+        let base_midxs = if base_midx_count == 0 {
+            Vec::new()
+        } else {
+            Self::parse_base(
+                required_chunk(Self::CHUNK_BASE)?,
+                base_midx_count as usize,
+                hash_len,
+            )?
+        };
 
         // Every large-offset redirect must land inside the LOFF table
         let large_count = large_offsets.as_ref().map_or(0, Vec::len);
@@ -295,6 +307,7 @@ impl MultiPackIndex {
                 object_offsets,
                 large_offsets,
                 reverse_index,
+                base_midxs,
                 checksum,
                 raw_data,
             },
@@ -323,14 +336,7 @@ impl MultiPackIndex {
         }
 
         let (input, chunk_count) = be_u8(input)?;
-
-        // Incremental multi-pack-index chains (multi-pack-index.d/) are not
-        // supported; a standalone file always has zero bases
         let (input, base_midx_count) = be_u8(input)?;
-        if base_midx_count != 0 {
-            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
-        }
-
         let (input, num_packs) = be_u32(input)?;
 
         Ok((
@@ -438,6 +444,19 @@ impl MultiPackIndex {
         Ok(positions)
     }
 
+    /// Parse the BASE chunk: the checksum of each base layer, bottom first
+    fn parse_base(
+        data: &[u8],
+        base_midx_count: usize,
+        hash_len: usize,
+    ) -> Result<Vec<Vec<u8>>, nom::Err<Error<&[u8]>>> {
+        if data.len() != base_midx_count * hash_len {
+            return Err(nom::Err::Error(Error::new(data, ErrorKind::LengthValue)));
+        }
+
+        Ok(data.chunks_exact(hash_len).map(<[u8]>::to_vec).collect())
+    }
+
     /// Get the total number of objects across all indexed packs
     #[must_use]
     pub const fn object_count(&self) -> usize {
@@ -476,6 +495,24 @@ impl MultiPackIndex {
         self.object_ids.get(index).map(hex::encode)
     }
 
+    /// Lexicographic index of an object by its binary object ID, narrowing
+    /// the search with the fan-out table
+    #[must_use]
+    pub fn position_of(&self, oid: &[u8]) -> Option<usize> {
+        let first_byte = *oid.first()? as usize;
+        let start = if first_byte == 0 {
+            0
+        } else {
+            self.fan_out[first_byte - 1] as usize
+        };
+        let end = (self.fan_out[first_byte] as usize).min(self.object_ids.len());
+        let candidates = self.object_ids.get(start..end)?;
+        candidates
+            .binary_search_by(|candidate| candidate.as_slice().cmp(oid))
+            .ok()
+            .map(|index| start + index)
+    }
+
     /// Get the pack name for a pack-int-id
     #[must_use]
     pub fn pack_name_for(&self, pack_id: u32) -> Option<&str> {
@@ -508,6 +545,9 @@ impl fmt::Display for MultiPackIndex {
         )?;
         writeln!(f, "Packs: {}", self.pack_count())?;
         writeln!(f, "Total objects: {}", self.object_count())?;
+        if self.base_midx_count != 0 {
+            writeln!(f, "Base layers: {}", self.base_midx_count)?;
+        }
         writeln!(f, "Checksum: {}", hex::encode(&self.checksum))?;
 
         writeln!(f, "\nChunks:")?;
@@ -554,12 +594,12 @@ impl fmt::Display for MultiPackIndex {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     /// Assemble a multi-pack-index from raw chunk payloads, computing the
     /// chunk lookup table offsets automatically
-    fn assemble_midx(
+    pub fn assemble_midx(
         version: u8,
         hash_function_id: u8,
         base_midx_count: u8,
@@ -598,7 +638,7 @@ mod tests {
     }
 
     /// Build a NUL-terminated, 4-byte-aligned PNAM payload
-    fn build_pnam(pack_names: &[&str]) -> Vec<u8> {
+    pub fn build_pnam(pack_names: &[&str]) -> Vec<u8> {
         let mut payload = Vec::new();
         for name in pack_names {
             payload.extend_from_slice(name.as_bytes());
@@ -874,12 +914,55 @@ mod tests {
 
     #[test]
     fn test_incremental_rejected() {
+        // A base MIDX count without the BASE chunk naming the bases
         let mut data = create_test_midx_data(&["pack-a.pack"], 1, 1, false);
         data[7] = 1; // base MIDX count
 
         assert!(MultiPackIndex::parse(&data).is_err());
     }
 
+    #[test]
+    fn test_incremental_layer() {
+        let hash_len = 20;
+        let (oidf, oidl) = build_oids(2, hash_len);
+        let ooff = [0u32, 0, 0, 100]
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect();
+        let bases = [[0xa0u8; 20], [0xa1u8; 20]].concat();
+
+        let chunks = vec![
+            (MultiPackIndex::CHUNK_PNAM, build_pnam(&["pack-c.pack"])),
+            (MultiPackIndex::CHUNK_OIDF, oidf),
+            (MultiPackIndex::CHUNK_OIDL, oidl),
+            (MultiPackIndex::CHUNK_OOFF, ooff),
+            (MultiPackIndex::CHUNK_BASE, bases.clone()),
+        ];
+        let data = assemble_midx(1, 1, 2, 1, &chunks, hash_len);
+        let (_, midx) = MultiPackIndex::parse(&data).unwrap();
+        assert_eq!(midx.base_midx_count, 2);
+        assert_eq!(midx.base_midxs, vec![vec![0xa0; 20], vec![0xa1; 20]]);
+
+        // The BASE chunk must hold exactly the counted checksums
+        let data = assemble_midx(1, 1, 3, 1, &chunks, hash_len);
+        assert!(MultiPackIndex::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_position_of() {
+        let data = create_test_midx_data(&["pack-a.pack"], 5, 1, false);
+        let (_, midx) = MultiPackIndex::parse(&data).unwrap();
+
+        let mut oid = vec![0x11u8; 20];
+        oid[0] = 3;
+        assert_eq!(midx.position_of(&oid), Some(3));
+        oid[1] = 0x12;
+        assert_eq!(midx.position_of(&oid), None);
+        oid[0] = 0xff;
+        assert_eq!(midx.position_of(&oid), None);
+        assert_eq!(midx.position_of(&[]), None);
+    }
+
     #[test]
     fn test_missing_required_chunk() {
         // No OOFF chunk
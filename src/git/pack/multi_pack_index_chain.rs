@@ -0,0 +1,452 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::MultiPackIndex;
+use crate::git::commit_graph::{BaseCheck, CommitGraphChain};
+
+#[derive(Debug, Error)]
+pub enum MultiPackIndexChainError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid multi-pack-index-chain file: {0}")]
+    InvalidChain(String),
+
+    #[error("Missing multi-pack-index layer: {0}")]
+    MissingLayer(String),
+
+    #[error("Invalid multi-pack-index layer {hash}: {reason}")]
+    InvalidLayer { hash: String, reason: String },
+}
+
+/// One layer of an incremental multi-pack-index: a
+/// `multi-pack-index-<hash>.midx` file
+#[derive(Debug, Clone)]
+pub struct MultiPackIndexLayer {
+    /// Hex checksum listed in the chain file, which also names the file
+    pub hash: String,
+    pub path: PathBuf,
+    pub midx: MultiPackIndex,
+    /// Global position of this layer's first object, i.e. the number of
+    /// objects in the layers below
+    pub first_object: usize,
+    /// Global pack-int-id of this layer's first pack, i.e. the number of
+    /// packs in the layers below
+    pub first_pack: usize,
+    pub base_check: BaseCheck,
+}
+
+impl MultiPackIndexLayer {
+    /// Global positions of this layer's objects
+    #[must_use]
+    pub const fn objects(&self) -> Range<usize> {
+        self.first_object..self.first_object + self.midx.object_count()
+    }
+
+    /// Global pack-int-ids of this layer's packs
+    #[must_use]
+    pub const fn packs(&self) -> Range<usize> {
+        self.first_pack..self.first_pack + self.midx.pack_count()
+    }
+
+    /// Whether the file's trailing checksum is the hash the chain lists
+    #[must_use]
+    pub fn checksum_matches_name(&self) -> bool {
+        hex::encode(&self.midx.checksum) == self.hash
+    }
+
+    /// Whether the layer fits where the chain puts it
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.base_check == BaseCheck::Matches && self.checksum_matches_name()
+    }
+}
+
+/// Where the chain stores an object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainObjectLocation {
+    /// Index of the layer indexing the object
+    pub layer: usize,
+    /// Global position of the object across the chain
+    pub position: usize,
+    pub pack_name: String,
+    pub offset: u64,
+}
+
+/// An incremental multi-pack-index (objects/pack/multi-pack-index.d)
+///
+/// `git multi-pack-index write --incremental` indexes only the packs that
+/// are not covered yet, in a new layer on top of the existing ones. The
+/// `multi-pack-index-chain` file lists the layers' checksums, base layer
+/// first; each layer names the layers below it in its BASE chunk. Object
+/// positions and pack-int-ids are global: a layer's objects and packs are
+/// numbered after those of all the layers below.
+#[derive(Debug, Clone)]
+pub struct MultiPackIndexChain {
+    /// Path of the `multi-pack-index-chain` file
+    pub path: PathBuf,
+    /// Layers in chain order, base layer first
+    pub layers: Vec<MultiPackIndexLayer>,
+}
+
+impl MultiPackIndexChain {
+    /// Directory of an incremental multi-pack-index, under objects/pack
+    pub const DIR_NAME: &str = "multi-pack-index.d";
+    /// File listing the layers of the chain
+    pub const CHAIN_FILE_NAME: &str = "multi-pack-index-chain";
+
+    /// Path of the layer with the given checksum in a multi-pack-index.d
+    /// directory
+    #[must_use]
+    pub fn layer_path(dir: &Path, hash: &str) -> PathBuf {
+        dir.join(format!("multi-pack-index-{hash}.midx"))
+    }
+
+    /// Whether a file name has the shape of a layer file,
+    /// `multi-pack-index-<hash>.midx`
+    #[must_use]
+    pub fn is_layer_file_name(name: &str) -> bool {
+        name.strip_prefix("multi-pack-index-")
+            .and_then(|rest| rest.strip_suffix(".midx"))
+            .is_some_and(crate::git::refs::is_object_id)
+    }
+
+    /// Read a chain file and every layer it lists
+    ///
+    /// The chain file has the same format as a commit-graph chain: one hex
+    /// checksum per line. Base lists that disagree with the chain are
+    /// recorded in each layer's [`BaseCheck`] rather than rejected, so they
+    /// can be shown.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The chain file or a layer file cannot be read
+    /// - The chain file is malformed
+    /// - A listed layer is missing
+    /// - A layer is not a valid multi-pack-index, or points an object at a
+    ///   pack it doesn't list
+    pub fn load(chain_path: &Path) -> Result<Self, MultiPackIndexChainError> {
+        let data = std::fs::read(chain_path)?;
+        let (_, hashes) = CommitGraphChain::parse_chain(&data)
+            .map_err(|e| MultiPackIndexChainError::InvalidChain(format!("{e:?}")))?;
+        let dir = chain_path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut layers: Vec<MultiPackIndexLayer> = Vec::with_capacity(hashes.len());
+        let (mut first_object, mut first_pack) = (0, 0);
+        for (index, hash) in hashes.iter().enumerate() {
+            let path = Self::layer_path(dir, hash);
+            if !path.is_file() {
+                return Err(MultiPackIndexChainError::MissingLayer(hash.clone()));
+            }
+            let layer_data = std::fs::read(&path)?;
+            let (_, midx) = MultiPackIndex::parse(&layer_data).map_err(|e| {
+                MultiPackIndexChainError::InvalidLayer {
+                    hash: hash.clone(),
+                    reason: format!("{e:?}"),
+                }
+            })?;
+
+            // Pack-int-ids in OOFF are local to the layer
+            if midx
+                .object_offsets
+                .iter()
+                .any(|object_offset| object_offset.pack_id >= midx.num_packs)
+            {
+                return Err(MultiPackIndexChainError::InvalidLayer {
+                    hash: hash.clone(),
+                    reason: "object in a pack beyond this layer".to_string(),
+                });
+            }
+
+            let base_check = Self::check_bases(&midx, &hashes[..index]);
+            let layer = MultiPackIndexLayer {
+                hash: hash.clone(),
+                path,
+                midx,
+                first_object,
+                first_pack,
+                base_check,
+            };
+            first_object = layer.objects().end;
+            first_pack = layer.packs().end;
+            layers.push(layer);
+        }
+
+        Ok(Self {
+            path: chain_path.to_path_buf(),
+            layers,
+        })
+    }
+
+    /// Compare a layer's BASE chunk with the hashes of the layers below it
+    fn check_bases(midx: &MultiPackIndex, expected: &[String]) -> BaseCheck {
+        if midx.base_midxs.len() != expected.len() {
+            return BaseCheck::CountMismatch {
+                expected: expected.len(),
+                found: midx.base_midxs.len(),
+            };
+        }
+        midx.base_midxs
+            .iter()
+            .map(hex::encode)
+            .zip(expected)
+            .enumerate()
+            .find(|(_, (found, expected))| found != *expected)
+            .map_or(BaseCheck::Matches, |(index, (found, expected))| {
+                BaseCheck::Mismatch {
+                    index,
+                    expected: expected.clone(),
+                    found,
+                }
+            })
+    }
+
+    /// Total number of objects across all layers
+    #[must_use]
+    pub fn object_count(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.objects().end)
+    }
+
+    /// Total number of packs across all layers
+    #[must_use]
+    pub fn pack_count(&self) -> usize {
+        self.layers.last().map_or(0, |layer| layer.packs().end)
+    }
+
+    /// Whether every layer's base list and checksum match the chain
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.layers.iter().all(MultiPackIndexLayer::is_consistent)
+    }
+
+    /// Index of the layer stored at `path`
+    #[must_use]
+    pub fn layer_index_of(&self, path: &Path) -> Option<usize> {
+        let name = path.file_name()?;
+        self.layers
+            .iter()
+            .position(|layer| layer.path.file_name() == Some(name))
+    }
+
+    /// Layer index and position within that layer of a global position
+    #[must_use]
+    pub fn locate(&self, global_position: usize) -> Option<(usize, usize)> {
+        self.layers
+            .iter()
+            .position(|layer| layer.objects().contains(&global_position))
+            .map(|index| (index, global_position - self.layers[index].first_object))
+    }
+
+    /// Hex object ID at a global position
+    #[must_use]
+    pub fn oid_hex_at(&self, global_position: usize) -> Option<String> {
+        let (layer, local) = self.locate(global_position)?;
+        self.layers[layer].midx.oid_hex_at(local)
+    }
+
+    /// Name of the pack with a global pack-int-id
+    #[must_use]
+    pub fn pack_name_for(&self, global_pack_id: usize) -> Option<&str> {
+        let layer = self
+            .layers
+            .iter()
+            .find(|layer| layer.packs().contains(&global_pack_id))?;
+        let local = u32::try_from(global_pack_id - layer.first_pack).ok()?;
+        layer.midx.pack_name_for(local)
+    }
+
+    /// Where an object is stored, by its binary object ID, searching from
+    /// the top layer down like git does
+    #[must_use]
+    pub fn find_object(&self, oid: &[u8]) -> Option<ChainObjectLocation> {
+        self.layers
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, layer)| {
+                let local = layer.midx.position_of(oid)?;
+                let (pack_id, offset) = layer.midx.offset_at(local)?;
+                Some(ChainObjectLocation {
+                    layer: index,
+                    position: layer.first_object + local,
+                    pack_name: layer.midx.pack_name_for(pack_id)?.to_string(),
+                    offset,
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::multi_pack_index::tests::{assemble_midx, build_pnam};
+    use super::*;
+
+    const HASH_LEN: usize = 20;
+
+    /// A layer indexing objects with the given first OID bytes, spread over
+    /// its packs round-robin, with a checksum made of `checksum_byte`
+    fn layer_data(
+        first_bytes: &[u8],
+        pack_names: &[&str],
+        bases: &[[u8; HASH_LEN]],
+        checksum_byte: u8,
+    ) -> Vec<u8> {
+        let mut oidl = Vec::new();
+        let mut oidf = Vec::new();
+        let mut ooff = Vec::new();
+        for (index, &first_byte) in first_bytes.iter().enumerate() {
+            let mut oid = [0x33u8; HASH_LEN];
+            oid[0] = first_byte;
+            oidl.extend_from_slice(&oid);
+            ooff.extend_from_slice(&((index % pack_names.len()) as u32).to_be_bytes());
+            ooff.extend_from_slice(&(12 + index as u32 * 100).to_be_bytes());
+        }
+        for byte in 0..=255u8 {
+            let cumulative = first_bytes.iter().filter(|&&b| b <= byte).count() as u32;
+            oidf.extend_from_slice(&cumulative.to_be_bytes());
+        }
+
+        let mut chunks = vec![
+            (MultiPackIndex::CHUNK_PNAM, build_pnam(pack_names)),
+            (MultiPackIndex::CHUNK_OIDF, oidf),
+            (MultiPackIndex::CHUNK_OIDL, oidl),
+            (MultiPackIndex::CHUNK_OOFF, ooff),
+        ];
+        if !bases.is_empty() {
+            chunks.push((MultiPackIndex::CHUNK_BASE, bases.concat()));
+        }
+        let mut data = assemble_midx(
+            1,
+            1,
+            bases.len() as u8,
+            pack_names.len() as u32,
+            &chunks,
+            HASH_LEN,
+        );
+        let checksum_start = data.len() - HASH_LEN;
+        data[checksum_start..].fill(checksum_byte);
+        data
+    }
+
+    /// Write a two-layer chain: two packs and three objects in the base
+    /// layer, one pack and one object on top
+    fn write_chain(dir: &Path, top_bases: &[[u8; HASH_LEN]]) -> PathBuf {
+        let base_hash = hex::encode([0xb0; HASH_LEN]);
+        let top_hash = hex::encode([0xb1; HASH_LEN]);
+        std::fs::write(
+            MultiPackIndexChain::layer_path(dir, &base_hash),
+            layer_data(
+                &[0x10, 0x30, 0x50],
+                &["pack-a.pack", "pack-b.pack"],
+                &[],
+                0xb0,
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            MultiPackIndexChain::layer_path(dir, &top_hash),
+            layer_data(&[0x20], &["pack-c.pack"], top_bases, 0xb1),
+        )
+        .unwrap();
+
+        let chain_path = dir.join(MultiPackIndexChain::CHAIN_FILE_NAME);
+        std::fs::write(&chain_path, format!("{base_hash}\n{top_hash}\n")).unwrap();
+        chain_path
+    }
+
+    #[test]
+    fn test_load_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xb0; HASH_LEN]]);
+        let chain = MultiPackIndexChain::load(&chain_path).unwrap();
+
+        assert_eq!(chain.layers.len(), 2);
+        assert!(chain.is_consistent());
+        assert_eq!(chain.object_count(), 4);
+        assert_eq!(chain.pack_count(), 3);
+        assert_eq!(chain.layers[1].objects(), 3..4);
+        assert_eq!(chain.layers[1].packs(), 2..3);
+        assert_eq!(chain.pack_name_for(2), Some("pack-c.pack"));
+        assert_eq!(chain.pack_name_for(3), None);
+        assert_eq!(chain.locate(3), Some((1, 0)));
+        assert!(chain.oid_hex_at(3).unwrap().starts_with("20"));
+
+        let top_path = chain.layers[1].path.clone();
+        assert_eq!(chain.layer_index_of(&top_path), Some(1));
+        assert!(MultiPackIndexChain::is_layer_file_name(
+            &top_path.file_name().unwrap().to_string_lossy()
+        ));
+    }
+
+    #[test]
+    fn test_find_object_across_layers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xb0; HASH_LEN]]);
+        let chain = MultiPackIndexChain::load(&chain_path).unwrap();
+
+        let mut oid = [0x33u8; HASH_LEN];
+        oid[0] = 0x20;
+        assert_eq!(
+            chain.find_object(&oid),
+            Some(ChainObjectLocation {
+                layer: 1,
+                position: 3,
+                pack_name: "pack-c.pack".to_string(),
+                offset: 12,
+            })
+        );
+
+        // The base layer's second object lives in its second pack
+        oid[0] = 0x30;
+        let location = chain.find_object(&oid).unwrap();
+        assert_eq!((location.layer, location.position), (0, 1));
+        assert_eq!(location.pack_name, "pack-b.pack");
+        assert_eq!(location.offset, 112);
+
+        oid[0] = 0x40;
+        assert_eq!(chain.find_object(&oid), None);
+    }
+
+    #[test]
+    fn test_base_list_checked_against_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xee; HASH_LEN]]);
+        let chain = MultiPackIndexChain::load(&chain_path).unwrap();
+        assert!(!chain.is_consistent());
+        assert_eq!(
+            chain.layers[1].base_check,
+            BaseCheck::Mismatch {
+                index: 0,
+                expected: hex::encode([0xb0; HASH_LEN]),
+                found: hex::encode([0xee; HASH_LEN]),
+            }
+        );
+
+        let chain_path = write_chain(temp_dir.path(), &[]);
+        let chain = MultiPackIndexChain::load(&chain_path).unwrap();
+        assert_eq!(
+            chain.layers[1].base_check,
+            BaseCheck::CountMismatch {
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_layer() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let chain_path = write_chain(temp_dir.path(), &[[0xb0; HASH_LEN]]);
+        std::fs::remove_file(MultiPackIndexChain::layer_path(
+            temp_dir.path(),
+            &hex::encode([0xb1; HASH_LEN]),
+        ))
+        .unwrap();
+        assert!(matches!(
+            MultiPackIndexChain::load(&chain_path),
+            Err(MultiPackIndexChainError::MissingLayer(_))
+        ));
+    }
+}
@@ -6,6 +6,7 @@ use nom::{
 use std::fmt;
 use std::io::Read;
 
+use crate::git::object_format::ObjectFormat;
 use crate::git::pack::PackError;
 use crate::git::pack::delta;
 
@@ -72,7 +73,7 @@ pub enum ObjectHeader {
     },
     RefDelta {
         uncompressed_data_size: usize,
-        base_ref: [u8; 20],
+        base_ref: Vec<u8>,
         raw_data: Vec<u8>,
     },
 }
@@ -131,6 +132,16 @@ impl ObjectHeader {
     /// parse error if a size or offset varint is too long to fit in its
     /// integer type (which can only mean a corrupt header).
     pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        Self::parse_with_format(input, ObjectFormat::Sha1)
+    }
+
+    /// Parse a header from a pack whose `ref_delta` bases are `object_format`
+    /// object IDs
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom error under the same conditions as [`Self::parse`].
+    pub fn parse_with_format(input: &[u8], object_format: ObjectFormat) -> IResult<&[u8], Self> {
         let original_input = input;
         let mut i = 0;
 
@@ -231,13 +242,13 @@ impl ObjectHeader {
                 }
             }
             ObjectType::RefDelta => {
-                if i + 20 > input.len() {
-                    return Err(nom::Err::Incomplete(nom::Needed::new(20)));
+                let hash_len = object_format.raw_len();
+                if i + hash_len > input.len() {
+                    return Err(nom::Err::Incomplete(nom::Needed::new(hash_len)));
                 }
-                // Read the 20-byte base object SHA-1
-                let mut ref_bytes = [0u8; 20];
-                ref_bytes.copy_from_slice(&input[i..i + 20]);
-                i += 20;
+                // Read the base object ID
+                let ref_bytes = input[i..i + hash_len].to_vec();
+                i += hash_len;
 
                 // Calculate header size and store raw data
                 let header_size = i;
@@ -284,7 +295,16 @@ impl Object {
     /// parse error if the header is corrupt, the data does not start with a
     /// zlib stream, or the decompressed size does not match the header.
     pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
-        let (input, header) = ObjectHeader::parse(input)?;
+        Self::parse_with_format(input, ObjectFormat::Sha1)
+    }
+
+    /// Parse a single object from a pack of an `object_format` repository
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom error under the same conditions as [`Self::parse`].
+    pub fn parse_with_format(input: &[u8], object_format: ObjectFormat) -> IResult<&[u8], Self> {
+        let (input, header) = ObjectHeader::parse_with_format(input, object_format)?;
         let pre_parse_input_size = input.len();
         let (remaining_input, data) = Self::parse_data(input, header.uncompressed_data_size())?;
         let compressed_size = pre_parse_input_size - remaining_input.len();
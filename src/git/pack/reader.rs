@@ -0,0 +1,644 @@
+//! Random access into a pack file through its index.
+//!
+//! The pack is memory-mapped, and its `.idx` gives every object's offset,
+//! so one entry can be decoded without parsing the entries before it. Only
+//! the entries asked for are inflated: listing a pack reads their headers,
+//! a few bytes each, however large the pack is.
+//!
+//! A pack without an index can still be read by offset. Its entries are
+//! found by walking the pack once, inflating each one only to find where
+//! the next starts.
+
+use flate2::read::ZlibDecoder;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use thiserror::Error;
+
+use super::object::{Object, ObjectHeader, ObjectType};
+use super::resolver::{PACK_HEADER_SIZE, apply_delta};
+use super::{Header, PackIndex, PackReverseIndex};
+use crate::git::object_format::ObjectFormat;
+
+/// Longest delta chain followed before giving up
+const MAX_DELTA_DEPTH: usize = 1000;
+/// Enough for the two size varints at the start of a delta
+const MAX_DELTA_SIZES_SIZE: u64 = 20;
+
+#[derive(Debug, Error)]
+pub enum PackReaderError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("No index for pack {0}")]
+    MissingIndex(PathBuf),
+
+    #[error("Invalid pack index: {0}")]
+    InvalidIndex(String),
+
+    #[error("Invalid pack header: {0}")]
+    InvalidHeader(String),
+
+    #[error("Object not in this pack: {0}")]
+    NotFound(String),
+
+    #[error("Corrupt pack entry at offset {offset}: {reason}")]
+    CorruptEntry { offset: u64, reason: String },
+}
+
+/// One object of the pack, as its index describes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackEntryLocation<'a> {
+    /// Byte offset of the entry in the pack file
+    pub offset: u64,
+    /// Object ID of the entry's resolved content
+    pub object_id: &'a [u8],
+}
+
+/// Where the base of a delta entry is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaBase {
+    /// Another entry of this pack, at this offset
+    Entry(u64),
+    /// An object the pack doesn't hold, by its binary object ID: the base
+    /// of a `ref_delta` in a thin pack, stored loose or in another pack
+    External(Vec<u8>),
+}
+
+/// A memory-mapped pack file and its index
+#[derive(Debug)]
+pub struct PackReader {
+    pack_path: PathBuf,
+    data: Mmap,
+    header: Header,
+    object_format: ObjectFormat,
+    /// `None` for a pack opened with [`Self::open_unindexed`]
+    index: Option<PackIndex>,
+    /// Positions in the index of the objects, in pack order, loaded the
+    /// first time entries are asked for by position
+    pack_order: OnceLock<Vec<u32>>,
+}
+
+impl PackReader {
+    /// Open a pack with the `.idx` next to it
+    ///
+    /// The pack order is taken from the `.rev` when there is a usable one,
+    /// and from the index's offsets otherwise, once it is first needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pack or its index can't be read, the pack
+    /// has no index, or either one's header is invalid.
+    pub fn open(pack_path: &Path) -> Result<Self, PackReaderError> {
+        let index_path = pack_path.with_extension("idx");
+        if !index_path.is_file() {
+            return Err(PackReaderError::MissingIndex(pack_path.to_path_buf()));
+        }
+        let index_data = std::fs::read(&index_path)?;
+        let (_, index) = PackIndex::parse(&index_data)
+            .map_err(|e| PackReaderError::InvalidIndex(format!("{e:?}")))?;
+
+        let mut reader = Self::open_unindexed(pack_path, index.object_format)?;
+        reader.index = Some(index);
+        Ok(reader)
+    }
+
+    /// Open a pack whose objects are named by `object_format`, without
+    /// its index
+    ///
+    /// Entries can only be read by offset, as [`Self::entry_offsets`]
+    /// finds them: the reader can't look objects up by ID, and a
+    /// `ref_delta` base is always [`DeltaBase::External`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pack can't be read or its header is invalid.
+    pub fn open_unindexed(
+        pack_path: &Path,
+        object_format: ObjectFormat,
+    ) -> Result<Self, PackReaderError> {
+        let file = File::open(pack_path)?;
+        // SAFETY: the map is read-only. Git never rewrites a pack in place:
+        // packs are written under a temporary name and renamed, and only
+        // deleted once unused, so the mapped bytes don't change under us.
+        let data = unsafe { Mmap::map(&file)? };
+        let (_, header) =
+            Header::parse(&data).map_err(|e| PackReaderError::InvalidHeader(format!("{e:?}")))?;
+
+        Ok(Self {
+            pack_path: pack_path.to_path_buf(),
+            data,
+            header,
+            object_format,
+            index: None,
+            pack_order: OnceLock::new(),
+        })
+    }
+
+    fn pack_order(&self) -> &[u32] {
+        self.pack_order.get_or_init(|| {
+            self.index.as_ref().map_or_else(Vec::new, |index| {
+                Self::load_pack_order(&self.pack_path.with_extension("rev"), index)
+            })
+        })
+    }
+
+    /// Index positions in pack order, from a `.rev` covering every object,
+    /// or by sorting the offsets
+    fn load_pack_order(rev_path: &Path, index: &PackIndex) -> Vec<u32> {
+        let from_rev = std::fs::read(rev_path).ok().and_then(|data| {
+            let (_, rev) = PackReverseIndex::parse(&data).ok()?;
+            (rev.object_count() == index.object_count()).then_some(rev.index_positions)
+        });
+        from_rev.unwrap_or_else(|| {
+            let mut order: Vec<u32> = (0..index.object_count())
+                .filter_map(|position| u32::try_from(position).ok())
+                .collect();
+            order.sort_by_key(|&position| index.get_object_offset(position as usize));
+            order
+        })
+    }
+
+    /// The pack's header
+    #[must_use]
+    pub const fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The pack's index, `None` if it was opened without
+    #[must_use]
+    pub const fn index(&self) -> Option<&PackIndex> {
+        self.index.as_ref()
+    }
+
+    /// The pack's index, for tests that add objects to it
+    #[cfg(test)]
+    pub(crate) fn index_mut(&mut self) -> &mut PackIndex {
+        self.index
+            .as_mut()
+            .expect("the pack was opened with its index")
+    }
+
+    /// Hash function naming the pack's objects
+    #[must_use]
+    pub const fn object_format(&self) -> ObjectFormat {
+        self.object_format
+    }
+
+    /// Number of objects in the pack, as the index or else the pack
+    /// header counts them
+    #[must_use]
+    pub fn object_count(&self) -> usize {
+        self.index
+            .as_ref()
+            .map_or(self.header.object_count as usize, PackIndex::object_count)
+    }
+
+    /// The object at `pack_position`, counting in pack order from 0; `None`
+    /// without an index
+    #[must_use]
+    pub fn entry(&self, pack_position: usize) -> Option<PackEntryLocation<'_>> {
+        let index = self.index.as_ref()?;
+        let index_position = *self.pack_order().get(pack_position)? as usize;
+        Some(PackEntryLocation {
+            offset: index.get_object_offset(index_position),
+            object_id: index.object_names.get(index_position)?,
+        })
+    }
+
+    /// The objects in pack order, none without an index
+    pub fn entries(&self) -> impl Iterator<Item = PackEntryLocation<'_>> {
+        (0..self.object_count()).map_while(|position| self.entry(position))
+    }
+
+    /// Offsets of the entries in pack order: from the index, or else by
+    /// walking the pack, which stops at the first corrupt entry
+    #[must_use]
+    pub fn entry_offsets(&self) -> Vec<u64> {
+        if self.index.is_some() {
+            return self.entries().map(|entry| entry.offset).collect();
+        }
+        let mut offsets = Vec::new();
+        let mut offset = PACK_HEADER_SIZE;
+        for _ in 0..self.header.object_count {
+            let Ok(end) = self.entry_end(offset) else {
+                break;
+            };
+            offsets.push(offset);
+            offset = end;
+        }
+        offsets
+    }
+
+    /// Offset of an object in the pack, by its binary object ID
+    #[must_use]
+    pub fn lookup(&self, oid: &[u8]) -> Option<u64> {
+        self.index.as_ref()?.lookup_object(oid)
+    }
+
+    /// Position in pack order of the entry at `offset`
+    #[must_use]
+    pub fn pack_position(&self, offset: u64) -> Option<usize> {
+        let index = self.index.as_ref()?;
+        let pack_order = self.pack_order();
+        // Pack order is offset order
+        let position = pack_order.partition_point(|&index_position| {
+            index.get_object_offset(index_position as usize) < offset
+        });
+        pack_order
+            .get(position)
+            .is_some_and(|&index_position| {
+                index.get_object_offset(index_position as usize) == offset
+            })
+            .then_some(position)
+    }
+
+    /// The header of the entry at `offset`, without inflating its data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid header starts at `offset`.
+    pub fn read_header_at(&self, offset: u64) -> Result<ObjectHeader, PackReaderError> {
+        ObjectHeader::parse_with_format(self.bytes_at(offset)?, self.object_format())
+            .map(|(_, header)| header)
+            .map_err(|e| Self::corrupt(offset, &format!("{e:?}")))
+    }
+
+    /// The entry at `offset`, with its data inflated; a delta's is its
+    /// instruction stream
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid entry starts at `offset`.
+    pub fn read_entry_at(&self, offset: u64) -> Result<Object, PackReaderError> {
+        Object::parse_with_format(self.bytes_at(offset)?, self.object_format())
+            .map(|(_, object)| object)
+            .map_err(|e| Self::corrupt(offset, &format!("{e:?}")))
+    }
+
+    /// Size of the object at `offset`, deltas applied, read from its
+    /// header and, for a delta, the first bytes of its data
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no valid entry starts at `offset`.
+    pub fn object_size_at(&self, offset: u64) -> Result<usize, PackReaderError> {
+        let header = self.read_header_at(offset)?;
+        if let ObjectHeader::Regular {
+            uncompressed_data_size,
+            ..
+        } = header
+        {
+            return Ok(uncompressed_data_size);
+        }
+        let data = self.bytes_at(offset)?.get(header.raw_data().len()..);
+        let mut sizes = Vec::new();
+        ZlibDecoder::new(data.unwrap_or_default())
+            .take(MAX_DELTA_SIZES_SIZE)
+            .read_to_end(&mut sizes)
+            .map_err(|e| Self::corrupt(offset, &e.to_string()))?;
+
+        // The delta's base size, then the size of the object it produces
+        let mut varints = sizes.split_inclusive(|byte| byte & 0x80 == 0);
+        match (varints.next(), varints.next()) {
+            (Some(_), Some(result)) if result.last().is_some_and(|byte| byte & 0x80 == 0) => {
+                Ok(result
+                    .iter()
+                    .rev()
+                    .fold(0, |size, byte| (size << 7) | usize::from(byte & 0x7f)))
+            }
+            _ => Err(Self::corrupt(offset, "truncated delta sizes")),
+        }
+    }
+
+    /// Type of the object at `offset`: its own, or that of the bottom of
+    /// its delta chain, found by reading headers only
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry of the chain is corrupt, or its base
+    /// isn't in this pack (a thin pack).
+    pub fn object_type_at(&self, offset: u64) -> Result<ObjectType, PackReaderError> {
+        self.object_type_at_with(offset, |_| {
+            Err(Self::corrupt(offset, "delta base not in this pack"))
+        })
+    }
+
+    /// Like [`Self::object_type_at`], asking `external_type` for the type
+    /// of a base the pack doesn't hold
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry of the chain is corrupt, or
+    /// `external_type` fails.
+    pub fn object_type_at_with<E: From<PackReaderError>>(
+        &self,
+        offset: u64,
+        external_type: impl FnOnce(&[u8]) -> Result<ObjectType, E>,
+    ) -> Result<ObjectType, E> {
+        let mut current = offset;
+        for _ in 0..MAX_DELTA_DEPTH {
+            let header = self.read_header_at(current)?;
+            if let ObjectHeader::Regular { obj_type, .. } = header {
+                return Ok(obj_type);
+            }
+            match self.delta_base(current, &header)? {
+                DeltaBase::Entry(base) => current = base,
+                DeltaBase::External(oid) => return external_type(&oid),
+            }
+        }
+        Err(Self::corrupt(offset, "delta chain too long").into())
+    }
+
+    /// Type and content of the object at `offset`, applying its delta
+    /// chain from the base up
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry of the chain is corrupt, or a
+    /// `ref_delta` base isn't in this pack (a thin pack).
+    pub fn read_object_at(&self, offset: u64) -> Result<(ObjectType, Vec<u8>), PackReaderError> {
+        self.read_object_at_with(offset, |_| {
+            Err(Self::corrupt(offset, "delta base not in this pack"))
+        })
+    }
+
+    /// Like [`Self::read_object_at`], asking `read_external` for a base
+    /// the pack doesn't hold
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry of the chain is corrupt, or
+    /// `read_external` fails.
+    pub fn read_object_at_with<E: From<PackReaderError>>(
+        &self,
+        offset: u64,
+        read_external: impl FnOnce(&[u8]) -> Result<(ObjectType, Vec<u8>), E>,
+    ) -> Result<(ObjectType, Vec<u8>), E> {
+        let mut deltas: Vec<Vec<u8>> = Vec::new();
+        let mut current = offset;
+        for _ in 0..MAX_DELTA_DEPTH {
+            let entry = self.read_entry_at(current)?;
+            let (obj_type, mut content) = match entry.header {
+                ObjectHeader::Regular { obj_type, .. } => (obj_type, entry.uncompressed_data),
+                _ => {
+                    deltas.push(entry.uncompressed_data);
+                    match self.delta_base(current, &entry.header)? {
+                        DeltaBase::Entry(base) => {
+                            current = base;
+                            continue;
+                        }
+                        DeltaBase::External(oid) => read_external(&oid)?,
+                    }
+                }
+            };
+            for delta in deltas.iter().rev() {
+                content = apply_delta(&content, delta)
+                    .ok_or_else(|| Self::corrupt(offset, "malformed delta"))?;
+            }
+            return Ok((obj_type, content));
+        }
+        Err(Self::corrupt(offset, "delta chain too long").into())
+    }
+
+    /// Type and content of an object, by its binary object ID
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pack doesn't hold the object, or under the
+    /// same conditions as [`Self::read_object_at`].
+    pub fn read_object(&self, oid: &[u8]) -> Result<(ObjectType, Vec<u8>), PackReaderError> {
+        let offset = self
+            .lookup(oid)
+            .ok_or_else(|| PackReaderError::NotFound(hex::encode(oid)))?;
+        self.read_object_at(offset)
+    }
+
+    /// Where the base of the entry at `offset`, whose header is `header`,
+    /// is; a regular entry is its own base
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an `ofs_delta` points before the pack's start.
+    pub fn delta_base(
+        &self,
+        offset: u64,
+        header: &ObjectHeader,
+    ) -> Result<DeltaBase, PackReaderError> {
+        match header {
+            ObjectHeader::Regular { .. } => Ok(DeltaBase::Entry(offset)),
+            ObjectHeader::OfsDelta { base_offset, .. } => u64::try_from(*base_offset)
+                .ok()
+                .and_then(|distance| offset.checked_sub(distance))
+                .map(DeltaBase::Entry)
+                .ok_or_else(|| Self::corrupt(offset, "base offset out of range")),
+            ObjectHeader::RefDelta { base_ref, .. } => Ok(self
+                .lookup(base_ref)
+                .map_or_else(|| DeltaBase::External(base_ref.clone()), DeltaBase::Entry)),
+        }
+    }
+
+    /// Offset just past the entry at `offset`, found by inflating its
+    /// data without keeping it
+    fn entry_end(&self, offset: u64) -> Result<u64, PackReaderError> {
+        let header = self.read_header_at(offset)?;
+        let data = self.bytes_at(offset)?.get(header.raw_data().len()..);
+        let mut decoder = ZlibDecoder::new(data.unwrap_or_default());
+        let size = io::copy(&mut decoder, &mut io::sink())
+            .map_err(|e| Self::corrupt(offset, &e.to_string()))?;
+        if usize::try_from(size).ok() != Some(header.uncompressed_data_size()) {
+            return Err(Self::corrupt(offset, "data size doesn't match the header"));
+        }
+        Ok(offset + header.raw_data().len() as u64 + decoder.total_in())
+    }
+
+    fn bytes_at(&self, offset: u64) -> Result<&[u8], PackReaderError> {
+        usize::try_from(offset)
+            .ok()
+            .and_then(|offset| self.data.get(offset..))
+            .ok_or_else(|| Self::corrupt(offset, "offset past the end of the pack"))
+    }
+
+    fn corrupt(offset: u64, reason: &str) -> PackReaderError {
+        PackReaderError::CorruptEntry {
+            offset,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::pack::resolver::object_id;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    const BASE: &[u8] = b"the quick brown fox";
+    const RESULT: &[u8] = b"the quick brown fox jumps";
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// A pack holding `RESULT` as a `ref_delta` on `BASE`, stored after
+    /// it, and an index for it; returns the pack path and both object IDs
+    fn write_pack(dir: &Path) -> (PathBuf, Vec<u8>, Vec<u8>) {
+        let base_oid = hex::decode(object_id(ObjectFormat::Sha1, ObjectType::Blob, BASE)).unwrap();
+        let result_oid =
+            hex::decode(object_id(ObjectFormat::Sha1, ObjectType::Blob, RESULT)).unwrap();
+
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&2u32.to_be_bytes());
+
+        let base_offset = pack.len() as u64;
+        pack.extend_from_slice(&[0xb0 | BASE.len() as u8 & 0x0f, BASE.len() as u8 >> 4]);
+        pack.extend(zlib(BASE));
+
+        let delta_offset = pack.len() as u64;
+        let mut delta = vec![BASE.len() as u8, RESULT.len() as u8];
+        delta.extend_from_slice(&[0x91, 0x00, BASE.len() as u8]);
+        delta.push((RESULT.len() - BASE.len()) as u8);
+        delta.extend_from_slice(&RESULT[BASE.len()..]);
+        pack.push(0x70 | delta.len() as u8);
+        pack.extend_from_slice(&base_oid);
+        pack.extend(zlib(&delta));
+        pack.extend_from_slice(&[0; 20]);
+
+        let mut objects = [
+            (base_oid.clone(), base_offset),
+            (result_oid.clone(), delta_offset),
+        ];
+        objects.sort();
+        let mut idx = vec![0xff, 0x74, 0x4f, 0x63, 0, 0, 0, 2];
+        for byte in 0..=255u8 {
+            let count = objects.iter().filter(|(oid, _)| oid[0] <= byte).count();
+            idx.extend_from_slice(&(count as u32).to_be_bytes());
+        }
+        for (oid, _) in &objects {
+            idx.extend_from_slice(oid);
+        }
+        idx.extend_from_slice(&[0; 8]);
+        for (_, offset) in &objects {
+            idx.extend_from_slice(&(*offset as u32).to_be_bytes());
+        }
+        idx.extend_from_slice(&[0; 40]);
+
+        let pack_path = dir.join("pack-test.pack");
+        std::fs::write(&pack_path, pack).unwrap();
+        std::fs::write(dir.join("pack-test.idx"), idx).unwrap();
+        (pack_path, base_oid, result_oid)
+    }
+
+    #[test]
+    fn test_entries_in_pack_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (pack_path, base_oid, result_oid) = write_pack(temp_dir.path());
+        let reader = PackReader::open(&pack_path).unwrap();
+
+        assert_eq!(reader.header().object_count, 2);
+        let entries: Vec<_> = reader.entries().collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].offset, 12);
+        assert_eq!(entries[0].object_id, base_oid);
+        assert_eq!(entries[1].object_id, result_oid);
+
+        let header = reader.read_header_at(entries[1].offset).unwrap();
+        assert_eq!(header.obj_type(), ObjectType::RefDelta);
+        let entry = reader.read_entry_at(entries[0].offset).unwrap();
+        assert_eq!(entry.uncompressed_data, BASE);
+    }
+
+    #[test]
+    fn test_read_objects_on_demand() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (pack_path, base_oid, result_oid) = write_pack(temp_dir.path());
+        let reader = PackReader::open(&pack_path).unwrap();
+
+        assert_eq!(
+            reader.read_object(&result_oid).unwrap(),
+            (ObjectType::Blob, RESULT.to_vec())
+        );
+        assert_eq!(reader.read_object(&base_oid).unwrap().1, BASE);
+        assert!(matches!(
+            reader.read_object(&[0xab; 20]),
+            Err(PackReaderError::NotFound(_))
+        ));
+        assert!(matches!(
+            reader.read_entry_at(1 << 20),
+            Err(PackReaderError::CorruptEntry { .. })
+        ));
+    }
+
+    #[test]
+    fn test_headers_and_positions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (pack_path, base_oid, result_oid) = write_pack(temp_dir.path());
+        let reader = PackReader::open(&pack_path).unwrap();
+
+        let result_offset = reader.lookup(&result_oid).unwrap();
+        assert_eq!(reader.object_size_at(result_offset).unwrap(), RESULT.len());
+        assert_eq!(
+            reader.object_type_at(result_offset).unwrap(),
+            ObjectType::Blob
+        );
+        assert_eq!(reader.pack_position(12), Some(0));
+        assert_eq!(reader.pack_position(result_offset), Some(1));
+        assert_eq!(reader.pack_position(13), None);
+
+        let header = reader.read_header_at(result_offset).unwrap();
+        assert_eq!(
+            reader.delta_base(result_offset, &header).unwrap(),
+            DeltaBase::Entry(12)
+        );
+        let (object_type, content) = reader
+            .read_object_at_with(result_offset, |_| -> Result<_, PackReaderError> {
+                unreachable!("the base is in the pack")
+            })
+            .unwrap();
+        assert_eq!(
+            (object_type, content.as_slice()),
+            (ObjectType::Blob, RESULT)
+        );
+        assert_eq!(reader.read_object(&base_oid).unwrap().1, BASE);
+    }
+
+    #[test]
+    fn test_open_unindexed_walks_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (pack_path, base_oid, _) = write_pack(temp_dir.path());
+        let indexed = PackReader::open(&pack_path).unwrap().entry_offsets();
+        assert_eq!(indexed.len(), 2);
+        let (base_offset, delta_offset) = (indexed[0], indexed[1]);
+
+        std::fs::remove_file(pack_path.with_extension("idx")).unwrap();
+        let reader = PackReader::open_unindexed(&pack_path, ObjectFormat::Sha1).unwrap();
+        assert!(reader.index().is_none());
+        assert_eq!(reader.object_count(), 2);
+        assert_eq!(reader.entry_offsets(), indexed);
+        assert_eq!(reader.read_object_at(base_offset).unwrap().1, BASE);
+
+        let header = reader.read_header_at(delta_offset).unwrap();
+        assert_eq!(
+            reader.delta_base(delta_offset, &header).unwrap(),
+            DeltaBase::External(base_oid)
+        );
+    }
+
+    #[test]
+    fn test_open_requires_an_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (pack_path, _, _) = write_pack(temp_dir.path());
+        std::fs::remove_file(pack_path.with_extension("idx")).unwrap();
+        assert!(matches!(
+            PackReader::open(&pack_path),
+            Err(PackReaderError::MissingIndex(_))
+        ));
+    }
+}
@@ -3,22 +3,35 @@
 //! Git object IDs are defined over the *resolved* content
 //! (`"{type} {size}\0" + content`), so delta objects have no ID of their
 //! own until their chain is applied. This module reconstructs that content
-//! using only the pack file itself — no `.idx` required.
+//! using only the pack file itself — no `.idx` required: a [`PackReader`]
+//! opened without one finds the entries by walking the pack.
+//!
+//! Like `git index-pack`, it sees the pack as a forest: regular objects are
+//! the roots, and each delta hangs below its base. The forest is built from
+//! the entry headers alone, and an entry is inflated from the mapped pack
+//! only when its tree reaches it. The trees are resolved in parallel, each
+//! one depth first, so a base is usually still at hand when its deltas are
+//! applied. Each tree keeps its bases in its own cache, bounded in bytes, so
+//! that threads never wait on each other; a base evicted before all its
+//! deltas were applied is rebuilt from its chain.
 
-use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use sha1::{Digest, Sha1};
+use rayon::prelude::*;
 
 use super::delta::{DeltaInstruction, parse_delta_instructions};
-use super::object::{Object, ObjectHeader, ObjectType};
+use super::object::{ObjectHeader, ObjectType};
+use super::reader::{DeltaBase, PackReader};
+use crate::git::object_format::ObjectFormat;
 
 /// Fixed size of the pack header: "PACK" + version + object count.
 pub const PACK_HEADER_SIZE: u64 = 12;
 
-/// Content of a resolved object: regular objects borrow their pack data,
-/// resolved deltas own the reconstructed bytes.
-type ResolvedContent<'a> = (ObjectType, Cow<'a, [u8]>);
+/// Bytes of resolved delta bases kept at once, git's default
+/// `core.deltaBaseCacheLimit`
+pub const DELTA_BASE_CACHE_LIMIT: usize = 96 * 1024 * 1024;
 
 /// The real identity of a pack object after delta resolution.
 #[derive(Debug, Clone)]
@@ -31,23 +44,20 @@ pub struct ResolvedObject {
     pub sha1: String,
 }
 
-fn git_object_digest(obj_type: ObjectType, data: &[u8]) -> [u8; 20] {
-    let mut hasher = Sha1::new();
-    hasher.update(format!("{obj_type} {}\0", data.len()).as_bytes());
-    hasher.update(data);
-    hasher.finalize().into()
+fn git_object_digest(object_format: ObjectFormat, obj_type: ObjectType, data: &[u8]) -> Vec<u8> {
+    object_format.object_id(&obj_type.to_string(), data)
 }
 
 /// Compute the git object ID for resolved content.
 #[must_use]
-pub fn object_id(obj_type: ObjectType, data: &[u8]) -> String {
-    hex::encode(git_object_digest(obj_type, data))
+pub fn object_id(object_format: ObjectFormat, obj_type: ObjectType, data: &[u8]) -> String {
+    hex::encode(git_object_digest(object_format, obj_type, data))
 }
 
 /// Apply a delta instruction stream (size varints already stripped by
 /// `Object::parse`) to base content. Returns `None` if an instruction is
 /// malformed or copies out of bounds.
-fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+pub(crate) fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
     let (_, instructions) = parse_delta_instructions(delta).ok()?;
     let mut result = Vec::new();
     for instruction in instructions {
@@ -62,111 +72,290 @@ fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
     Some(result)
 }
 
-/// Resolve every object in a pack to its real type, size, and git object ID.
+/// Resolve the entries of a pack at `offsets` to their real type, size,
+/// and git object ID, caching up to [`DELTA_BASE_CACHE_LIMIT`] bytes of
+/// delta bases.
 ///
-/// `objects` must be the objects of a single pack in on-disk order, starting
-/// with the first object after the pack header — each object's byte offset is
-/// reconstructed from the header and compressed sizes, which `ofs_delta`
-/// resolution depends on.
+/// `offsets` are those of the pack's entries, as
+/// [`PackReader::entry_offsets`] gives them: an `ofs_delta` whose base is
+/// at none of them isn't resolved.
 ///
-/// The returned vector is aligned with the input. An entry is `None` when the
-/// object cannot be resolved from this pack alone: a `ref_delta` whose base
-/// lives outside the pack (thin pack), a base offset pointing at no object
-/// boundary, or a malformed delta stream.
+/// The returned vector is aligned with `offsets`. An entry is `None` when
+/// the object cannot be resolved from this pack alone: a `ref_delta` whose
+/// base lives outside the pack (thin pack), a base offset pointing at no
+/// object boundary, a corrupt entry, or a malformed delta stream.
+#[must_use]
+pub fn resolve_objects(reader: &PackReader, offsets: &[u64]) -> Vec<Option<ResolvedObject>> {
+    resolve_objects_with_cache_limit(reader, offsets, DELTA_BASE_CACHE_LIMIT)
+}
+
+/// [`resolve_objects`], caching at most `cache_limit` bytes of resolved delta
+/// bases; those evicted are rebuilt from their chain when needed again
 #[must_use]
-pub fn resolve_objects(objects: &[Object]) -> Vec<Option<ResolvedObject>> {
-    let mut offsets = Vec::with_capacity(objects.len());
-    let mut offset_to_index = HashMap::with_capacity(objects.len());
-    let mut offset = PACK_HEADER_SIZE;
-    for (index, object) in objects.iter().enumerate() {
-        offsets.push(offset);
-        offset_to_index.insert(offset, index);
-        offset += (object.header.raw_data().len() + object.compressed_size) as u64;
+pub fn resolve_objects_with_cache_limit(
+    reader: &PackReader,
+    offsets: &[u64],
+    cache_limit: usize,
+) -> Vec<Option<ResolvedObject>> {
+    let forest = DeltaForest::new(reader, offsets);
+    // A thread resolves one tree at a time, so the trees' caches together
+    // stay within the limit
+    let tree_cache_limit = cache_limit / rayon::current_num_threads();
+    let resolved: Vec<(usize, ResolvedObject)> = forest
+        .roots
+        .par_iter()
+        .flat_map_iter(|&(root, obj_type)| forest.resolve_tree(root, obj_type, tree_cache_limit))
+        .collect();
+
+    let mut results = vec![None; offsets.len()];
+    for (index, object) in resolved {
+        results[index] = Some(object);
     }
+    results
+}
 
-    // Resolved content per object: regular objects borrow their data,
-    // resolved deltas own theirs. Kept for the whole pass so later links
-    // in a chain (and ref_deltas found by digest) can read their base.
-    let mut content: Vec<Option<ResolvedContent>> = (0..objects.len()).map(|_| None).collect();
-    let mut digest_to_index: HashMap<[u8; 20], usize> = HashMap::new();
-    let mut results: Vec<Option<ResolvedObject>> = (0..objects.len()).map(|_| None).collect();
-    let mut failed = vec![false; objects.len()];
-
-    // Bases usually precede their deltas, so this converges in one or two
-    // passes; each extra pass resolves at least one more chain link.
-    loop {
-        let mut progress = false;
-
-        for index in 0..objects.len() {
-            if content[index].is_some() || failed[index] {
+/// The entries of a pack arranged by which delta applies to which base,
+/// by their position in the resolved offsets
+struct DeltaForest<'a> {
+    reader: &'a PackReader,
+    offsets: &'a [u64],
+    /// Regular objects, which need no base, with their type
+    roots: Vec<(usize, ObjectType)>,
+    /// The deltas applying to each entry, found by offset
+    children: Vec<Vec<usize>>,
+    /// The `ref_delta`s applying to each base object ID the pack can't
+    /// look up
+    ref_children: HashMap<Vec<u8>, Vec<usize>>,
+    /// Deltas already taken by some base, so that several objects with the
+    /// same ID don't resolve a `ref_delta` twice, or forever in a cycle
+    claimed: Vec<AtomicBool>,
+}
+
+impl<'a> DeltaForest<'a> {
+    fn new(reader: &'a PackReader, offsets: &'a [u64]) -> Self {
+        let positions: HashMap<u64, usize> = offsets
+            .iter()
+            .enumerate()
+            .map(|(index, &offset)| (offset, index))
+            .collect();
+
+        let mut roots = Vec::new();
+        let mut children = vec![Vec::new(); offsets.len()];
+        let mut ref_children: HashMap<Vec<u8>, Vec<usize>> = HashMap::new();
+        for (index, &offset) in offsets.iter().enumerate() {
+            let Ok(header) = reader.read_header_at(offset) else {
+                continue;
+            };
+            if let ObjectHeader::Regular { obj_type, .. } = header {
+                if matches!(
+                    obj_type,
+                    ObjectType::Commit | ObjectType::Tree | ObjectType::Blob | ObjectType::Tag
+                ) {
+                    roots.push((index, obj_type));
+                }
                 continue;
             }
-
-            // Ok(Some(..)) = resolved, Ok(None) = base not available yet
-            // (retry next pass), Err(()) = permanently unresolvable.
-            let outcome: Result<Option<ResolvedContent>, ()> = match &objects[index].header {
-                ObjectHeader::Regular { obj_type, .. } => match obj_type {
-                    ObjectType::Commit | ObjectType::Tree | ObjectType::Blob | ObjectType::Tag => {
-                        Ok(Some((
-                            *obj_type,
-                            Cow::Borrowed(objects[index].uncompressed_data.as_slice()),
-                        )))
-                    }
-                    _ => Err(()),
-                },
-                ObjectHeader::OfsDelta { base_offset, .. } => u64::try_from(*base_offset)
-                    .ok()
-                    .and_then(|distance| offsets[index].checked_sub(distance))
-                    .and_then(|base| offset_to_index.get(&base).copied())
-                    .map_or(Err(()), |base_index| match &content[base_index] {
-                        Some((base_type, base_data)) => {
-                            apply_delta(base_data, &objects[index].uncompressed_data)
-                                .map(|data| Some((*base_type, Cow::Owned(data))))
-                                .ok_or(())
-                        }
-                        None if failed[base_index] => Err(()),
-                        None => Ok(None),
-                    }),
-                ObjectHeader::RefDelta { base_ref, .. } => {
-                    // The base may appear later in the pack or be missing
-                    // entirely (thin pack) — only the fixpoint decides.
-                    match digest_to_index.get(base_ref) {
-                        Some(&base_index) => match &content[base_index] {
-                            Some((base_type, base_data)) => {
-                                apply_delta(base_data, &objects[index].uncompressed_data)
-                                    .map(|data| Some((*base_type, Cow::Owned(data))))
-                                    .ok_or(())
-                            }
-                            None => Ok(None),
-                        },
-                        None => Ok(None),
+            match reader.delta_base(offset, &header) {
+                // A base offset pointing at no entry leaves the delta out
+                // of the forest
+                Ok(DeltaBase::Entry(base)) => {
+                    if let Some(&base_index) = positions.get(&base)
+                        && base_index != index
+                    {
+                        children[base_index].push(index);
                     }
                 }
+                Ok(DeltaBase::External(base_ref)) => {
+                    ref_children.entry(base_ref).or_default().push(index);
+                }
+                Err(_) => {}
+            }
+        }
+
+        Self {
+            reader,
+            offsets,
+            roots,
+            children,
+            ref_children,
+            claimed: offsets.iter().map(|_| AtomicBool::new(false)).collect(),
+        }
+    }
+
+    /// Resolve a regular object and every delta below it, depth first,
+    /// caching up to `cache_limit` bytes of its bases
+    fn resolve_tree(
+        &self,
+        root: usize,
+        obj_type: ObjectType,
+        cache_limit: usize,
+    ) -> Vec<(usize, ResolvedObject)> {
+        let mut cache = DeltaBaseCache::new(cache_limit);
+        let mut resolved = Vec::new();
+        // The base each delta of this tree applies to
+        let mut parents = HashMap::new();
+        let mut pending = Vec::new();
+
+        let Some(data) = self.stored_data(root) else {
+            return resolved;
+        };
+        let digest = self.record(root, obj_type, &data, &mut resolved);
+        if self.take_children(root, &digest, &mut parents, &mut pending) {
+            cache.insert(root, data.into());
+        }
+
+        while let Some(delta) = pending.pop() {
+            let Some(data) = self
+                .content(parents[&delta], &parents, &mut cache)
+                .zip(self.stored_data(delta))
+                .and_then(|(base, delta)| apply_delta(&base, &delta))
+            else {
+                continue;
             };
+            let digest = self.record(delta, obj_type, &data, &mut resolved);
+            if self.take_children(delta, &digest, &mut parents, &mut pending) {
+                cache.insert(delta, data.into());
+            }
+        }
+        resolved
+    }
 
-            match outcome {
-                Err(()) => failed[index] = true,
-                Ok(None) => {}
-                Ok(Some((obj_type, data))) => {
-                    let digest = git_object_digest(obj_type, &data);
-                    digest_to_index.insert(digest, index);
-                    results[index] = Some(ResolvedObject {
-                        obj_type,
-                        size: data.len(),
-                        sha1: hex::encode(digest),
-                    });
-                    content[index] = Some((obj_type, data));
-                    progress = true;
-                }
+    /// Push the deltas applying to `base`, whose object ID is `digest`, to
+    /// be resolved next, returning whether there are any
+    fn take_children(
+        &self,
+        base: usize,
+        digest: &[u8],
+        parents: &mut HashMap<usize, usize>,
+        pending: &mut Vec<usize>,
+    ) -> bool {
+        let ref_children = self
+            .ref_children
+            .get(digest)
+            .into_iter()
+            .flatten()
+            .filter(|&&child| !self.claimed[child].swap(true, Ordering::Relaxed));
+        let pending_before = pending.len();
+        for &child in self.children[base].iter().chain(ref_children) {
+            parents.insert(child, base);
+            pending.push(child);
+        }
+        pending.len() > pending_before
+    }
+
+    /// Content of a resolved object of this tree: cached, or that of its
+    /// nearest cached base, or else its root, with the deltas since
+    /// applied again
+    fn content(
+        &self,
+        index: usize,
+        parents: &HashMap<usize, usize>,
+        cache: &mut DeltaBaseCache,
+    ) -> Option<Rc<[u8]>> {
+        let mut chain = Vec::new();
+        let mut current = index;
+        let mut content = loop {
+            if let Some(data) = cache.get(current) {
+                break data;
             }
+            // Only the root has no base
+            let Some(&base) = parents.get(&current) else {
+                break Rc::from(self.stored_data(current)?);
+            };
+            chain.push(current);
+            current = base;
+        };
+
+        for &delta in chain.iter().rev() {
+            content = apply_delta(&content, &self.stored_data(delta)?)?.into();
+            cache.insert(delta, Rc::clone(&content));
         }
+        Some(content)
+    }
+
+    /// The inflated data of an entry: a regular object's content, or a
+    /// delta's instruction stream
+    fn stored_data(&self, index: usize) -> Option<Vec<u8>> {
+        self.reader
+            .read_entry_at(self.offsets[index])
+            .ok()
+            .map(|entry| entry.uncompressed_data)
+    }
+
+    fn record(
+        &self,
+        index: usize,
+        obj_type: ObjectType,
+        data: &[u8],
+        resolved: &mut Vec<(usize, ResolvedObject)>,
+    ) -> Vec<u8> {
+        let digest = git_object_digest(self.reader.object_format(), obj_type, data);
+        resolved.push((
+            index,
+            ResolvedObject {
+                obj_type,
+                size: data.len(),
+                sha1: hex::encode(&digest),
+            },
+        ));
+        digest
+    }
+}
+
+/// Resolved delta bases by object index, evicting the least recently used
+/// once their total size passes a limit
+#[derive(Debug)]
+struct DeltaBaseCache {
+    limit: usize,
+    size: usize,
+    /// Incremented on every use, to order the entries by recency
+    clock: u64,
+    entries: HashMap<usize, (Rc<[u8]>, u64)>,
+    by_last_use: BTreeMap<u64, usize>,
+}
 
-        if !progress {
-            break;
+impl DeltaBaseCache {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            size: 0,
+            clock: 0,
+            entries: HashMap::new(),
+            by_last_use: BTreeMap::new(),
         }
     }
 
-    results
+    fn get(&mut self, index: usize) -> Option<Rc<[u8]>> {
+        let (data, last_use) = self.entries.get_mut(&index)?;
+        self.by_last_use.remove(last_use);
+        self.clock += 1;
+        *last_use = self.clock;
+        self.by_last_use.insert(self.clock, index);
+        Some(Rc::clone(data))
+    }
+
+    /// Cache a base, unless it alone is over the limit
+    fn insert(&mut self, index: usize, data: Rc<[u8]>) {
+        if data.len() > self.limit {
+            return;
+        }
+        self.clock += 1;
+        self.size += data.len();
+        if let Some((replaced, last_use)) = self.entries.insert(index, (data, self.clock)) {
+            self.size -= replaced.len();
+            self.by_last_use.remove(&last_use);
+        }
+        self.by_last_use.insert(self.clock, index);
+
+        while self.size > self.limit {
+            let Some((_, evicted)) = self.by_last_use.pop_first() else {
+                break;
+            };
+            if let Some((data, _)) = self.entries.remove(&evicted) {
+                self.size -= data.len();
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -211,15 +400,19 @@ mod tests {
         payload
     }
 
-    fn parse_pack_objects(pack: &[u8]) -> Vec<Object> {
-        let (mut data, _header) = crate::git::pack::Header::parse(pack).unwrap();
-        let mut objects = Vec::new();
-        while !data.is_empty() {
-            let (rest, object) = Object::parse(data).unwrap();
-            objects.push(object);
-            data = rest;
-        }
-        objects
+    /// Resolve the entries of `pack`, written without an index
+    fn resolve_pack(
+        pack: &[u8],
+        object_format: ObjectFormat,
+        cache_limit: usize,
+    ) -> Vec<Option<ResolvedObject>> {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pack_path = temp_dir.path().join("pack-test.pack");
+        std::fs::write(&pack_path, pack).unwrap();
+        let reader = PackReader::open_unindexed(&pack_path, object_format).unwrap();
+        let offsets = reader.entry_offsets();
+        assert_eq!(offsets.len(), reader.object_count());
+        resolve_objects_with_cache_limit(&reader, &offsets, cache_limit)
     }
 
     fn pack_header(object_count: u32) -> Vec<u8> {
@@ -246,25 +439,104 @@ mod tests {
         pack.push(base_distance);
         pack.extend_from_slice(&zlib_compress(&payload));
 
-        let objects = parse_pack_objects(&pack);
-        let resolved = resolve_objects(&objects);
+        let resolved = resolve_pack(&pack, ObjectFormat::Sha1, DELTA_BASE_CACHE_LIMIT);
 
         let base = resolved[0].as_ref().unwrap();
         assert_eq!(base.obj_type, ObjectType::Blob);
-        assert_eq!(base.sha1, object_id(ObjectType::Blob, BASE_CONTENT));
+        assert_eq!(
+            base.sha1,
+            object_id(ObjectFormat::Sha1, ObjectType::Blob, BASE_CONTENT)
+        );
 
         let delta = resolved[1].as_ref().unwrap();
         assert_eq!(delta.obj_type, ObjectType::Blob);
         assert_eq!(delta.size, RESULT_CONTENT.len());
         assert_eq!(delta.sha1, RESULT_SHA1);
-        assert_eq!(delta.sha1, object_id(ObjectType::Blob, RESULT_CONTENT));
+        assert_eq!(
+            delta.sha1,
+            object_id(ObjectFormat::Sha1, ObjectType::Blob, RESULT_CONTENT)
+        );
+    }
+
+    /// An `ofs_delta` entry `distance` bytes after its base, appending
+    /// `suffix` to the base's `base_len` bytes
+    fn appending_ofs_delta(distance: usize, base_len: usize, suffix: &[u8]) -> Vec<u8> {
+        let mut payload = vec![
+            base_len as u8,
+            (base_len + suffix.len()) as u8,
+            0x90, // copy, one size byte follows, offset 0
+            base_len as u8,
+            suffix.len() as u8,
+        ];
+        payload.extend_from_slice(suffix);
+        assert!(payload.len() < 16 && distance < 128);
+
+        let mut entry = vec![(ObjectType::OfsDelta as u8) << 4 | payload.len() as u8];
+        entry.push(distance as u8);
+        entry.extend_from_slice(&zlib_compress(&payload));
+        entry
+    }
+
+    #[test]
+    fn rebuilds_evicted_bases_from_their_chain() {
+        // base <- d1 <- d2, and d1 <- d3: d3 needs d1 again after d2
+        let mut pack = pack_header(4);
+        let base = pack.len();
+        pack.extend_from_slice(&blob_entry(BASE_CONTENT));
+        let d1 = pack.len();
+        let entry = appending_ofs_delta(d1 - base, BASE_CONTENT.len(), b" jumps");
+        pack.extend_from_slice(&entry);
+        for suffix in [b" over", b" high"] {
+            let entry = appending_ofs_delta(pack.len() - d1, RESULT_CONTENT.len(), suffix);
+            pack.extend_from_slice(&entry);
+        }
+
+        let cached = resolve_pack(&pack, ObjectFormat::Sha1, DELTA_BASE_CACHE_LIMIT);
+        let uncached = resolve_pack(&pack, ObjectFormat::Sha1, 0);
+
+        let expected = [
+            BASE_CONTENT,
+            RESULT_CONTENT,
+            b"the quick brown fox jumps over",
+            b"the quick brown fox jumps high",
+        ];
+        for (resolved, content) in [cached, uncached]
+            .iter()
+            .flat_map(|r| r.iter().zip(expected))
+        {
+            let resolved = resolved.as_ref().unwrap();
+            assert_eq!(resolved.size, content.len());
+            assert_eq!(
+                resolved.sha1,
+                object_id(ObjectFormat::Sha1, ObjectType::Blob, content)
+            );
+        }
+    }
+
+    #[test]
+    fn delta_base_cache_evicts_least_recently_used() {
+        let mut cache = DeltaBaseCache::new(10);
+        cache.insert(0, Rc::from(&b"aaaa"[..]));
+        cache.insert(1, Rc::from(&b"bbbb"[..]));
+        assert!(cache.get(0).is_some());
+        cache.insert(2, Rc::from(&b"cccc"[..]));
+
+        assert!(cache.get(1).is_none());
+        assert_eq!(cache.get(0).as_deref(), Some(&b"aaaa"[..]));
+        assert_eq!(cache.get(2).as_deref(), Some(&b"cccc"[..]));
+        assert_eq!(cache.size, 8);
+
+        // Larger than the whole cache: not kept, and evicts nothing
+        cache.insert(3, Rc::from(&[0; 11][..]));
+        assert!(cache.get(3).is_none());
+        assert_eq!(cache.size, 8);
     }
 
     #[test]
     fn resolves_ref_delta_even_when_base_comes_later() {
-        // Delta first, base second: forces a second resolution pass
+        // Delta first, base second: the base ID is only known once it's hashed
         let mut pack = pack_header(2);
-        let base_digest = git_object_digest(ObjectType::Blob, BASE_CONTENT);
+        let base_digest = git_object_digest(ObjectFormat::Sha1, ObjectType::Blob, BASE_CONTENT);
 
         let payload = delta_payload();
         pack.push((ObjectType::RefDelta as u8) << 4 | payload.len() as u8);
@@ -272,8 +544,7 @@ mod tests {
         pack.extend_from_slice(&zlib_compress(&payload));
         pack.extend_from_slice(&blob_entry(BASE_CONTENT));
 
-        let objects = parse_pack_objects(&pack);
-        let resolved = resolve_objects(&objects);
+        let resolved = resolve_pack(&pack, ObjectFormat::Sha1, DELTA_BASE_CACHE_LIMIT);
 
         let delta = resolved[0].as_ref().unwrap();
         assert_eq!(delta.obj_type, ObjectType::Blob);
@@ -289,8 +560,30 @@ mod tests {
         pack.extend_from_slice(&[0xAB; 20]);
         pack.extend_from_slice(&zlib_compress(&payload));
 
-        let objects = parse_pack_objects(&pack);
-        let resolved = resolve_objects(&objects);
+        let resolved = resolve_pack(&pack, ObjectFormat::Sha1, DELTA_BASE_CACHE_LIMIT);
         assert!(resolved[0].is_none());
     }
+
+    #[test]
+    fn resolves_sha256_ref_delta() {
+        let mut pack = pack_header(2);
+        let base_digest = git_object_digest(ObjectFormat::Sha256, ObjectType::Blob, BASE_CONTENT);
+        assert_eq!(base_digest.len(), 32);
+
+        let payload = delta_payload();
+        pack.push((ObjectType::RefDelta as u8) << 4 | payload.len() as u8);
+        pack.extend_from_slice(&base_digest);
+        pack.extend_from_slice(&zlib_compress(&payload));
+        pack.extend_from_slice(&blob_entry(BASE_CONTENT));
+
+        let resolved = resolve_pack(&pack, ObjectFormat::Sha256, DELTA_BASE_CACHE_LIMIT);
+
+        let delta = resolved[0].as_ref().unwrap();
+        assert_eq!(delta.obj_type, ObjectType::Blob);
+        assert_eq!(
+            delta.sha1,
+            object_id(ObjectFormat::Sha256, ObjectType::Blob, RESULT_CONTENT)
+        );
+        assert_eq!(delta.sha1.len(), 64);
+    }
 }
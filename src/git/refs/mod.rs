@@ -0,0 +1,16 @@
+pub mod packed;
+pub mod reflog;
+pub mod reftable;
+pub mod store;
+
+pub use packed::{PackedRef, PackedRefs, PeelState};
+pub use reflog::{MalformedReflogLine, Reflog, ReflogEntry};
+pub use reftable::{Reftable, ReftableStack};
+pub use store::{
+    RefStorage, RefStore, RefStoreError, RefTarget, Reference, Resolution, ResolvedTarget,
+};
+
+/// Whether a string is a full hex object ID (SHA-1 or SHA-256)
+pub(crate) fn is_object_id(s: &str) -> bool {
+    matches!(s.len(), 40 | 64) && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
@@ -0,0 +1,283 @@
+use nom::{
+    IResult,
+    error::{Error, ErrorKind},
+};
+use std::fmt;
+use std::path::Path;
+
+use super::is_object_id;
+
+/// What is known about the object a packed ref peels to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeelState {
+    /// A `^<oid>` line follows the ref: it names an annotated tag, which
+    /// ultimately points at this object
+    Peeled(String),
+    /// The header's traits guarantee the ref doesn't point at a tag
+    NotATag,
+    /// No peel line and no trait to rule out a tag: readers must look up
+    /// the object to find out
+    Unknown,
+}
+
+impl fmt::Display for PeelState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Peeled(oid) => f.pad(oid),
+            Self::NotATag => f.pad("-"),
+            Self::Unknown => f.pad("?"),
+        }
+    }
+}
+
+/// One ref line of a packed-refs file, with its optional peel line
+#[derive(Debug, Clone)]
+pub struct PackedRef {
+    /// Full ref name, e.g. `refs/heads/main`
+    pub name: String,
+    /// Hex object ID the ref points at
+    pub oid: String,
+    /// Hex object ID from the `^<oid>` line that follows the ref, if any
+    pub peeled: Option<String>,
+    /// 1-based line number of the ref line
+    pub line: usize,
+    /// Contents of a loose ref file with the same name, which takes
+    /// precedence over this entry; filled in by [`PackedRefs::resolve_loose_refs`]
+    pub shadowed_by: Option<String>,
+}
+
+impl PackedRef {
+    /// Whether the loose file shadowing this ref points somewhere else
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.shadowed_by
+            .as_ref()
+            .is_some_and(|loose| *loose != self.oid)
+    }
+}
+
+/// The `.git/packed-refs` file
+///
+/// `git pack-refs` (and `git gc`) moves refs out of individual files under
+/// `refs/` into this one text file, one `<oid> <name>` line per ref. When
+/// the ref names an annotated tag, a `^<oid>` line with the object the tag
+/// points at follows, so readers don't have to open the tag.
+#[derive(Debug, Clone, Default)]
+pub struct PackedRefs {
+    /// Traits declared by the `# pack-refs with:` header line
+    pub traits: Vec<String>,
+    pub refs: Vec<PackedRef>,
+}
+
+impl PackedRefs {
+    /// Prefix of the header line declaring the file's traits
+    pub const HEADER_PREFIX: &'static str = "# pack-refs with:";
+
+    /// Parse the contents of a packed-refs file
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if a line is not valid UTF-8, an object ID
+    /// is not 40 or 64 hex digits, a ref line has no name, or a peel line
+    /// doesn't follow a ref line.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let mut packed_refs = Self::default();
+
+        for (index, raw_line) in input.split(|&b| b == b'\n').enumerate() {
+            let error = || nom::Err::Error(Error::new(raw_line, ErrorKind::Verify));
+            let line = std::str::from_utf8(raw_line).map_err(|_| error())?;
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(traits) = line.strip_prefix(Self::HEADER_PREFIX) {
+                if index == 0 {
+                    packed_refs.traits = traits.split_whitespace().map(str::to_string).collect();
+                }
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(peeled) = line.strip_prefix('^') {
+                let last = packed_refs.refs.last_mut().ok_or_else(error)?;
+                if last.peeled.is_some() || !is_object_id(peeled) {
+                    return Err(error());
+                }
+                last.peeled = Some(peeled.to_string());
+                continue;
+            }
+
+            let (oid, name) = line.split_once(' ').ok_or_else(error)?;
+            if !is_object_id(oid) || name.is_empty() {
+                return Err(error());
+            }
+            packed_refs.refs.push(PackedRef {
+                name: name.to_string(),
+                oid: oid.to_string(),
+                peeled: None,
+                line: index + 1,
+                shadowed_by: None,
+            });
+        }
+
+        Ok((&input[input.len()..], packed_refs))
+    }
+
+    /// Whether the header declares a trait ("peeled", "fully-peeled", "sorted")
+    #[must_use]
+    pub fn has_trait(&self, name: &str) -> bool {
+        self.traits.iter().any(|t| t == name)
+    }
+
+    /// What is known about the object a ref peels to
+    ///
+    /// With `fully-peeled` every ref that can be peeled has a peel line;
+    /// with plain `peeled` that only holds for refs under `refs/tags/`.
+    #[must_use]
+    pub fn peel_state(&self, packed_ref: &PackedRef) -> PeelState {
+        if let Some(peeled) = &packed_ref.peeled {
+            PeelState::Peeled(peeled.clone())
+        } else if self.has_trait("fully-peeled")
+            || (self.has_trait("peeled") && packed_ref.name.starts_with("refs/tags/"))
+        {
+            PeelState::NotATag
+        } else {
+            PeelState::Unknown
+        }
+    }
+
+    /// Whether the refs are in the byte order the `sorted` trait promises
+    #[must_use]
+    pub fn is_sorted(&self) -> bool {
+        self.refs.windows(2).all(|pair| pair[0].name < pair[1].name)
+    }
+
+    /// Look a ref up by its full name
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<&PackedRef> {
+        if self.has_trait("sorted") {
+            self.refs
+                .binary_search_by(|packed_ref| packed_ref.name.as_str().cmp(name))
+                .ok()
+                .map(|i| &self.refs[i])
+        } else {
+            self.refs.iter().find(|packed_ref| packed_ref.name == name)
+        }
+    }
+
+    /// Number of refs that a loose ref file shadows
+    #[must_use]
+    pub fn shadowed_count(&self) -> usize {
+        self.refs
+            .iter()
+            .filter(|packed_ref| packed_ref.shadowed_by.is_some())
+            .count()
+    }
+
+    /// Record, for every ref, the contents of the loose ref file with the
+    /// same name under `git_dir`, if there is one
+    pub fn resolve_loose_refs(&mut self, git_dir: &Path) {
+        for packed_ref in &mut self.refs {
+            packed_ref.shadowed_by = std::fs::read_to_string(git_dir.join(&packed_ref.name))
+                .ok()
+                .map(|content| content.trim_end().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "1111111111111111111111111111111111111111";
+    const TAG: &str = "2222222222222222222222222222222222222222";
+    const TAGGED: &str = "3333333333333333333333333333333333333333";
+
+    fn packed_refs_file(header: &str) -> String {
+        format!(
+            "{header}{MAIN} refs/heads/main\n{TAG} refs/tags/v1.0\n^{TAGGED}\n{MAIN} refs/tags/v2.0\n"
+        )
+    }
+
+    #[test]
+    fn test_parse_with_peel_lines() {
+        let data = packed_refs_file("# pack-refs with: peeled fully-peeled sorted \n");
+        let (remaining, packed_refs) = PackedRefs::parse(data.as_bytes()).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(packed_refs.traits, ["peeled", "fully-peeled", "sorted"]);
+        assert_eq!(packed_refs.refs.len(), 3);
+
+        let tag = &packed_refs.refs[1];
+        assert_eq!(tag.name, "refs/tags/v1.0");
+        assert_eq!(tag.oid, TAG);
+        assert_eq!(tag.line, 3);
+        assert_eq!(
+            packed_refs.peel_state(tag),
+            PeelState::Peeled(TAGGED.to_string())
+        );
+        assert_eq!(
+            packed_refs.peel_state(&packed_refs.refs[0]),
+            PeelState::NotATag
+        );
+        assert!(packed_refs.is_sorted());
+        assert_eq!(packed_refs.find("refs/tags/v2.0").unwrap().oid, MAIN);
+        assert!(packed_refs.find("refs/heads/other").is_none());
+    }
+
+    #[test]
+    fn test_peel_state_depends_on_traits() {
+        let data = packed_refs_file("# pack-refs with: peeled \n");
+        let (_, packed_refs) = PackedRefs::parse(data.as_bytes()).unwrap();
+        // Only tags are guaranteed to be peeled
+        assert_eq!(
+            packed_refs.peel_state(&packed_refs.refs[0]),
+            PeelState::Unknown
+        );
+        assert_eq!(
+            packed_refs.peel_state(&packed_refs.refs[2]),
+            PeelState::NotATag
+        );
+
+        let (_, packed_refs) = PackedRefs::parse(packed_refs_file("").as_bytes()).unwrap();
+        assert!(packed_refs.traits.is_empty());
+        assert_eq!(
+            packed_refs.peel_state(&packed_refs.refs[2]),
+            PeelState::Unknown
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        // Peel line without a ref before it
+        assert!(PackedRefs::parse(format!("^{TAGGED}\n").as_bytes()).is_err());
+        // Two peel lines for one ref
+        assert!(
+            PackedRefs::parse(format!("{TAG} refs/tags/a\n^{TAGGED}\n^{TAGGED}\n").as_bytes())
+                .is_err()
+        );
+        // Short object ID
+        assert!(PackedRefs::parse(b"1234 refs/heads/main\n").is_err());
+        // Missing ref name
+        assert!(PackedRefs::parse(format!("{MAIN}\n").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_resolve_loose_refs() {
+        let data = packed_refs_file("# pack-refs with: peeled fully-peeled sorted \n");
+        let (_, mut packed_refs) = PackedRefs::parse(data.as_bytes()).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let heads = temp_dir.path().join("refs/heads");
+        std::fs::create_dir_all(&heads).unwrap();
+        std::fs::write(heads.join("main"), format!("{TAGGED}\n")).unwrap();
+
+        packed_refs.resolve_loose_refs(temp_dir.path());
+        assert_eq!(packed_refs.shadowed_count(), 1);
+        assert_eq!(packed_refs.refs[0].shadowed_by.as_deref(), Some(TAGGED));
+        assert!(packed_refs.refs[0].is_stale());
+        assert!(!packed_refs.refs[1].is_stale());
+    }
+}
@@ -0,0 +1,476 @@
+use nom::IResult;
+use std::path::{Path, PathBuf};
+
+use super::store::is_per_worktree_ref;
+use super::{RefStoreError, ReftableStack, is_object_id};
+use crate::git::repository::Repository;
+
+/// Object ID git writes as the old value when a ref is created
+const NULL_OID_CHAR: u8 = b'0';
+
+/// One line of a reflog: a single update of the ref
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// Hex object ID before the update (all zeros when the ref was created)
+    pub old_oid: String,
+    /// Hex object ID after the update
+    pub new_oid: String,
+    /// Name of whoever made the update
+    pub name: String,
+    pub email: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: i64,
+    /// UTC offset as written by git, e.g. `+0200`
+    pub timezone: String,
+    /// Why the ref moved, e.g. `commit: Fix typo` or `reset: moving to HEAD~2`
+    pub message: String,
+}
+
+impl ReflogEntry {
+    /// Whether this update created the ref
+    #[must_use]
+    pub fn is_creation(&self) -> bool {
+        self.old_oid.bytes().all(|b| b == NULL_OID_CHAR)
+    }
+
+    /// Whether this update deleted the ref
+    #[must_use]
+    pub fn is_deletion(&self) -> bool {
+        self.new_oid.bytes().all(|b| b == NULL_OID_CHAR)
+    }
+
+    /// The operation, i.e. the message up to its first colon
+    /// (`commit`, `checkout`, `reset`, `rebase (finish)`, ...)
+    #[must_use]
+    pub fn action(&self) -> &str {
+        self.message
+            .split_once(':')
+            .map_or(self.message.as_str(), |(action, _)| action)
+    }
+
+    /// UTC offset in minutes, parsed from [`ReflogEntry::timezone`]
+    #[must_use]
+    pub fn offset_minutes(&self) -> i32 {
+        let (sign, digits) = match self.timezone.as_bytes().first() {
+            Some(b'-') => (-1, &self.timezone[1..]),
+            Some(b'+') => (1, &self.timezone[1..]),
+            _ => (1, self.timezone.as_str()),
+        };
+        let value: i32 = digits.parse().unwrap_or_default();
+        sign * (value / 100 * 60 + value % 100)
+    }
+
+    /// Parse one reflog line:
+    /// `<old> <new> <name> <<email>> <timestamp> <timezone>\t<message>`
+    fn parse_line(line: &str) -> Option<Self> {
+        let (header, message) = line.split_once('\t').unwrap_or((line, ""));
+        let (old_oid, rest) = header.split_once(' ')?;
+        let (new_oid, identity) = rest.split_once(' ')?;
+        if !is_object_id(old_oid) || !is_object_id(new_oid) {
+            return None;
+        }
+
+        let (identity, timezone) = identity.rsplit_once(' ')?;
+        let (identity, timestamp) = identity.rsplit_once(' ')?;
+        let email_start = identity.find('<')?;
+        let email_end = identity.rfind('>')?;
+        if email_end < email_start {
+            return None;
+        }
+
+        Some(Self {
+            old_oid: old_oid.to_string(),
+            new_oid: new_oid.to_string(),
+            name: identity[..email_start].trim().to_string(),
+            email: identity[email_start + 1..email_end].to_string(),
+            timestamp: timestamp.parse().ok()?,
+            timezone: timezone.to_string(),
+            message: message.to_string(),
+        })
+    }
+}
+
+/// A reflog line that could not be parsed, skipped the way git skips it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MalformedReflogLine {
+    /// 1-based line number in the file
+    pub line_number: usize,
+    /// The line, lossily decoded
+    pub content: String,
+}
+
+/// The reflog of one ref, from `.git/logs/<ref name>` or the log records
+/// of a reftable stack
+///
+/// Git appends a line every time the ref moves, so the log keeps the
+/// commits a ref pointed at even after `git reset` or a rebase leaves them
+/// unreachable, until `git reflog expire` prunes old entries.
+#[derive(Debug, Clone, Default)]
+pub struct Reflog {
+    /// Full name of the ref, e.g. `HEAD` or `refs/heads/main`
+    pub ref_name: String,
+    /// Entries oldest first, as stored in the file
+    pub entries: Vec<ReflogEntry>,
+    /// Lines left out of `entries` because they could not be parsed
+    pub malformed_lines: Vec<MalformedReflogLine>,
+}
+
+impl Reflog {
+    /// Parse the contents of a reflog file
+    ///
+    /// Like git, a line that is not valid UTF-8, whose object IDs are not
+    /// 40 or 64 hex digits, or whose identity or timestamp is malformed
+    /// does not spoil the rest of the log: it is left out of `entries` and
+    /// recorded in `malformed_lines`.
+    ///
+    /// # Errors
+    ///
+    /// Never fails; returns a nom result to match the other parsers.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let mut entries = Vec::new();
+        let mut malformed_lines = Vec::new();
+        for (index, raw_line) in input.split(|&b| b == b'\n').enumerate() {
+            if raw_line.is_empty() {
+                continue;
+            }
+            match std::str::from_utf8(raw_line)
+                .ok()
+                .and_then(ReflogEntry::parse_line)
+            {
+                Some(entry) => entries.push(entry),
+                None => malformed_lines.push(MalformedReflogLine {
+                    line_number: index + 1,
+                    content: String::from_utf8_lossy(raw_line).into_owned(),
+                }),
+            }
+        }
+        Ok((
+            &input[input.len()..],
+            Self {
+                ref_name: String::new(),
+                entries,
+                malformed_lines,
+            },
+        ))
+    }
+
+    /// Path of the reflog of `ref_name` (a full ref name)
+    #[must_use]
+    pub fn log_path(git_dir: &Path, ref_name: &str) -> PathBuf {
+        git_dir.join("logs").join(ref_name)
+    }
+
+    /// Full name of the ref whose reflog is `log_path`, plus the git
+    /// directory it belongs to, if the path lies under the `logs` directory
+    /// of a git directory
+    #[must_use]
+    pub fn ref_for_log_path(log_path: &Path) -> Option<(PathBuf, String)> {
+        let logs_dir = log_path
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.file_name().is_some_and(|name| name == "logs"))?;
+        let git_dir = logs_dir.parent()?;
+        if !git_dir.join("HEAD").is_file() {
+            return None;
+        }
+        let ref_name = log_path
+            .strip_prefix(logs_dir)
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        Some((git_dir.to_path_buf(), ref_name))
+    }
+
+    /// Git directory holding the reflog of `ref_name`, seen from
+    /// `git_dir`: a linked worktree keeps the logs of `HEAD` and its other
+    /// per-worktree refs, and `common_dir` those of the shared ones
+    fn log_dir<'a>(git_dir: &'a Path, common_dir: &'a Path, ref_name: &str) -> &'a Path {
+        if is_per_worktree_ref(ref_name) {
+            git_dir
+        } else {
+            common_dir
+        }
+    }
+
+    /// Full name of the ref that a short name such as `main`, `origin/main`
+    /// or `HEAD` refers to, following git's lookup order, among the refs
+    /// that have a reflog
+    ///
+    /// The shared refs of a linked worktree are looked up in the common
+    /// directory its `commondir` file names.
+    #[must_use]
+    pub fn find_ref(git_dir: &Path, name: &str) -> Option<String> {
+        let common_dir =
+            Repository::read_commondir(git_dir).unwrap_or_else(|| git_dir.to_path_buf());
+        Self::find_ref_with(git_dir, &common_dir, name)
+    }
+
+    /// [`Self::find_ref`], with the shared refs in `common_dir`
+    #[must_use]
+    pub fn find_ref_with(git_dir: &Path, common_dir: &Path, name: &str) -> Option<String> {
+        let reftable_logs = |dir: &Path| {
+            ReftableStack::exists(dir)
+                .then(|| ReftableStack::load(dir).ok())
+                .flatten()
+                .map(|stack| stack.log_ref_names())
+                .unwrap_or_default()
+        };
+        let (own_logs, shared_logs) = (reftable_logs(git_dir), reftable_logs(common_dir));
+        [
+            name.to_string(),
+            format!("refs/{name}"),
+            format!("refs/tags/{name}"),
+            format!("refs/heads/{name}"),
+            format!("refs/remotes/{name}"),
+            format!("refs/remotes/{name}/HEAD"),
+        ]
+        .into_iter()
+        .find(|candidate| {
+            let log_dir = Self::log_dir(git_dir, common_dir, candidate);
+            let reftable_logs = if log_dir == git_dir {
+                &own_logs
+            } else {
+                &shared_logs
+            };
+            Self::log_path(log_dir, candidate).is_file() || reftable_logs.contains(candidate)
+        })
+    }
+
+    /// Read the reflog of a ref, given its full name
+    ///
+    /// The reflogs of a linked worktree's shared refs are read from the
+    /// common directory its `commondir` file names.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The ref has no reflog
+    /// - The log file can't be read or parsed
+    /// - The repository uses reftables and its stack can't be read
+    pub fn load(git_dir: &Path, ref_name: &str) -> Result<Self, RefStoreError> {
+        let common_dir =
+            Repository::read_commondir(git_dir).unwrap_or_else(|| git_dir.to_path_buf());
+        Self::load_with(git_dir, &common_dir, ref_name)
+    }
+
+    /// [`Self::load`], with the shared refs in `common_dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`Self::load`].
+    pub fn load_with(
+        git_dir: &Path,
+        common_dir: &Path,
+        ref_name: &str,
+    ) -> Result<Self, RefStoreError> {
+        let log_dir = Self::log_dir(git_dir, common_dir, ref_name);
+        let path = Self::log_path(log_dir, ref_name);
+        if !path.is_file() && ReftableStack::exists(log_dir) {
+            return ReftableStack::load(log_dir)?
+                .reflog(ref_name)
+                .ok_or_else(|| RefStoreError::NoReflog(ref_name.to_string()));
+        }
+        if !path.is_file() {
+            return Err(RefStoreError::NoReflog(ref_name.to_string()));
+        }
+        let data = std::fs::read(&path)?;
+        let (_, mut reflog) =
+            Self::parse(&data).map_err(|e| RefStoreError::InvalidReflog(format!("{e:?}")))?;
+        reflog.ref_name = ref_name.to_string();
+        Ok(reflog)
+    }
+
+    /// Entries newest first, numbered the way `<ref>@{n}` counts them
+    pub fn newest_first(&self) -> impl Iterator<Item = (usize, &ReflogEntry)> {
+        self.entries.iter().rev().enumerate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZERO: &str = "0000000000000000000000000000000000000000";
+    const FIRST: &str = "1111111111111111111111111111111111111111";
+    const SECOND: &str = "2222222222222222222222222222222222222222";
+
+    fn reflog_file() -> String {
+        format!(
+            "{ZERO} {FIRST} Jane Doe <jane@example.com> 1700000000 +0000\tcommit (initial): Start\n\
+             {FIRST} {SECOND} Jane Doe <jane@example.com> 1700000100 -0230\tcommit: More\n\
+             {SECOND} {FIRST} Bot <> 1700000200 +0100\treset: moving to HEAD~1\n"
+        )
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let data = reflog_file();
+        let (remaining, reflog) = Reflog::parse(data.as_bytes()).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(reflog.entries.len(), 3);
+
+        let first = &reflog.entries[0];
+        assert!(first.is_creation());
+        assert_eq!(first.name, "Jane Doe");
+        assert_eq!(first.email, "jane@example.com");
+        assert_eq!(first.timestamp, 1_700_000_000);
+        assert_eq!(first.action(), "commit (initial)");
+
+        let second = &reflog.entries[1];
+        assert_eq!(second.old_oid, FIRST);
+        assert_eq!(second.new_oid, SECOND);
+        assert_eq!(second.offset_minutes(), -150);
+        assert_eq!(second.message, "commit: More");
+
+        let reset = &reflog.entries[2];
+        assert_eq!(reset.email, "");
+        assert_eq!(reset.action(), "reset");
+        assert_eq!(reflog.newest_first().next().unwrap().1, reset);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let (_, reflog) = Reflog::parse(b"not a reflog line\n").unwrap();
+        assert!(reflog.entries.is_empty());
+        assert_eq!(reflog.malformed_lines.len(), 1);
+        // Missing timezone
+        let line = format!("{ZERO} {FIRST} Jane <j@e> 1700000000\tcommit\n");
+        let (_, reflog) = Reflog::parse(line.as_bytes()).unwrap();
+        assert!(reflog.entries.is_empty());
+        // Entry without a message is fine
+        let line = format!("{ZERO} {FIRST} Jane <j@e> 1700000000 +0000\n");
+        let (_, reflog) = Reflog::parse(line.as_bytes()).unwrap();
+        assert_eq!(reflog.entries[0].message, "");
+        assert!(reflog.malformed_lines.is_empty());
+    }
+
+    #[test]
+    fn test_parse_skips_malformed_lines() {
+        let mut data = reflog_file().into_bytes();
+        data.extend_from_slice(b"garbage\n\xff\xfe\n");
+        data.extend_from_slice(
+            format!("{FIRST} {SECOND} Jane <j@e> 1700000300 +0000\tcommit: Last\n").as_bytes(),
+        );
+
+        let (_, reflog) = Reflog::parse(&data).unwrap();
+        assert_eq!(reflog.entries.len(), 4);
+        assert_eq!(reflog.entries[3].message, "commit: Last");
+        assert_eq!(
+            reflog.malformed_lines,
+            vec![
+                MalformedReflogLine {
+                    line_number: 4,
+                    content: "garbage".to_string(),
+                },
+                MalformedReflogLine {
+                    line_number: 5,
+                    content: "\u{fffd}\u{fffd}".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_and_load() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path();
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        let log_path = Reflog::log_path(git_dir, "refs/heads/main");
+        std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+        std::fs::write(&log_path, reflog_file()).unwrap();
+
+        assert_eq!(
+            Reflog::find_ref(git_dir, "main").as_deref(),
+            Some("refs/heads/main")
+        );
+        assert_eq!(Reflog::find_ref(git_dir, "HEAD"), None);
+        assert_eq!(
+            Reflog::ref_for_log_path(&log_path),
+            Some((git_dir.to_path_buf(), "refs/heads/main".to_string()))
+        );
+
+        assert!(matches!(
+            Reflog::load(git_dir, "HEAD"),
+            Err(RefStoreError::NoReflog(_))
+        ));
+        let reflog = Reflog::load(git_dir, "refs/heads/main").unwrap();
+        assert_eq!(reflog.ref_name, "refs/heads/main");
+        assert_eq!(reflog.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_find_and_load_in_linked_worktree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let common_dir = temp_dir.path().join(".git");
+        let admin_dir = common_dir.join("worktrees/feature");
+        std::fs::create_dir_all(&admin_dir).unwrap();
+        std::fs::write(common_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(admin_dir.join("HEAD"), "ref: refs/heads/feature\n").unwrap();
+        std::fs::write(admin_dir.join("commondir"), "../..\n").unwrap();
+        for (dir, ref_name) in [
+            (&common_dir, "refs/heads/feature"),
+            (&common_dir, "HEAD"),
+            (&admin_dir, "HEAD"),
+        ] {
+            let log_path = Reflog::log_path(dir, ref_name);
+            std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+            std::fs::write(&log_path, reflog_file()).unwrap();
+        }
+        // The worktree's HEAD has a shorter history than the main one's
+        std::fs::write(
+            Reflog::log_path(&admin_dir, "HEAD"),
+            &reflog_file()[..reflog_file().find('\n').unwrap() + 1],
+        )
+        .unwrap();
+
+        assert_eq!(
+            Reflog::find_ref(&admin_dir, "feature").as_deref(),
+            Some("refs/heads/feature")
+        );
+        assert_eq!(
+            Reflog::load(&admin_dir, "refs/heads/feature")
+                .unwrap()
+                .entries
+                .len(),
+            3
+        );
+        assert_eq!(Reflog::load(&admin_dir, "HEAD").unwrap().entries.len(), 1);
+
+        // GIT_COMMON_DIR can name the common directory instead
+        std::fs::remove_file(admin_dir.join("commondir")).unwrap();
+        assert_eq!(Reflog::find_ref(&admin_dir, "feature"), None);
+        assert_eq!(
+            Reflog::find_ref_with(&admin_dir, &common_dir, "feature").as_deref(),
+            Some("refs/heads/feature")
+        );
+        assert_eq!(
+            Reflog::load_with(&admin_dir, &common_dir, "HEAD")
+                .unwrap()
+                .entries
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_find_and_load_from_reftable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path();
+        std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/.invalid\n").unwrap();
+        crate::git::refs::reftable::tests::write_stack(git_dir);
+
+        assert_eq!(
+            Reflog::find_ref(git_dir, "main").as_deref(),
+            Some("refs/heads/main")
+        );
+        let reflog = Reflog::load(git_dir, "refs/heads/main").unwrap();
+        assert_eq!(reflog.ref_name, "refs/heads/main");
+        assert_eq!(reflog.entries.len(), 2);
+        assert!(matches!(
+            Reflog::load(git_dir, "HEAD"),
+            Err(RefStoreError::NoReflog(_))
+        ));
+    }
+}
@@ -0,0 +1,1172 @@
+use flate2::{Crc, Decompress, FlushDecompress, Status};
+use nom::{
+    IResult, Parser,
+    bytes::complete::{tag, take},
+    error::{Error, ErrorKind},
+    number::complete::{be_i16, be_u8, be_u24, be_u32, be_u64},
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::{RefStoreError, Reflog, ReflogEntry};
+
+/// Size of the file header in version 1; version 2 adds a 4-byte hash ID
+const HEADER_SIZE_V1: usize = 24;
+/// Footer fields after its copy of the header: five positions and a CRC-32
+const FOOTER_FIELDS_SIZE: usize = 5 * 8 + 4;
+/// `hash_id` of a version 2 table using SHA-256 ("s256")
+const HASH_ID_SHA256: u32 = 0x7332_3536;
+
+/// Kind of a reftable block, from its first byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockType {
+    /// `r`: refs sorted by name
+    Ref,
+    /// `o`: object ID prefixes mapped to the ref blocks that mention them
+    Obj,
+    /// `g`: zlib-compressed reflog entries
+    Log,
+    /// `i`: the last key of each block of a section, to binary search it
+    Index,
+}
+
+impl BlockType {
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'r' => Some(Self::Ref),
+            b'o' => Some(Self::Obj),
+            b'g' => Some(Self::Log),
+            b'i' => Some(Self::Index),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BlockType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Ref => "ref",
+            Self::Obj => "obj",
+            Self::Log => "log",
+            Self::Index => "index",
+        })
+    }
+}
+
+/// The header every table starts with (and its footer repeats)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReftableHeader {
+    pub version: u8,
+    /// Size ref and obj blocks are padded to; 0 for unaligned tables
+    pub block_size: u32,
+    /// Update indexes of the oldest and newest change recorded in the table
+    pub min_update_index: u64,
+    pub max_update_index: u64,
+    /// Version 2 only: `sha1` or `s256`
+    pub hash_id: Option<u32>,
+}
+
+impl ReftableHeader {
+    /// Size of the header in bytes
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        match self.hash_id {
+            Some(_) => HEADER_SIZE_V1 + 4,
+            None => HEADER_SIZE_V1,
+        }
+    }
+
+    /// Size of object IDs in bytes
+    #[must_use]
+    pub const fn hash_size(&self) -> usize {
+        match self.hash_id {
+            Some(HASH_ID_SHA256) => 32,
+            _ => 20,
+        }
+    }
+}
+
+/// Where one block sits in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReftableBlock {
+    pub block_type: BlockType,
+    /// File offset of the block header; the first block's header follows
+    /// the file header
+    pub offset: usize,
+    /// `block_len` from the block header: header, records and restart
+    /// table, uncompressed for log blocks. The first block's also counts
+    /// the file header.
+    pub block_len: usize,
+    /// Bytes the block takes in the file, padding or compression included
+    pub stored_size: usize,
+    /// Offsets of the records stored with their full key, which readers
+    /// binary search before scanning forward
+    pub restart_offsets: Vec<u32>,
+    pub record_count: usize,
+}
+
+/// What a ref record stores for the ref
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefValue {
+    /// The ref was deleted at this update index, hiding older tables' value
+    Deletion,
+    /// Hex object ID
+    Object(String),
+    /// Annotated tag and the object it peels to
+    Peeled { oid: String, peeled: String },
+    /// Name of another ref
+    Symbolic(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefRecord {
+    pub name: String,
+    pub update_index: u64,
+    pub value: RefValue,
+    /// Leading bytes shared with the previous record's name and not stored
+    pub prefix_length: usize,
+}
+
+/// Maps an abbreviated object ID to the ref blocks with refs pointing at it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjRecord {
+    /// Hex object ID prefix, `obj_id_len` bytes long
+    pub oid_prefix: String,
+    /// File offsets of the ref blocks; empty when there are too many and
+    /// readers must scan all ref blocks
+    pub block_positions: Vec<u64>,
+}
+
+/// What a log record stores for one update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogValue {
+    /// The entry was deleted (`git reflog delete`), hiding older tables'
+    Deletion,
+    Update {
+        old_oid: String,
+        new_oid: String,
+        name: String,
+        email: String,
+        /// Seconds since the Unix epoch
+        time: u64,
+        /// UTC offset in minutes
+        tz_offset: i16,
+        message: String,
+    },
+}
+
+/// One reflog entry, keyed by ref name and update index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub ref_name: String,
+    pub update_index: u64,
+    pub value: LogValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexRecord {
+    /// Section the indexed blocks belong to
+    pub section: BlockType,
+    /// Last key of the indexed block
+    pub last_key: Vec<u8>,
+    /// File offset of the indexed block
+    pub block_position: u64,
+}
+
+impl IndexRecord {
+    /// The last key, readable: log keys end in a NUL and a reversed
+    /// update index, shown as `<ref>@<update index>`
+    #[must_use]
+    pub fn key_label(&self) -> String {
+        if self.section == BlockType::Log
+            && let Some(name_end) = self.last_key.len().checked_sub(9)
+            && self.last_key[name_end] == 0
+        {
+            let mut reversed = [0u8; 8];
+            reversed.copy_from_slice(&self.last_key[name_end + 1..]);
+            return format!(
+                "{}@{}",
+                String::from_utf8_lossy(&self.last_key[..name_end]),
+                u64::MAX - u64::from_be_bytes(reversed)
+            );
+        }
+        String::from_utf8_lossy(&self.last_key).into_owned()
+    }
+}
+
+/// The footer: where each section starts, and a checksum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReftableFooter {
+    pub ref_index_position: u64,
+    pub obj_position: u64,
+    /// Length of the abbreviated object IDs in obj records
+    pub obj_id_len: u8,
+    pub obj_index_position: u64,
+    pub log_position: u64,
+    pub log_index_position: u64,
+    pub crc32: u32,
+    /// Whether `crc32` matches the footer bytes before it
+    pub crc_matches: bool,
+}
+
+/// One reftable file (`reftable/*.ref`)
+///
+/// The reftable ref backend (`extensions.refStorage=reftable`) keeps refs
+/// and reflogs in a stack of immutable tables instead of loose files,
+/// `packed-refs` and `logs/`. A table holds ref blocks, sorted by name with
+/// prefix-compressed keys; an optional obj section mapping object IDs back
+/// to ref blocks; zlib-compressed log blocks; and an index per section.
+/// Each block ends with a restart table pointing at the records stored
+/// with their full key.
+#[derive(Debug, Clone)]
+pub struct Reftable {
+    pub header: ReftableHeader,
+    pub blocks: Vec<ReftableBlock>,
+    pub refs: Vec<RefRecord>,
+    pub objs: Vec<ObjRecord>,
+    pub logs: Vec<LogRecord>,
+    pub indexes: Vec<IndexRecord>,
+    pub footer: ReftableFooter,
+}
+
+impl Reftable {
+    /// Parse a reftable file from raw bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom parse error if the input is not a valid reftable:
+    /// wrong "REFT" signature or version, a footer that doesn't repeat the
+    /// header, an unknown block type, or a block or record running past
+    /// its end.
+    pub fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (_, header) = Self::parse_header(input)?;
+        let footer_size = header.size() + FOOTER_FIELDS_SIZE;
+        let footer_start = input
+            .len()
+            .checked_sub(footer_size)
+            .filter(|&start| start >= header.size())
+            .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Eof)))?;
+        let footer = Self::parse_footer(&input[footer_start..], &header)?;
+
+        let mut table = Self {
+            header,
+            blocks: Vec::new(),
+            refs: Vec::new(),
+            objs: Vec::new(),
+            logs: Vec::new(),
+            indexes: Vec::new(),
+            footer,
+        };
+
+        let mut position = table.header.size();
+        let mut section = BlockType::Ref;
+        while position < footer_start {
+            // Padding after an aligned block
+            if input[position] == 0 {
+                position += 1;
+                continue;
+            }
+            let block = table.parse_block(&input[..footer_start], position, &mut section)?;
+            position = block.offset + block.stored_size;
+            table.blocks.push(block);
+        }
+
+        Ok((&input[input.len()..], table))
+    }
+
+    fn parse_header(input: &[u8]) -> IResult<&[u8], ReftableHeader> {
+        let (rest, _) = tag(&b"REFT"[..]).parse(input)?;
+        let (rest, version) = be_u8(rest)?;
+        if version != 1 && version != 2 {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)));
+        }
+        let (rest, block_size) = be_u24(rest)?;
+        let (rest, min_update_index) = be_u64(rest)?;
+        let (rest, max_update_index) = be_u64(rest)?;
+        let (rest, hash_id) = if version == 2 {
+            let (rest, hash_id) = be_u32(rest)?;
+            (rest, Some(hash_id))
+        } else {
+            (rest, None)
+        };
+        Ok((
+            rest,
+            ReftableHeader {
+                version,
+                block_size,
+                min_update_index,
+                max_update_index,
+                hash_id,
+            },
+        ))
+    }
+
+    fn parse_footer<'a>(
+        input: &'a [u8],
+        header: &ReftableHeader,
+    ) -> Result<ReftableFooter, nom::Err<Error<&'a [u8]>>> {
+        let (rest, footer_header) = Self::parse_header(input)?;
+        if footer_header != *header {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+        }
+        let (rest, ref_index_position) = be_u64(rest)?;
+        let (rest, obj_field) = be_u64(rest)?;
+        let (rest, obj_index_position) = be_u64(rest)?;
+        let (rest, log_position) = be_u64(rest)?;
+        let (rest, log_index_position) = be_u64(rest)?;
+        let (_, crc32) = be_u32(rest)?;
+
+        let mut crc = Crc::new();
+        crc.update(&input[..input.len() - 4]);
+        Ok(ReftableFooter {
+            ref_index_position,
+            obj_position: obj_field >> 5,
+            obj_id_len: (obj_field & 0x1f) as u8,
+            obj_index_position,
+            log_position,
+            log_index_position,
+            crc32,
+            crc_matches: crc.sum() == crc32,
+        })
+    }
+
+    /// Parse the block whose header is at `position`, collecting its
+    /// records. `section` tracks the kind of blocks an index block indexes.
+    fn parse_block<'a>(
+        &mut self,
+        input: &'a [u8],
+        position: usize,
+        section: &mut BlockType,
+    ) -> Result<ReftableBlock, nom::Err<Error<&'a [u8]>>> {
+        let error = |kind| nom::Err::Error(Error::new(&input[position..], kind));
+        let (rest, type_byte) = be_u8(&input[position..])?;
+        let block_type = BlockType::from_byte(type_byte).ok_or_else(|| error(ErrorKind::Tag))?;
+        let (_, block_len) = be_u24(rest)?;
+        let block_len = block_len as usize;
+        if block_type != BlockType::Index {
+            *section = block_type;
+        }
+
+        // The first block shares its bytes with the file header
+        let block_start = if position == self.header.size() {
+            0
+        } else {
+            position
+        };
+        let records_start = position - block_start + 4;
+
+        let (block, stored_size): (Vec<u8>, usize) = if block_type == BlockType::Log {
+            let (inflated, consumed) = inflate(
+                &input[position + 4..],
+                block_len.saturating_sub(records_start),
+            )
+            .ok_or_else(|| error(ErrorKind::Verify))?;
+            let mut block = input[block_start..position + 4].to_vec();
+            block.extend(inflated);
+            (block, 4 + consumed)
+        } else {
+            let block = input
+                .get(block_start..block_start + block_len)
+                .ok_or_else(|| error(ErrorKind::Eof))?;
+            (block.to_vec(), block_start + block_len - position)
+        };
+        if block.len() != block_len || block_len < records_start + 2 {
+            return Err(error(ErrorKind::LengthValue));
+        }
+
+        let restart_count = u16::from_be_bytes([block[block_len - 2], block[block_len - 1]]);
+        let restarts_start = (block_len - 2)
+            .checked_sub(3 * restart_count as usize)
+            .filter(|&start| start >= records_start)
+            .ok_or_else(|| error(ErrorKind::LengthValue))?;
+        let restart_offsets = block[restarts_start..block_len - 2]
+            .chunks_exact(3)
+            .map(|offset| u32::from_be_bytes([0, offset[0], offset[1], offset[2]]))
+            .collect();
+
+        let record_count = self
+            .parse_records(&block[records_start..restarts_start], block_type, *section)
+            .map_err(|_| error(ErrorKind::Verify))?;
+
+        Ok(ReftableBlock {
+            block_type,
+            offset: position,
+            block_len,
+            stored_size,
+            restart_offsets,
+            record_count,
+        })
+    }
+
+    /// Parse the records of one block, returning how many there were
+    fn parse_records<'a>(
+        &mut self,
+        mut input: &'a [u8],
+        block_type: BlockType,
+        section: BlockType,
+    ) -> Result<usize, nom::Err<Error<&'a [u8]>>> {
+        let hash_size = self.header.hash_size();
+        let mut last_key: Vec<u8> = Vec::new();
+        let mut count = 0;
+        while !input.is_empty() {
+            let (rest, prefix_length) = varint(input)?;
+            let (rest, suffix_and_type) = varint(rest)?;
+            let (rest, suffix) = take(suffix_and_type >> 3).parse(rest)?;
+            let prefix_length = usize::try_from(prefix_length).unwrap_or(usize::MAX);
+            if prefix_length > last_key.len() {
+                return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+            }
+            last_key.truncate(prefix_length);
+            last_key.extend_from_slice(suffix);
+            let extra = (suffix_and_type & 0x7) as u8;
+
+            input = match block_type {
+                BlockType::Ref => {
+                    let (rest, record) =
+                        self.parse_ref_value(rest, &last_key, extra, prefix_length, hash_size)?;
+                    self.refs.push(record);
+                    rest
+                }
+                BlockType::Obj => {
+                    let (rest, record) = parse_obj_positions(rest, &last_key, extra)?;
+                    self.objs.push(record);
+                    rest
+                }
+                BlockType::Log => {
+                    let (rest, record) = parse_log_value(rest, &last_key, extra, hash_size)?;
+                    self.logs.push(record);
+                    rest
+                }
+                BlockType::Index => {
+                    let (rest, block_position) = varint(rest)?;
+                    self.indexes.push(IndexRecord {
+                        section,
+                        last_key: last_key.clone(),
+                        block_position,
+                    });
+                    rest
+                }
+            };
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn parse_ref_value<'a>(
+        &self,
+        input: &'a [u8],
+        key: &[u8],
+        value_type: u8,
+        prefix_length: usize,
+        hash_size: usize,
+    ) -> IResult<&'a [u8], RefRecord> {
+        let (rest, update_index_delta) = varint(input)?;
+        let (rest, value) = match value_type {
+            0 => (rest, RefValue::Deletion),
+            1 => {
+                let (rest, oid) = take(hash_size).parse(rest)?;
+                (rest, RefValue::Object(hex::encode(oid)))
+            }
+            2 => {
+                let (rest, oid) = take(hash_size).parse(rest)?;
+                let (rest, peeled) = take(hash_size).parse(rest)?;
+                (
+                    rest,
+                    RefValue::Peeled {
+                        oid: hex::encode(oid),
+                        peeled: hex::encode(peeled),
+                    },
+                )
+            }
+            3 => {
+                let (rest, length) = varint(rest)?;
+                let (rest, target) = take(length).parse(rest)?;
+                (
+                    rest,
+                    RefValue::Symbolic(String::from_utf8_lossy(target).into_owned()),
+                )
+            }
+            _ => return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag))),
+        };
+        Ok((
+            rest,
+            RefRecord {
+                name: String::from_utf8_lossy(key).into_owned(),
+                update_index: self.header.min_update_index + update_index_delta,
+                value,
+                prefix_length,
+            },
+        ))
+    }
+
+    /// Blocks of one kind
+    pub fn blocks_of(&self, block_type: BlockType) -> impl Iterator<Item = &ReftableBlock> {
+        self.blocks
+            .iter()
+            .filter(move |block| block.block_type == block_type)
+    }
+}
+
+fn parse_obj_positions<'a>(input: &'a [u8], key: &[u8], count: u8) -> IResult<&'a [u8], ObjRecord> {
+    let (mut rest, count) = if count == 0 {
+        varint(input)?
+    } else {
+        (input, u64::from(count))
+    };
+    let mut block_positions = Vec::new();
+    let mut position = 0u64;
+    for index in 0..count {
+        let (next, delta) = varint(rest)?;
+        // The first position is absolute, the others relative to the last
+        position = if index == 0 {
+            delta
+        } else {
+            position.wrapping_add(delta)
+        };
+        block_positions.push(position);
+        rest = next;
+    }
+    Ok((
+        rest,
+        ObjRecord {
+            oid_prefix: hex::encode(key),
+            block_positions,
+        },
+    ))
+}
+
+fn parse_log_value<'a>(
+    input: &'a [u8],
+    key: &[u8],
+    log_type: u8,
+    hash_size: usize,
+) -> IResult<&'a [u8], LogRecord> {
+    // Key: ref name, NUL, then the update index subtracted from u64::MAX so
+    // that newer entries sort first
+    let error = || nom::Err::Error(Error::new(input, ErrorKind::Verify));
+    let name_end = key.len().checked_sub(9).ok_or_else(error)?;
+    if key[name_end] != 0 {
+        return Err(error());
+    }
+    let mut reversed = [0u8; 8];
+    reversed.copy_from_slice(&key[name_end + 1..]);
+    let ref_name = String::from_utf8_lossy(&key[..name_end]).into_owned();
+    let update_index = u64::MAX - u64::from_be_bytes(reversed);
+
+    let (rest, value) = match log_type {
+        0 => (input, LogValue::Deletion),
+        1 => {
+            let (rest, old_oid) = take(hash_size).parse(input)?;
+            let (rest, new_oid) = take(hash_size).parse(rest)?;
+            let (rest, name) = length_prefixed_string(rest)?;
+            let (rest, email) = length_prefixed_string(rest)?;
+            let (rest, time) = varint(rest)?;
+            let (rest, tz_offset) = be_i16(rest)?;
+            let (rest, message) = length_prefixed_string(rest)?;
+            (
+                rest,
+                LogValue::Update {
+                    old_oid: hex::encode(old_oid),
+                    new_oid: hex::encode(new_oid),
+                    name,
+                    email,
+                    time,
+                    tz_offset,
+                    message,
+                },
+            )
+        }
+        _ => return Err(nom::Err::Error(Error::new(input, ErrorKind::Tag))),
+    };
+    Ok((
+        rest,
+        LogRecord {
+            ref_name,
+            update_index,
+            value,
+        },
+    ))
+}
+
+fn length_prefixed_string(input: &[u8]) -> IResult<&[u8], String> {
+    let (rest, length) = varint(input)?;
+    let (rest, bytes) = take(length).parse(rest)?;
+    Ok((rest, String::from_utf8_lossy(bytes).into_owned()))
+}
+
+/// Reftable varint: big-endian groups of 7 bits, with one added to every
+/// group but the last so that each value has a single encoding (the same
+/// scheme as OFS_DELTA offsets in packs)
+fn varint(input: &[u8]) -> IResult<&[u8], u64> {
+    let (mut rest, byte) = be_u8(input)?;
+    let mut value = u64::from(byte & 0x7f);
+    let mut more = byte & 0x80 != 0;
+    while more {
+        let (next, byte) = be_u8(rest)?;
+        value = value
+            .checked_add(1)
+            .and_then(|value| value.checked_mul(128))
+            .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::TooLarge)))?
+            | u64::from(byte & 0x7f);
+        more = byte & 0x80 != 0;
+        rest = next;
+    }
+    Ok((rest, value))
+}
+
+/// Inflate a zlib stream into `size` bytes, returning them and the number
+/// of compressed bytes read
+fn inflate(input: &[u8], size: usize) -> Option<(Vec<u8>, usize)> {
+    let mut decompress = Decompress::new(true);
+    let mut output = Vec::with_capacity(size);
+    let status = decompress
+        .decompress_vec(input, &mut output, FlushDecompress::Finish)
+        .ok()?;
+    (status == Status::StreamEnd && output.len() == size).then(|| {
+        (
+            output,
+            usize::try_from(decompress.total_in()).unwrap_or(usize::MAX),
+        )
+    })
+}
+
+/// The tables of a reftable ref store, listed in `reftable/tables.list`
+///
+/// Each transaction writes a new table on top of the stack and tables are
+/// compacted into bigger ones over time. Readers merge the tables: for
+/// every ref name (and every reflog entry) the newest table that mentions
+/// it wins, and deletion records hide the older values.
+#[derive(Debug, Clone)]
+pub struct ReftableStack {
+    /// The `reftable` directory
+    pub dir: PathBuf,
+    /// Tables oldest first, as listed, with their file names
+    pub tables: Vec<(String, Reftable)>,
+}
+
+impl ReftableStack {
+    /// Directory of the reftable store, in the git directory
+    pub const DIR_NAME: &str = "reftable";
+    /// File listing the tables of the stack, oldest first
+    pub const TABLES_LIST: &str = "tables.list";
+
+    /// Whether the repository whose git directory is `git_dir` stores its
+    /// refs in reftables
+    #[must_use]
+    pub fn exists(git_dir: &Path) -> bool {
+        Self::tables_list_path(git_dir).is_file()
+    }
+
+    #[must_use]
+    pub fn tables_list_path(git_dir: &Path) -> PathBuf {
+        git_dir.join(Self::DIR_NAME).join(Self::TABLES_LIST)
+    }
+
+    /// Whether `path` names a table of a stack, `reftable/*.ref`
+    #[must_use]
+    pub fn is_table_path(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "ref")
+            && path
+                .parent()
+                .and_then(Path::file_name)
+                .is_some_and(|dir| dir == Self::DIR_NAME)
+    }
+
+    /// Read `tables.list` and every table it names
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - `tables.list` or a table can't be read
+    /// - A listed table is missing or is not a valid reftable
+    pub fn load(git_dir: &Path) -> Result<Self, RefStoreError> {
+        Self::load_list(&Self::tables_list_path(git_dir))
+    }
+
+    /// Read a `tables.list` file and every table it names
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file or a table can't be read
+    /// - A listed table is missing or is not a valid reftable
+    pub fn load_list(tables_list: &Path) -> Result<Self, RefStoreError> {
+        let dir = tables_list.parent().unwrap_or_else(|| Path::new("."));
+        let list = std::fs::read_to_string(tables_list)?;
+        let mut tables = Vec::new();
+        for name in list.lines().map(str::trim).filter(|name| !name.is_empty()) {
+            let path = dir.join(name);
+            if !path.is_file() {
+                return Err(RefStoreError::InvalidReftable(format!(
+                    "{name} is listed but missing"
+                )));
+            }
+            let data = std::fs::read(&path)?;
+            let (_, table) = Reftable::parse(&data)
+                .map_err(|e| RefStoreError::InvalidReftable(format!("{name}: {e:?}")))?;
+            tables.push((name.to_string(), table));
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            tables,
+        })
+    }
+
+    /// Current value of every ref, by name, with the index of the table it
+    /// comes from. Deleted refs are left out.
+    #[must_use]
+    pub fn refs(&self) -> BTreeMap<String, (usize, &RefRecord)> {
+        let mut refs = BTreeMap::new();
+        for (index, (_, table)) in self.tables.iter().enumerate() {
+            for record in &table.refs {
+                if record.value == RefValue::Deletion {
+                    refs.remove(&record.name);
+                } else {
+                    refs.insert(record.name.clone(), (index, record));
+                }
+            }
+        }
+        refs
+    }
+
+    /// Reflog entries of a ref after merging the tables, oldest first
+    #[must_use]
+    pub fn log_records(&self, ref_name: &str) -> Vec<&LogRecord> {
+        let mut records = BTreeMap::new();
+        for (_, table) in &self.tables {
+            for record in table.logs.iter().filter(|r| r.ref_name == ref_name) {
+                if record.value == LogValue::Deletion {
+                    records.remove(&record.update_index);
+                } else {
+                    records.insert(record.update_index, record);
+                }
+            }
+        }
+        records.into_values().collect()
+    }
+
+    /// Names of the refs that have reflog entries
+    #[must_use]
+    pub fn log_ref_names(&self) -> BTreeSet<String> {
+        self.tables
+            .iter()
+            .flat_map(|(_, table)| &table.logs)
+            .map(|record| record.ref_name.clone())
+            .filter(|name| !self.log_records(name).is_empty())
+            .collect()
+    }
+
+    /// The reflog of a ref, if it has entries
+    #[must_use]
+    pub fn reflog(&self, ref_name: &str) -> Option<Reflog> {
+        let entries: Vec<ReflogEntry> = self
+            .log_records(ref_name)
+            .into_iter()
+            .filter_map(|record| match &record.value {
+                LogValue::Deletion => None,
+                LogValue::Update {
+                    old_oid,
+                    new_oid,
+                    name,
+                    email,
+                    time,
+                    tz_offset,
+                    message,
+                } => Some(ReflogEntry {
+                    old_oid: old_oid.clone(),
+                    new_oid: new_oid.clone(),
+                    name: name.clone(),
+                    email: email.clone(),
+                    timestamp: i64::try_from(*time).unwrap_or(i64::MAX),
+                    timezone: format_timezone(*tz_offset),
+                    // Git stores the message with its trailing newline
+                    message: message.trim_end_matches('\n').to_string(),
+                }),
+            })
+            .collect();
+        (!entries.is_empty()).then(|| Reflog {
+            ref_name: ref_name.to_string(),
+            entries,
+            malformed_lines: Vec::new(),
+        })
+    }
+}
+
+/// UTC offset in minutes as git writes it, e.g. `-0230`
+fn format_timezone(minutes: i16) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.unsigned_abs();
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    pub const MAIN: &str = "1111111111111111111111111111111111111111";
+    pub const TAG: &str = "2222222222222222222222222222222222222222";
+    pub const TAGGED: &str = "3333333333333333333333333333333333333333";
+
+    fn put_varint(value: u64, out: &mut Vec<u8>) {
+        let mut bytes = vec![(value & 0x7f) as u8];
+        let mut value = value >> 7;
+        while value != 0 {
+            value -= 1;
+            bytes.push(0x80 | (value & 0x7f) as u8);
+            value >>= 7;
+        }
+        out.extend(bytes.iter().rev());
+    }
+
+    /// Encode records into a block body: records, restart table and count.
+    /// `first_offset` is where the first record sits relative to the start
+    /// of the block. Like git, every 16th record is a restart point.
+    fn block_body(records: &[(Vec<u8>, u8, Vec<u8>)], first_offset: usize) -> Vec<u8> {
+        let mut body = Vec::new();
+        let mut restarts = Vec::new();
+        let mut last_key: &[u8] = &[];
+        for (index, (key, extra, value)) in records.iter().enumerate() {
+            // Share a prefix with the previous key, except at restarts
+            let prefix = if index % 16 == 0 {
+                restarts.push(first_offset + body.len());
+                0
+            } else {
+                last_key.iter().zip(key).take_while(|(a, b)| a == b).count()
+            };
+            put_varint(prefix as u64, &mut body);
+            put_varint(
+                (((key.len() - prefix) as u64) << 3) | u64::from(*extra),
+                &mut body,
+            );
+            body.extend_from_slice(&key[prefix..]);
+            body.extend_from_slice(value);
+            last_key = key;
+        }
+        for restart in &restarts {
+            body.extend_from_slice(&(*restart as u32).to_be_bytes()[1..]);
+        }
+        body.extend_from_slice(&(restarts.len() as u16).to_be_bytes());
+        body
+    }
+
+    fn ref_record(name: &str, delta: u64, value: &RefValue) -> (Vec<u8>, u8, Vec<u8>) {
+        let mut data = Vec::new();
+        put_varint(delta, &mut data);
+        let value_type = match value {
+            RefValue::Deletion => 0,
+            RefValue::Object(oid) => {
+                data.extend(hex::decode(oid).unwrap());
+                1
+            }
+            RefValue::Peeled { oid, peeled } => {
+                data.extend(hex::decode(oid).unwrap());
+                data.extend(hex::decode(peeled).unwrap());
+                2
+            }
+            RefValue::Symbolic(target) => {
+                put_varint(target.len() as u64, &mut data);
+                data.extend_from_slice(target.as_bytes());
+                3
+            }
+        };
+        (name.as_bytes().to_vec(), value_type, data)
+    }
+
+    /// A log record moving `name` from `old` to `new`
+    pub fn log_record(
+        name: &str,
+        update_index: u64,
+        old: &str,
+        new: &str,
+        message: &str,
+    ) -> (Vec<u8>, u8, Vec<u8>) {
+        let mut key = name.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(&(u64::MAX - update_index).to_be_bytes());
+
+        let mut data = hex::decode(old).unwrap();
+        data.extend(hex::decode(new).unwrap());
+        for field in ["Jane Doe", "jane@example.com"] {
+            put_varint(field.len() as u64, &mut data);
+            data.extend_from_slice(field.as_bytes());
+        }
+        put_varint(1_700_000_000 + update_index, &mut data);
+        data.extend_from_slice(&(-150i16).to_be_bytes());
+        put_varint(message.len() as u64, &mut data);
+        data.extend_from_slice(message.as_bytes());
+        (key, 1, data)
+    }
+
+    /// Assemble a version 1 table: one ref block padded to `block_size`,
+    /// its index, an obj block, then one log block. Log records must be sorted by key.
+    pub fn build_table(
+        min_update_index: u64,
+        max_update_index: u64,
+        refs: &[(&str, u64, RefValue)],
+        logs: &[(Vec<u8>, u8, Vec<u8>)],
+    ) -> Vec<u8> {
+        let block_size = 256u32;
+        let mut header = b"REFT".to_vec();
+        header.push(1);
+        header.extend_from_slice(&block_size.to_be_bytes()[1..]);
+        header.extend_from_slice(&min_update_index.to_be_bytes());
+        header.extend_from_slice(&max_update_index.to_be_bytes());
+
+        let mut data = header.clone();
+        let ref_records: Vec<_> = refs
+            .iter()
+            .map(|(name, delta, value)| ref_record(name, *delta, value))
+            .collect();
+        let body = block_body(&ref_records, HEADER_SIZE_V1 + 4);
+        data.push(b'r');
+        data.extend_from_slice(&((HEADER_SIZE_V1 + 4 + body.len()) as u32).to_be_bytes()[1..]);
+        data.extend(body);
+        data.resize(block_size as usize, 0);
+
+        // A one-entry index pointing at the ref block
+        let ref_index_position = data.len() as u64;
+        let mut position = Vec::new();
+        put_varint(0, &mut position);
+        let last_name = refs.last().map_or("", |(name, ..)| name);
+        let body = block_body(&[(last_name.as_bytes().to_vec(), 0, position)], 4);
+        data.push(b'i');
+        data.extend_from_slice(&((4 + body.len()) as u32).to_be_bytes()[1..]);
+        data.extend(body);
+
+        // Every ref points into the first block
+        let obj_position = data.len() as u64;
+        let obj_records = vec![(hex::decode(&MAIN[..4]).unwrap(), 1, vec![0])];
+        let body = block_body(&obj_records, 4);
+        data.push(b'o');
+        data.extend_from_slice(&((4 + body.len()) as u32).to_be_bytes()[1..]);
+        data.extend(body);
+
+        let log_position = if logs.is_empty() {
+            0
+        } else {
+            let position = data.len() as u64;
+            let body = block_body(logs, 4);
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&body).unwrap();
+            data.push(b'g');
+            data.extend_from_slice(&((4 + body.len()) as u32).to_be_bytes()[1..]);
+            data.extend(encoder.finish().unwrap());
+            position
+        };
+
+        let mut footer = header;
+        footer.extend_from_slice(&ref_index_position.to_be_bytes());
+        footer.extend_from_slice(&((obj_position << 5) | 2).to_be_bytes());
+        footer.extend_from_slice(&0u64.to_be_bytes());
+        footer.extend_from_slice(&log_position.to_be_bytes());
+        footer.extend_from_slice(&0u64.to_be_bytes());
+        let mut crc = Crc::new();
+        crc.update(&footer);
+        footer.extend_from_slice(&crc.sum().to_be_bytes());
+        data.extend(footer);
+        data
+    }
+
+    /// Write a two-table stack: the base table creates main, a tag and
+    /// HEAD; the top one deletes the tag and moves main
+    pub fn write_stack(git_dir: &Path) {
+        let zero = "0".repeat(40);
+        let base = build_table(
+            1,
+            2,
+            &[
+                ("HEAD", 0, RefValue::Symbolic("refs/heads/main".to_string())),
+                ("refs/heads/main", 0, RefValue::Object(TAGGED.to_string())),
+                (
+                    "refs/tags/v1",
+                    1,
+                    RefValue::Peeled {
+                        oid: TAG.to_string(),
+                        peeled: TAGGED.to_string(),
+                    },
+                ),
+            ],
+            &[log_record(
+                "refs/heads/main",
+                1,
+                &zero,
+                TAGGED,
+                "commit (initial): Start\n",
+            )],
+        );
+        let top = build_table(
+            3,
+            3,
+            &[
+                ("refs/heads/main", 0, RefValue::Object(MAIN.to_string())),
+                ("refs/tags/v1", 0, RefValue::Deletion),
+            ],
+            &[log_record(
+                "refs/heads/main",
+                3,
+                TAGGED,
+                MAIN,
+                "commit: More\n",
+            )],
+        );
+
+        let dir = git_dir.join(ReftableStack::DIR_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("0x000000000001-0x000000000002-00000001.ref"), base).unwrap();
+        std::fs::write(dir.join("0x000000000003-0x000000000003-00000002.ref"), top).unwrap();
+        std::fs::write(
+            dir.join(ReftableStack::TABLES_LIST),
+            "0x000000000001-0x000000000002-00000001.ref\n\
+             0x000000000003-0x000000000003-00000002.ref\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 16_511, 16_512, u64::from(u32::MAX)] {
+            let mut encoded = Vec::new();
+            put_varint(value, &mut encoded);
+            assert_eq!(varint(&encoded).unwrap(), (&[][..], value));
+        }
+        // 128 is 0x80 0x00, not 0x81 0x00
+        assert_eq!(varint(&[0x80, 0x00]).unwrap().1, 128);
+        assert!(varint(&[0x80]).is_err());
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let data = build_table(
+            5,
+            6,
+            &[
+                ("HEAD", 0, RefValue::Symbolic("refs/heads/main".to_string())),
+                ("refs/heads/main", 1, RefValue::Object(MAIN.to_string())),
+                ("refs/heads/maint", 1, RefValue::Deletion),
+            ],
+            &[
+                log_record("refs/heads/main", 6, MAIN, TAG, "commit: Two\n"),
+                log_record("refs/heads/main", 5, TAGGED, MAIN, "commit: One\n"),
+            ],
+        );
+        let (_, table) = Reftable::parse(&data).unwrap();
+
+        assert_eq!(table.header.version, 1);
+        assert_eq!(table.header.block_size, 256);
+        assert!(table.footer.crc_matches);
+        assert!(table.footer.obj_position > 256);
+        assert_eq!(table.footer.obj_id_len, 2);
+
+        let types: Vec<_> = table.blocks.iter().map(|b| b.block_type).collect();
+        assert_eq!(
+            types,
+            [
+                BlockType::Ref,
+                BlockType::Index,
+                BlockType::Obj,
+                BlockType::Log
+            ]
+        );
+        assert_eq!(table.footer.ref_index_position, 256);
+        assert_eq!(table.indexes[0].section, BlockType::Ref);
+        assert_eq!(table.indexes[0].key_label(), "refs/heads/maint");
+        assert_eq!(table.indexes[0].block_position, 0);
+        assert_eq!(table.blocks[0].offset, HEADER_SIZE_V1);
+        assert_eq!(
+            table.blocks[0].restart_offsets,
+            [(HEADER_SIZE_V1 + 4) as u32]
+        );
+        assert_eq!(table.blocks[0].record_count, 3);
+
+        // "refs/heads/maint" shares "refs/heads/main" with its predecessor
+        assert_eq!(table.refs[2].name, "refs/heads/maint");
+        assert_eq!(table.refs[2].prefix_length, "refs/heads/main".len());
+        assert_eq!(table.refs[2].value, RefValue::Deletion);
+        assert_eq!(table.refs[1].update_index, 6);
+        assert_eq!(
+            table.refs[0].value,
+            RefValue::Symbolic("refs/heads/main".to_string())
+        );
+
+        assert_eq!(table.objs[0].oid_prefix, &MAIN[..4]);
+        assert_eq!(table.objs[0].block_positions, [0]);
+
+        assert_eq!(table.logs.len(), 2);
+        assert_eq!(table.logs[0].update_index, 6);
+        let LogValue::Update {
+            tz_offset, message, ..
+        } = &table.logs[1].value
+        else {
+            panic!("expected an update");
+        };
+        assert_eq!(*tz_offset, -150);
+        assert_eq!(message, "commit: One\n");
+    }
+
+    #[test]
+    fn test_parse_invalid_table() {
+        let data = build_table(
+            1,
+            1,
+            &[("HEAD", 0, RefValue::Object(MAIN.to_string()))],
+            &[],
+        );
+        assert!(Reftable::parse(&data[..20]).is_err());
+
+        // A footer that doesn't repeat the header
+        let mut bad_footer = data.clone();
+        let footer_start = bad_footer.len() - 68;
+        bad_footer[footer_start + 10] ^= 0xff;
+        assert!(Reftable::parse(&bad_footer).is_err());
+
+        // A corrupted position only fails the CRC
+        let mut bad_crc = data;
+        let footer_start = bad_crc.len() - 68;
+        bad_crc[footer_start + 30] ^= 0xff;
+        assert!(!Reftable::parse(&bad_crc).unwrap().1.footer.crc_matches);
+    }
+
+    #[test]
+    fn test_stack_merges_tables() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        write_stack(temp_dir.path());
+        assert!(ReftableStack::exists(temp_dir.path()));
+        let stack = ReftableStack::load(temp_dir.path()).unwrap();
+        assert_eq!(stack.tables.len(), 2);
+
+        let refs = stack.refs();
+        assert_eq!(
+            refs.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["HEAD", "refs/heads/main"]
+        );
+        let (table, main) = refs["refs/heads/main"];
+        assert_eq!(table, 1);
+        assert_eq!(main.value, RefValue::Object(MAIN.to_string()));
+
+        let reflog = stack.reflog("refs/heads/main").unwrap();
+        assert_eq!(reflog.entries.len(), 2);
+        assert!(reflog.entries[0].is_creation());
+        assert_eq!(reflog.entries[1].message, "commit: More");
+        assert_eq!(reflog.entries[1].timezone, "-0230");
+        assert_eq!(reflog.entries[1].timestamp, 1_700_000_003);
+        assert!(stack.reflog("HEAD").is_none());
+        assert_eq!(
+            stack.log_ref_names().into_iter().collect::<Vec<_>>(),
+            ["refs/heads/main"]
+        );
+
+        std::fs::write(
+            ReftableStack::tables_list_path(temp_dir.path()),
+            "0x000000000009-0x000000000009-00000009.ref\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            ReftableStack::load(temp_dir.path()),
+            Err(RefStoreError::InvalidReftable(_))
+        ));
+    }
+}
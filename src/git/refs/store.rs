@@ -0,0 +1,655 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use super::reftable::RefValue;
+use super::{PackedRefs, ReftableStack, is_object_id};
+use crate::git::repository::Repository;
+
+/// Symbolic refs followed before giving up, like git's `SYMREF_MAXDEPTH`
+pub const MAX_SYMREF_DEPTH: usize = 5;
+
+/// Refs stored directly in the git directory rather than under `refs/`
+///
+/// `FETCH_HEAD` is left out: it lists fetched heads with extra columns and
+/// is not a ref file.
+pub const PSEUDO_REFS: [&str; 8] = [
+    "HEAD",
+    "ORIG_HEAD",
+    "MERGE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "REBASE_HEAD",
+    "BISECT_HEAD",
+    "AUTO_MERGE",
+];
+
+/// Namespaces under `refs/` that each worktree keeps for itself, in its
+/// own git directory, like the pseudo-refs
+pub const PER_WORKTREE_PREFIXES: [&str; 3] = ["refs/bisect/", "refs/worktree/", "refs/rewritten/"];
+
+/// Whether a ref belongs to a single worktree rather than the repository
+#[must_use]
+pub fn is_per_worktree_ref(name: &str) -> bool {
+    PSEUDO_REFS.contains(&name)
+        || PER_WORKTREE_PREFIXES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+}
+
+#[derive(Debug, Error)]
+pub enum RefStoreError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid packed-refs file: {0}")]
+    InvalidPackedRefs(String),
+
+    #[error("Invalid reflog: {0}")]
+    InvalidReflog(String),
+
+    #[error("No reflog for ref: {0}")]
+    NoReflog(String),
+
+    #[error("Invalid reftable: {0}")]
+    InvalidReftable(String),
+}
+
+/// Where a ref's current value comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefStorage {
+    /// A file under the git directory
+    Loose,
+    /// A line in `packed-refs`
+    Packed,
+    /// A loose file that shadows an entry in `packed-refs`
+    LooseOverPacked,
+    /// A record in the reftable stack
+    Reftable,
+}
+
+impl fmt::Display for RefStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Loose => "loose",
+            Self::Packed => "packed",
+            Self::LooseOverPacked => "loose+packed",
+            Self::Reftable => "reftable",
+        })
+    }
+}
+
+/// What a ref holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefTarget {
+    /// Hex object ID
+    Object(String),
+    /// Name of another ref (`ref: <name>`)
+    Symbolic(String),
+    /// Contents that are neither an object ID nor a symref
+    Malformed(String),
+}
+
+impl RefTarget {
+    /// Parse the contents of a loose ref file
+    ///
+    /// Only the first line counts: `MERGE_HEAD` lists one commit per line
+    /// for an octopus merge.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let content = content.lines().next().unwrap_or_default().trim();
+        if let Some(target) = content.strip_prefix("ref:") {
+            Self::Symbolic(target.trim().to_string())
+        } else if is_object_id(content) {
+            Self::Object(content.to_string())
+        } else {
+            Self::Malformed(content.to_string())
+        }
+    }
+}
+
+impl fmt::Display for RefTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Object(oid) => write!(f, "{oid}"),
+            Self::Symbolic(name) => write!(f, "ref: {name}"),
+            Self::Malformed(content) => write!(f, "malformed: {content:?}"),
+        }
+    }
+}
+
+/// One ref with its value after loose files have been applied over
+/// `packed-refs`
+#[derive(Debug, Clone)]
+pub struct Reference {
+    /// Full name, e.g. `refs/heads/main` or `HEAD`
+    pub name: String,
+    pub target: RefTarget,
+    pub storage: RefStorage,
+    /// Object an annotated tag peels to, from `packed-refs`; only kept when
+    /// the packed entry is the current value
+    pub peeled: Option<String>,
+}
+
+impl Reference {
+    /// Whether this is a symbolic ref
+    #[must_use]
+    pub const fn is_symbolic(&self) -> bool {
+        matches!(self.target, RefTarget::Symbolic(_))
+    }
+}
+
+/// Where following a ref's symref chain ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedTarget {
+    /// An object ID
+    Object(String),
+    /// A symref points at a ref that doesn't exist (an unborn branch for HEAD)
+    Dangling(String),
+    /// The chain comes back to this ref
+    Cycle(String),
+    /// A ref in the chain is malformed
+    Malformed(String),
+    /// More than [`MAX_SYMREF_DEPTH`] symrefs in a row
+    TooDeep,
+}
+
+impl fmt::Display for ResolvedTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Object(oid) => write!(f, "{oid}"),
+            Self::Dangling(name) => write!(f, "{name} (dangling)"),
+            Self::Cycle(name) => write!(f, "{name} (cycle)"),
+            Self::Malformed(name) => write!(f, "{name} (malformed)"),
+            Self::TooDeep => write!(f, "… (more than {MAX_SYMREF_DEPTH} symrefs)"),
+        }
+    }
+}
+
+/// The result of following a ref through its symrefs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    /// Names of the refs followed, starting with the resolved ref; a ref
+    /// that breaks the chain is named by `end` instead
+    pub chain: Vec<String>,
+    pub end: ResolvedTarget,
+}
+
+impl Resolution {
+    /// Object ID the ref resolves to, if the chain ends in one
+    #[must_use]
+    pub fn object_id(&self) -> Option<&str> {
+        match &self.end {
+            ResolvedTarget::Object(oid) => Some(oid),
+            _ => None,
+        }
+    }
+
+    /// Whether the chain ends anywhere but at an object
+    #[must_use]
+    pub const fn is_broken(&self) -> bool {
+        !matches!(self.end, ResolvedTarget::Object(_))
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in self.chain.iter().skip(1) {
+            write!(f, "{name} → ")?;
+        }
+        write!(f, "{}", self.end)
+    }
+}
+
+/// All refs of a repository: loose ref files, `packed-refs` and the
+/// pseudo-refs such as `HEAD`, or the reftable stack
+///
+/// Git reads a loose file in preference to a packed entry of the same
+/// name, so right after `git gc` most refs exist only in `packed-refs`,
+/// and a later update writes a loose file that shadows the packed line.
+/// Repositories using the reftable backend keep every ref, `HEAD`
+/// included, in `reftable/`.
+///
+/// A linked worktree's git directory only holds its per-worktree refs;
+/// the others come from the common directory its `commondir` file, or
+/// `GIT_COMMON_DIR`, names.
+#[derive(Debug, Clone)]
+pub struct RefStore {
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+    refs: BTreeMap<String, Reference>,
+}
+
+impl RefStore {
+    /// Read every ref of the repository whose git directory is `git_dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `refs` directory can't be walked, or
+    /// `packed-refs` exists but can't be read or parsed.
+    pub fn load(git_dir: &Path) -> Result<Self, RefStoreError> {
+        let common_dir =
+            Repository::read_commondir(git_dir).unwrap_or_else(|| git_dir.to_path_buf());
+        Self::load_with(git_dir, &common_dir)
+    }
+
+    /// Read every ref seen from `git_dir`, whose shared refs are in
+    /// `common_dir`: the git directory itself, unless it is a linked
+    /// worktree's
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`Self::load`].
+    pub fn load_with(git_dir: &Path, common_dir: &Path) -> Result<Self, RefStoreError> {
+        if common_dir != git_dir {
+            return Self::load_worktree(git_dir, common_dir);
+        }
+        if ReftableStack::exists(git_dir) {
+            return Self::load_reftable(git_dir);
+        }
+        let mut refs = BTreeMap::new();
+
+        let packed_refs_path = git_dir.join("packed-refs");
+        if packed_refs_path.is_file() {
+            let data = fs::read(&packed_refs_path)?;
+            let (_, packed_refs) = PackedRefs::parse(&data)
+                .map_err(|e| RefStoreError::InvalidPackedRefs(format!("{e:?}")))?;
+            for packed_ref in packed_refs.refs {
+                refs.insert(
+                    packed_ref.name.clone(),
+                    Reference {
+                        name: packed_ref.name,
+                        target: RefTarget::Object(packed_ref.oid),
+                        storage: RefStorage::Packed,
+                        peeled: packed_ref.peeled,
+                    },
+                );
+            }
+        }
+
+        let mut loose = Vec::new();
+        Self::collect_loose_refs(&git_dir.join("refs"), "refs", &mut loose)?;
+        for name in PSEUDO_REFS {
+            if git_dir.join(name).is_file() {
+                loose.push(name.to_string());
+            }
+        }
+
+        for name in loose {
+            let Ok(content) = fs::read_to_string(git_dir.join(&name)) else {
+                continue;
+            };
+            let target = RefTarget::parse(&content);
+            let reference = match refs.remove(&name) {
+                Some(packed) => Reference {
+                    // The peeled value only describes the packed object
+                    peeled: packed.peeled.filter(|_| packed.target == target),
+                    name: name.clone(),
+                    target,
+                    storage: RefStorage::LooseOverPacked,
+                },
+                None => Reference {
+                    name: name.clone(),
+                    target,
+                    storage: RefStorage::Loose,
+                    peeled: None,
+                },
+            };
+            refs.insert(name, reference);
+        }
+
+        Ok(Self {
+            git_dir: git_dir.to_path_buf(),
+            common_dir: git_dir.to_path_buf(),
+            refs,
+        })
+    }
+
+    /// Read the refs of a repository using the reftable backend
+    ///
+    /// Its `HEAD` file and `refs/heads` file are placeholders that keep
+    /// older git versions from mistaking the directory for something else;
+    /// pseudo-refs that still live in files (such as `MERGE_HEAD`) are only
+    /// read when the stack doesn't have them.
+    fn load_reftable(git_dir: &Path) -> Result<Self, RefStoreError> {
+        let stack = ReftableStack::load(git_dir)?;
+        let mut refs: BTreeMap<String, Reference> = stack
+            .refs()
+            .into_iter()
+            .filter_map(|(name, (_, record))| {
+                let (target, peeled) = match &record.value {
+                    RefValue::Object(oid) => (RefTarget::Object(oid.clone()), None),
+                    RefValue::Peeled { oid, peeled } => {
+                        (RefTarget::Object(oid.clone()), Some(peeled.clone()))
+                    }
+                    RefValue::Symbolic(target) => (RefTarget::Symbolic(target.clone()), None),
+                    RefValue::Deletion => return None,
+                };
+                let reference = Reference {
+                    name: name.clone(),
+                    target,
+                    storage: RefStorage::Reftable,
+                    peeled,
+                };
+                Some((name, reference))
+            })
+            .collect();
+
+        for name in PSEUDO_REFS.into_iter().skip(1) {
+            if refs.contains_key(name) {
+                continue;
+            }
+            if let Ok(content) = fs::read_to_string(git_dir.join(name)) {
+                refs.insert(
+                    name.to_string(),
+                    Reference {
+                        name: name.to_string(),
+                        target: RefTarget::parse(&content),
+                        storage: RefStorage::Loose,
+                        peeled: None,
+                    },
+                );
+            }
+        }
+
+        Ok(Self {
+            git_dir: git_dir.to_path_buf(),
+            common_dir: git_dir.to_path_buf(),
+            refs,
+        })
+    }
+
+    /// Read the refs seen from a linked worktree: the shared refs of the
+    /// common directory, with the worktree's own `HEAD`, pseudo-refs and
+    /// per-worktree namespaces in place of the main worktree's
+    fn load_worktree(git_dir: &Path, common_dir: &Path) -> Result<Self, RefStoreError> {
+        let mut store = Self::load(common_dir)?;
+        store.refs.retain(|name, _| !is_per_worktree_ref(name));
+
+        let mut loose: Vec<String> = PSEUDO_REFS
+            .into_iter()
+            .filter(|name| git_dir.join(name).is_file())
+            .map(str::to_string)
+            .collect();
+        for prefix in PER_WORKTREE_PREFIXES {
+            let prefix = prefix.trim_end_matches('/');
+            Self::collect_loose_refs(&git_dir.join(prefix), prefix, &mut loose)?;
+        }
+        for name in loose {
+            let Ok(content) = fs::read_to_string(git_dir.join(&name)) else {
+                continue;
+            };
+            store.refs.insert(
+                name.clone(),
+                Reference {
+                    name,
+                    target: RefTarget::parse(&content),
+                    storage: RefStorage::Loose,
+                    peeled: None,
+                },
+            );
+        }
+
+        store.git_dir = git_dir.to_path_buf();
+        Ok(store)
+    }
+
+    /// Names of the files under `dir`, recursively, as ref names
+    fn collect_loose_refs(
+        dir: &Path,
+        prefix: &str,
+        names: &mut Vec<String>,
+    ) -> Result<(), RefStoreError> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let name = format!("{prefix}/{file_name}");
+            let path = entry.path();
+            if path.is_dir() {
+                Self::collect_loose_refs(&path, &name, names)?;
+            } else if !file_name.ends_with(".lock") {
+                names.push(name);
+            }
+        }
+        Ok(())
+    }
+
+    /// The git directory the refs were read from
+    #[must_use]
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// The directory the shared refs were read from, the git directory
+    /// itself outside linked worktrees
+    #[must_use]
+    pub fn common_dir(&self) -> &Path {
+        &self.common_dir
+    }
+
+    /// Path of the loose file a ref is (or would be) stored in
+    #[must_use]
+    pub fn loose_path(&self, name: &str) -> PathBuf {
+        if is_per_worktree_ref(name) {
+            self.git_dir.join(name)
+        } else {
+            self.common_dir.join(name)
+        }
+    }
+
+    /// Look a ref up by its full name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Reference> {
+        self.refs.get(name)
+    }
+
+    /// Every ref, pseudo-refs first, then by name
+    pub fn iter(&self) -> impl Iterator<Item = &Reference> {
+        self.refs.values()
+    }
+
+    /// Refs whose name starts with `prefix` (e.g. `refs/heads/`), by name
+    pub fn refs_under<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a Reference> {
+        self.refs
+            .range(prefix.to_string()..)
+            .take_while(move |(name, _)| name.starts_with(prefix))
+            .map(|(_, reference)| reference)
+    }
+
+    /// Number of refs
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.refs.len()
+    }
+
+    /// Whether there are no refs at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.refs.is_empty()
+    }
+
+    /// Follow a ref through its symrefs to an object ID
+    #[must_use]
+    pub fn resolve(&self, name: &str) -> Resolution {
+        let mut chain = vec![name.to_string()];
+        loop {
+            let current = chain[chain.len() - 1].clone();
+            let target = match self.refs.get(&current).map(|reference| &reference.target) {
+                Some(RefTarget::Object(oid)) => {
+                    return Resolution {
+                        chain,
+                        end: ResolvedTarget::Object(oid.clone()),
+                    };
+                }
+                Some(RefTarget::Symbolic(target)) => target,
+                None => return Self::stop(chain, ResolvedTarget::Dangling(current)),
+                Some(RefTarget::Malformed(_)) => {
+                    return Self::stop(chain, ResolvedTarget::Malformed(current));
+                }
+            };
+            if chain.contains(target) {
+                let end = ResolvedTarget::Cycle(target.clone());
+                return Resolution { chain, end };
+            }
+            if chain.len() > MAX_SYMREF_DEPTH {
+                return Resolution {
+                    chain,
+                    end: ResolvedTarget::TooDeep,
+                };
+            }
+            chain.push(target.clone());
+        }
+    }
+
+    /// End a resolution at a broken ref, which `end` names, so the chain
+    /// only keeps the refs that led to it
+    fn stop(mut chain: Vec<String>, end: ResolvedTarget) -> Resolution {
+        if chain.len() > 1 {
+            chain.pop();
+        }
+        Resolution { chain, end }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "1111111111111111111111111111111111111111";
+    const OLD: &str = "2222222222222222222222222222222222222222";
+    const TAG: &str = "3333333333333333333333333333333333333333";
+    const TAGGED: &str = "4444444444444444444444444444444444444444";
+
+    fn write(git_dir: &Path, name: &str, content: &str) {
+        let path = git_dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn repository() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path();
+        write(
+            git_dir,
+            "packed-refs",
+            &format!(
+                "# pack-refs with: peeled fully-peeled sorted \n\
+                 {OLD} refs/heads/main\n\
+                 {OLD} refs/heads/topic/old\n\
+                 {TAG} refs/tags/v1\n^{TAGGED}\n"
+            ),
+        );
+        write(git_dir, "refs/heads/main", &format!("{MAIN}\n"));
+        write(git_dir, "refs/heads/feature/x", &format!("{MAIN}\n"));
+        write(git_dir, "refs/heads/main.lock", "");
+        write(git_dir, "HEAD", "ref: refs/heads/main\n");
+        write(
+            git_dir,
+            "refs/remotes/origin/HEAD",
+            "ref: refs/remotes/origin/main\n",
+        );
+        temp_dir
+    }
+
+    #[test]
+    fn test_loose_refs_shadow_packed_refs() {
+        let temp_dir = repository();
+        let store = RefStore::load(temp_dir.path()).unwrap();
+
+        let names: Vec<_> = store.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "HEAD",
+                "refs/heads/feature/x",
+                "refs/heads/main",
+                "refs/heads/topic/old",
+                "refs/remotes/origin/HEAD",
+                "refs/tags/v1",
+            ]
+        );
+
+        let main = store.get("refs/heads/main").unwrap();
+        assert_eq!(main.target, RefTarget::Object(MAIN.to_string()));
+        assert_eq!(main.storage, RefStorage::LooseOverPacked);
+        let old = store.get("refs/heads/topic/old").unwrap();
+        assert_eq!(old.storage, RefStorage::Packed);
+        assert_eq!(
+            store.get("refs/tags/v1").unwrap().peeled.as_deref(),
+            Some(TAGGED)
+        );
+        assert_eq!(store.refs_under("refs/heads/").count(), 3);
+        assert_eq!(store.refs_under("refs/tags/").count(), 1);
+    }
+
+    #[test]
+    fn test_resolve_symrefs() {
+        let temp_dir = repository();
+        let store = RefStore::load(temp_dir.path()).unwrap();
+
+        let head = store.resolve("HEAD");
+        assert_eq!(head.chain, ["HEAD", "refs/heads/main"]);
+        assert_eq!(head.object_id(), Some(MAIN));
+
+        let origin = store.resolve("refs/remotes/origin/HEAD");
+        assert_eq!(
+            origin.end,
+            ResolvedTarget::Dangling("refs/remotes/origin/main".to_string())
+        );
+        assert!(origin.is_broken());
+
+        assert_eq!(
+            store.resolve("refs/heads/missing").end,
+            ResolvedTarget::Dangling("refs/heads/missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_cycles_and_malformed_refs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path();
+        write(git_dir, "refs/heads/a", "ref: refs/heads/b\n");
+        write(git_dir, "refs/heads/b", "ref: refs/heads/a\n");
+        write(git_dir, "refs/heads/bad", "not an object id\n");
+        write(git_dir, "HEAD", "ref: refs/heads/bad\n");
+        let store = RefStore::load(git_dir).unwrap();
+
+        let cycle = store.resolve("refs/heads/a");
+        assert_eq!(cycle.chain, ["refs/heads/a", "refs/heads/b"]);
+        assert_eq!(cycle.end, ResolvedTarget::Cycle("refs/heads/a".to_string()));
+        assert_eq!(
+            store.resolve("HEAD").end,
+            ResolvedTarget::Malformed("refs/heads/bad".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reftable_refs() {
+        use crate::git::refs::reftable::tests::{MAIN, write_stack};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path();
+        write_stack(git_dir);
+        // Placeholders git writes for tools that don't know reftables
+        write(git_dir, "HEAD", "ref: refs/heads/.invalid\n");
+        write(
+            git_dir,
+            "refs/heads",
+            "this repository uses the reftable format\n",
+        );
+        write(git_dir, "ORIG_HEAD", &format!("{MAIN}\n"));
+        let store = RefStore::load(git_dir).unwrap();
+
+        let names: Vec<_> = store.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["HEAD", "ORIG_HEAD", "refs/heads/main"]);
+        let main = store.get("refs/heads/main").unwrap();
+        assert_eq!(main.storage, RefStorage::Reftable);
+        assert_eq!(store.get("ORIG_HEAD").unwrap().storage, RefStorage::Loose);
+        assert_eq!(store.resolve("HEAD").object_id(), Some(MAIN));
+    }
+}
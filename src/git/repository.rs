@@ -1,5 +1,13 @@
-use crate::git::loose_object::{LooseObject, LooseObjectError};
-use crate::git::pack::{PackError, PackIndex};
+use crate::git::alternates::{Alternate, AlternatesError};
+use crate::git::commit_graph::{CommitGraphChain, CommitGraphChainError};
+use crate::git::compat_map::{CompatMap, CompatMapError};
+use crate::git::loose_object::{LooseObject, LooseObjectError, ParsedContent};
+use crate::git::object_database::{ObjectDatabase, ObjectDatabases};
+use crate::git::object_format::ObjectFormat;
+use crate::git::pack::{MultiPackIndexChain, MultiPackIndexChainError, PackError, PackIndex};
+use crate::git::refs::{RefStore, RefStoreError, Reflog};
+use crate::git::submodule::{GitlinkTarget, Submodule, SubmoduleError};
+use crate::git::worktree::{Worktree, WorktreeError};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -86,17 +94,17 @@ impl PackGroup {
             })
     }
 
-    /// Look up an object by SHA-1 hash using the index file
+    /// Look up an object by its ID using the index file
     /// Returns the byte offset in the pack file if found
     ///
     /// # Errors
     ///
     /// Returns a `PackError` if the index file exists but cannot be read or
     /// parsed.
-    pub fn lookup_object_offset(&self, sha1: &[u8; 20]) -> Result<Option<u64>, PackError> {
+    pub fn lookup_object_offset(&self, oid: &[u8]) -> Result<Option<u64>, PackError> {
         Ok(self
             .load_index()?
-            .and_then(|index| index.lookup_object(sha1)))
+            .and_then(|index| index.lookup_object(oid)))
     }
 
     /// Get basic statistics about the pack group
@@ -182,6 +190,81 @@ impl std::fmt::Display for PackGroupStats {
     }
 }
 
+/// Represents the files of a split commit-graph (objects/info/commit-graphs)
+///
+/// Layers are ordered the way the chain file lists them, base layer first.
+/// Layer files the chain no longer lists are kept apart: git leaves them
+/// behind after merging layers until they expire.
+#[derive(Debug, Clone)]
+pub struct CommitGraphChainGroup {
+    pub chain_file: PathBuf,
+    /// Layer files listed in the chain that exist on disk, base layer first
+    pub layer_files: Vec<PathBuf>,
+    /// Layer files not listed in the chain
+    pub stale_layer_files: Vec<PathBuf>,
+}
+
+impl CommitGraphChainGroup {
+    /// Returns all available file paths in this group with a display label
+    #[must_use]
+    pub fn get_all_files(&self) -> Vec<(String, &PathBuf)> {
+        let file_name = |path: &PathBuf| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let mut files = vec![(file_name(&self.chain_file), &self.chain_file)];
+        for (index, path) in self.layer_files.iter().enumerate() {
+            files.push((format!("layer {index}: {}", file_name(path)), path));
+        }
+        for path in &self.stale_layer_files {
+            files.push((format!("stale: {}", file_name(path)), path));
+        }
+
+        files
+    }
+}
+
+/// Represents the files of an incremental multi-pack-index
+/// (objects/pack/multi-pack-index.d)
+///
+/// Layers are ordered the way the chain file lists them, base layer first.
+/// Layer files the chain no longer lists are kept apart, like those of a
+/// split commit-graph.
+#[derive(Debug, Clone)]
+pub struct MultiPackIndexChainGroup {
+    pub chain_file: PathBuf,
+    /// Layer files listed in the chain that exist on disk, base layer first
+    pub layer_files: Vec<PathBuf>,
+    /// Layer files not listed in the chain
+    pub stale_layer_files: Vec<PathBuf>,
+}
+
+impl MultiPackIndexChainGroup {
+    /// Returns all available file paths in this group with a display label
+    #[must_use]
+    pub fn get_all_files(&self) -> Vec<(String, &PathBuf)> {
+        let file_name = |path: &PathBuf| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        };
+
+        let mut files = vec![(file_name(&self.chain_file), &self.chain_file)];
+        for (index, path) in self.layer_files.iter().enumerate() {
+            files.push((format!("layer {index}: {}", file_name(path)), path));
+        }
+        for path in &self.stale_layer_files {
+            files.push((format!("stale: {}", file_name(path)), path));
+        }
+
+        files
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum RepositoryError {
     #[error("IO error: {0}")]
@@ -195,6 +278,27 @@ pub enum RepositoryError {
 
     #[error("Pack error: {0}")]
     PackError(#[from] PackError),
+
+    #[error("Ref error: {0}")]
+    RefStoreError(#[from] RefStoreError),
+
+    #[error("Worktree error: {0}")]
+    WorktreeError(#[from] WorktreeError),
+
+    #[error("Submodule error: {0}")]
+    SubmoduleError(#[from] SubmoduleError),
+
+    #[error("Alternates error: {0}")]
+    AlternatesError(#[from] AlternatesError),
+
+    #[error("Commit-graph chain error: {0}")]
+    CommitGraphChainError(#[from] CommitGraphChainError),
+
+    #[error("Multi-pack-index chain error: {0}")]
+    MultiPackIndexChainError(#[from] MultiPackIndexChainError),
+
+    #[error("Compat map error: {0}")]
+    CompatMapError(#[from] CompatMapError),
 }
 
 /// Statistics about loose objects in the repository
@@ -224,37 +328,315 @@ impl LooseObjectStats {
     }
 }
 
+/// Environment variables that relocate a repository's directories
+///
+/// `GIT_DIR` names the git directory outright, skipping discovery;
+/// `GIT_COMMON_DIR` and `GIT_OBJECT_DIRECTORY` move the shared directory
+/// and the object store away from it.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryEnv {
+    pub git_dir: Option<PathBuf>,
+    pub common_dir: Option<PathBuf>,
+    pub object_directory: Option<PathBuf>,
+}
+
+impl DiscoveryEnv {
+    /// Read the variables from the process environment, ignoring empty ones
+    #[must_use]
+    pub fn from_env() -> Self {
+        let var = |name| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        Self {
+            git_dir: var("GIT_DIR"),
+            common_dir: var("GIT_COMMON_DIR"),
+            object_directory: var("GIT_OBJECT_DIRECTORY"),
+        }
+    }
+}
+
 /// Represents a Git repository
+///
+/// The directories are resolved once, when the repository is discovered:
+/// in a linked worktree or a submodule `.git` is a file pointing elsewhere,
+/// a bare repository has no `.git` at all, and the environment can move
+/// any of them.
 pub struct Repository {
+    /// The work tree, or the git directory of a bare repository
     path: PathBuf,
+    /// Per-worktree state: HEAD, index, logs
+    git_dir: PathBuf,
+    /// State shared by all worktrees: refs, packed-refs, config, objects
+    common_dir: PathBuf,
+    objects_dir: PathBuf,
+    work_tree: Option<PathBuf>,
+    /// Hash function naming the objects, from `extensions.objectFormat`
+    object_format: ObjectFormat,
+    /// Second hash function the objects are also named by, from
+    /// `extensions.compatObjectFormat`
+    compat_object_format: Option<ObjectFormat>,
 }
 
 impl Repository {
-    /// Creates a new Repository instance from a path
+    /// Name of the file or directory marking a work tree
+    pub const DOT_GIT: &str = ".git";
+    /// Prefix of the single line of a gitfile
+    const GITFILE_PREFIX: &str = "gitdir:";
+
+    /// Discovers the repository containing a path, honouring `GIT_DIR`,
+    /// `GIT_COMMON_DIR` and `GIT_OBJECT_DIRECTORY`
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - The provided path does not contain a .git directory
+    /// - Neither the path nor any of its parents is in a repository
+    /// - A `.git` file or `GIT_DIR` points to something that isn't a git
+    ///   directory
     pub fn new(path: impl AsRef<Path>) -> Result<Self, RepositoryError> {
-        let path = path.as_ref().to_path_buf();
-        let git_dir = path.join(".git");
+        Self::discover_with(path, &DiscoveryEnv::from_env())
+    }
 
-        if !git_dir.exists() {
-            return Err(RepositoryError::NotGitRepository(
-                "No .git directory found".to_string(),
-            ));
+    /// Discovers the repository containing a path the way git does: walk up
+    /// the parents until one has a `.git` directory or gitfile, or is
+    /// itself a bare git directory
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - Neither the path nor any of its parents is in a repository
+    /// - A `.git` file or `env.git_dir` points to something that isn't a
+    ///   git directory
+    pub fn discover_with(
+        path: impl AsRef<Path>,
+        env: &DiscoveryEnv,
+    ) -> Result<Self, RepositoryError> {
+        let path = std::path::absolute(path.as_ref())?;
+
+        if let Some(git_dir) = &env.git_dir {
+            let git_dir = std::path::absolute(git_dir)?;
+            if !git_dir.is_dir() {
+                return Err(RepositoryError::NotGitRepository(format!(
+                    "GIT_DIR {} is not a directory",
+                    git_dir.display()
+                )));
+            }
+            // Without GIT_WORK_TREE git takes the current directory as the
+            // top of the work tree
+            let work_tree = (path != git_dir).then_some(path);
+            return Ok(Self::with_dirs(git_dir, work_tree, env));
         }
 
-        Ok(Self { path })
+        for dir in path.ancestors() {
+            let dot_git = dir.join(Self::DOT_GIT);
+            if dot_git.is_dir() {
+                return Ok(Self::with_dirs(dot_git, Some(dir.to_path_buf()), env));
+            }
+            if dot_git.is_file() {
+                let git_dir = Self::read_gitfile(&dot_git)?;
+                if !Self::is_git_dir(&git_dir) {
+                    return Err(RepositoryError::NotGitRepository(format!(
+                        "{} points to {}, which is not a git directory",
+                        dot_git.display(),
+                        git_dir.display()
+                    )));
+                }
+                return Ok(Self::with_dirs(git_dir, Some(dir.to_path_buf()), env));
+            }
+            if Self::is_git_dir(dir) {
+                return Ok(Self::with_dirs(dir.to_path_buf(), None, env));
+            }
+        }
+
+        Err(RepositoryError::NotGitRepository(format!(
+            "No .git directory found in {} or any parent",
+            path.display()
+        )))
+    }
+
+    /// Opens the repository whose git directory is `git_dir`, without
+    /// discovery or environment overrides
+    #[must_use]
+    pub fn from_git_dir(git_dir: &Path) -> Self {
+        let work_tree = git_dir
+            .parent()
+            .filter(|_| {
+                git_dir
+                    .file_name()
+                    .is_some_and(|name| name == Self::DOT_GIT)
+            })
+            .map(Path::to_path_buf);
+        Self::with_dirs(git_dir.to_path_buf(), work_tree, &DiscoveryEnv::default())
+    }
+
+    /// Opens an object directory on its own, such as an alternate lent
+    /// to another repository, as the objects of the repository around it
+    #[must_use]
+    pub fn for_objects_dir(objects_dir: &Path) -> Self {
+        let git_dir = objects_dir
+            .parent()
+            .map_or_else(|| objects_dir.to_path_buf(), Path::to_path_buf);
+        let env = DiscoveryEnv {
+            object_directory: Some(objects_dir.to_path_buf()),
+            ..DiscoveryEnv::default()
+        };
+        Self::with_dirs(git_dir, None, &env)
+    }
+
+    /// Resolve the common and object directories of a git directory
+    fn with_dirs(git_dir: PathBuf, work_tree: Option<PathBuf>, env: &DiscoveryEnv) -> Self {
+        let common_dir = env
+            .common_dir
+            .clone()
+            .or_else(|| Self::read_commondir(&git_dir))
+            .unwrap_or_else(|| git_dir.clone());
+        let objects_dir = env
+            .object_directory
+            .clone()
+            .unwrap_or_else(|| common_dir.join("objects"));
+        // An unreadable config or an unknown format falls back to SHA-1
+        // rather than failing discovery
+        let object_format = ObjectFormat::for_repository(&common_dir).unwrap_or_default();
+        let compat_object_format = ObjectFormat::compat_for_repository(&common_dir)
+            .ok()
+            .flatten()
+            .filter(|&format| format != object_format);
+
+        Self {
+            path: work_tree.clone().unwrap_or_else(|| git_dir.clone()),
+            git_dir,
+            common_dir,
+            objects_dir,
+            work_tree,
+            object_format,
+            compat_object_format,
+        }
+    }
+
+    /// Target of a gitfile: a `.git` file holding `gitdir: <path>`, the
+    /// path being relative to the file's directory unless absolute
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or lacks the `gitdir:`
+    /// line.
+    pub fn read_gitfile(path: &Path) -> Result<PathBuf, RepositoryError> {
+        let content = fs::read_to_string(path)?;
+        let target = content
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix(Self::GITFILE_PREFIX))
+            .map(str::trim)
+            .filter(|target| !target.is_empty())
+            .ok_or_else(|| {
+                RepositoryError::NotGitRepository(format!("Invalid gitfile {}", path.display()))
+            })?;
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+        Ok(base.join(target))
+    }
+
+    /// The shared directory named by a git directory's `commondir` file,
+    /// which linked worktrees have
+    pub(crate) fn read_commondir(git_dir: &Path) -> Option<PathBuf> {
+        let content = fs::read_to_string(git_dir.join("commondir")).ok()?;
+        let common_dir = content.lines().next()?.trim();
+        (!common_dir.is_empty()).then(|| git_dir.join(common_dir))
     }
 
-    /// Returns the path to the repository
+    /// Whether a directory looks like a git directory: a `HEAD` file plus
+    /// `objects` and `refs`, which a linked worktree keeps in its common
+    /// directory
+    #[must_use]
+    pub fn is_git_dir(dir: &Path) -> bool {
+        if !dir.join("HEAD").is_file() {
+            return false;
+        }
+        let common_dir = Self::read_commondir(dir).unwrap_or_else(|| dir.to_path_buf());
+        common_dir.join("objects").is_dir() && common_dir.join("refs").is_dir()
+    }
+
+    /// Returns the path to the repository: its work tree, or its git
+    /// directory when bare
     #[must_use]
     pub fn get_path(&self) -> &Path {
         &self.path
     }
 
+    /// The git directory: `.git`, the target of a gitfile, or the
+    /// repository itself when bare
+    #[must_use]
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// The directory shared by all worktrees, the git directory itself
+    /// outside linked worktrees
+    #[must_use]
+    pub fn common_dir(&self) -> &Path {
+        &self.common_dir
+    }
+
+    /// The object store, normally `objects` in the common directory
+    #[must_use]
+    pub fn objects_dir(&self) -> &Path {
+        &self.objects_dir
+    }
+
+    /// The work tree, or `None` for a bare repository
+    #[must_use]
+    pub fn work_tree(&self) -> Option<&Path> {
+        self.work_tree.as_deref()
+    }
+
+    /// Whether the repository has no work tree
+    #[must_use]
+    pub const fn is_bare(&self) -> bool {
+        self.work_tree.is_none()
+    }
+
+    /// The hash function naming the repository's objects
+    #[must_use]
+    pub const fn object_format(&self) -> ObjectFormat {
+        self.object_format
+    }
+
+    /// The second hash function naming the repository's objects, if it
+    /// keeps a compatibility map
+    #[must_use]
+    pub const fn compat_object_format(&self) -> Option<ObjectFormat> {
+        self.compat_object_format
+    }
+
+    /// Loads the translation between the repository's object names and
+    /// their compat names, from its own object directory and its
+    /// alternates
+    ///
+    /// Returns `None` when the repository has no compatibility format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - A loose object map or pack index exists but cannot be read
+    /// - A loose object map is malformed
+    /// - An alternates file exists but cannot be read
+    pub fn load_compat_map(&self) -> Result<Option<CompatMap>, RepositoryError> {
+        let Some(compat_format) = self.compat_object_format else {
+            return Ok(None);
+        };
+        let mut map = CompatMap::load(&self.objects_dir, self.object_format, compat_format)?;
+        for alternate in self.list_alternates()? {
+            if alternate.exists {
+                map.merge(CompatMap::load(
+                    &alternate.path,
+                    self.object_format,
+                    compat_format,
+                )?);
+            }
+        }
+        Ok(Some(map))
+    }
+
     /// Lists all pack files in the repository
     ///
     /// # Errors
@@ -262,7 +644,7 @@ impl Repository {
     /// This function will return an error if:
     /// - File system operations fail when reading the objects/pack directory
     pub fn list_pack_files(&self) -> Result<Vec<PathBuf>, RepositoryError> {
-        let pack_dir = self.path.join(".git/objects/pack");
+        let pack_dir = self.objects_dir.join("pack");
 
         if !pack_dir.exists() {
             return Ok(Vec::new()); // Return empty list if pack directory doesn't exist
@@ -290,7 +672,7 @@ impl Repository {
     /// This function will return an error if:
     /// - File system operations fail when reading the objects/pack directory
     pub fn list_pack_groups(&self) -> Result<HashMap<String, PackGroup>, RepositoryError> {
-        let pack_dir = self.path.join(".git/objects/pack");
+        let pack_dir = self.objects_dir.join("pack");
 
         if !pack_dir.exists() {
             return Ok(HashMap::new());
@@ -329,7 +711,7 @@ impl Repository {
     /// per-pack stem, so it is not part of any [`PackGroup`].
     #[must_use]
     pub fn get_multi_pack_index(&self) -> Option<PathBuf> {
-        let midx_path = self.path.join(".git/objects/pack/multi-pack-index");
+        let midx_path = self.objects_dir.join("pack/multi-pack-index");
         midx_path.is_file().then_some(midx_path)
     }
 
@@ -340,7 +722,7 @@ impl Repository {
     /// [`PackGroup`].
     #[must_use]
     pub fn get_multi_pack_index_bitmap(&self) -> Option<PathBuf> {
-        let pack_dir = self.path.join(".git/objects/pack");
+        let pack_dir = self.objects_dir.join("pack");
         fs::read_dir(pack_dir).ok()?.flatten().find_map(|entry| {
             let path = entry.path();
             let name = entry.file_name();
@@ -350,79 +732,232 @@ impl Repository {
         })
     }
 
-    /// Lists all head refs (local branches) in the repository
+    /// Returns the path to the single-file commit-graph if the repository
+    /// has one
+    #[must_use]
+    pub fn get_commit_graph(&self) -> Option<PathBuf> {
+        let commit_graph_path = self.objects_dir.join("info/commit-graph");
+        commit_graph_path.is_file().then_some(commit_graph_path)
+    }
+
+    /// Lists the files of a split commit-graph grouped by chain order
+    ///
+    /// Returns `Ok(None)` when the repository has no commit-graph chain.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - File system operations fail when reading the refs/heads directory
-    pub fn list_head_refs(&self) -> Result<Vec<PathBuf>, RepositoryError> {
-        Self::list_refs_in_dir(self.path.join(".git/refs/heads"))
+    /// - File system operations fail when reading the commit-graphs directory
+    /// - The chain file is malformed
+    pub fn get_commit_graph_chain_group(
+        &self,
+    ) -> Result<Option<CommitGraphChainGroup>, RepositoryError> {
+        let dir = self
+            .objects_dir
+            .join("info")
+            .join(CommitGraphChain::DIR_NAME);
+        let chain_file = dir.join(CommitGraphChain::CHAIN_FILE_NAME);
+        if !chain_file.is_file() {
+            return Ok(None);
+        }
+
+        let data = fs::read(&chain_file)?;
+        let (_, hashes) = CommitGraphChain::parse_chain(&data)
+            .map_err(|e| CommitGraphChainError::InvalidChain(format!("{e:?}")))?;
+        let (layer_files, stale_layer_files) = Self::list_chain_layers(
+            &dir,
+            &hashes,
+            CommitGraphChain::layer_path,
+            CommitGraphChain::is_layer_file_name,
+        )?;
+
+        Ok(Some(CommitGraphChainGroup {
+            chain_file,
+            layer_files,
+            stale_layer_files,
+        }))
     }
 
-    /// Lists all remote refs grouped by remote name
+    /// Lists the layers of an incremental multi-pack-index grouped by chain
+    /// order
+    ///
+    /// Returns `Ok(None)` when the repository has no multi-pack-index chain.
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - File system operations fail when reading the refs/remotes directory
-    pub fn list_remote_refs(&self) -> Result<Vec<(String, Vec<PathBuf>)>, RepositoryError> {
-        let remotes_dir = self.path.join(".git/refs/remotes");
-        if !remotes_dir.exists() {
-            return Ok(Vec::new());
+    /// - File system operations fail when reading the multi-pack-index.d
+    ///   directory
+    /// - The chain file is malformed
+    pub fn get_multi_pack_index_chain_group(
+        &self,
+    ) -> Result<Option<MultiPackIndexChainGroup>, RepositoryError> {
+        let dir = self
+            .objects_dir
+            .join("pack")
+            .join(MultiPackIndexChain::DIR_NAME);
+        let chain_file = dir.join(MultiPackIndexChain::CHAIN_FILE_NAME);
+        if !chain_file.is_file() {
+            return Ok(None);
         }
 
-        let mut remotes = Vec::new();
-        for entry in fs::read_dir(remotes_dir)? {
-            let entry = entry?;
-            if entry.path().is_dir() {
-                let remote_name = entry.file_name().to_string_lossy().to_string();
+        let data = fs::read(&chain_file)?;
+        let (_, hashes) = CommitGraphChain::parse_chain(&data)
+            .map_err(|e| MultiPackIndexChainError::InvalidChain(format!("{e:?}")))?;
+        let (layer_files, stale_layer_files) = Self::list_chain_layers(
+            &dir,
+            &hashes,
+            MultiPackIndexChain::layer_path,
+            MultiPackIndexChain::is_layer_file_name,
+        )?;
+
+        Ok(Some(MultiPackIndexChainGroup {
+            chain_file,
+            layer_files,
+            stale_layer_files,
+        }))
+    }
 
-                let remote_refs = Self::list_refs_in_dir(entry.path())?;
-                remotes.push((remote_name, remote_refs));
+    /// Layer files of a chain directory: those the chain lists, in chain
+    /// order, and the stale ones it doesn't
+    fn list_chain_layers(
+        dir: &Path,
+        hashes: &[String],
+        layer_path: fn(&Path, &str) -> PathBuf,
+        is_layer_file_name: fn(&str) -> bool,
+    ) -> Result<(Vec<PathBuf>, Vec<PathBuf>), RepositoryError> {
+        let layer_files: Vec<PathBuf> = hashes
+            .iter()
+            .map(|hash| layer_path(dir, hash))
+            .filter(|path| path.is_file())
+            .collect();
+
+        let mut stale_layer_files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_layer = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(is_layer_file_name);
+            if is_layer && !layer_files.contains(&path) {
+                stale_layer_files.push(path);
             }
         }
+        stale_layer_files.sort();
 
-        Ok(remotes)
+        Ok((layer_files, stale_layer_files))
     }
 
-    /// Lists all tag refs in the repository
+    /// Lists the linked worktrees recorded in the common directory
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - File system operations fail when reading the refs/tags directory
-    pub fn list_tag_refs(&self) -> Result<Vec<PathBuf>, RepositoryError> {
-        Self::list_refs_in_dir(self.path.join(".git/refs/tags"))
+    /// - The worktrees directory exists but cannot be read
+    pub fn list_worktrees(&self) -> Result<Vec<Worktree>, RepositoryError> {
+        Ok(Worktree::list(&self.common_dir)?)
     }
 
-    /// Checks if stash ref exists
+    /// Lists the alternate object directories the repository borrows
+    /// objects from, following their own alternates
     ///
     /// # Errors
     ///
     /// This function will return an error if:
-    /// - File system operations fail when checking for stash refs
-    pub fn has_stash_ref(&self) -> Result<bool, RepositoryError> {
-        let stash_path = self.path.join(".git/refs/stash");
-        Ok(stash_path.exists())
+    /// - An alternates file exists but cannot be read
+    pub fn list_alternates(&self) -> Result<Vec<Alternate>, RepositoryError> {
+        Ok(Alternate::list(&self.objects_dir)?)
     }
 
-    /// Helper method to list refs in a directory
-    fn list_refs_in_dir(dir_path: PathBuf) -> Result<Vec<PathBuf>, RepositoryError> {
-        if !dir_path.exists() {
-            return Ok(Vec::new());
-        }
+    /// Lists the submodules whose git directories are kept in the common
+    /// directory, with their paths and URLs from `.gitmodules`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The modules directory exists but cannot be read
+    pub fn list_submodules(&self) -> Result<Vec<Submodule>, RepositoryError> {
+        Ok(Submodule::list(
+            &self.common_dir,
+            self.work_tree.as_deref(),
+        )?)
+    }
 
-        let mut refs = Vec::new();
-        for entry in fs::read_dir(dir_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                refs.push(path);
-            }
+    /// Lists the submodules of the repository and, recursively, those of
+    /// its submodules
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - A modules directory exists but cannot be read
+    pub fn list_all_submodules(&self) -> Result<Vec<Submodule>, RepositoryError> {
+        Ok(Submodule::list_all(
+            &self.common_dir,
+            self.work_tree.as_deref(),
+        )?)
+    }
+
+    /// Resolves the gitlinks of a tree object in the submodules checked out
+    /// at their paths
+    ///
+    /// A tree doesn't record where it sits, so gitlinks are only followed
+    /// when the tree is the directory of HEAD's tree holding them. Objects
+    /// are read through the databases in `databases`.
+    #[must_use]
+    pub fn resolve_gitlinks(
+        &self,
+        object: &LooseObject,
+        databases: &ObjectDatabases,
+    ) -> Vec<GitlinkTarget> {
+        let submodules = self.list_submodules().unwrap_or_default();
+        let database = databases.for_repository(self);
+        let tree_dir = self
+            .head_tree(&database)
+            .zip(hex::decode(&object.object_id).ok())
+            .and_then(|(head_tree, tree_oid)| {
+                GitlinkTarget::tree_dir(&database, &head_tree, &tree_oid, &submodules)
+            });
+        GitlinkTarget::for_object(object, tree_dir.as_deref(), &submodules, databases)
+    }
+
+    /// ID of the tree of the commit HEAD points at
+    fn head_tree(&self, database: &ObjectDatabase) -> Option<Vec<u8>> {
+        let head = self.load_ref_store().ok()?.resolve("HEAD");
+        let commit = database.read(&hex::decode(head.object_id()?).ok()?).ok()?;
+        match commit.parsed_content? {
+            ParsedContent::Commit(commit) => hex::decode(commit.tree).ok(),
+            _ => None,
         }
+    }
 
-        Ok(refs)
+    /// Reads every ref of the repository, merging loose ref files over
+    /// `packed-refs`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - File system operations fail when walking the refs directory
+    /// - The packed-refs file cannot be read or parsed
+    pub fn load_ref_store(&self) -> Result<RefStore, RepositoryError> {
+        Ok(RefStore::load_with(&self.git_dir, &self.common_dir)?)
+    }
+
+    /// Reads the reflog of a ref, given as a full name or a short one such
+    /// as `main` or `origin/main`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - No ref by that name has a reflog
+    /// - The log file cannot be read or parsed
+    pub fn load_reflog(&self, name: &str) -> Result<Reflog, RepositoryError> {
+        let ref_name = Reflog::find_ref_with(&self.git_dir, &self.common_dir, name)
+            .ok_or_else(|| RefStoreError::NoReflog(name.to_string()))?;
+        Ok(Reflog::load_with(
+            &self.git_dir,
+            &self.common_dir,
+            &ref_name,
+        )?)
     }
 
     /// Lists a sample of loose objects in the repository
@@ -433,7 +968,7 @@ impl Repository {
     /// This function will return an error if:
     /// - File system operations fail when reading loose object directories
     pub fn list_loose_objects(&self, limit: usize) -> Result<Vec<PathBuf>, RepositoryError> {
-        let objects_dir = self.path.join(".git/objects");
+        let objects_dir = &self.objects_dir;
         if !objects_dir.exists() {
             return Ok(Vec::new());
         }
@@ -441,7 +976,7 @@ impl Repository {
         let mut loose_objects = Vec::new();
         let mut count = 0;
 
-        for entry in fs::read_dir(&objects_dir)? {
+        for entry in fs::read_dir(objects_dir)? {
             let entry = entry?;
             let dir_name = entry.file_name().to_string_lossy().to_string();
 
@@ -476,26 +1011,6 @@ impl Repository {
         Ok(LooseObject::read_from_path(path)?)
     }
 
-    /// Reads and parses a loose object by its hash
-    ///
-    /// # Errors
-    ///
-    /// This function will return an error if:
-    /// - The object file cannot be found or read
-    /// - The object cannot be decompressed or parsed
-    pub fn read_loose_object_by_hash(&self, hash: &str) -> Result<LooseObject, RepositoryError> {
-        if hash.len() != 40 {
-            return Err(RepositoryError::LooseObjectError(
-                LooseObjectError::InvalidFormat("Hash must be 40 characters".to_string()),
-            ));
-        }
-
-        let (dir, file) = hash.split_at(2);
-        let path = self.path.join(".git/objects").join(dir).join(file);
-
-        self.read_loose_object(&path)
-    }
-
     /// List parsed loose objects with a limit
     ///
     /// # Errors
@@ -529,12 +1044,12 @@ impl Repository {
     /// Check if a loose object exists by its hash
     #[must_use]
     pub fn loose_object_exists(&self, hash: &str) -> bool {
-        if hash.len() != 40 {
+        if hash.len() != self.object_format.hex_len() {
             return false;
         }
 
         let (dir, file) = hash.split_at(2);
-        let path = self.path.join(".git/objects").join(dir).join(file);
+        let path = self.objects_dir.join(dir).join(file);
 
         path.exists() && path.is_file()
     }
@@ -547,14 +1062,14 @@ impl Repository {
     /// - File system operations fail when reading loose object directories
     /// - Objects cannot be parsed or analyzed
     pub fn get_loose_object_stats(&self) -> Result<LooseObjectStats, RepositoryError> {
-        let objects_dir = self.path.join(".git/objects");
+        let objects_dir = &self.objects_dir;
         if !objects_dir.exists() {
             return Ok(LooseObjectStats::default());
         }
 
         let mut stats = LooseObjectStats::default();
 
-        for entry in fs::read_dir(&objects_dir)? {
+        for entry in fs::read_dir(objects_dir)? {
             let entry = entry?;
             let dir_name = entry.file_name().to_string_lossy().to_string();
 
@@ -613,4 +1128,159 @@ mod tests {
         assert_eq!(pack_files.len(), 2);
         assert!(pack_files.iter().all(|p| p.extension().unwrap() == "pack"));
     }
+
+    /// Create the minimal layout of a git directory
+    fn init_git_dir(git_dir: &Path) {
+        fs::create_dir_all(git_dir.join("objects/pack")).unwrap();
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+    }
+
+    #[test]
+    fn test_discover_from_subdirectory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        init_git_dir(&temp_dir.path().join(".git"));
+        let subdir = temp_dir.path().join("src/nested");
+        fs::create_dir_all(&subdir).unwrap();
+
+        let repo = Repository::discover_with(&subdir, &DiscoveryEnv::default()).unwrap();
+        assert_eq!(repo.get_path(), temp_dir.path());
+        assert_eq!(repo.git_dir(), temp_dir.path().join(".git"));
+        assert_eq!(repo.common_dir(), repo.git_dir());
+        assert_eq!(repo.objects_dir(), temp_dir.path().join(".git/objects"));
+        assert!(!repo.is_bare());
+    }
+
+    #[test]
+    fn test_discover_bare() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bare = temp_dir.path().join("project.git");
+        init_git_dir(&bare);
+
+        let repo = Repository::discover_with(&bare, &DiscoveryEnv::default()).unwrap();
+        assert!(repo.is_bare());
+        assert_eq!(repo.get_path(), bare);
+        assert_eq!(repo.git_dir(), bare);
+        assert_eq!(repo.objects_dir(), bare.join("objects"));
+    }
+
+    #[test]
+    fn test_discover_gitfile_with_commondir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let main_git_dir = temp_dir.path().join("main/.git");
+        init_git_dir(&main_git_dir);
+        fs::write(main_git_dir.join("objects/pack/pack-1.pack"), b"").unwrap();
+
+        // A linked worktree: its .git file points into the main repository,
+        // whose commondir file points back to the shared directory
+        let worktree_git_dir = main_git_dir.join("worktrees/feature");
+        fs::create_dir_all(&worktree_git_dir).unwrap();
+        fs::write(worktree_git_dir.join("HEAD"), "ref: refs/heads/feature\n").unwrap();
+        fs::write(worktree_git_dir.join("commondir"), "../..\n").unwrap();
+        let worktree = temp_dir.path().join("feature");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(
+            worktree.join(".git"),
+            "gitdir: ../main/.git/worktrees/feature\n",
+        )
+        .unwrap();
+
+        let repo = Repository::discover_with(&worktree, &DiscoveryEnv::default()).unwrap();
+        assert_eq!(repo.work_tree(), Some(worktree.as_path()));
+        assert_eq!(
+            repo.git_dir(),
+            worktree.join("../main/.git/worktrees/feature")
+        );
+        assert_eq!(
+            repo.common_dir().canonicalize().unwrap(),
+            main_git_dir.canonicalize().unwrap()
+        );
+        assert_eq!(repo.list_pack_files().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_discover_invalid_gitfile() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join(".git"), "gitdir: missing\n").unwrap();
+        assert!(matches!(
+            Repository::discover_with(temp_dir.path(), &DiscoveryEnv::default()),
+            Err(RepositoryError::NotGitRepository(_))
+        ));
+
+        fs::write(temp_dir.path().join(".git"), "not a gitfile\n").unwrap();
+        assert!(matches!(
+            Repository::discover_with(temp_dir.path(), &DiscoveryEnv::default()),
+            Err(RepositoryError::NotGitRepository(_))
+        ));
+    }
+
+    #[test]
+    fn test_discover_with_environment() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join("elsewhere");
+        init_git_dir(&git_dir);
+        let objects = temp_dir.path().join("objects");
+        fs::create_dir_all(objects.join("pack")).unwrap();
+        let work_tree = temp_dir.path().join("work");
+        fs::create_dir_all(&work_tree).unwrap();
+
+        let env = DiscoveryEnv {
+            git_dir: Some(git_dir.clone()),
+            common_dir: None,
+            object_directory: Some(objects.clone()),
+        };
+        let repo = Repository::discover_with(&work_tree, &env).unwrap();
+        assert_eq!(repo.git_dir(), git_dir);
+        assert_eq!(repo.work_tree(), Some(work_tree.as_path()));
+        assert_eq!(repo.common_dir(), git_dir);
+        assert_eq!(repo.objects_dir(), objects);
+
+        let env = DiscoveryEnv {
+            git_dir: Some(temp_dir.path().join("missing")),
+            ..DiscoveryEnv::default()
+        };
+        assert!(Repository::discover_with(&work_tree, &env).is_err());
+    }
+
+    #[test]
+    fn test_refs_and_reflogs_in_environment_common_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let common_dir = temp_dir.path().join("main/.git");
+        init_git_dir(&common_dir);
+        fs::write(
+            common_dir.join("refs/heads/feature"),
+            format!("{}\n", "1".repeat(40)),
+        )
+        .unwrap();
+        let log_path = Reflog::log_path(&common_dir, "refs/heads/feature");
+        fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+        fs::write(
+            &log_path,
+            format!(
+                "{} {} A <a@b> 1700000000 +0000\tbranch: Created\n",
+                "0".repeat(40),
+                "1".repeat(40)
+            ),
+        )
+        .unwrap();
+
+        // A worktree's git directory without a commondir file
+        let git_dir = temp_dir.path().join("feature-admin");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/feature\n").unwrap();
+
+        let env = DiscoveryEnv {
+            git_dir: Some(git_dir.clone()),
+            common_dir: Some(common_dir.clone()),
+            object_directory: None,
+        };
+        let repo = Repository::discover_with(temp_dir.path(), &env).unwrap();
+        assert_eq!(repo.common_dir(), common_dir);
+
+        let head = repo.load_ref_store().unwrap().resolve("HEAD");
+        assert_eq!(head.object_id(), Some("1".repeat(40).as_str()));
+        let reflog = repo.load_reflog("feature").unwrap();
+        assert_eq!(reflog.ref_name, "refs/heads/feature");
+        assert_eq!(reflog.entries.len(), 1);
+    }
 }
@@ -0,0 +1,469 @@
+//! Submodules: the nested repositories under `modules/` in the common
+//! directory.
+//!
+//! A submodule's work tree has a `.git` file pointing at
+//! `.git/modules/<name>`, a complete git directory of its own, so the
+//! superproject keeps every module's objects and refs. The superproject's
+//! trees record each submodule as a gitlink: an entry with mode 160000
+//! naming a commit of the nested repository.
+
+use crate::git::loose_object::{LooseObject, ParsedContent, TreeEntryType};
+use crate::git::object_database::{ObjectDatabase, ObjectDatabases};
+use crate::git::repository::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SubmoduleError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// One nested repository of the superproject
+#[derive(Debug, Clone)]
+pub struct Submodule {
+    /// Name of the submodule, its directory under `modules/`
+    pub name: String,
+    pub git_dir: PathBuf,
+    /// Path in the superproject's work tree, from `.gitmodules`
+    pub path: Option<String>,
+    /// Where the submodule is cloned from, from `.gitmodules`
+    pub url: Option<String>,
+}
+
+impl Submodule {
+    /// Directory of the common directory holding the submodules
+    pub const DIR_NAME: &str = "modules";
+    /// File of the superproject's work tree describing its submodules
+    pub const GITMODULES: &str = ".gitmodules";
+
+    /// Every submodule kept in `common_dir`, by name, with its path and URL
+    /// from the `.gitmodules` of `work_tree` when there is one
+    ///
+    /// Names may contain slashes, so directories under `modules/` that are
+    /// not git directories are searched for nested ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `modules` directory exists but can't be
+    /// read.
+    pub fn list(common_dir: &Path, work_tree: Option<&Path>) -> Result<Vec<Self>, SubmoduleError> {
+        let dir = common_dir.join(Self::DIR_NAME);
+        let mut git_dirs = Vec::new();
+        if dir.is_dir() {
+            Self::collect_git_dirs(&dir, "", &mut git_dirs)?;
+        }
+
+        let gitmodules = work_tree
+            .and_then(|work_tree| fs::read_to_string(work_tree.join(Self::GITMODULES)).ok())
+            .map(|content| Self::parse_gitmodules(&content))
+            .unwrap_or_default();
+
+        let mut submodules: Vec<Self> = git_dirs
+            .into_iter()
+            .map(|(name, git_dir)| {
+                let entry = gitmodules.iter().find(|entry| entry.name == name);
+                Self {
+                    path: entry.and_then(|entry| entry.path.clone()),
+                    url: entry.and_then(|entry| entry.url.clone()),
+                    name,
+                    git_dir,
+                }
+            })
+            .collect();
+        submodules.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(submodules)
+    }
+
+    /// Every submodule of the superproject and, recursively, of the
+    /// submodules themselves
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a `modules` directory exists but can't be read.
+    pub fn list_all(
+        common_dir: &Path,
+        work_tree: Option<&Path>,
+    ) -> Result<Vec<Self>, SubmoduleError> {
+        let mut submodules = Self::list(common_dir, work_tree)?;
+        let mut index = 0;
+        while index < submodules.len() {
+            let nested = Self::list(&submodules[index].git_dir, None)?;
+            submodules.extend(nested);
+            index += 1;
+        }
+        Ok(submodules)
+    }
+
+    /// Git directories under `dir`, with their names relative to
+    /// `modules/`
+    fn collect_git_dirs(
+        dir: &Path,
+        prefix: &str,
+        git_dirs: &mut Vec<(String, PathBuf)>,
+    ) -> Result<(), SubmoduleError> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let name = if prefix.is_empty() {
+                file_name.to_string()
+            } else {
+                format!("{prefix}/{file_name}")
+            };
+            if Repository::is_git_dir(&path) {
+                git_dirs.push((name, path));
+            } else {
+                Self::collect_git_dirs(&path, &name, git_dirs)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The `[submodule "<name>"]` sections of a `.gitmodules` file
+    ///
+    /// Only `path` and `url` are kept; quoting and escapes beyond the
+    /// section name's quotes are not handled.
+    #[must_use]
+    pub fn parse_gitmodules(content: &str) -> Vec<Self> {
+        let mut submodules: Vec<Self> = Vec::new();
+        // Whether the keys being read belong to the last submodule
+        let mut in_submodule = false;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = section
+                    .trim()
+                    .strip_prefix("submodule")
+                    .map(str::trim)
+                    .and_then(|name| name.strip_prefix('"'))
+                    .and_then(|name| name.strip_suffix('"'));
+                in_submodule = name.is_some();
+                if let Some(name) = name {
+                    submodules.push(Self {
+                        name: name.to_string(),
+                        git_dir: PathBuf::new(),
+                        path: None,
+                        url: None,
+                    });
+                }
+                continue;
+            }
+            let (true, Some(submodule), Some((key, value))) =
+                (in_submodule, submodules.last_mut(), line.split_once('='))
+            else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"').to_string();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "path" => submodule.path = Some(value),
+                "url" => submodule.url = Some(value),
+                _ => {}
+            }
+        }
+        submodules
+    }
+
+    /// Read an object of the nested repository, through its database in
+    /// `databases`
+    #[must_use]
+    pub fn read_object(&self, oid: &[u8], databases: &ObjectDatabases) -> Option<LooseObject> {
+        databases.for_git_dir(&self.git_dir).read(oid).ok()
+    }
+}
+
+/// Where a gitlink's commit lives
+#[derive(Debug, Clone)]
+pub struct GitlinkTarget {
+    /// Name of the tree entry
+    pub entry_name: String,
+    /// Full path of the entry in the superproject, when the tree's
+    /// directory is known
+    pub path: Option<String>,
+    /// Hex ID of the commit the gitlink records
+    pub oid: String,
+    /// The submodule holding the commit
+    pub submodule: Option<Submodule>,
+    pub commit: Option<LooseObject>,
+}
+
+impl GitlinkTarget {
+    /// Find the commit of a gitlink in the submodule checked out at `path`,
+    /// the gitlink's full path in the superproject's work tree
+    #[must_use]
+    pub fn resolve(
+        path: &str,
+        oid: &str,
+        submodules: &[Submodule],
+        databases: &ObjectDatabases,
+    ) -> Self {
+        let submodule = submodules
+            .iter()
+            .find(|submodule| submodule.path.as_deref() == Some(path));
+        let commit = submodule
+            .zip(hex::decode(oid).ok())
+            .and_then(|(submodule, binary_oid)| submodule.read_object(&binary_oid, databases));
+
+        Self {
+            entry_name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: Some(path.to_string()),
+            oid: oid.to_string(),
+            submodule: submodule.cloned(),
+            commit,
+        }
+    }
+
+    /// Resolve every gitlink of a tree object, in entry order
+    ///
+    /// `tree_dir` is the directory of the superproject the tree records,
+    /// empty for the root; when it is unknown the gitlinks are listed
+    /// without their submodules.
+    #[must_use]
+    pub fn for_object(
+        object: &LooseObject,
+        tree_dir: Option<&str>,
+        submodules: &[Submodule],
+        databases: &ObjectDatabases,
+    ) -> Vec<Self> {
+        let Some(ParsedContent::Tree(tree)) = object.get_parsed_content() else {
+            return Vec::new();
+        };
+        tree.entries
+            .iter()
+            .filter(|entry| entry.object_type == TreeEntryType::Submodule)
+            .map(|entry| match tree_dir {
+                Some("") => Self::resolve(&entry.name, &entry.sha1, submodules, databases),
+                Some(dir) => Self::resolve(
+                    &format!("{dir}/{}", entry.name),
+                    &entry.sha1,
+                    submodules,
+                    databases,
+                ),
+                None => Self {
+                    entry_name: entry.name.clone(),
+                    path: None,
+                    oid: entry.sha1.clone(),
+                    submodule: None,
+                    commit: None,
+                },
+            })
+            .collect()
+    }
+
+    /// The directory of `head_tree` that `tree_oid` records, among those
+    /// holding a submodule, empty for the root itself
+    ///
+    /// Gitlinks only name submodules from the directory they are checked
+    /// out in, so only those directories are walked.
+    #[must_use]
+    pub fn tree_dir(
+        database: &ObjectDatabase,
+        head_tree: &[u8],
+        tree_oid: &[u8],
+        submodules: &[Submodule],
+    ) -> Option<String> {
+        let mut dirs: Vec<&str> = submodules
+            .iter()
+            .filter_map(|submodule| submodule.path.as_deref())
+            .map(|path| path.rsplit_once('/').map_or("", |(dir, _)| dir))
+            .collect();
+        dirs.sort_unstable();
+        dirs.dedup();
+        dirs.into_iter()
+            .find(|dir| {
+                Self::lookup_dir(database, head_tree, dir).is_some_and(|oid| oid == tree_oid)
+            })
+            .map(str::to_string)
+    }
+
+    /// ID of the tree at `dir` under the tree `root`
+    fn lookup_dir(database: &ObjectDatabase, root: &[u8], dir: &str) -> Option<Vec<u8>> {
+        let mut oid = root.to_vec();
+        for component in dir.split('/').filter(|component| !component.is_empty()) {
+            let object = database.read(&oid).ok()?;
+            let Some(ParsedContent::Tree(tree)) = object.parsed_content else {
+                return None;
+            };
+            let entry = tree.entries.into_iter().find(|entry| {
+                entry.name == component && entry.object_type == TreeEntryType::Tree
+            })?;
+            oid = hex::decode(entry.sha1).ok()?;
+        }
+        Some(oid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::loose_object::LooseObjectType;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use sha1::{Digest, Sha1};
+    use std::io::Write;
+
+    /// Store a loose object and return its hex ID
+    fn write_loose_object(git_dir: &Path, kind: &str, content: &[u8]) -> String {
+        let mut raw = format!("{kind} {}\0", content.len()).into_bytes();
+        raw.extend_from_slice(content);
+        let oid = hex::encode(Sha1::digest(&raw));
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw).unwrap();
+        let path = git_dir.join("objects").join(&oid[..2]).join(&oid[2..]);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, encoder.finish().unwrap()).unwrap();
+        oid
+    }
+
+    fn init_git_dir(git_dir: &Path) {
+        fs::create_dir_all(git_dir.join("objects")).unwrap();
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+    }
+
+    #[test]
+    fn test_parse_gitmodules() {
+        let submodules = Submodule::parse_gitmodules(
+            "[submodule \"lib/core\"]\n\tpath = vendor/core\n\turl = https://example.com/core.git\n\
+             # a comment\n[core]\n\tpath = ignored\n[submodule \"docs\"]\n\tpath = docs\n",
+        );
+        assert_eq!(submodules.len(), 2);
+        assert_eq!(submodules[0].name, "lib/core");
+        assert_eq!(submodules[0].path.as_deref(), Some("vendor/core"));
+        assert_eq!(
+            submodules[0].url.as_deref(),
+            Some("https://example.com/core.git")
+        );
+        assert_eq!(submodules[1].name, "docs");
+        assert_eq!(submodules[1].url, None);
+    }
+
+    #[test]
+    fn test_list_and_resolve_gitlinks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let common_dir = temp_dir.path().join(".git");
+        let core_dir = common_dir.join("modules/lib/core");
+        let docs_dir = common_dir.join("modules/docs");
+        init_git_dir(&core_dir);
+        init_git_dir(&docs_dir);
+        fs::write(
+            temp_dir.path().join(Submodule::GITMODULES),
+            "[submodule \"lib/core\"]\n\tpath = vendor/core\n",
+        )
+        .unwrap();
+
+        let submodules = Submodule::list(&common_dir, Some(temp_dir.path())).unwrap();
+        let databases = ObjectDatabases::default();
+        let names: Vec<_> = submodules.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["docs", "lib/core"]);
+        assert_eq!(submodules[1].path.as_deref(), Some("vendor/core"));
+        assert_eq!(submodules[0].path, None);
+
+        let commit = write_loose_object(
+            &core_dir,
+            "commit",
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\nInitial\n",
+        );
+        let target = GitlinkTarget::resolve("vendor/core", &commit, &submodules, &databases);
+        assert_eq!(target.entry_name, "core");
+        assert_eq!(
+            target.submodule.map(|submodule| submodule.name).as_deref(),
+            Some("lib/core")
+        );
+        assert_eq!(
+            target.commit.map(|commit| commit.object_type),
+            Some(LooseObjectType::Commit)
+        );
+
+        // Only the exact path names a submodule
+        let target = GitlinkTarget::resolve("core", &commit, &submodules, &databases);
+        assert!(target.submodule.is_none() && target.commit.is_none());
+        let target = GitlinkTarget::resolve("other/vendor/core", &commit, &submodules, &databases);
+        assert!(target.submodule.is_none());
+
+        // Other submodules are not searched for a missing commit
+        let target =
+            GitlinkTarget::resolve("vendor/core", &"ab".repeat(20), &submodules, &databases);
+        assert_eq!(
+            target.submodule.map(|submodule| submodule.git_dir),
+            Some(core_dir)
+        );
+        assert!(target.commit.is_none());
+    }
+
+    #[test]
+    fn test_gitlinks_of_head_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let common_dir = temp_dir.path().join(".git");
+        let core_dir = common_dir.join("modules/lib/core");
+        init_git_dir(&common_dir);
+        init_git_dir(&core_dir);
+        fs::write(
+            temp_dir.path().join(Submodule::GITMODULES),
+            "[submodule \"lib/core\"]\n\tpath = vendor/core\n",
+        )
+        .unwrap();
+        let submodules = Submodule::list(&common_dir, Some(temp_dir.path())).unwrap();
+
+        let commit = write_loose_object(
+            &core_dir,
+            "commit",
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\nInitial\n",
+        );
+        let mut vendor = b"160000 core\0".to_vec();
+        vendor.extend_from_slice(&hex::decode(&commit).unwrap());
+        let vendor_oid = write_loose_object(&common_dir, "tree", &vendor);
+        let mut root = b"40000 vendor\0".to_vec();
+        root.extend_from_slice(&hex::decode(&vendor_oid).unwrap());
+        let root_oid = write_loose_object(&common_dir, "tree", &root);
+
+        let databases = ObjectDatabases::default();
+        let database = databases.for_git_dir(&common_dir);
+        let [root_oid, vendor_oid] = [root_oid, vendor_oid].map(|oid| hex::decode(oid).unwrap());
+        assert_eq!(
+            GitlinkTarget::tree_dir(&database, &root_oid, &vendor_oid, &submodules).as_deref(),
+            Some("vendor")
+        );
+        assert_eq!(
+            GitlinkTarget::tree_dir(&database, &root_oid, &root_oid, &submodules),
+            None
+        );
+
+        let vendor_tree = database.read(&vendor_oid).unwrap();
+        let gitlinks =
+            GitlinkTarget::for_object(&vendor_tree, Some("vendor"), &submodules, &databases);
+        assert_eq!(gitlinks.len(), 1);
+        assert_eq!(gitlinks[0].path.as_deref(), Some("vendor/core"));
+        assert!(gitlinks[0].commit.is_some());
+
+        let gitlinks = GitlinkTarget::for_object(&vendor_tree, None, &submodules, &databases);
+        assert_eq!(gitlinks[0].entry_name, "core");
+        assert!(gitlinks[0].submodule.is_none() && gitlinks[0].path.is_none());
+    }
+
+    #[test]
+    fn test_list_all_includes_nested_submodules() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let common_dir = temp_dir.path().join(".git");
+        init_git_dir(&common_dir.join("modules/outer"));
+        init_git_dir(&common_dir.join("modules/outer/modules/inner"));
+
+        let names = |submodules: Vec<Submodule>| -> Vec<String> {
+            submodules.into_iter().map(|s| s.name).collect()
+        };
+        assert_eq!(
+            names(Submodule::list(&common_dir, None).unwrap()),
+            ["outer"]
+        );
+        assert_eq!(
+            names(Submodule::list_all(&common_dir, None).unwrap()),
+            ["outer", "inner"]
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Linked worktrees: the `worktrees/<name>/` directories of a repository.
+//!
+//! `git worktree add` checks a branch out in another directory whose `.git`
+//! is a file pointing back at `worktrees/<name>/` in the main repository.
+//! That directory holds what each worktree has for itself (HEAD, index,
+//! per-worktree refs and logs), plus `gitdir` and `commondir` files tying
+//! it to the checkout and to the shared repository.
+
+use crate::git::index::GitIndex;
+use crate::git::object_database::ObjectDatabase;
+use crate::git::refs::{RefStore, RefStoreError, RefTarget, Resolution};
+use crate::git::repository::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WorktreeError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Not a worktree directory: {0}")]
+    NotAWorktree(String),
+
+    #[error("Ref error: {0}")]
+    RefStoreError(#[from] RefStoreError),
+}
+
+/// One linked worktree, as recorded in the main repository
+#[derive(Debug, Clone)]
+pub struct Worktree {
+    /// Name of the directory under `worktrees/`
+    pub name: String,
+    /// The worktree's git directory, `worktrees/<name>`
+    pub admin_dir: PathBuf,
+    /// Where the worktree is checked out, from the `gitdir` file
+    pub checkout: Option<PathBuf>,
+    /// Content of the worktree's HEAD
+    pub head: Option<RefTarget>,
+    /// Reason given to `git worktree lock`, empty when none was given
+    pub locked: Option<String>,
+    /// Why `git worktree prune` would remove the worktree
+    pub prunable: Option<String>,
+}
+
+impl Worktree {
+    /// Directory of the common directory holding the linked worktrees
+    pub const DIR_NAME: &str = "worktrees";
+
+    /// Read the worktree whose git directory is `admin_dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `admin_dir` is not a directory.
+    pub fn load(admin_dir: &Path) -> Result<Self, WorktreeError> {
+        if !admin_dir.is_dir() {
+            return Err(WorktreeError::NotAWorktree(admin_dir.display().to_string()));
+        }
+
+        let gitdir_path = admin_dir.join("gitdir");
+        let gitdir = fs::read_to_string(&gitdir_path).ok().map(|content| {
+            let target = content
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            admin_dir.join(target)
+        });
+        let locked = fs::read_to_string(admin_dir.join("locked"))
+            .ok()
+            .map(|reason| reason.trim().to_string());
+        // A locked worktree is never pruned, even if its checkout is gone
+        let prunable = if locked.is_some() {
+            None
+        } else {
+            match &gitdir {
+                None if gitdir_path.exists() => Some("unable to read gitdir file".to_string()),
+                None => Some("gitdir file does not exist".to_string()),
+                Some(path) if path == admin_dir => Some("invalid gitdir file".to_string()),
+                Some(path) if !path.exists() => {
+                    Some("gitdir file points to non-existent location".to_string())
+                }
+                Some(_) => None,
+            }
+        };
+
+        Ok(Self {
+            name: admin_dir
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            admin_dir: admin_dir.to_path_buf(),
+            // gitdir names the checkout's .git file
+            checkout: gitdir.and_then(|path| path.parent().map(Path::to_path_buf)),
+            head: fs::read_to_string(admin_dir.join("HEAD"))
+                .ok()
+                .map(|content| RefTarget::parse(&content)),
+            locked,
+            prunable,
+        })
+    }
+
+    /// Every linked worktree of the repository whose common directory is
+    /// `common_dir`, by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `worktrees` directory exists but can't be
+    /// read.
+    pub fn list(common_dir: &Path) -> Result<Vec<Self>, WorktreeError> {
+        let dir = common_dir.join(Self::DIR_NAME);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut worktrees = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                worktrees.push(Self::load(&path)?);
+            }
+        }
+        worktrees.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(worktrees)
+    }
+
+    /// Whether a path is a worktree's git directory, `worktrees/<name>`
+    /// with a `gitdir` or `HEAD` file
+    #[must_use]
+    pub fn is_admin_dir(path: &Path) -> bool {
+        path.parent()
+            .and_then(Path::file_name)
+            .is_some_and(|name| name == Self::DIR_NAME)
+            && (path.join("gitdir").is_file() || path.join("HEAD").is_file())
+    }
+
+    /// Path of the worktree's index
+    #[must_use]
+    pub fn index_path(&self) -> PathBuf {
+        self.admin_dir.join("index")
+    }
+
+    /// The refs as seen from this worktree: its own HEAD and per-worktree
+    /// refs over the shared ones
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the refs can't be read.
+    pub fn load_ref_store(&self) -> Result<RefStore, WorktreeError> {
+        Ok(RefStore::load(&self.admin_dir)?)
+    }
+}
+
+/// A worktree with what it points at: its HEAD resolved through the ref
+/// store and its parsed index
+#[derive(Debug, Clone)]
+pub struct WorktreeDetails {
+    pub worktree: Worktree,
+    pub head: Option<Resolution>,
+    /// `None` when the worktree has no index yet
+    pub index: Option<Result<GitIndex, String>>,
+}
+
+impl WorktreeDetails {
+    /// Read the worktree whose git directory is `admin_dir`, resolve its
+    /// HEAD and parse its index, reading objects from `database`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `admin_dir` is not a directory or the refs
+    /// can't be read.
+    pub fn load(admin_dir: &Path, database: &ObjectDatabase) -> Result<Self, WorktreeError> {
+        let worktree = Worktree::load(admin_dir)?;
+        let head = worktree
+            .head
+            .is_some()
+            .then(|| worktree.load_ref_store())
+            .transpose()?
+            .map(|store| store.resolve("HEAD"));
+        let index_path = worktree.index_path();
+        let index = index_path.is_file().then(|| {
+            let data =
+                fs::read(&index_path).map_err(|e| format!("Error reading index file: {e}"))?;
+            let object_format = Repository::from_git_dir(&worktree.admin_dir).object_format();
+            let (_, mut index) = GitIndex::parse_with_format(&data, object_format)
+                .map_err(|e| format!("Error parsing index: {e:?}"))?;
+            index.resolve_repository_data(&worktree.admin_dir, database);
+            Ok(index)
+        });
+
+        Ok(Self {
+            worktree,
+            head,
+            index,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::ResolvedTarget;
+
+    const MAIN: &str = "1111111111111111111111111111111111111111";
+    const FEATURE: &str = "2222222222222222222222222222222222222222";
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// A main repository with one worktree per name, each checked out next
+    /// to it on a branch of the same name
+    fn repository(names: &[&str]) -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let git_dir = temp_dir.path().join("main/.git");
+        write(&git_dir.join("HEAD"), "ref: refs/heads/main\n");
+        write(&git_dir.join("refs/heads/main"), &format!("{MAIN}\n"));
+        write(&git_dir.join("refs/bisect/bad"), &format!("{MAIN}\n"));
+        write(
+            &git_dir.join("packed-refs"),
+            &names
+                .iter()
+                .map(|name| format!("{FEATURE} refs/heads/{name}\n"))
+                .collect::<String>(),
+        );
+
+        for name in names {
+            let admin_dir = git_dir.join("worktrees").join(name);
+            let checkout = temp_dir.path().join(name);
+            write(
+                &admin_dir.join("HEAD"),
+                &format!("ref: refs/heads/{name}\n"),
+            );
+            write(&admin_dir.join("commondir"), "../..\n");
+            write(
+                &admin_dir.join("gitdir"),
+                &format!("{}\n", checkout.join(".git").display()),
+            );
+            write(
+                &checkout.join(".git"),
+                &format!("gitdir: {}\n", admin_dir.display()),
+            );
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_list_worktrees() {
+        let temp_dir = repository(&["b-feature", "a-fix"]);
+        let common_dir = temp_dir.path().join("main/.git");
+        let worktrees = Worktree::list(&common_dir).unwrap();
+
+        let names: Vec<_> = worktrees.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["a-fix", "b-feature"]);
+        assert_eq!(
+            worktrees[0].checkout.as_deref(),
+            Some(temp_dir.path().join("a-fix").as_path())
+        );
+        assert_eq!(
+            worktrees[0].head,
+            Some(RefTarget::Symbolic("refs/heads/a-fix".to_string()))
+        );
+        assert!(worktrees[0].locked.is_none());
+        assert!(worktrees[0].prunable.is_none());
+        assert!(Worktree::is_admin_dir(&worktrees[0].admin_dir));
+        assert!(!Worktree::is_admin_dir(&common_dir));
+    }
+
+    #[test]
+    fn test_locked_and_prunable() {
+        let temp_dir = repository(&["gone", "kept"]);
+        fs::remove_dir_all(temp_dir.path().join("gone")).unwrap();
+        fs::remove_dir_all(temp_dir.path().join("kept")).unwrap();
+        let worktrees_dir = temp_dir.path().join("main/.git/worktrees");
+        fs::write(worktrees_dir.join("kept/locked"), "on a USB stick\n").unwrap();
+
+        let gone = Worktree::load(&worktrees_dir.join("gone")).unwrap();
+        assert_eq!(
+            gone.prunable.as_deref(),
+            Some("gitdir file points to non-existent location")
+        );
+        let kept = Worktree::load(&worktrees_dir.join("kept")).unwrap();
+        assert_eq!(kept.locked.as_deref(), Some("on a USB stick"));
+        assert!(kept.prunable.is_none());
+
+        fs::remove_file(worktrees_dir.join("gone/gitdir")).unwrap();
+        let gone = Worktree::load(&worktrees_dir.join("gone")).unwrap();
+        assert_eq!(gone.prunable.as_deref(), Some("gitdir file does not exist"));
+    }
+
+    #[test]
+    fn test_head_resolved_through_shared_refs() {
+        let temp_dir = repository(&["feature"]);
+        let admin_dir = temp_dir.path().join("main/.git/worktrees/feature");
+        let details = WorktreeDetails::load(&admin_dir, &ObjectDatabase::open(&admin_dir)).unwrap();
+
+        let head = details.head.unwrap();
+        assert_eq!(head.chain, ["HEAD", "refs/heads/feature"]);
+        assert_eq!(head.end, ResolvedTarget::Object(FEATURE.to_string()));
+        assert!(details.index.is_none());
+
+        // The main worktree's HEAD and bisect refs stay out of the
+        // worktree's view; shared refs point into the common directory
+        let store = details.worktree.load_ref_store().unwrap();
+        assert!(store.get("refs/bisect/bad").is_none());
+        assert!(store.get("refs/heads/main").is_some());
+        assert_eq!(store.loose_path("HEAD"), admin_dir.join("HEAD"));
+        assert_eq!(
+            store.loose_path("refs/heads/main"),
+            admin_dir.join("../..").join("refs/heads/main")
+        );
+    }
+}
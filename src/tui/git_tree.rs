@@ -1,8 +1,11 @@
 use crate::tui::model::{GitObject, GitObjectType};
 
 /// Build the complete .git directory file tree structure
+///
+/// Submodules' git directories get the same structure, so this recurses
+/// into each of them.
 pub fn build_git_file_tree(plumber: &crate::GitPlumber) -> Result<Vec<GitObject>, String> {
-    let git_path = plumber.get_repo_path().join(".git");
+    let git_path = plumber.get_git_dir();
     let mut git_contents = Vec::new();
 
     // Build objects directory with pack and loose objects
@@ -52,12 +55,176 @@ pub fn build_git_file_tree(plumber: &crate::GitPlumber) -> Result<Vec<GitObject>
         Err(e) => return Err(format!("Error reading .git directory: {e}")),
     }
 
+    // Linked worktrees each get a group labelled with their checkout and
+    // state, their HEAD resolved through the worktree's own ref store
+    if let Ok(worktrees) = plumber.list_worktrees()
+        && let Some(worktrees_folder) = git_contents.iter_mut().find(|child| {
+            child.name == crate::git::worktree::Worktree::DIR_NAME
+                && matches!(child.obj_type, GitObjectType::FileSystemFolder { .. })
+        })
+    {
+        label_worktrees_folder(worktrees_folder, &worktrees);
+    }
+
+    // Submodules are nested repositories: each gets a group with its own
+    // objects and refs instead of a plain listing of its git directory
+    if let Ok(submodules) = plumber.list_submodules()
+        && !submodules.is_empty()
+        && let Some(modules_folder) = git_contents.iter_mut().find(|child| {
+            child.name == crate::git::submodule::Submodule::DIR_NAME
+                && matches!(child.obj_type, GitObjectType::FileSystemFolder { .. })
+        })
+    {
+        modules_folder.children = submodules
+            .iter()
+            .map(build_submodule_folder)
+            .collect::<Result<_, _>>()?;
+    }
+
+    // Reftable repositories keep their reflogs in the tables, not in logs/
+    if !git_path.join("logs").exists()
+        && let Some(logs_folder) = build_reftable_logs_folder(&git_path)
+    {
+        git_contents.push(logs_folder);
+    }
+
     Ok(git_contents)
 }
 
-/// Build the objects directory with pack and loose object folders
+/// A logs folder listing the refs whose reflog lives in the reftable stack
+///
+/// Entries point at where the loose reflog would be, `logs/<ref>`, so they
+/// load like one: the reflog loader falls back to the stack.
+fn build_reftable_logs_folder(git_path: &std::path::Path) -> Option<GitObject> {
+    use crate::git::refs::ReftableStack;
+
+    if !ReftableStack::exists(git_path) {
+        return None;
+    }
+    let stack = ReftableStack::load(git_path).ok()?;
+    let ref_names = stack.log_ref_names();
+    if ref_names.is_empty() {
+        return None;
+    }
+
+    let logs_path = git_path.join("logs");
+    let mut logs_folder = GitObject::new_filesystem_folder(logs_path.clone(), false);
+    for ref_name in ref_names {
+        let mut file = GitObject::new_filesystem_file(logs_path.join(&ref_name));
+        file.name = ref_name;
+        logs_folder.add_child(file);
+    }
+    // Mark as loaded since the stack populates it
+    if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut logs_folder.obj_type {
+        *is_loaded = true;
+    }
+    Some(logs_folder)
+}
+
+/// A group for one submodule, named after it and holding the contents of
+/// its git directory
+fn build_submodule_folder(
+    submodule: &crate::git::submodule::Submodule,
+) -> Result<GitObject, String> {
+    let plumber = crate::GitPlumber::for_git_dir(&submodule.git_dir);
+    let mut folder = GitObject::new_filesystem_folder(submodule.git_dir.clone(), false);
+    folder.name = match &submodule.path {
+        Some(path) if *path != submodule.name => format!("{} → {path}", submodule.name),
+        _ => submodule.name.clone(),
+    };
+    folder.children = build_git_file_tree(&plumber)?;
+    // Mark as loaded since we populated it
+    if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut folder.obj_type {
+        *is_loaded = true;
+    }
+    Ok(folder)
+}
+
+/// Label each worktree group and the files that tie it to its checkout
+fn label_worktrees_folder(
+    worktrees_folder: &mut GitObject,
+    worktrees: &[crate::git::worktree::Worktree],
+) {
+    for group in &mut worktrees_folder.children {
+        let Some(worktree) = worktrees
+            .iter()
+            .find(|worktree| worktree.name == group.name)
+        else {
+            continue;
+        };
+        if worktree.locked.is_some() {
+            group.name = format!("{} (locked)", worktree.name);
+        } else if worktree.prunable.is_some() {
+            group.name = format!("{} (prunable)", worktree.name);
+        }
+
+        let ref_store = worktree.load_ref_store().ok();
+        for child in &mut group.children {
+            match child.name.as_str() {
+                "HEAD" => {
+                    if let Some(ref_store) = &ref_store
+                        && let Some(head) = ref_store.get("HEAD")
+                    {
+                        *child = GitObject::new_ref(ref_store, head);
+                    }
+                }
+                "gitdir" => {
+                    if let Some(checkout) = &worktree.checkout {
+                        child.name = format!("gitdir → {}", checkout.display());
+                    }
+                }
+                "locked" => {
+                    if let Some(reason) = worktree.locked.as_ref().filter(|r| !r.is_empty()) {
+                        child.name = format!("locked: {reason}");
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Build the objects directory with pack and loose object folders, plus
+/// the alternate object directories it borrows from
 fn build_objects_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String> {
-    let objects_path = plumber.get_repo_path().join(".git/objects");
+    let mut objects_folder = build_object_store_folder(plumber)?;
+
+    if let Ok(alternates) = plumber.list_alternates()
+        && !alternates.is_empty()
+    {
+        objects_folder.add_child(build_alternates_category(&alternates)?);
+    }
+
+    Ok(objects_folder)
+}
+
+/// An "Alternates" category holding each alternate object directory laid
+/// out like the objects folder, in the order git searches them
+///
+/// The list already includes alternates of alternates, so their own
+/// alternates aren't listed again.
+fn build_alternates_category(
+    alternates: &[crate::git::alternates::Alternate],
+) -> Result<GitObject, String> {
+    let mut category = GitObject::new_category("Alternates");
+    for alternate in alternates {
+        let group = if alternate.exists {
+            let plumber = crate::GitPlumber::for_objects_dir(&alternate.path);
+            let mut store = build_object_store_folder(&plumber)?;
+            store.name = alternate.path.display().to_string();
+            store.expanded = false;
+            store
+        } else {
+            GitObject::new_category(&format!("{} (missing)", alternate.entry))
+        };
+        category.add_child(group);
+    }
+    Ok(category)
+}
+
+/// Build one object directory with pack and loose object folders
+fn build_object_store_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String> {
+    let objects_path = plumber.get_objects_dir();
     let mut objects_folder = GitObject::new_filesystem_folder(objects_path.clone(), true);
 
     // Make educational folders start expanded
@@ -90,6 +257,27 @@ fn build_objects_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String
             ));
         }
 
+        // An incremental multi-pack-index spans all packs as well; its
+        // layers are listed in chain order, base layer first
+        if let Ok(Some(chain_group)) = plumber.get_multi_pack_index_chain_group()
+            && let Some(chain_dir) = chain_group.chain_file.parent()
+        {
+            let mut chain_folder = GitObject::new_filesystem_folder(chain_dir.to_path_buf(), false);
+            chain_folder.children = chain_group
+                .get_all_files()
+                .into_iter()
+                .map(|(label, path)| {
+                    let mut file = GitObject::new_filesystem_file(path.clone());
+                    file.name = label;
+                    file
+                })
+                .collect();
+            if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut chain_folder.obj_type {
+                *is_loaded = true;
+            }
+            pack_folder.add_child(chain_folder);
+        }
+
         // A MIDX-owned bitmap (multi-pack-index-<checksum>.bitmap) also spans
         // all packs, so it too sits next to the pack group folders
         if let Some(midx_bitmap_path) = plumber.get_multi_pack_index_bitmap() {
@@ -109,7 +297,34 @@ fn build_objects_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String
     // Add info folder if it exists (use full loading)
     let info_path = objects_path.join("info");
     if info_path.exists() {
-        objects_folder.add_child(build_full_filesystem_folder(info_path, false)?);
+        let mut info_folder = build_full_filesystem_folder(info_path, false)?;
+
+        // Split commit-graph layers are listed in chain order, base layer
+        // first, the way pack files are grouped per pack
+        if let Ok(Some(chain_group)) = plumber.get_commit_graph_chain_group()
+            && let Some(commit_graphs_folder) = info_folder
+                .children
+                .iter_mut()
+                .find(|child| child.name == crate::git::commit_graph::CommitGraphChain::DIR_NAME)
+        {
+            commit_graphs_folder.children = chain_group
+                .get_all_files()
+                .into_iter()
+                .map(|(label, path)| {
+                    let mut file = GitObject::new_filesystem_file(path.clone());
+                    file.name = label;
+                    file
+                })
+                .collect();
+        }
+
+        objects_folder.add_child(info_folder);
+    }
+
+    // The loose object map of a repository with a compatibility format
+    let loose_object_idx = objects_path.join(crate::git::compat_map::LooseObjectIdx::FILE);
+    if loose_object_idx.is_file() {
+        objects_folder.add_child(GitObject::new_filesystem_file(loose_object_idx));
     }
 
     // Create a special folder for loose objects with educational content
@@ -147,88 +362,61 @@ fn build_objects_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String
 }
 
 /// Build the refs directory with all reference subcategories
+///
+/// Refs come from the ref store rather than a directory listing, so refs
+/// that only exist in packed-refs (e.g. right after `git gc`) show up too.
 fn build_refs_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String> {
-    let refs_path = plumber.get_repo_path().join(".git/refs");
-    let mut refs_folder = GitObject::new_filesystem_folder(refs_path, true);
+    let refs_path = plumber.get_common_dir().join("refs");
+    let ref_store = plumber
+        .load_ref_store()
+        .map_err(|e| format!("Error loading refs: {e}"))?;
+    let mut refs_folder = GitObject::new_filesystem_folder(refs_path.clone(), true);
 
     // Make educational folders start expanded
     refs_folder.expanded = true;
 
-    // Add heads folder with educational content
-    let heads_path = plumber.get_repo_path().join(".git/refs/heads");
-    if heads_path.exists() {
-        let mut heads_folder = GitObject::new_filesystem_folder(heads_path, true);
-        heads_folder.expanded = true; // Educational folders start expanded
-
-        match plumber.list_head_refs() {
-            Ok(head_refs) => {
-                for path in head_refs {
-                    heads_folder.add_child(GitObject::new_ref(path));
-                }
-            }
-            Err(e) => return Err(format!("Error loading head refs: {e}")),
-        }
-        // Mark as loaded since we populated it
-        if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut heads_folder.obj_type {
-            *is_loaded = true;
-        }
-        refs_folder.add_child(heads_folder);
-    }
-
-    // Add remotes folder with educational content
-    let remotes_path = plumber.get_repo_path().join(".git/refs/remotes");
-    if remotes_path.exists() {
-        let mut remotes_folder = GitObject::new_filesystem_folder(remotes_path, true);
-        remotes_folder.expanded = true; // Educational folders start expanded
-
-        match plumber.list_remote_refs() {
-            Ok(remote_refs) => {
-                for (remote_name, refs) in remote_refs {
-                    let mut remote_category = GitObject::new_category(&remote_name);
-                    for path in refs {
-                        remote_category.add_child(GitObject::new_ref(path));
-                    }
-                    remotes_folder.add_child(remote_category);
-                }
-            }
-            Err(e) => return Err(format!("Error loading remote refs: {e}")),
-        }
-        // Mark as loaded since we populated it
-        if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut remotes_folder.obj_type {
-            *is_loaded = true;
+    // heads, remotes and tags keep their educational folder even when empty
+    for kind in ["heads", "remotes", "tags"] {
+        if refs_path.join(kind).exists()
+            || ref_store
+                .refs_under(&format!("refs/{kind}/"))
+                .next()
+                .is_some()
+        {
+            refs_folder.add_child(new_refs_subfolder(refs_path.join(kind)));
         }
-        refs_folder.add_child(remotes_folder);
     }
 
-    // Add tags folder with educational content
-    let tags_path = plumber.get_repo_path().join(".git/refs/tags");
-    if tags_path.exists() {
-        let mut tags_folder = GitObject::new_filesystem_folder(tags_path, true);
-        tags_folder.expanded = true; // Educational folders start expanded
-
-        match plumber.list_tag_refs() {
-            Ok(tag_refs) => {
-                for path in tag_refs {
-                    tags_folder.add_child(GitObject::new_ref(path));
+    // Refs directly under refs/ (such as the stash) go after the folders
+    let mut top_level_refs = Vec::new();
+    for reference in ref_store.refs_under("refs/") {
+        let relative = &reference.name["refs/".len()..];
+        let Some((folders, _)) = relative.rsplit_once('/') else {
+            top_level_refs.push(GitObject::new_ref(&ref_store, reference));
+            continue;
+        };
+
+        let mut folder = &mut refs_folder;
+        let mut folder_path = refs_path.clone();
+        for component in folders.split('/') {
+            folder_path.push(component);
+            let index = match folder
+                .children
+                .iter()
+                .position(|child| child.name == component)
+            {
+                Some(index) => index,
+                None => {
+                    folder.add_child(new_refs_subfolder(folder_path.clone()));
+                    folder.children.len() - 1
                 }
-            }
-            Err(e) => return Err(format!("Error loading tag refs: {e}")),
+            };
+            folder = &mut folder.children[index];
         }
-        // Mark as loaded since we populated it
-        if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut tags_folder.obj_type {
-            *is_loaded = true;
-        }
-        refs_folder.add_child(tags_folder);
+        folder.add_child(GitObject::new_ref(&ref_store, reference));
     }
-
-    // Add stash file if it exists
-    match plumber.has_stash_ref() {
-        Ok(true) => {
-            let stash_path = plumber.get_repo_path().join(".git/refs/stash");
-            refs_folder.add_child(GitObject::new_ref(stash_path));
-        }
-        Ok(false) => {}
-        Err(e) => return Err(format!("Error checking stash ref: {e}")),
+    for reference in top_level_refs {
+        refs_folder.add_child(reference);
     }
 
     // Mark refs folder as loaded since we populated it
@@ -239,6 +427,21 @@ fn build_refs_folder(plumber: &crate::GitPlumber) -> Result<GitObject, String> {
     Ok(refs_folder)
 }
 
+/// A folder under refs/, populated from the ref store rather than loaded
+/// from disk
+fn new_refs_subfolder(path: std::path::PathBuf) -> GitObject {
+    let is_educational = path
+        .file_name()
+        .is_some_and(|name| name == "heads" || name == "remotes" || name == "tags");
+    let mut folder = GitObject::new_filesystem_folder(path, is_educational);
+    folder.expanded = true;
+    // Mark as loaded since the ref store populates it
+    if let GitObjectType::FileSystemFolder { is_loaded, .. } = &mut folder.obj_type {
+        *is_loaded = true;
+    }
+    folder
+}
+
 /// Build a complete filesystem folder with all contents loaded recursively
 /// This replaces the lazy loading approach with a full tree that includes all files and modification times
 fn build_full_filesystem_folder(
@@ -1,3 +1,4 @@
+use crate::git::refs::RefStorage;
 use crate::tui::message::Message;
 use crate::tui::model::{AppState, AppView, GitObjectType};
 
@@ -117,12 +118,23 @@ impl AppState {
     // Build the details text shown in the "Object Details" pane
     fn describe_git_object(obj: &crate::tui::model::GitObject) -> String {
         match &obj.obj_type {
-            GitObjectType::Ref { content, .. } => {
+            GitObjectType::Ref {
+                content,
+                storage,
+                resolution,
+                ..
+            } => {
                 // Use cached reference content
                 let ref_content = content.as_deref().unwrap_or("Unable to read reference");
+                let stored_in = match storage {
+                    RefStorage::Loose => "loose file",
+                    RefStorage::Packed => "packed-refs",
+                    RefStorage::LooseOverPacked => "loose file (shadows packed-refs)",
+                    RefStorage::Reftable => "reftable stack",
+                };
                 format!(
-                    "Type: Git Reference\nName: {}\nPoints to: {}",
-                    obj.name, ref_content
+                    "Type: Git Reference\nName: {}\nPoints to: {}\nResolves to: {}\nStored in: {}",
+                    obj.name, ref_content, resolution, stored_in
                 )
             }
             GitObjectType::LooseObject {
@@ -225,7 +237,7 @@ impl AppState {
 
     // Load educational content for the currently selected object
     #[must_use]
-    pub fn load_educational_content(&self, _plumber: &crate::GitPlumber) -> Message {
+    pub fn load_educational_content(&self, plumber: &crate::GitPlumber) -> Message {
         match &self.view {
             AppView::Main { state } => {
                 if state.tree.flat_view.is_empty() {
@@ -245,6 +257,16 @@ impl AppState {
                                 self.educational_content_provider.get_category_content(name);
                             Message::LoadEducationalContent(Ok(content))
                         }
+                        GitObjectType::FileSystemFolder { path, .. }
+                            if crate::git::worktree::Worktree::is_admin_dir(path) =>
+                        {
+                            let result = crate::git::worktree::WorktreeDetails::load(
+                                path,
+                                &plumber.object_database_for(path),
+                            )
+                            .map_err(|e| format!("Error loading worktree: {e}"));
+                            Message::LoadWorktreeDetails(Box::new(result))
+                        }
                         GitObjectType::FileSystemFolder {
                             path,
                             is_educational,
@@ -276,6 +298,169 @@ impl AppState {
                                 Message::LoadEducationalContent(Ok(content))
                             }
                         }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path
+                                .file_name()
+                                .and_then(|name| name.to_str())
+                                .is_some_and(crate::git::index::GitIndex::is_index_file_name) =>
+                        {
+                            // Try to parse the index (staging area) for detailed preview
+                            match std::fs::read(path) {
+                                Ok(index_data) => {
+                                    let object_format = path.parent().map_or_else(
+                                        Default::default,
+                                        |git_dir| {
+                                            crate::git::repository::Repository::from_git_dir(
+                                                git_dir,
+                                            )
+                                            .object_format()
+                                        },
+                                    );
+                                    match crate::git::index::GitIndex::parse_with_format(
+                                        &index_data,
+                                        object_format,
+                                    ) {
+                                        Ok((_, mut git_index)) => {
+                                            // Split indexes, conflict stages and the cache tree need the repository around the index
+                                            if let Some(git_dir) = path.parent() {
+                                                git_index.resolve_repository_data(
+                                                    git_dir,
+                                                    &plumber.object_database_for(git_dir),
+                                                );
+                                            }
+                                            Message::LoadGitIndexDetails(Box::new(Ok(git_index)))
+                                        }
+                                        Err(e) => Message::LoadGitIndexDetails(Box::new(Err(
+                                            format!("Error parsing index: {e:?}"),
+                                        ))),
+                                    }
+                                }
+                                Err(e) => Message::LoadGitIndexDetails(Box::new(Err(format!(
+                                    "Error reading index file: {e}"
+                                )))),
+                            }
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().is_some_and(|name| name == "packed-refs") =>
+                        {
+                            match std::fs::read(path) {
+                                Ok(data) => match crate::git::refs::PackedRefs::parse(&data) {
+                                    Ok((_, mut packed_refs)) => {
+                                        // Loose ref files shadow packed entries
+                                        if let Some(git_dir) = path.parent() {
+                                            packed_refs.resolve_loose_refs(git_dir);
+                                        }
+                                        Message::LoadPackedRefsDetails(Box::new(Ok(packed_refs)))
+                                    }
+                                    Err(e) => Message::LoadPackedRefsDetails(Box::new(Err(
+                                        format!("Error parsing packed-refs: {e:?}"),
+                                    ))),
+                                },
+                                Err(e) => Message::LoadPackedRefsDetails(Box::new(Err(format!(
+                                    "Error reading packed-refs file: {e}"
+                                )))),
+                            }
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().and_then(|name| name.to_str()).is_some_and(
+                                |name| {
+                                    name == "commit-graph"
+                                        || crate::git::commit_graph::CommitGraphChain::is_layer_file_name(name)
+                                },
+                            ) =>
+                        {
+                            // Layers of a split chain number their commits after the
+                            // layers below
+                            let first_position =
+                                crate::git::commit_graph::CommitGraphChain::layer_first_position(
+                                    path,
+                                )
+                                .unwrap_or(0);
+                            let result = match std::fs::read(path) {
+                                Ok(data) => crate::git::commit_graph::CommitGraph::parse(&data)
+                                    .map(|(_, commit_graph)| commit_graph)
+                                    .map_err(|e| format!("Error parsing commit-graph: {e:?}")),
+                                Err(e) => Err(format!("Error reading commit-graph file: {e}")),
+                            };
+                            Message::LoadCommitGraphDetails {
+                                git_dir: crate::git::commit_graph::CommitGraph::git_dir_of(path)
+                                    .unwrap_or_default(),
+                                first_position,
+                                result: Box::new(result),
+                            }
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().is_some_and(|name| {
+                                name == crate::git::commit_graph::CommitGraphChain::CHAIN_FILE_NAME
+                            }) =>
+                        {
+                            let result = crate::git::commit_graph::CommitGraphChain::load(path)
+                                .map_err(|e| format!("Error loading commit-graph chain: {e}"));
+                            Message::LoadCommitGraphChainDetails(Box::new(result))
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().and_then(|name| name.to_str()).is_some_and(
+                                crate::git::pack::MultiPackIndexChain::is_layer_file_name,
+                            ) =>
+                        {
+                            let result = match std::fs::read(path) {
+                                Ok(data) => crate::git::pack::MultiPackIndex::parse(&data)
+                                    .map(|(_, midx)| midx)
+                                    .map_err(|e| format!("Error parsing multi-pack-index: {e:?}")),
+                                Err(e) => Err(format!("Error reading multi-pack-index file: {e}")),
+                            };
+                            Message::LoadMultiPackIndexDetails(Box::new(result))
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().is_some_and(|name| {
+                                name == crate::git::pack::MultiPackIndexChain::CHAIN_FILE_NAME
+                            }) =>
+                        {
+                            let result = crate::git::pack::MultiPackIndexChain::load(path)
+                                .map_err(|e| format!("Error loading multi-pack-index chain: {e}"));
+                            Message::LoadMultiPackIndexChainDetails(Box::new(result))
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if crate::git::refs::ReftableStack::is_table_path(path) =>
+                        {
+                            let result = match std::fs::read(path) {
+                                Ok(data) => crate::git::refs::Reftable::parse(&data)
+                                    .map(|(_, reftable)| reftable)
+                                    .map_err(|e| format!("Error parsing reftable: {e:?}")),
+                                Err(e) => Err(format!("Error reading reftable: {e}")),
+                            };
+                            Message::LoadReftableDetails(Box::new(result))
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().is_some_and(|name| {
+                                name == crate::git::refs::ReftableStack::TABLES_LIST
+                            }) =>
+                        {
+                            let result = crate::git::refs::ReftableStack::load_list(path)
+                                .map_err(|e| format!("Error loading reftable stack: {e}"));
+                            Message::LoadReftableStackDetails(Box::new(result))
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if path.file_name().is_some_and(|name| {
+                                name == crate::git::compat_map::LooseObjectIdx::FILE
+                            }) =>
+                        {
+                            let result = plumber.load_loose_object_idx(path);
+                            Message::LoadLooseObjectIdxDetails(Box::new(result))
+                        }
+                        GitObjectType::FileSystemFile { path, .. }
+                            if crate::git::refs::Reflog::ref_for_log_path(path).is_some() =>
+                        {
+                            let (git_dir, ref_name) =
+                                crate::git::refs::Reflog::ref_for_log_path(path)
+                                    .unwrap_or_default();
+                            let result = crate::git::refs::Reflog::load(&git_dir, &ref_name)
+                                .map_err(|e| format!("Error loading reflog: {e}"));
+                            Message::LoadReflogDetails {
+                                git_dir,
+                                result: Box::new(result),
+                            }
+                        }
                         GitObjectType::FileSystemFile { path, .. } => {
                             // For files, show basic file info
                             let content = ratatui::text::Text::from(format!(
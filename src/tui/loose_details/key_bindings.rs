@@ -19,6 +19,12 @@ pub const fn handle_key_event(key: KeyEvent, app: &AppState) -> Option<Message>
             KeyCode::PageDown | KeyCode::Char('G') => Some(Message::LooseObjectNavigation(
                 LooseObjectNavigation::ScrollToBottom,
             )),
+            KeyCode::Tab => Some(Message::LooseObjectNavigation(
+                LooseObjectNavigation::NextGitlink,
+            )),
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => Some(
+                Message::LooseObjectNavigation(LooseObjectNavigation::OpenGitlink),
+            ),
             _ => None,
         },
         _ => None,
@@ -1,6 +1,7 @@
 use super::LooseObjectViewState;
 use crate::tui::message::{LooseObjectNavigation, Message};
 use crate::tui::model::{AppState, AppView};
+use crate::tui::widget::loose_obj_details::LooseObjectWidget;
 
 impl AppState {
     pub fn handle_loose_object_view_mode_message(&mut self, msg: Message) -> bool {
@@ -38,6 +39,51 @@ impl AppState {
                         loose_widget.scroll_to_bottom();
                     }
                 }
+                LooseObjectNavigation::NextGitlink => {
+                    if let AppView::LooseObjectDetail {
+                        state: LooseObjectViewState { loose_widget },
+                    } = &mut self.view
+                    {
+                        loose_widget.select_next_gitlink();
+                    }
+                }
+                LooseObjectNavigation::OpenGitlink => {
+                    if let AppView::LooseObjectDetail {
+                        state: LooseObjectViewState { loose_widget },
+                    } = &mut self.view
+                        && loose_widget.selected_gitlink().is_none()
+                    {
+                        // Nothing selected yet: select the first gitlink
+                        loose_widget.select_next_gitlink();
+                    } else if let AppView::LooseObjectDetail {
+                        state: LooseObjectViewState { loose_widget },
+                    } = &self.view
+                        && let Some(gitlink) = loose_widget.selected_gitlink()
+                    {
+                        match &gitlink.commit {
+                            Some(commit) => {
+                                let commit_view = AppView::LooseObjectDetail {
+                                    state: LooseObjectViewState {
+                                        loose_widget: LooseObjectWidget::new(commit.clone()),
+                                    },
+                                };
+                                self.push_view(commit_view);
+                            }
+                            None => {
+                                self.error = Some(match &gitlink.submodule {
+                                    Some(submodule) => format!(
+                                        "Commit {} of {} is not in submodule {}",
+                                        gitlink.oid, gitlink.entry_name, submodule.name
+                                    ),
+                                    None => format!(
+                                        "No submodule is known to be checked out at {}",
+                                        gitlink.path.as_deref().unwrap_or(&gitlink.entry_name)
+                                    ),
+                                });
+                            }
+                        }
+                    }
+                }
             },
             _ => {
                 unreachable!(
@@ -21,16 +21,29 @@ pub fn render(f: &mut ratatui::Frame, app: &mut AppState, area: ratatui::layout:
 
 pub fn navigation_hints(app: &AppState) -> Vec<Span<'_>> {
     match &app.view {
-        AppView::LooseObjectDetail { .. } => {
-            vec![
+        AppView::LooseObjectDetail {
+            state: LooseObjectViewState { loose_widget, .. },
+        } => {
+            let mut hints = vec![
                 Span::styled("↕", Style::default().fg(Color::Blue)),
                 Span::raw(" to scroll | "),
+            ];
+            if loose_widget.has_gitlinks() {
+                hints.extend([
+                    Span::styled("Tab", Style::default().fg(Color::Blue)),
+                    Span::raw(" select submodule | "),
+                    Span::styled("Enter", Style::default().fg(Color::Blue)),
+                    Span::raw(" open its commit | "),
+                ]);
+            }
+            hints.extend([
                 Span::raw(""),
                 Span::styled("Q", Style::default().fg(Color::Blue)),
                 Span::styled("/", Style::default().fg(Color::Gray)),
                 Span::styled("←", Style::default().fg(Color::Blue)),
                 Span::raw(" - go back"),
-            ]
+            ]);
+            hints
         }
         _ => Vec::new(),
     }
@@ -1,17 +1,55 @@
 use crate::tui::main_view::{
     PackFocus, PackPreViewState, PreviewState, RegularPreViewState, RowKind,
 };
-use crate::tui::message::{MainNavigation, Message};
+use crate::tui::message::{BloomQueryInput, MainNavigation, Message};
 use crate::tui::model::{AppState, AppView};
+use crate::tui::widget::CommitGraphWidget;
 use crossterm::event::{KeyCode, KeyEvent};
 
-use super::RegularFocus;
+use super::{MainViewState, RegularFocus};
+
+/// The commit-graph widget shown in the focused preview, if any
+fn focused_commit_graph_widget(state: &MainViewState) -> Option<&CommitGraphWidget> {
+    match &state.preview_state {
+        PreviewState::Regular(RegularPreViewState {
+            focus: RegularFocus::Preview,
+            commit_graph_widget,
+            ..
+        }) => commit_graph_widget.as_ref(),
+        _ => None,
+    }
+}
 
 /// Handle key events for the main view and convert them to appropriate messages
 #[must_use]
 pub fn handle_key_event(key: KeyEvent, app: &AppState) -> Option<Message> {
     match &app.view {
+        // Typing a path for the commit-graph's Bloom filters
+        AppView::Main { state }
+            if focused_commit_graph_widget(state)
+                .is_some_and(CommitGraphWidget::is_editing_query) =>
+        {
+            match key.code {
+                KeyCode::Char(c) => Some(Message::BloomQuery(BloomQueryInput::Char(c))),
+                KeyCode::Backspace => Some(Message::BloomQuery(BloomQueryInput::Backspace)),
+                KeyCode::Enter => Some(Message::BloomQuery(BloomQueryInput::Submit)),
+                KeyCode::Esc => Some(Message::BloomQuery(BloomQueryInput::Cancel)),
+                _ => None,
+            }
+        }
         AppView::Main { state } => match key.code {
+            KeyCode::Char('/')
+                if focused_commit_graph_widget(state)
+                    .is_some_and(CommitGraphWidget::has_bloom_filters) =>
+            {
+                Some(Message::BloomQuery(BloomQueryInput::Start))
+            }
+            KeyCode::Char('v')
+                if focused_commit_graph_widget(state)
+                    .is_some_and(CommitGraphWidget::has_bloom_filters) =>
+            {
+                Some(Message::BloomQuery(BloomQueryInput::Verify))
+            }
             KeyCode::Char('r') => Some(Message::Refresh),
             KeyCode::Char('q') | KeyCode::Esc => Some(Message::Quit),
             KeyCode::Char('t') => Some(Message::MainNavigation(MainNavigation::ToggleExpand)),
@@ -75,7 +113,10 @@ pub fn handle_key_event(key: KeyEvent, app: &AppState) -> Option<Message> {
                             ))
                         }
                     }
-                    RegularFocus::Preview => None,
+                    RegularFocus::Preview => preview_state
+                        .reflog_widget
+                        .as_ref()
+                        .map(|_| Message::OpenReflogEntry),
                 },
                 PreviewState::Pack(state) => match state.focus {
                     PackFocus::GitObjects => Some(Message::MainNavigation(
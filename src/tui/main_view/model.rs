@@ -2,8 +2,10 @@ use crate::educational_content::EducationalContent;
 use crate::tui::message::Message;
 use crate::tui::model::{GitObject, GitObjectType, PackObject};
 use crate::tui::widget::{
-    MultiPackIndexWidget, PackBitmapWidget, PackIndexWidget, PackMtimesWidget, PackObjectWidget,
-    PackReverseIndexWidget,
+    CommitGraphChainWidget, CommitGraphWidget, GitIndexWidget, LooseObjectIdxWidget,
+    MultiPackIndexChainWidget, MultiPackIndexWidget, PackBitmapWidget, PackIndexWidget,
+    PackMtimesWidget, PackObjectWidget, PackReverseIndexWidget, PackedRefsWidget, ReflogWidget,
+    ReftableStackWidget, ReftableWidget, WorktreeWidget,
 };
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
@@ -195,6 +197,16 @@ pub struct RegularPreViewState {
     pub pack_mtimes_widget: Option<PackMtimesWidget>,
     pub pack_bitmap_widget: Option<PackBitmapWidget>,
     pub multi_pack_index_widget: Option<MultiPackIndexWidget>,
+    pub git_index_widget: Option<GitIndexWidget>,
+    pub packed_refs_widget: Option<PackedRefsWidget>,
+    pub reflog_widget: Option<ReflogWidget>,
+    pub commit_graph_widget: Option<CommitGraphWidget>,
+    pub commit_graph_chain_widget: Option<CommitGraphChainWidget>,
+    pub reftable_widget: Option<ReftableWidget>,
+    pub reftable_stack_widget: Option<ReftableStackWidget>,
+    pub loose_object_idx_widget: Option<LooseObjectIdxWidget>,
+    pub multi_pack_index_chain_widget: Option<MultiPackIndexChainWidget>,
+    pub worktree_widget: Option<WorktreeWidget>,
 }
 
 impl Default for RegularPreViewState {
@@ -214,6 +226,16 @@ impl RegularPreViewState {
             pack_mtimes_widget: None,
             pack_bitmap_widget: None,
             multi_pack_index_widget: None,
+            git_index_widget: None,
+            packed_refs_widget: None,
+            reflog_widget: None,
+            commit_graph_widget: None,
+            commit_graph_chain_widget: None,
+            reftable_widget: None,
+            reftable_stack_widget: None,
+            loose_object_idx_widget: None,
+            multi_pack_index_chain_widget: None,
+            worktree_widget: None,
         }
     }
 
@@ -256,6 +278,102 @@ impl RegularPreViewState {
             ..Self::new()
         }
     }
+
+    #[must_use]
+    pub fn new_with_git_index(git_index: crate::git::index::GitIndex) -> Self {
+        Self {
+            git_index_widget: Some(GitIndexWidget::new(git_index)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_packed_refs(packed_refs: crate::git::refs::PackedRefs) -> Self {
+        Self {
+            packed_refs_widget: Some(PackedRefsWidget::new(packed_refs)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_reflog(reflog: crate::git::refs::Reflog, git_dir: std::path::PathBuf) -> Self {
+        Self {
+            reflog_widget: Some(ReflogWidget::new(reflog, git_dir)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_commit_graph(
+        commit_graph: crate::git::commit_graph::CommitGraph,
+        first_position: usize,
+        git_dir: std::path::PathBuf,
+    ) -> Self {
+        Self {
+            commit_graph_widget: Some(CommitGraphWidget::new(
+                commit_graph,
+                first_position,
+                git_dir,
+            )),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_commit_graph_chain(
+        commit_graph_chain: crate::git::commit_graph::CommitGraphChain,
+    ) -> Self {
+        Self {
+            commit_graph_chain_widget: Some(CommitGraphChainWidget::new(commit_graph_chain)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_reftable(reftable: crate::git::refs::Reftable) -> Self {
+        Self {
+            reftable_widget: Some(ReftableWidget::new(reftable)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_reftable_stack(reftable_stack: crate::git::refs::ReftableStack) -> Self {
+        Self {
+            reftable_stack_widget: Some(ReftableStackWidget::new(reftable_stack)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_loose_object_idx(
+        loose_object_idx: crate::git::compat_map::LooseObjectIdx,
+    ) -> Self {
+        Self {
+            loose_object_idx_widget: Some(LooseObjectIdxWidget::new(loose_object_idx)),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_multi_pack_index_chain(
+        multi_pack_index_chain: crate::git::pack::MultiPackIndexChain,
+    ) -> Self {
+        Self {
+            multi_pack_index_chain_widget: Some(MultiPackIndexChainWidget::new(
+                multi_pack_index_chain,
+            )),
+            ..Self::new()
+        }
+    }
+
+    #[must_use]
+    pub fn new_with_worktree(worktree: crate::git::worktree::WorktreeDetails) -> Self {
+        Self {
+            worktree_widget: Some(WorktreeWidget::new(worktree)),
+            ..Self::new()
+        }
+    }
 }
 
 impl MainViewState {
@@ -1,7 +1,7 @@
 use super::model::{MainViewState, PackColumnPreviousFocus, PackFocus, PreviewState};
 use super::{PackPreViewState, RegularFocus, RegularPreViewState};
 use crate::tui::loose_details::LooseObjectViewState;
-use crate::tui::message::{MainNavigation, Message};
+use crate::tui::message::{BloomQueryInput, MainNavigation, Message};
 use crate::tui::model::{AppState, AppView, GitObjectType};
 use crate::tui::pack_details::PackViewState;
 use crate::tui::widget::{PackObjectWidget, loose_obj_details::LooseObjectWidget};
@@ -66,7 +66,8 @@ impl AppState {
                 if selected_pack_object < pack_preview_state.pack_object_list.len() {
                     // Update the widget to show the selected pack object's details
                     pack_preview_state.pack_object_widget_state = PackObjectWidget::new(
-                        pack_preview_state.pack_object_list[selected_pack_object].clone(),
+                        pack_preview_state.pack_object_list[selected_pack_object]
+                            .with_object_data(),
                     );
                 }
             }
@@ -343,7 +344,7 @@ impl AppState {
                         if *selected_pack_object < pack_object_list.len() - 1 {
                             *selected_pack_object += 1;
                             *pack_widget_state = PackObjectWidget::new(
-                                pack_object_list[*selected_pack_object].clone(),
+                                pack_object_list[*selected_pack_object].with_object_data(),
                             );
 
                             // Update scroll position to keep selected item visible
@@ -357,7 +358,8 @@ impl AppState {
                         } else {
                             *selected_pack_object = 0;
                             *pack_object_list_scroll_position = 0;
-                            *pack_widget_state = PackObjectWidget::new(pack_object_list[0].clone());
+                            *pack_widget_state =
+                                PackObjectWidget::new(pack_object_list[0].with_object_data());
                         }
                     }
                 }
@@ -384,7 +386,7 @@ impl AppState {
                         if *selected_pack_object > 0 {
                             *selected_pack_object -= 1;
                             *pack_widget_state = PackObjectWidget::new(
-                                pack_object_list[*selected_pack_object].clone(),
+                                pack_object_list[*selected_pack_object].with_object_data(),
                             );
 
                             // Update scroll position to keep selected item visible
@@ -418,7 +420,8 @@ impl AppState {
                     {
                         *selected_pack_object = 0;
                         *pack_object_list_scroll_position = 0;
-                        *pack_widget_state = PackObjectWidget::new(pack_object_list[0].clone());
+                        *pack_widget_state =
+                            PackObjectWidget::new(pack_object_list[0].with_object_data());
                     }
                 }
                 MainNavigation::SelectLastPackObject => {
@@ -440,8 +443,9 @@ impl AppState {
                         && !pack_object_list.is_empty()
                     {
                         *selected_pack_object = pack_object_list.len() - 1;
-                        *pack_widget_state =
-                            PackObjectWidget::new(pack_object_list[*selected_pack_object].clone());
+                        *pack_widget_state = PackObjectWidget::new(
+                            pack_object_list[*selected_pack_object].with_object_data(),
+                        );
                         // Update scroll position to show the last item
                         let visible_height = self.layout_dimensions.pack_objects_height;
                         if *selected_pack_object >= visible_height {
@@ -568,6 +572,16 @@ impl AppState {
                                 pack_mtimes_widget,
                                 pack_bitmap_widget,
                                 multi_pack_index_widget,
+                                git_index_widget,
+                                packed_refs_widget,
+                                reflog_widget,
+                                commit_graph_widget,
+                                commit_graph_chain_widget,
+                                reftable_widget,
+                                reftable_stack_widget,
+                                loose_object_idx_widget,
+                                multi_pack_index_chain_widget,
+                                worktree_widget,
                                 ..
                             }) => {
                                 if let Some(widget) = pack_index_widget {
@@ -580,6 +594,26 @@ impl AppState {
                                     widget.scroll_up();
                                 } else if let Some(widget) = multi_pack_index_widget {
                                     widget.scroll_up();
+                                } else if let Some(widget) = git_index_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = packed_refs_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = reflog_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = commit_graph_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = commit_graph_chain_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = reftable_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = reftable_stack_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = loose_object_idx_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = multi_pack_index_chain_widget {
+                                    widget.scroll_up();
+                                } else if let Some(widget) = worktree_widget {
+                                    widget.scroll_up();
                                 } else if *preview_scroll_position > 0 {
                                     *preview_scroll_position -= 1;
                                 }
@@ -612,6 +646,16 @@ impl AppState {
                                 pack_mtimes_widget,
                                 pack_bitmap_widget,
                                 multi_pack_index_widget,
+                                git_index_widget,
+                                packed_refs_widget,
+                                reflog_widget,
+                                commit_graph_widget,
+                                commit_graph_chain_widget,
+                                reftable_widget,
+                                reftable_stack_widget,
+                                loose_object_idx_widget,
+                                multi_pack_index_chain_widget,
+                                worktree_widget,
                                 ..
                             }) => {
                                 if let Some(widget) = pack_index_widget {
@@ -624,6 +668,26 @@ impl AppState {
                                     widget.scroll_down();
                                 } else if let Some(widget) = multi_pack_index_widget {
                                     widget.scroll_down();
+                                } else if let Some(widget) = git_index_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = packed_refs_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = reflog_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = commit_graph_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = commit_graph_chain_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = reftable_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = reftable_stack_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = loose_object_idx_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = multi_pack_index_chain_widget {
+                                    widget.scroll_down();
+                                } else if let Some(widget) = worktree_widget {
+                                    widget.scroll_down();
                                 } else {
                                     let content_lines = content.educational_content.lines.len();
                                     let visible_height =
@@ -665,6 +729,16 @@ impl AppState {
                                 pack_mtimes_widget,
                                 pack_bitmap_widget,
                                 multi_pack_index_widget,
+                                git_index_widget,
+                                packed_refs_widget,
+                                reflog_widget,
+                                commit_graph_widget,
+                                commit_graph_chain_widget,
+                                reftable_widget,
+                                reftable_stack_widget,
+                                loose_object_idx_widget,
+                                multi_pack_index_chain_widget,
+                                worktree_widget,
                                 ..
                             }) => {
                                 if let Some(widget) = pack_index_widget {
@@ -677,6 +751,26 @@ impl AppState {
                                     widget.scroll_to_top();
                                 } else if let Some(widget) = multi_pack_index_widget {
                                     widget.scroll_to_top();
+                                } else if let Some(widget) = git_index_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = packed_refs_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = reflog_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = commit_graph_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = commit_graph_chain_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = reftable_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = reftable_stack_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = loose_object_idx_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = multi_pack_index_chain_widget {
+                                    widget.scroll_to_top();
+                                } else if let Some(widget) = worktree_widget {
+                                    widget.scroll_to_top();
                                 } else {
                                     *preview_scroll_position = 0;
                                 }
@@ -709,6 +803,16 @@ impl AppState {
                                 pack_mtimes_widget,
                                 pack_bitmap_widget,
                                 multi_pack_index_widget,
+                                git_index_widget,
+                                packed_refs_widget,
+                                reflog_widget,
+                                commit_graph_widget,
+                                commit_graph_chain_widget,
+                                reftable_widget,
+                                reftable_stack_widget,
+                                loose_object_idx_widget,
+                                multi_pack_index_chain_widget,
+                                worktree_widget,
                                 ..
                             }) => {
                                 if let Some(widget) = pack_index_widget {
@@ -721,6 +825,26 @@ impl AppState {
                                     widget.scroll_to_bottom();
                                 } else if let Some(widget) = multi_pack_index_widget {
                                     widget.scroll_to_bottom();
+                                } else if let Some(widget) = git_index_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = packed_refs_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = reflog_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = commit_graph_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = commit_graph_chain_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = reftable_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = reftable_stack_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = loose_object_idx_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = multi_pack_index_chain_widget {
+                                    widget.scroll_to_bottom();
+                                } else if let Some(widget) = worktree_widget {
+                                    widget.scroll_to_bottom();
                                 } else {
                                     let content_lines = content.educational_content.lines.len();
                                     let visible_height =
@@ -773,10 +897,20 @@ impl AppState {
                             ..
                         } = &node.obj_type
                     {
-                        // Create the new loose object view
+                        // Gitlinks of a tree link to the submodules' commits
+                        let gitlinks = plumber.resolve_gitlinks(loose_obj);
+                        let compat_id = plumber
+                            .load_compat_map()
+                            .ok()
+                            .flatten()
+                            .and_then(|map| map.compat_id(&loose_obj.object_id));
                         let loose_view = AppView::LooseObjectDetail {
                             state: LooseObjectViewState {
-                                loose_widget: LooseObjectWidget::new(loose_obj.clone()),
+                                loose_widget: LooseObjectWidget::with_gitlinks(
+                                    loose_obj.clone(),
+                                    gitlinks,
+                                )
+                                .with_compat_id(compat_id),
                             },
                         };
 
@@ -785,6 +919,72 @@ impl AppState {
                     }
                 }
             }
+            Message::OpenReflogEntry => {
+                if let AppView::Main { state } = &self.view
+                    && let PreviewState::Regular(RegularPreViewState {
+                        reflog_widget: Some(reflog_widget),
+                        ..
+                    }) = &state.preview_state
+                    && let Some(entry) = reflog_widget.selected_entry()
+                {
+                    if entry.is_deletion() {
+                        self.error = Some(
+                            "This entry deleted the ref: there is no commit to open".to_string(),
+                        );
+                        return true;
+                    }
+                    // The commit may be loose or packed
+                    let result = hex::decode(&entry.new_oid)
+                        .map_err(|e| e.to_string())
+                        .and_then(|oid| {
+                            plumber
+                                .object_database_for(reflog_widget.git_dir())
+                                .read(&oid)
+                                .map_err(|e| e.to_string())
+                        });
+                    match result {
+                        Ok(object) => {
+                            let compat_id = plumber
+                                .load_compat_map()
+                                .ok()
+                                .flatten()
+                                .and_then(|map| map.compat_id(&object.object_id));
+                            let loose_view = AppView::LooseObjectDetail {
+                                state: LooseObjectViewState {
+                                    loose_widget: LooseObjectWidget::new(object)
+                                        .with_compat_id(compat_id),
+                                },
+                            };
+                            self.push_view(loose_view);
+                        }
+                        Err(e) => {
+                            self.error = Some(format!("Cannot open {}: {e}", entry.new_oid));
+                        }
+                    }
+                }
+            }
+            Message::BloomQuery(input) => {
+                if let AppView::Main { state } = &mut self.view
+                    && let PreviewState::Regular(RegularPreViewState {
+                        commit_graph_widget: Some(widget),
+                        ..
+                    }) = &mut state.preview_state
+                {
+                    match input {
+                        BloomQueryInput::Start => widget.start_query(),
+                        BloomQueryInput::Char(c) => widget.push_query_char(c),
+                        BloomQueryInput::Backspace => widget.pop_query_char(),
+                        BloomQueryInput::Submit => widget.submit_query(),
+                        BloomQueryInput::Cancel => widget.cancel_query(),
+                        BloomQueryInput::Verify => {
+                            if let Some(git_dir) = widget.git_dir() {
+                                let database = plumber.object_database_for(git_dir);
+                                widget.verify_bloom_filters(&database);
+                            }
+                        }
+                    }
+                }
+            }
             Message::OpenMainView => {
                 // Pop the previous view from the stack to restore state
                 if !self.pop_view() {
@@ -212,6 +212,72 @@ fn render_regular_preview_layout(
                 content_chunks[1],
                 matches!(preview_state.focus, RegularFocus::Preview),
             );
+        } else if let Some(git_index_widget) = &mut preview_state.git_index_widget {
+            // Render git index widget
+            git_index_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(packed_refs_widget) = &mut preview_state.packed_refs_widget {
+            // Render packed-refs widget
+            packed_refs_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(reflog_widget) = &mut preview_state.reflog_widget {
+            // Render reflog timeline widget
+            reflog_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(commit_graph_widget) = &mut preview_state.commit_graph_widget {
+            commit_graph_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(commit_graph_chain_widget) = &mut preview_state.commit_graph_chain_widget
+        {
+            commit_graph_chain_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(reftable_widget) = &mut preview_state.reftable_widget {
+            reftable_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(reftable_stack_widget) = &mut preview_state.reftable_stack_widget {
+            reftable_stack_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(loose_object_idx_widget) = &mut preview_state.loose_object_idx_widget {
+            loose_object_idx_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(multi_pack_index_chain_widget) =
+            &mut preview_state.multi_pack_index_chain_widget
+        {
+            multi_pack_index_chain_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
+        } else if let Some(worktree_widget) = &mut preview_state.worktree_widget {
+            worktree_widget.render(
+                f,
+                content_chunks[1],
+                matches!(preview_state.focus, RegularFocus::Preview),
+            );
         } else {
             // Render regular educational content
             let bottom_title = if !main_view.tree.flat_view.is_empty()
@@ -366,14 +432,20 @@ fn render_pack_file_preview(
                 matches!(preview_state.focus, PackFocus::PackObjectsList),
                 |_absolute_index, pack_obj, is_selected| {
                     let display_text = format!(
-                        "{}: {} | {} bytes{}",
+                        "{}: {} | {} bytes{}{}",
                         pack_obj.index,
                         pack_obj.obj_type,
                         pack_obj.size,
                         pack_obj
                             .sha1
                             .as_ref()
-                            .map_or_else(String::new, |hash| format!(" | {hash}"))
+                            .map_or_else(String::new, |hash| format!(" | {hash}")),
+                        pack_obj
+                            .compat_id
+                            .as_ref()
+                            .map_or_else(String::new, |compat| {
+                                format!(" | {} {}", compat.object_format, compat.id)
+                            })
                     );
 
                     ListItem::new(display_text).style(
@@ -1,4 +1,6 @@
+use crate::git::index::GitIndex;
 use crate::git::pack::{MultiPackIndex, PackBitmap, PackIndex, PackMtimes, PackReverseIndex};
+use crate::git::refs::{PackedRefs, Reflog};
 use crate::tui::model::PackObject;
 #[derive(Debug, Clone)]
 pub enum Command {
@@ -31,12 +33,35 @@ pub enum Message {
     LoadPackMtimesDetails(Box<Result<PackMtimes, String>>),
     LoadPackBitmapDetails(Box<Result<PackBitmap, String>>),
     LoadMultiPackIndexDetails(Box<Result<MultiPackIndex, String>>),
+    LoadGitIndexDetails(Box<Result<GitIndex, String>>),
+    LoadPackedRefsDetails(Box<Result<PackedRefs, String>>),
+    LoadReflogDetails {
+        git_dir: std::path::PathBuf,
+        result: Box<Result<Reflog, String>>,
+    },
+    LoadCommitGraphDetails {
+        /// Git directory holding the file, to read trees when verifying its
+        /// Bloom filters
+        git_dir: std::path::PathBuf,
+        /// Global graph position of the file's first commit (nonzero for
+        /// the upper layers of a split chain)
+        first_position: usize,
+        result: Box<Result<crate::git::commit_graph::CommitGraph, String>>,
+    },
+    LoadCommitGraphChainDetails(Box<Result<crate::git::commit_graph::CommitGraphChain, String>>),
+    LoadReftableDetails(Box<Result<crate::git::refs::Reftable, String>>),
+    LoadReftableStackDetails(Box<Result<crate::git::refs::ReftableStack, String>>),
+    LoadLooseObjectIdxDetails(Box<Result<crate::git::compat_map::LooseObjectIdx, String>>),
+    LoadMultiPackIndexChainDetails(Box<Result<crate::git::pack::MultiPackIndexChain, String>>),
+    LoadWorktreeDetails(Box<Result<crate::git::worktree::WorktreeDetails, String>>),
     MainNavigation(MainNavigation),
     PackNavigation(PackNavigation),
     LooseObjectNavigation(LooseObjectNavigation),
     OpenMainView,
     OpenPackView,
     OpenLooseObjectView,
+    OpenReflogEntry,
+    BloomQuery(BloomQueryInput),
     // Timer message for animations
     TimerTick,
     // Terminal resize event
@@ -90,4 +115,19 @@ pub enum LooseObjectNavigation {
     ScrollDown,
     ScrollToTop,
     ScrollToBottom,
+    /// Select the tree's next gitlink
+    NextGitlink,
+    /// Open the submodule commit of the selected gitlink
+    OpenGitlink,
+}
+
+/// Editing the path asked of a commit-graph's Bloom filters
+#[derive(Debug)]
+pub enum BloomQueryInput {
+    Start,
+    Char(char),
+    Backspace,
+    Submit,
+    Cancel,
+    Verify,
 }
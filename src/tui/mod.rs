@@ -46,9 +46,24 @@ pub fn run_tui(plumber: &crate::GitPlumber, opts: &RunOptions) -> Result<(), Str
     app.effects.push(crate::tui::message::Command::LoadInitial);
 
     // Start filesystem watcher for live updates
-    if let Ok(w) = crate::tui::watcher::spawn_git_watcher(&app.repo_path, tx.clone()) {
+    let watched_dirs = [
+        plumber.get_git_dir(),
+        plumber.get_common_dir(),
+        plumber.get_objects_dir(),
+    ];
+    let worktree_checkouts: Vec<_> = plumber
+        .list_worktrees()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|worktree| worktree.checkout)
+        .collect();
+    if let Ok(w) =
+        crate::tui::watcher::spawn_git_watcher(&watched_dirs, &worktree_checkouts, tx.clone())
+    {
         app.fs_watcher = Some(w);
-    } else if let Err(e) = crate::tui::watcher::spawn_git_watcher(&app.repo_path, tx.clone()) {
+    } else if let Err(e) =
+        crate::tui::watcher::spawn_git_watcher(&watched_dirs, &worktree_checkouts, tx.clone())
+    {
         eprintln!("Watcher error: {e}");
     }
 
@@ -5,6 +5,7 @@ use std::time::SystemTime;
 // Import main view types from the main_view module
 use crate::educational_content::EducationalContent;
 use crate::git::loose_object::LooseObject;
+use crate::git::refs::{RefStorage, RefStore, Reference};
 use crate::tui::loose_details::LooseObjectViewState;
 use crate::tui::main_view::MainViewState;
 use crate::tui::pack_details::PackViewState;
@@ -19,9 +20,33 @@ pub struct PackObject {
     pub index: usize,
     pub obj_type: String,
     pub size: u32,
-    pub sha1: Option<String>,      // SHA-1 hash of the object
+    pub sha1: Option<String>, // SHA-1 hash of the object
+    pub compat_id: Option<crate::git::compat_map::CompatObjectId>, // Name in the compat format
     pub base_info: Option<String>, // For delta objects
     pub object_data: Option<crate::git::pack::Object>, // The actual parsed object
+    pub source: Option<PackObjectSource>, // Where to read `object_data` from on demand
+}
+
+/// The pack entry behind a `PackObject` listed from its header alone
+#[derive(Debug, Clone)]
+pub struct PackObjectSource {
+    pub reader: std::sync::Arc<crate::git::pack::PackReader>,
+    pub offset: u64,
+}
+
+impl PackObject {
+    /// This object with its parsed entry, read from the pack if the list
+    /// only holds its header
+    #[must_use]
+    pub fn with_object_data(&self) -> Self {
+        let mut object = self.clone();
+        if object.object_data.is_none()
+            && let Some(source) = &self.source
+        {
+            object.object_data = source.reader.read_entry_at(source.offset).ok();
+        }
+        object
+    }
 }
 
 // Define a tree structure for Git objects
@@ -51,8 +76,12 @@ pub enum GitObjectType {
         modified_time: Option<SystemTime>,
     },
     Ref {
+        /// Loose file the ref is (or would be) stored in
         path: PathBuf,
         content: Option<String>,
+        storage: RefStorage,
+        /// Where the ref's symref chain ends, for display
+        resolution: String,
     },
     LooseObject {
         size: Option<u64>,
@@ -284,23 +313,21 @@ impl GitObject {
     }
 
     #[must_use]
-    pub fn new_ref(path: PathBuf) -> Self {
-        let name = path
-            .file_name()
+    pub fn new_ref(ref_store: &RefStore, reference: &Reference) -> Self {
+        let name = reference
+            .name
+            .rsplit('/')
+            .next()
             .unwrap_or_default()
-            .to_string_lossy()
             .to_string();
 
-        // Load reference content
-        let ref_content = std::fs::read_to_string(&path)
-            .ok()
-            .map(|content| content.trim().to_string());
-
         Self {
             name,
             obj_type: GitObjectType::Ref {
-                path,
-                content: ref_content,
+                path: ref_store.loose_path(&reference.name),
+                content: Some(reference.target.to_string()),
+                storage: reference.storage,
+                resolution: ref_store.resolve(&reference.name).to_string(),
             },
             children: Vec::new(),
             expanded: false,
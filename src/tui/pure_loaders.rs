@@ -1,60 +1,114 @@
+use crate::git::compat_map::CompatMap;
+use crate::git::object_format::ObjectFormat;
+use crate::git::pack::{ObjectHeader, PackIndex, PackReader};
 use crate::tui::message::InitialGitData;
 use std::path::Path;
+use std::sync::Arc;
 
-use crate::tui::model::PackObject;
+use crate::tui::model::{PackObject, PackObjectSource};
 
 pub fn load_pack_objects_pure(pack_path: &Path) -> Result<Vec<PackObject>, String> {
-    let pack_data =
-        std::fs::read(pack_path).map_err(|e| format!("Error reading pack file: {e}"))?;
-
-    let mut parsed_objects = Vec::new();
-    match crate::git::pack::Header::parse(&pack_data) {
-        Ok((mut data, _header)) => {
-            while !data.is_empty() {
-                match crate::git::pack::Object::parse(data) {
-                    Ok((new_data, object)) => {
-                        parsed_objects.push(object);
-                        data = new_data;
-                    }
-                    Err(_) => break,
-                }
-            }
-        }
-        Err(e) => {
-            return Err(format!("Error parsing pack header: {e:?}"));
-        }
+    // The index lists the objects without parsing them; without a usable
+    // one, the pack is walked and its deltas resolved to name them
+    match PackReader::open(pack_path) {
+        Ok(reader) => list_indexed_pack_objects(Arc::new(reader)),
+        Err(_) => list_unindexed_pack_objects(pack_path),
     }
+}
 
-    // Resolve delta chains so every object gets its real git object ID;
-    // unresolvable objects (e.g. thin-pack deltas) get sha1 = None
-    let resolved = crate::git::pack::resolve_objects(&parsed_objects);
+/// The pack's objects in pack order, with only their headers read; the
+/// entries themselves are read when each object is selected
+fn list_indexed_pack_objects(reader: Arc<PackReader>) -> Result<Vec<PackObject>, String> {
+    let compat_map = reader.index().and_then(pack_compat_map);
+    let names = reader
+        .entries()
+        .map(|entry| (entry.offset, Some(hex::encode(entry.object_id))))
+        .collect();
+    list_pack_objects(reader, names, compat_map.as_ref())
+}
 
-    let objects: Vec<PackObject> = parsed_objects
+/// The objects of a pack that has no index, named by resolving their
+/// deltas; unresolvable ones (e.g. thin-pack deltas) get sha1 = None
+fn list_unindexed_pack_objects(pack_path: &Path) -> Result<Vec<PackObject>, String> {
+    let object_format = ObjectFormat::from_pack_name(pack_path).unwrap_or_default();
+    let reader = PackReader::open_unindexed(pack_path, object_format)
+        .map_err(|e| format!("Error reading pack file: {e}"))?;
+    let offsets = reader.entry_offsets();
+    let resolved = crate::git::pack::resolve_objects(&reader, &offsets);
+    let names = offsets
         .into_iter()
         .zip(resolved)
+        .map(|(offset, resolved)| (offset, resolved.map(|resolved| resolved.sha1)))
+        .collect();
+    let compat_map = load_pack_compat_map(pack_path);
+    list_pack_objects(Arc::new(reader), names, compat_map.as_ref())
+}
+
+/// The entries at the offsets of `names`, in order, with the object ID
+/// each is known by; an unreadable header fails the whole list rather
+/// than hiding the objects after it
+fn list_pack_objects(
+    reader: Arc<PackReader>,
+    names: Vec<(u64, Option<String>)>,
+    compat_map: Option<&CompatMap>,
+) -> Result<Vec<PackObject>, String> {
+    names
+        .into_iter()
         .enumerate()
-        .map(|(index, (object, resolved))| {
-            let base_info = match &object.header {
-                crate::git::pack::ObjectHeader::OfsDelta { base_offset, .. } => {
-                    Some(format!("Base offset: {base_offset}"))
-                }
-                crate::git::pack::ObjectHeader::RefDelta { base_ref, .. } => {
-                    Some(format!("Base ref: {}", hex::encode(base_ref)))
-                }
-                crate::git::pack::ObjectHeader::Regular { .. } => None,
-            };
-            PackObject {
-                index: index + 1,
-                obj_type: object.header.obj_type().to_string(),
-                size: u32::try_from(object.header.uncompressed_data_size()).unwrap_or(u32::MAX),
-                sha1: resolved.map(|r| r.sha1),
-                base_info,
-                object_data: Some(object),
-            }
+        .map(|(position, (offset, sha1))| {
+            let header = reader
+                .read_header_at(offset)
+                .map_err(|e| format!("Error reading object header at offset {offset}: {e}"))?;
+            let compat_id = sha1
+                .as_ref()
+                .zip(compat_map)
+                .and_then(|(sha1, map)| map.compat_id(sha1));
+            Ok(PackObject {
+                index: position + 1,
+                obj_type: header.obj_type().to_string(),
+                size: u32::try_from(header.uncompressed_data_size()).unwrap_or(u32::MAX),
+                sha1,
+                compat_id,
+                base_info: base_info(&header),
+                object_data: None,
+                source: Some(PackObjectSource {
+                    reader: Arc::clone(&reader),
+                    offset,
+                }),
+            })
         })
-        .collect();
+        .collect()
+}
+
+/// Where a delta object's base is, for the object list
+fn base_info(header: &ObjectHeader) -> Option<String> {
+    match header {
+        ObjectHeader::OfsDelta { base_offset, .. } => Some(format!("Base offset: {base_offset}")),
+        ObjectHeader::RefDelta { base_ref, .. } => {
+            Some(format!("Base ref: {}", hex::encode(base_ref)))
+        }
+        ObjectHeader::Regular { .. } => None,
+    }
+}
+
+/// The compat IDs of a pack's objects, from the second object format of
+/// its version-3 index
+fn load_pack_compat_map(pack_path: &Path) -> Option<CompatMap> {
+    let data = std::fs::read(pack_path.with_extension("idx")).ok()?;
+    // Older versions have no compat tables; skip parsing them
+    if data.get(4..8) != Some(&3u32.to_be_bytes()[..]) {
+        return None;
+    }
+    let (_, index) = PackIndex::parse(&data).ok()?;
+    pack_compat_map(&index)
+}
 
-    Ok(objects)
+/// The compat IDs an index's compat tables give its objects
+fn pack_compat_map(index: &PackIndex) -> Option<CompatMap> {
+    let compat_format = index.compat.as_ref()?.object_format;
+    let mut map = CompatMap::new(index.object_format, compat_format);
+    map.add_pack_index(index);
+    Some(map)
 }
 
 /// Build the initial Git objects list without touching AppState/UI.
@@ -122,6 +122,16 @@ impl AppState {
                                     && r.pack_reverse_index_widget.is_none()
                                     && r.pack_mtimes_widget.is_none()
                                     && r.multi_pack_index_widget.is_none()
+                                    && r.git_index_widget.is_none()
+                                    && r.packed_refs_widget.is_none()
+                                    && r.reflog_widget.is_none()
+                                    && r.commit_graph_widget.is_none()
+                                    && r.commit_graph_chain_widget.is_none()
+                                    && r.reftable_widget.is_none()
+                                    && r.reftable_stack_widget.is_none()
+                                    && r.loose_object_idx_widget.is_none()
+                                    && r.multi_pack_index_chain_widget.is_none()
+                                    && r.worktree_widget.is_none()
                                 {
                                     r.preview_scroll_position = snap.preview_scroll;
                                 }
@@ -209,6 +219,16 @@ impl AppState {
                                 regular_state.pack_mtimes_widget = None;
                                 regular_state.pack_bitmap_widget = None;
                                 regular_state.multi_pack_index_widget = None;
+                                regular_state.git_index_widget = None;
+                                regular_state.packed_refs_widget = None;
+                                regular_state.reflog_widget = None;
+                                regular_state.commit_graph_widget = None;
+                                regular_state.commit_graph_chain_widget = None;
+                                regular_state.reftable_widget = None;
+                                regular_state.reftable_stack_widget = None;
+                                regular_state.loose_object_idx_widget = None;
+                                regular_state.multi_pack_index_chain_widget = None;
+                                regular_state.worktree_widget = None;
                             }
                             PreviewState::Pack(_) => {
                                 // Preserve pack preview state - don't reset it!
@@ -307,6 +327,169 @@ impl AppState {
                 }
             },
 
+            Message::LoadGitIndexDetails(result) => match *result {
+                Ok(git_index) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        // Switch to Regular preview state with git index widget
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_git_index(
+                                git_index,
+                            ),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadPackedRefsDetails(result) => match *result {
+                Ok(packed_refs) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        // Switch to Regular preview state with packed-refs widget
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_packed_refs(
+                                packed_refs,
+                            ),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadReflogDetails { git_dir, result } => match *result {
+                Ok(reflog) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        // Switch to Regular preview state with reflog timeline widget
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_reflog(
+                                reflog, git_dir,
+                            ),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadCommitGraphDetails {
+                git_dir,
+                first_position,
+                result,
+            } => match *result {
+                Ok(commit_graph) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_commit_graph(
+                                commit_graph,
+                                first_position,
+                                git_dir,
+                            ),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadCommitGraphChainDetails(result) => {
+                match *result {
+                    Ok(commit_graph_chain) => {
+                        if let AppView::Main { state } = &mut self.view {
+                            state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_commit_graph_chain(commit_graph_chain),
+                        );
+                            self.error = None;
+                        }
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                    }
+                }
+            }
+
+            Message::LoadReftableDetails(result) => match *result {
+                Ok(reftable) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_reftable(reftable),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadReftableStackDetails(result) => match *result {
+                Ok(reftable_stack) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_reftable_stack(
+                                reftable_stack,
+                            ),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadLooseObjectIdxDetails(result) => match *result {
+                Ok(loose_object_idx) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_loose_object_idx(
+                                loose_object_idx,
+                            ),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadMultiPackIndexChainDetails(result) => match *result {
+                Ok(multi_pack_index_chain) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_multi_pack_index_chain(multi_pack_index_chain),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
+            Message::LoadWorktreeDetails(result) => match *result {
+                Ok(worktree) => {
+                    if let AppView::Main { state } = &mut self.view {
+                        state.preview_state = PreviewState::Regular(
+                            crate::tui::main_view::RegularPreViewState::new_with_worktree(worktree),
+                        );
+                        self.error = None;
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                }
+            },
+
             Message::LoadPackObjects { path, result } => match result {
                 Ok(objects) => {
                     if let AppView::Main {
@@ -325,7 +508,7 @@ impl AppState {
                             preview_state.pack_object_list_scroll_position = 0;
                             if !preview_state.pack_object_list.is_empty() {
                                 preview_state.pack_object_widget_state = PackObjectWidget::new(
-                                    preview_state.pack_object_list[0].clone(),
+                                    preview_state.pack_object_list[0].with_object_data(),
                                 );
                             }
                             self.error = None;
@@ -361,6 +544,16 @@ impl AppState {
                                 || r.pack_mtimes_widget.is_some()
                                 || r.pack_bitmap_widget.is_some()
                                 || r.multi_pack_index_widget.is_some()
+                                || r.git_index_widget.is_some()
+                                || r.packed_refs_widget.is_some()
+                                || r.reflog_widget.is_some()
+                                || r.commit_graph_widget.is_some()
+                                || r.commit_graph_chain_widget.is_some()
+                                || r.reftable_widget.is_some()
+                                || r.reftable_stack_widget.is_some()
+                                || r.loose_object_idx_widget.is_some()
+                                || r.multi_pack_index_chain_widget.is_some()
+                                || r.worktree_widget.is_some()
                             {
                                 0 // PackIndex, PackReverseIndex and PackMtimes widgets manage their own scrolling
                             } else {
@@ -381,14 +574,17 @@ impl AppState {
 
                     // Ghosts are pruned during flatten_tree; no explicit cleanup needed here.
                 }
-                // Reload everything from scratch
+                // Reload everything from scratch, packs included
+                plumber.refresh_object_databases();
                 self.effects.push(crate::tui::message::Command::LoadInitial);
             }
 
             Message::MainNavigation(_)
             | Message::OpenMainView
             | Message::OpenPackView
-            | Message::OpenLooseObjectView => {
+            | Message::OpenLooseObjectView
+            | Message::OpenReflogEntry
+            | Message::BloomQuery(_) => {
                 return self.handle_main_view_mode_message(msg, plumber);
             }
 
@@ -409,6 +605,16 @@ impl AppState {
             | Message::LoadPackMtimesDetails(_)
             | Message::LoadPackBitmapDetails(_)
             | Message::LoadMultiPackIndexDetails(_)
+            | Message::LoadGitIndexDetails(_)
+            | Message::LoadPackedRefsDetails(_)
+            | Message::LoadReflogDetails { .. }
+            | Message::LoadCommitGraphDetails { .. }
+            | Message::LoadCommitGraphChainDetails(_)
+            | Message::LoadReftableDetails(_)
+            | Message::LoadReftableStackDetails(_)
+            | Message::LoadLooseObjectIdxDetails(_)
+            | Message::LoadMultiPackIndexChainDetails(_)
+            | Message::LoadWorktreeDetails(_)
             | Message::LoadPackObjects { .. }
             | Message::GitObjectsLoaded(_) => {
                 return self.handle_load_result_message(msg, plumber);
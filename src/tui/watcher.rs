@@ -1,11 +1,22 @@
-use std::path::Path;
+use std::path::PathBuf;
 use std::sync::mpsc as std_mpsc;
 use std::time::Duration;
 
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 
+/// Watch a repository's git directories for changes
+///
+/// `dirs` is usually the git directory alone, but the common directory
+/// and the object store can live elsewhere (linked worktrees,
+/// `GIT_OBJECT_DIRECTORY`); directories inside another one are watched
+/// through it.
+///
+/// `checkouts` are the directories of linked worktrees: only their `.git`
+/// file matters here, since removing it makes the worktree prunable, so
+/// they are watched without recursion and other changes in them ignored.
 pub fn spawn_git_watcher(
-    repo_path: &Path,
+    dirs: &[PathBuf],
+    checkouts: &[PathBuf],
     tx_ui: crossbeam_channel::Sender<crate::tui::message::Message>,
 ) -> Result<RecommendedWatcher, notify::Error> {
     // Std mpsc channel for notify callback; we'll bridge to crossbeam
@@ -18,10 +29,25 @@ pub fn spawn_git_watcher(
 
     // No extra config necessary for notify v8 here; we do our own debounce below.
 
-    // Watch the .git directory recursively if it exists
-    let git_dir = repo_path.join(".git");
-    if git_dir.exists() {
-        watcher.watch(&git_dir, RecursiveMode::Recursive)?;
+    // Watch each git directory recursively if it exists
+    for (index, dir) in dirs.iter().enumerate() {
+        let nested = dirs
+            .iter()
+            .enumerate()
+            .any(|(other, parent)| other != index && dir.starts_with(parent) && dir != parent);
+        let duplicate = dirs[..index].contains(dir);
+        if dir.exists() && !nested && !duplicate {
+            watcher.watch(dir, RecursiveMode::Recursive)?;
+        }
+    }
+
+    // Watch each linked worktree's checkout for its .git file only
+    let mut watched_checkouts = Vec::new();
+    for checkout in checkouts {
+        if checkout.is_dir() && !dirs.iter().any(|dir| checkout.starts_with(dir)) {
+            watcher.watch(checkout, RecursiveMode::NonRecursive)?;
+            watched_checkouts.push(checkout.clone());
+        }
     }
 
     // Quiet-period debounce with dynamic blocking: block when idle, timeout when pending
@@ -41,7 +67,7 @@ pub fn spawn_git_watcher(
 
             match recv_result {
                 Ok(Ok(event)) => {
-                    if is_meaningful(&event) {
+                    if is_meaningful(&event) && !is_checkout_noise(&event, &watched_checkouts) {
                         pending = true;
                     }
                 }
@@ -78,3 +104,14 @@ fn is_meaningful(event: &Event) -> bool {
         EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) | EventKind::Any
     )
 }
+
+/// Whether an event only concerns files of a worktree checkout other than
+/// its `.git` file
+fn is_checkout_noise(event: &Event, checkouts: &[PathBuf]) -> bool {
+    !event.paths.is_empty()
+        && event.paths.iter().all(|path| {
+            path.parent()
+                .is_some_and(|parent| checkouts.iter().any(|checkout| checkout == parent))
+                && path.file_name().is_none_or(|name| name != ".git")
+        })
+}
@@ -0,0 +1,135 @@
+use crate::git::commit_graph::CommitGraphChain;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Commits beyond this count are summarized instead of listed
+const MAX_LISTED_COMMITS: usize = 1000;
+/// Object IDs are abbreviated to this many hex digits in the table
+const SHORT_OID_LEN: usize = 12;
+
+pub struct ChainCommitsFormatter<'a> {
+    chain: &'a CommitGraphChain,
+}
+
+impl<'a> ChainCommitsFormatter<'a> {
+    #[must_use]
+    pub const fn new(chain: &'a CommitGraphChain) -> Self {
+        Self { chain }
+    }
+
+    pub fn format_commits(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "COMMITS ACROSS LAYERS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  A commit's global position is its layer's first position plus its row",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  in that layer's OIDL. Parents are global positions, shown with the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  layer they live in (e.g. 3@L0).",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let commit_count = self.chain.commit_count();
+        if commit_count == 0 {
+            lines.push(Line::from("No commits in this chain."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("Global", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Layer", bold),
+            Span::styled(" │ ", gray),
+            Span::styled(" Local", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Commit      ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Parents", bold),
+        ]));
+        lines.push(Line::styled(
+            "───────┼───────┼────────┼──────────────┼──────────────",
+            gray,
+        ));
+
+        for position in 0..commit_count.min(MAX_LISTED_COMMITS) {
+            self.format_commit(position, lines);
+        }
+
+        if commit_count > MAX_LISTED_COMMITS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more commits not shown",
+                    commit_count - MAX_LISTED_COMMITS
+                ),
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_commit(&self, position: usize, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+        let Some((layer_index, local)) = self.chain.locate(position) else {
+            return;
+        };
+        let graph = &self.chain.layers[layer_index].graph;
+        let oid = graph.oid_hex_at(local).unwrap_or_default();
+
+        let mut spans = vec![
+            Span::styled(format!("{position:6}"), Style::default().fg(Color::Cyan)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                format!("{:>5}", format!("L{layer_index}")),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{local:6}")),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                oid[..SHORT_OID_LEN.min(oid.len())].to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(" │ ", gray),
+        ];
+
+        match graph.parent_positions(local) {
+            Some(parents) if parents.is_empty() => spans.push(Span::styled("(root)", gray)),
+            Some(parents) => {
+                for (index, &parent) in parents.iter().enumerate() {
+                    if index > 0 {
+                        spans.push(Span::from(", "));
+                    }
+                    let parent_layer = self
+                        .chain
+                        .locate(parent as usize)
+                        .map_or_else(|| "?".to_string(), |(layer, _)| format!("L{layer}"));
+                    let style = if parent_layer == format!("L{layer_index}") {
+                        Style::default().fg(Color::LightBlue)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    spans.push(Span::styled(format!("{parent}@{parent_layer}"), style));
+                }
+            }
+            None => spans.push(Span::styled(
+                "(broken EDGE list)",
+                Style::default().fg(Color::Red),
+            )),
+        }
+
+        lines.push(Line::from(spans));
+    }
+}
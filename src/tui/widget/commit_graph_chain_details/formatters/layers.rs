@@ -0,0 +1,124 @@
+use crate::git::commit_graph::{BaseCheck, CommitGraphChain, CommitGraphLayer};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Checksums are abbreviated to this many hex digits in the table
+const SHORT_HASH_LEN: usize = 12;
+
+pub struct LayersFormatter<'a> {
+    chain: &'a CommitGraphChain,
+}
+
+impl<'a> LayersFormatter<'a> {
+    #[must_use]
+    pub const fn new(chain: &'a CommitGraphChain) -> Self {
+        Self { chain }
+    }
+
+    pub fn format_layers(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "LAYERS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  In chain order, base layer first. Each layer's BASE chunk must list",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  exactly the layers below it, and its trailing checksum must be the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  hash in its graph-<hash>.graph file name.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        if self.chain.layers.is_empty() {
+            lines.push(Line::from("The chain lists no layers."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("Layer", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Checksum    ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Commits", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Positions    ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Name", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Base list", bold),
+        ]));
+        lines.push(Line::styled(
+            "──────┼──────────────┼─────────┼───────────────┼──────┼─────────────────────",
+            gray,
+        ));
+
+        for (index, layer) in self.chain.layers.iter().enumerate() {
+            Self::format_layer(index, layer, lines);
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_layer(index: usize, layer: &CommitGraphLayer, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+        let ok = Style::default().fg(Color::LightGreen);
+        let bad = Style::default().fg(Color::Red);
+        let positions = layer.positions();
+
+        let name_check = if layer.checksum_matches_name() {
+            Span::styled("✓   ", ok)
+        } else {
+            Span::styled("✗   ", bad)
+        };
+        let base_check = match &layer.base_check {
+            BaseCheck::Matches if index == 0 => Span::styled("✓ (no bases)", ok),
+            BaseCheck::Matches if index == 1 => Span::styled("✓ layer 0", ok),
+            BaseCheck::Matches => Span::styled(format!("✓ layers 0-{}", index - 1), ok),
+            BaseCheck::CountMismatch { expected, found } => {
+                Span::styled(format!("✗ lists {found} bases, expected {expected}"), bad)
+            }
+            BaseCheck::Mismatch {
+                index: base_index,
+                expected,
+                found,
+            } => Span::styled(
+                format!(
+                    "✗ base {base_index} is {}, expected {}",
+                    &found[..SHORT_HASH_LEN.min(found.len())],
+                    &expected[..SHORT_HASH_LEN.min(expected.len())]
+                ),
+                bad,
+            ),
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{index:5}"), Style::default().fg(Color::Cyan)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                layer.hash[..SHORT_HASH_LEN.min(layer.hash.len())].to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{:7}", layer.graph.commit_count())),
+            Span::styled(" │ ", gray),
+            Span::from(format!(
+                "{:<13}",
+                format!("{}..{}", positions.start, positions.end)
+            )),
+            Span::styled(" │ ", gray),
+            name_check,
+            Span::styled(" │ ", gray),
+            base_check,
+        ]));
+    }
+}
@@ -0,0 +1,105 @@
+pub mod commits;
+pub mod layers;
+
+use crate::git::commit_graph::CommitGraphChain;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+pub use commits::ChainCommitsFormatter;
+pub use layers::LayersFormatter;
+
+pub struct CommitGraphChainFormatter<'a> {
+    chain: &'a CommitGraphChain,
+}
+
+impl<'a> CommitGraphChainFormatter<'a> {
+    #[must_use]
+    pub const fn new(chain: &'a CommitGraphChain) -> Self {
+        Self { chain }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = vec![
+            Line::from("A split commit-graph: instead of rewriting one big file, each"),
+            Line::from("`git commit-graph write --split` adds a layer on top, and layers"),
+            Line::from("are merged when the upper ones grow too large. The chain file"),
+            Line::from("lists the layers' checksums, base layer first; each layer names"),
+            Line::from("the layers below it in its BASE chunk. Graph positions run across"),
+            Line::from("layers, so parents can point into any layer below."),
+            Line::from(""),
+        ];
+
+        self.add_chain_section(&mut lines);
+        LayersFormatter::new(self.chain).format_layers(&mut lines);
+        ChainCommitsFormatter::new(self.chain).format_commits(&mut lines);
+        self.add_stack_diagram(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_chain_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "COMMIT-GRAPH CHAIN",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Chain file: "),
+            Span::styled(self.chain.path.display().to_string(), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Layers: "),
+            Span::styled(format!("{}", self.chain.layers.len()), value_style),
+            Span::from(", total commits: "),
+            Span::styled(format!("{}", self.chain.commit_count()), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Consistency: "),
+            if self.chain.is_consistent() {
+                Span::styled("✓ every layer matches the chain", value_style)
+            } else {
+                Span::styled(
+                    "✗ some layers disagree with the chain (see below)",
+                    Style::default().fg(Color::Red),
+                )
+            },
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    fn add_stack_diagram(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "LAYER STACK",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        lines.push(Line::from("┌─────────────────────────────────┐"));
+        for (index, layer) in self.chain.layers.iter().enumerate().rev() {
+            let positions = layer.positions();
+            let label = if index == 0 {
+                format!("Layer {index} (base)")
+            } else {
+                format!("Layer {index}")
+            };
+            lines.push(Line::from(format!(
+                "│ {label:<18} {:<12} │ positions {}..{}",
+                &layer.hash[..12.min(layer.hash.len())],
+                positions.start,
+                positions.end
+            )));
+            if index > 0 {
+                lines.push(Line::from("├─────────────────────────────────┤"));
+            }
+        }
+        lines.push(Line::from("└─────────────────────────────────┘"));
+        lines.push(Line::from(""));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::git::commit_graph::bloom::normalize_path;
+use crate::git::commit_graph::{BloomAnswer, BloomCheck, BloomFilters, CommitGraph};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+use super::commits::MAX_LISTED_COMMITS;
+
+/// Object IDs are abbreviated to this many hex digits in the tables
+const SHORT_OID_LEN: usize = 12;
+
+pub struct BloomFormatter<'a> {
+    commit_graph: &'a CommitGraph,
+    bloom_filters: &'a BloomFilters,
+    first_position: usize,
+}
+
+impl<'a> BloomFormatter<'a> {
+    /// None when the commit-graph has no Bloom filter chunks
+    #[must_use]
+    pub fn new(commit_graph: &'a CommitGraph, first_position: usize) -> Option<Self> {
+        Some(Self {
+            commit_graph,
+            bloom_filters: commit_graph.bloom_filters.as_ref()?,
+            first_position,
+        })
+    }
+
+    pub fn format_bloom_overview(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "CHANGED-PATH BLOOM FILTERS (BIDX + BDAT)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        for text in [
+            "  Each commit has a small Bloom filter of the paths it changed against its",
+            "  first parent (plus their leading directories). A path sets a few bits;",
+            "  if any of them is clear, the commit definitely didn't touch the path.",
+            "  That is why `git log -- <path>` is fast: it only diffs the commits whose",
+            "  filter answers \"maybe\". False positives cost a diff, never a wrong result.",
+        ] {
+            lines.push(Line::from(vec![Span::styled(text, gray)]));
+        }
+        lines.push(Line::from(""));
+
+        let settings = self.bloom_filters.settings;
+        let value_style = Style::default().fg(Color::LightGreen);
+        let hash_description = match settings.hash_version {
+            1 => "murmur3 over sign-extended bytes, git < 2.42",
+            _ => "murmur3 over unsigned bytes",
+        };
+        lines.push(Line::from(vec![
+            Span::from("  Hash version: "),
+            Span::styled(
+                format!("{} ({hash_description})", settings.hash_version),
+                value_style,
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Hashes per path: "),
+            Span::styled(format!("{}", settings.num_hashes), value_style),
+            Span::from(", bits per entry: "),
+            Span::styled(format!("{}", settings.bits_per_entry), value_style),
+        ]));
+
+        let commit_count = self.commit_graph.commit_count();
+        let filters = || (0..commit_count).filter_map(|p| self.bloom_filters.filter(p));
+        let not_computed = filters().filter(|filter| filter.is_empty()).count();
+        let truncated = (0..commit_count)
+            .filter(|&position| self.bloom_filters.is_truncated_large(position))
+            .count();
+        lines.push(Line::from(vec![
+            Span::from("  Filters: "),
+            Span::styled(
+                format!("{}", commit_count - not_computed - truncated),
+                value_style,
+            ),
+            Span::from(" computed, "),
+            Span::styled(format!("{truncated}"), value_style),
+            Span::from(" too large (all bits set), "),
+            Span::styled(format!("{not_computed}"), value_style),
+            Span::from(" not computed"),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Filter data: "),
+            Span::styled(
+                format!("{} bytes", self.bloom_filters.data.len()),
+                value_style,
+            ),
+            Span::from(format!(
+                " (BIDX {} bytes, BDAT {} bytes)",
+                self.bloom_filters.index.len() * 4,
+                self.bloom_filters.bdat_size()
+            )),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    /// Answer of every commit's filter for `path`
+    pub fn format_query(&self, path: &str, lines: &mut Vec<Line<'static>>) {
+        let path = normalize_path(path);
+        lines.push(Line::styled(
+            format!("PATH QUERY: {path}"),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  The path and each leading directory are looked up; all must answer",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  \"maybe\" for the commit to be diffed. Bit positions are the hashes",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  modulo the filter size in bits.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let mut keys = vec![path];
+        keys.extend(path.match_indices('/').map(|(i, _)| &path[..i]));
+        for key in keys {
+            let hashes = self
+                .bloom_filters
+                .key_hashes(key)
+                .iter()
+                .map(|hash| format!("{hash:08x}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {key}: "), Style::default().fg(Color::Yellow)),
+                Span::styled(hashes, gray),
+            ]));
+        }
+        lines.push(Line::from(""));
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("   Pos", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Commit      ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Filter", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Answer", bold),
+        ]));
+        lines.push(Line::styled(
+            "───────┼──────────────┼────────┼────────────────",
+            gray,
+        ));
+
+        let commit_count = self.commit_graph.commit_count();
+        let mut diffed = 0;
+        for position in 0..commit_count {
+            let answer = self.bloom_filters.query(position, path);
+            if answer != Some(BloomAnswer::DefinitelyNot) {
+                diffed += 1;
+            }
+            if position >= MAX_LISTED_COMMITS {
+                continue;
+            }
+
+            let filter_size = self.bloom_filters.filter(position).map_or(0, <[u8]>::len);
+            let answer = match answer {
+                Some(BloomAnswer::Maybe) if self.bloom_filters.is_truncated_large(position) => {
+                    Span::styled("maybe (too large)", Style::default().fg(Color::Yellow))
+                }
+                Some(BloomAnswer::Maybe) => {
+                    Span::styled("maybe", Style::default().fg(Color::Yellow))
+                }
+                Some(BloomAnswer::DefinitelyNot) => {
+                    Span::styled("definitely not", Style::default().fg(Color::LightGreen))
+                }
+                Some(BloomAnswer::NotComputed) | None => {
+                    Span::styled("no filter", Style::default().fg(Color::Magenta))
+                }
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:6}", self.first_position + position),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::from(self.short_oid(position)),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{filter_size:4} B")),
+                Span::styled(" │ ", gray),
+                answer,
+            ]));
+        }
+        if commit_count > MAX_LISTED_COMMITS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more commits not shown",
+                    commit_count - MAX_LISTED_COMMITS
+                ),
+                gray,
+            )]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::from("  `git log -- "),
+            Span::from(path.to_string()),
+            Span::from("` diffs "),
+            Span::styled(format!("{diffed}"), Style::default().fg(Color::LightGreen)),
+            Span::from(format!(
+                " of {commit_count} commits; the filters rule out the rest."
+            )),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    /// Outcome of recomputing the filters from tree diffs
+    pub fn format_verification(&self, checks: &[BloomCheck], lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "FILTER VERIFICATION",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Each filter is rebuilt from the diff of the commit's root tree against",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  its first parent's. A changed path the stored filter rules out is a",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  false negative: `git log -- <path>` would silently skip the commit.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let count =
+            |predicate: fn(&BloomCheck) -> bool| checks.iter().filter(|c| predicate(c)).count();
+        let identical = count(|check| matches!(check, BloomCheck::Identical { .. }));
+        let differs = count(|check| matches!(check, BloomCheck::Differs { .. }));
+        let false_negatives = count(|check| matches!(check, BloomCheck::FalseNegatives(_)));
+        let not_computed = count(|check| matches!(check, BloomCheck::NotComputed));
+        let skipped = count(|check| matches!(check, BloomCheck::Skipped(_)));
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Identical: "),
+            Span::styled(format!("{identical}"), value_style),
+            Span::from(", different but sound: "),
+            Span::styled(format!("{differs}"), value_style),
+            Span::from(", not computed: "),
+            Span::styled(format!("{not_computed}"), value_style),
+            Span::from(", skipped: "),
+            Span::styled(format!("{skipped}"), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  False negatives: "),
+            if false_negatives == 0 {
+                Span::styled("none ✓", value_style)
+            } else {
+                Span::styled(
+                    format!("{false_negatives} commits ✗"),
+                    Style::default().fg(Color::Red),
+                )
+            },
+        ]));
+        lines.push(Line::from(""));
+
+        for (position, check) in checks.iter().enumerate().take(MAX_LISTED_COMMITS) {
+            let detail = match check {
+                BloomCheck::Identical { .. } | BloomCheck::NotComputed => continue,
+                BloomCheck::Differs { changed_paths } => Span::styled(
+                    format!("differs from git's filter ({changed_paths} paths), no false negative"),
+                    Style::default().fg(Color::Yellow),
+                ),
+                BloomCheck::FalseNegatives(paths) => Span::styled(
+                    format!("false negatives: {}", paths.join(", ")),
+                    Style::default().fg(Color::Red),
+                ),
+                BloomCheck::Skipped(reason) => Span::styled(
+                    format!("skipped: {reason}"),
+                    Style::default().fg(Color::Magenta),
+                ),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:6}", self.first_position + position),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::from(self.short_oid(position)),
+                Span::styled(" │ ", gray),
+                detail,
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn short_oid(&self, position: usize) -> String {
+        let oid = self.commit_graph.oid_hex_at(position).unwrap_or_default();
+        oid[..SHORT_OID_LEN.min(oid.len())].to_string()
+    }
+}
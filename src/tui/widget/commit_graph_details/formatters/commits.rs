@@ -0,0 +1,243 @@
+use crate::git::commit_graph::CommitGraph;
+use crate::tui::widget::formatters_utils::format_epoch_with_offset;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Commits beyond this count are summarized instead of listed
+pub const MAX_LISTED_COMMITS: usize = 1000;
+/// Object IDs are abbreviated to this many hex digits in the table
+const SHORT_OID_LEN: usize = 12;
+
+pub struct CommitsFormatter<'a> {
+    commit_graph: &'a CommitGraph,
+    first_position: usize,
+}
+
+impl<'a> CommitsFormatter<'a> {
+    /// `first_position` is the global graph position of the file's first
+    /// commit: zero for a standalone commit-graph, the commit count of the
+    /// layers below for a layer of a split chain
+    #[must_use]
+    pub const fn new(commit_graph: &'a CommitGraph, first_position: usize) -> Self {
+        Self {
+            commit_graph,
+            first_position,
+        }
+    }
+
+    pub fn format_commits_overview(&self, lines: &mut Vec<Line<'static>>) {
+        self.format_generation_summary(lines);
+        self.format_commit_table(lines);
+    }
+
+    fn format_generation_summary(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "GENERATION DATA (GDA2 + GDO2)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Generation numbers let walks stop early: a commit can't reach one with",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  a higher generation. v1 is the topological level stored in CDAT; v2, the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  corrected commit date, is the commit time plus the GDA2 offset. Offsets",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  that don't fit in 31 bits have the high bit set and index GDO2 instead.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Total commits: "),
+            Span::styled(format!("{}", self.commit_graph.commit_count()), value_style),
+            Span::from(", octopus merges (EDGE): "),
+            Span::styled(
+                format!("{}", self.commit_graph.octopus_count()),
+                value_style,
+            ),
+        ]));
+        if self.commit_graph.generation_data.is_some() {
+            lines.push(Line::from(vec![
+                Span::from("  Generation data: "),
+                Span::styled("v2 (corrected commit dates)", value_style),
+                Span::from(", overflowing into GDO2: "),
+                Span::styled(
+                    format!("{}", self.commit_graph.generation_overflow_count()),
+                    value_style,
+                ),
+            ]));
+        } else {
+            lines.push(Line::from(vec![
+                Span::from("  Generation data: "),
+                Span::styled("v1 only (topological levels, no GDA2 chunk)", value_style),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_commit_table(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "COMMITS (OIDL + CDAT)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Commit IDs in lexicographic order (OIDL); a commit's row number is its",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  graph position, which is how parents refer to it in CDAT and EDGE.",
+            gray,
+        )]));
+        if self.commit_graph.base_graph_count > 0 {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  In a split chain positions are global: this layer starts at {}, and",
+                    self.first_position
+                ),
+                gray,
+            )]));
+            lines.push(Line::from(vec![Span::styled(
+                "  parents below that (dimmed) live in the base layers.",
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+
+        let commit_count = self.commit_graph.commit_count();
+        if commit_count == 0 {
+            lines.push(Line::from("No commits in this commit-graph."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("   Pos", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Commit      ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Root tree   ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Level", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Commit time              ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Gen. offset", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Parents", bold),
+        ]));
+        lines.push(Line::styled(
+            "───────┼──────────────┼──────────────┼───────┼───────────────────────────┼─────────────┼──────────",
+            gray,
+        ));
+
+        for position in 0..commit_count.min(MAX_LISTED_COMMITS) {
+            self.format_commit(position, lines);
+        }
+
+        if commit_count > MAX_LISTED_COMMITS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more commits not shown",
+                    commit_count - MAX_LISTED_COMMITS
+                ),
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_commit(&self, position: usize, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+        let commit = &self.commit_graph.commits[position];
+        let oid = self.commit_graph.oid_hex_at(position).unwrap_or_default();
+
+        let generation = match self.commit_graph.corrected_commit_date(position) {
+            Some(date) => {
+                let overflowed = self
+                    .commit_graph
+                    .generation_data
+                    .as_ref()
+                    .and_then(|data| data.get(position))
+                    .is_some_and(|&value| value & CommitGraph::GENERATION_OVERFLOW != 0);
+                let style = if overflowed {
+                    Style::default().fg(Color::Magenta)
+                } else {
+                    Style::default().fg(Color::LightGreen)
+                };
+                Span::styled(format!("{:>11}", date - commit.commit_time), style)
+            }
+            None => Span::styled(format!("{:>11}", "-"), gray),
+        };
+
+        let parents = match self.commit_graph.parent_positions(position) {
+            Some(parents) if parents.is_empty() => vec![Span::styled("(root)", gray)],
+            Some(parents) => {
+                let mut spans = Vec::new();
+                for (index, &parent) in parents.iter().enumerate() {
+                    if index > 0 {
+                        spans.push(Span::from(", "));
+                    }
+                    let style = if (parent as usize) < self.first_position {
+                        Style::default().fg(Color::DarkGray)
+                    } else {
+                        Style::default().fg(Color::LightBlue)
+                    };
+                    spans.push(Span::styled(parent.to_string(), style));
+                }
+                if commit.is_octopus() {
+                    spans.push(Span::styled(
+                        " (EDGE)",
+                        Style::default().fg(Color::LightBlue),
+                    ));
+                }
+                spans
+            }
+            None => vec![Span::styled(
+                "(broken EDGE list)",
+                Style::default().fg(Color::Red),
+            )],
+        };
+
+        let mut spans = vec![
+            Span::styled(
+                format!("{:6}", self.first_position + position),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                oid[..SHORT_OID_LEN.min(oid.len())].to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(" │ ", gray),
+            Span::from(hex::encode(&commit.root_tree)[..SHORT_OID_LEN].to_string()),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{:5}", commit.topological_level)),
+            Span::styled(" │ ", gray),
+            Span::from(format_epoch_with_offset(
+                i64::try_from(commit.commit_time).unwrap_or(i64::MAX),
+                0,
+            )),
+            Span::styled(" │ ", gray),
+            generation,
+            Span::styled(" │ ", gray),
+        ];
+        spans.extend(parents);
+        lines.push(Line::from(spans));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::git::commit_graph::CommitGraph;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+pub struct HeaderFormatter<'a> {
+    commit_graph: &'a CommitGraph,
+}
+
+impl<'a> HeaderFormatter<'a> {
+    #[must_use]
+    pub const fn new(commit_graph: &'a CommitGraph) -> Self {
+        Self { commit_graph }
+    }
+
+    pub fn format_header(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "COMMIT-GRAPH HEADER",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let border_style = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::LightGreen);
+
+        lines.push(Line::from(vec![
+            Span::styled(" Byte ", border_style),
+            Span::styled("│ ", border_style),
+            Span::styled(
+                "Field            ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("│ ", border_style),
+            Span::styled("Value", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "──────┼──────────────────┼─────────────────────",
+            border_style,
+        ));
+
+        let raw = &self.commit_graph.raw_data;
+        let signature_utf8: String = raw[0..4].iter().map(|&b| b as char).collect();
+
+        let mut push_row = |byte_range: &str, field: &str, value: String| {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {byte_range:<5}"), border_style),
+                Span::styled("│ ", border_style),
+                Span::from(format!("{field:<17}")),
+                Span::styled("│ ", border_style),
+                Span::styled(value, value_style),
+            ]));
+        };
+
+        push_row(
+            "0-3",
+            "Signature",
+            format!("\"{}\" ({})", signature_utf8, hex::encode(&raw[0..4])),
+        );
+        push_row("4", "Version", format!("{}", self.commit_graph.version));
+        push_row(
+            "5",
+            "Hash version",
+            format!(
+                "{} ({})",
+                self.commit_graph.hash_version,
+                self.commit_graph.hash_function_name()
+            ),
+        );
+        push_row(
+            "6",
+            "Chunk count",
+            format!("{}", self.commit_graph.chunk_count),
+        );
+        push_row(
+            "7",
+            "Base graph count",
+            format!("{}", self.commit_graph.base_graph_count),
+        );
+
+        lines.push(Line::from(""));
+    }
+}
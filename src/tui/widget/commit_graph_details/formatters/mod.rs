@@ -0,0 +1,203 @@
+pub mod bloom;
+pub mod chunks;
+pub mod commits;
+pub mod header;
+
+use crate::git::commit_graph::{BloomCheck, CommitGraph};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+pub use bloom::BloomFormatter;
+pub use chunks::ChunksFormatter;
+pub use commits::CommitsFormatter;
+pub use header::HeaderFormatter;
+
+pub struct CommitGraphFormatter<'a> {
+    commit_graph: &'a CommitGraph,
+    first_position: usize,
+    bloom_query: Option<&'a str>,
+    bloom_checks: Option<&'a [BloomCheck]>,
+}
+
+impl<'a> CommitGraphFormatter<'a> {
+    #[must_use]
+    pub const fn new(commit_graph: &'a CommitGraph) -> Self {
+        Self {
+            commit_graph,
+            first_position: 0,
+            bloom_query: None,
+            bloom_checks: None,
+        }
+    }
+
+    /// Number commits from `first_position` on, for a layer of a split
+    /// chain whose base layers hold that many commits
+    #[must_use]
+    pub const fn with_first_position(mut self, first_position: usize) -> Self {
+        self.first_position = first_position;
+        self
+    }
+
+    /// Answer each commit's Bloom filter for `path` in the Bloom section
+    #[must_use]
+    pub const fn with_bloom_query(mut self, path: &'a str) -> Self {
+        self.bloom_query = Some(path);
+        self
+    }
+
+    /// Show the outcome of recomputing the Bloom filters
+    #[must_use]
+    pub const fn with_bloom_checks(mut self, checks: &'a [BloomCheck]) -> Self {
+        self.bloom_checks = Some(checks);
+        self
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        self.generate_content_with_bloom_start().0
+    }
+
+    /// The content, and the line the Bloom filter section starts at if the
+    /// file has one
+    #[must_use]
+    pub fn generate_content_with_bloom_start(&self) -> (Text<'static>, Option<usize>) {
+        let mut lines = vec![
+            Line::from("A cache of the commit graph: for every commit, its root tree, its"),
+            Line::from("parents (as positions in this file), its commit time and a"),
+            Line::from("generation number. History walks read this instead of inflating"),
+            Line::from("commit objects. Data lives in chunks located via the chunk lookup"),
+            Line::from("table."),
+            Line::from(""),
+        ];
+
+        HeaderFormatter::new(self.commit_graph).format_header(&mut lines);
+        ChunksFormatter::new(self.commit_graph).format_chunk_table(&mut lines);
+        self.add_base_graphs_section(&mut lines);
+        let bloom_start = self.add_bloom_section(&mut lines);
+        CommitsFormatter::new(self.commit_graph, self.first_position)
+            .format_commits_overview(&mut lines);
+        self.add_checksum_section(&mut lines);
+        self.add_structure_diagram(&mut lines);
+
+        (Text::from(lines), bloom_start)
+    }
+
+    fn add_bloom_section(&self, lines: &mut Vec<Line<'static>>) -> Option<usize> {
+        let formatter = BloomFormatter::new(self.commit_graph, self.first_position)?;
+        let start = lines.len();
+        formatter.format_bloom_overview(lines);
+        if let Some(path) = self.bloom_query {
+            formatter.format_query(path, lines);
+        }
+        if let Some(checks) = self.bloom_checks {
+            formatter.format_verification(checks, lines);
+        }
+        Some(start)
+    }
+
+    fn add_base_graphs_section(&self, lines: &mut Vec<Line<'static>>) {
+        if self.commit_graph.base_graphs.is_empty() {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "BASE GRAPHS (BASE)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            "  This file is a layer of a split chain. BASE lists the checksums of the",
+            Style::default().fg(Color::Gray),
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  layers below it, bottom first; each names a graph-<checksum>.graph file.",
+            Style::default().fg(Color::Gray),
+        )]));
+        lines.push(Line::from(""));
+
+        for (index, base) in self.commit_graph.base_graphs.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {index:3}"), Style::default().fg(Color::Cyan)),
+                Span::styled(" │ ", Style::default().fg(Color::Gray)),
+                Span::styled(hex::encode(base), Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn add_checksum_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "CHECKSUM",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let checksum_start = self
+            .commit_graph
+            .raw_data
+            .len()
+            .saturating_sub(self.commit_graph.checksum_size());
+
+        lines.push(Line::from(vec![
+            Span::styled(" Byte", Style::default().fg(Color::Gray)),
+            Span::styled(" │ ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                "Commit-graph checksum Hex",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        lines.push(Line::from(vec![Span::styled(
+            "──────┼─────────────────────────────────────────",
+            Style::default().fg(Color::Gray),
+        )]));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{checksum_start:5}"),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(" │ ", Style::default().fg(Color::Gray)),
+            Span::from(hex::encode(&self.commit_graph.checksum)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    fn add_structure_diagram(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "FILE STRUCTURE DIAGRAM",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let chunk_count = self.commit_graph.chunks.len();
+
+        lines.push(Line::from("┌─────────────────────────────────┐"));
+        lines.push(Line::from("│ Header (magic \"CGPH\", ...)      │ 8 bytes"));
+        lines.push(Line::from("├─────────────────────────────────┤"));
+        lines.push(Line::from(format!(
+            "│ Chunk Lookup Table              │ {} × 12 bytes",
+            chunk_count + 1
+        )));
+
+        for chunk in &self.commit_graph.chunks {
+            lines.push(Line::from("├─────────────────────────────────┤"));
+            lines.push(Line::from(format!(
+                "│ {:<4} Chunk                      │ {} bytes",
+                chunk.id_str(),
+                chunk.size
+            )));
+        }
+
+        lines.push(Line::from("├─────────────────────────────────┤"));
+        lines.push(Line::from(format!(
+            "│ Checksum                        │ {} bytes",
+            self.commit_graph.checksum_size()
+        )));
+        lines.push(Line::from("└─────────────────────────────────┘"));
+        lines.push(Line::from(""));
+    }
+}
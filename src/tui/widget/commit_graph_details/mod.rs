@@ -0,0 +1,248 @@
+pub mod formatters;
+
+use crate::git::commit_graph::{BloomCheck, CommitGraph};
+use crate::git::object_database::ObjectDatabase;
+use crate::tui::widget::ScrollableTextWidget;
+use ratatui::text::ToText;
+use std::path::{Path, PathBuf};
+
+use formatters::CommitGraphFormatter;
+
+#[derive(Debug, Clone)]
+pub enum CommitGraphWidget {
+    Uninitialized,
+    Initialized {
+        commit_graph: Box<CommitGraph>,
+        first_position: usize,
+        /// Git directory holding the file, to read trees when verifying
+        /// its Bloom filters
+        git_dir: PathBuf,
+        /// Path asked of the Bloom filters, empty for none
+        bloom_query: String,
+        /// Whether keys currently edit `bloom_query`
+        editing_query: bool,
+        bloom_checks: Option<Vec<BloomCheck>>,
+        scrollable_widget: ScrollableTextWidget,
+    },
+}
+
+impl CommitGraphWidget {
+    /// `first_position` is the global graph position of the file's first
+    /// commit, nonzero for the upper layers of a split chain
+    #[must_use]
+    pub fn new(commit_graph: CommitGraph, first_position: usize, git_dir: PathBuf) -> Self {
+        let mut widget = Self::Initialized {
+            commit_graph: Box::new(commit_graph),
+            first_position,
+            git_dir,
+            bloom_query: String::new(),
+            editing_query: false,
+            bloom_checks: None,
+            scrollable_widget: ScrollableTextWidget::new(),
+        };
+        // Pre-generate and cache the content
+        widget.refresh_content();
+        widget.scroll_to_top();
+        widget
+    }
+
+    /// Git directory holding the file, whose objects verifying the Bloom
+    /// filters reads
+    #[must_use]
+    pub fn git_dir(&self) -> Option<&Path> {
+        match self {
+            Self::Initialized { git_dir, .. } => Some(git_dir),
+            Self::Uninitialized => None,
+        }
+    }
+
+    /// Whether the file has changed-path Bloom filters to query
+    #[must_use]
+    pub const fn has_bloom_filters(&self) -> bool {
+        matches!(self, Self::Initialized { commit_graph, .. } if commit_graph.bloom_filters.is_some())
+    }
+
+    /// Whether keys are being typed into the Bloom filter path query
+    #[must_use]
+    pub const fn is_editing_query(&self) -> bool {
+        matches!(
+            self,
+            Self::Initialized {
+                editing_query: true,
+                ..
+            }
+        )
+    }
+
+    /// Start typing a path to ask the Bloom filters about
+    pub fn start_query(&mut self) {
+        if let Self::Initialized {
+            commit_graph,
+            editing_query,
+            ..
+        } = self
+        {
+            *editing_query = commit_graph.bloom_filters.is_some();
+        }
+    }
+
+    pub fn push_query_char(&mut self, c: char) {
+        if let Self::Initialized { bloom_query, .. } = self {
+            bloom_query.push(c);
+        }
+    }
+
+    pub fn pop_query_char(&mut self) {
+        if let Self::Initialized { bloom_query, .. } = self {
+            bloom_query.pop();
+        }
+    }
+
+    /// Stop editing and answer every commit's filter for the typed path
+    pub fn submit_query(&mut self) {
+        if let Self::Initialized { editing_query, .. } = self {
+            *editing_query = false;
+        }
+        self.refresh_content();
+    }
+
+    /// Stop editing and drop the query
+    pub fn cancel_query(&mut self) {
+        if let Self::Initialized {
+            editing_query,
+            bloom_query,
+            ..
+        } = self
+        {
+            *editing_query = false;
+            bloom_query.clear();
+        }
+        self.refresh_content();
+    }
+
+    /// Recompute every Bloom filter from the tree diffs of the commits in
+    /// `database` and show how the stored ones compare
+    pub fn verify_bloom_filters(&mut self, database: &ObjectDatabase) {
+        if let Self::Initialized {
+            commit_graph,
+            first_position,
+            bloom_checks,
+            ..
+        } = self
+            && let Some(filters) = &commit_graph.bloom_filters
+        {
+            *bloom_checks = Some(filters.verify(commit_graph, *first_position, database));
+        }
+        self.refresh_content();
+    }
+
+    /// Regenerate the content for the current query and checks, scrolled
+    /// to the Bloom filter section
+    fn refresh_content(&mut self) {
+        if let Self::Initialized {
+            commit_graph,
+            first_position,
+            bloom_query,
+            bloom_checks,
+            scrollable_widget,
+            ..
+        } = self
+        {
+            let mut formatter =
+                CommitGraphFormatter::new(commit_graph).with_first_position(*first_position);
+            if !bloom_query.is_empty() {
+                formatter = formatter.with_bloom_query(bloom_query);
+            }
+            if let Some(checks) = bloom_checks {
+                formatter = formatter.with_bloom_checks(checks);
+            }
+            let (content, bloom_start) = formatter.generate_content_with_bloom_start();
+            scrollable_widget.set_text(content);
+            if let Some(line) = bloom_start {
+                scrollable_widget.scroll_to_line(line);
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn text(&self) -> ratatui::text::Text<'static> {
+        match self {
+            Self::Initialized {
+                scrollable_widget, ..
+            } => scrollable_widget.text(),
+            Self::Uninitialized => "Initializing Commit Graph Preview...".to_text(),
+        }
+    }
+
+    pub const fn scroll_up(&mut self) {
+        if let Self::Initialized {
+            scrollable_widget, ..
+        } = self
+        {
+            scrollable_widget.scroll_up();
+        }
+    }
+
+    pub fn scroll_down(&mut self) {
+        if let Self::Initialized {
+            scrollable_widget, ..
+        } = self
+        {
+            scrollable_widget.scroll_down();
+        }
+    }
+
+    pub const fn scroll_to_top(&mut self) {
+        if let Self::Initialized {
+            scrollable_widget, ..
+        } = self
+        {
+            scrollable_widget.scroll_to_top();
+        }
+    }
+
+    pub const fn scroll_to_bottom(&mut self) {
+        if let Self::Initialized {
+            scrollable_widget, ..
+        } = self
+        {
+            scrollable_widget.scroll_to_bottom();
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        f: &mut ratatui::Frame,
+        area: ratatui::layout::Rect,
+        is_focused: bool,
+    ) {
+        match self {
+            Self::Initialized {
+                commit_graph,
+                bloom_query,
+                editing_query,
+                scrollable_widget,
+                ..
+            } => {
+                let title = if *editing_query {
+                    format!(
+                        "Commit Graph Details - path: {bloom_query}_ (Enter: query, Esc: cancel)"
+                    )
+                } else if commit_graph.bloom_filters.is_none() {
+                    "Commit Graph Details".to_string()
+                } else if bloom_query.is_empty() {
+                    "Commit Graph Details (/: query a path, v: verify Bloom filters)".to_string()
+                } else {
+                    format!("Commit Graph Details - path: {bloom_query} (/: edit, v: verify)")
+                };
+                scrollable_widget.render(f, area, &title, is_focused);
+            }
+            Self::Uninitialized => {
+                // For uninitialized state, create a temporary scrollable widget with the loading message
+                let mut temp_widget = ScrollableTextWidget::new();
+                temp_widget.set_text("Initializing Commit Graph Preview...".to_text());
+                temp_widget.render(f, area, "Commit Graph Details", is_focused);
+            }
+        }
+    }
+}
@@ -36,15 +36,49 @@ pub fn format_u32_as_hex_bytes(value: u32) -> String {
 /// ```
 #[must_use]
 pub fn format_epoch_utc(epoch: u32) -> String {
-    let secs = i64::from(epoch);
-    let days = secs / 86_400;
-    let rem = secs % 86_400;
+    format!("{} UTC", format_civil_datetime(i64::from(epoch)))
+}
+
+/// Format an epoch-seconds timestamp in the local time of a git timezone
+/// offset (given in minutes east of UTC), followed by the offset itself
+///
+/// # Examples
+///
+/// ```
+/// use git_plumber::tui::widget::formatters_utils::format_epoch_with_offset;
+///
+/// assert_eq!(
+///     format_epoch_with_offset(1_700_000_000, 120),
+///     "2023-11-15 00:13:20 +0200"
+/// );
+/// assert_eq!(
+///     format_epoch_with_offset(1_700_000_000, -150),
+///     "2023-11-14 19:43:20 -0230"
+/// );
+/// ```
+#[must_use]
+pub fn format_epoch_with_offset(epoch: i64, offset_minutes: i32) -> String {
+    let local = epoch + i64::from(offset_minutes) * 60;
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let offset = offset_minutes.unsigned_abs();
+    format!(
+        "{} {sign}{:02}{:02}",
+        format_civil_datetime(local),
+        offset / 60,
+        offset % 60
+    )
+}
+
+/// `YYYY-MM-DD hh:mm:ss` for seconds since 1970-01-01 00:00:00
+fn format_civil_datetime(secs: i64) -> String {
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
     let (hour, minute, second) = (rem / 3_600, (rem % 3_600) / 60, rem % 60);
 
     // Shift epoch from 1970-01-01 to 0000-03-01 so leap days fall at era ends
     let z = days + 719_468;
-    let era = z / 146_097;
-    let doe = z % 146_097; // day of 400-year era
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097); // day of 400-year era
     let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365; // year of era
     let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // day of March-based year
     let mp = (5 * doy + 2) / 153; // March-based month
@@ -52,7 +86,7 @@ pub fn format_epoch_utc(epoch: u32) -> String {
     let month = if mp < 10 { mp + 3 } else { mp - 9 };
     let year = yoe + era * 400 + i64::from(month <= 2);
 
-    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} UTC")
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}")
 }
 
 #[cfg(test)]
@@ -69,6 +103,20 @@ mod tests {
         assert_eq!(format_epoch_utc(u32::MAX), "2106-02-07 06:28:15 UTC");
     }
 
+    #[test]
+    fn test_format_epoch_with_offset() {
+        assert_eq!(format_epoch_with_offset(0, 0), "1970-01-01 00:00:00 +0000");
+        // Offset crossing midnight backwards, before the epoch
+        assert_eq!(
+            format_epoch_with_offset(0, -300),
+            "1969-12-31 19:00:00 -0500"
+        );
+        assert_eq!(
+            format_epoch_with_offset(1_700_000_000, 330),
+            "2023-11-15 03:43:20 +0530"
+        );
+    }
+
     #[test]
     fn test_format_u32_as_hex_bytes() {
         assert_eq!(format_u32_as_hex_bytes(0x00000000), "00 00 00 00");
@@ -0,0 +1,169 @@
+use crate::git::index::{CacheTree, CacheTreeStatus, GitIndex};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Directories beyond this count are summarized instead of listed
+const MAX_LISTED_NODES: usize = 300;
+
+pub struct CacheTreeFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> CacheTreeFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_cache_tree(&self, lines: &mut Vec<Line<'static>>) {
+        if self.git_index.extension(&CacheTree::SIGNATURE).is_none() {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "CACHE TREE (TREE)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  The tree object ID of every directory whose entries are unchanged since",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  the last tree was written. git commit reuses these trees and only hashes",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  directories invalidated (entry count -1) by a later git add or rm.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let Some(cache_tree) = &self.git_index.cache_tree else {
+            lines.push(Line::from(vec![Span::styled(
+                "  The extension is present but could not be decoded.",
+                Style::default().fg(Color::Red),
+            )]));
+            lines.push(Line::from(""));
+            return;
+        };
+
+        Self::format_summary(cache_tree, lines);
+        Self::format_nodes(cache_tree, lines);
+    }
+
+    fn format_summary(cache_tree: &CacheTree, lines: &mut Vec<Line<'static>>) {
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Directories: "),
+            Span::styled(format!("{}", cache_tree.nodes.len()), value_style),
+            Span::from(", valid: "),
+            Span::styled(format!("{}", cache_tree.valid_count()), value_style),
+            Span::from(", invalidated: "),
+            Span::styled(
+                format!("{}", cache_tree.nodes.len() - cache_tree.valid_count()),
+                value_style,
+            ),
+        ]));
+
+        let verified = cache_tree.nodes.iter().any(|node| node.status.is_some());
+        if verified {
+            let errors = cache_tree.error_count();
+            let (text, style) = if errors == 0 {
+                (
+                    "  Verification: every valid tree exists in the object database".to_string(),
+                    value_style,
+                )
+            } else {
+                (
+                    format!("  Verification: {errors} corrupt node(s)"),
+                    Style::default().fg(Color::Red),
+                )
+            };
+            lines.push(Line::from(vec![Span::styled(text, style)]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_nodes(cache_tree: &CacheTree, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+
+        lines.push(Line::from(vec![
+            Span::styled("Entries", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled("Subs", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Tree        ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Status                ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled("Directory", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "────────┼──────┼──────────────┼────────────────────────┼──────────────────",
+            gray,
+        ));
+
+        for node in cache_tree.nodes.iter().take(MAX_LISTED_NODES) {
+            let tree = node
+                .oid_hex()
+                .map_or_else(|| "-".to_string(), |oid| oid[..12].to_string());
+            let (status, status_style) = match &node.status {
+                None => (
+                    if node.is_valid() {
+                        "valid"
+                    } else {
+                        "invalidated"
+                    }
+                    .to_string(),
+                    gray,
+                ),
+                Some(CacheTreeStatus::Invalidated) => ("invalidated".to_string(), gray),
+                Some(status) if status.is_error() => {
+                    (status.to_string(), Style::default().fg(Color::Red))
+                }
+                Some(status) => (status.to_string(), Style::default().fg(Color::LightGreen)),
+            };
+            let name = if node.path.is_empty() {
+                "(root)".to_string()
+            } else {
+                node.path.rsplit('/').next().unwrap_or_default().to_string()
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:7}", node.entry_count),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:4}", node.subtree_count)),
+                Span::styled(" │ ", gray),
+                Span::styled(format!("{tree:<12}"), Style::default().fg(Color::Yellow)),
+                Span::styled(" │ ", gray),
+                Span::styled(format!("{status:<22}"), status_style),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{}{name}", "  ".repeat(node.depth))),
+            ]));
+        }
+
+        if cache_tree.nodes.len() > MAX_LISTED_NODES {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more directories not shown",
+                    cache_tree.nodes.len() - MAX_LISTED_NODES
+                ),
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::git::index::{Conflict, ConflictSide, GitIndex};
+use crate::tui::widget::loose_obj_details::formatters::BlobFormatter;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Conflicts beyond this count are listed without their blob contents
+const MAX_DETAILED_CONFLICTS: usize = 20;
+/// Lines shown per side in the side-by-side comparison
+const MAX_SIDE_BY_SIDE_LINES: usize = 30;
+/// Width of one column in the side-by-side comparison
+const COLUMN_WIDTH: usize = 36;
+
+pub struct ConflictsFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> ConflictsFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_conflicts(&self, lines: &mut Vec<Line<'static>>) {
+        let conflicts = self.git_index.conflicts();
+        if conflicts.is_empty() {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "MERGE CONFLICTS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  A merge that could not be resolved automatically leaves up to three",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  entries per path instead of one: stage 1 holds the common ancestor,",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  stage 2 our version (HEAD) and stage 3 theirs (MERGE_HEAD).",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::from("  Unmerged paths: "),
+            Span::styled(
+                format!("{}", conflicts.len()),
+                Style::default().fg(Color::LightGreen),
+            ),
+        ]));
+        lines.push(Line::from(""));
+
+        for (i, conflict) in conflicts.iter().enumerate() {
+            self.format_conflict(conflict, i < MAX_DETAILED_CONFLICTS, lines);
+        }
+        if conflicts.len() > MAX_DETAILED_CONFLICTS {
+            lines.push(Line::from(vec![Span::styled(
+                format!("  Blob contents shown for the first {MAX_DETAILED_CONFLICTS} paths only"),
+                gray,
+            )]));
+            lines.push(Line::from(""));
+        }
+    }
+
+    fn format_conflict(&self, conflict: &Conflict, detailed: bool, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+
+        lines.push(Line::from(vec![
+            Span::styled(
+                conflict.path.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  ({})", conflict.kind()),
+                Style::default().fg(Color::Red),
+            ),
+        ]));
+
+        for side in ConflictSide::ALL {
+            let mut spans = vec![
+                Span::styled(
+                    format!("  {} ", side.stage()),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(format!("{side:<6}"), Self::side_style(side)),
+                Span::styled(" │ ", gray),
+            ];
+            match conflict.side(side) {
+                Some(entry) => {
+                    spans.push(Span::styled(
+                        format!("{:06o}", entry.mode),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                    spans.push(Span::styled(" │ ", gray));
+                    spans.push(Span::styled(
+                        entry.oid_hex(),
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+                None => spans.push(Span::styled("(absent)", gray)),
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+
+        if !detailed {
+            return;
+        }
+
+        self.format_side_by_side(conflict, lines);
+
+        for side in ConflictSide::ALL {
+            let Some(entry) = conflict.side(side) else {
+                continue;
+            };
+            let oid = entry.oid_hex();
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  Stage {} ({side}): ", side.stage()),
+                    Self::side_style(side),
+                ),
+                Span::styled(oid.clone(), Style::default().fg(Color::Yellow)),
+            ]));
+            match self.git_index.conflict_blobs.get(&oid) {
+                Some(Ok(blob)) => {
+                    lines.push(Line::from(""));
+                    BlobFormatter::new(&blob.content, blob.is_binary()).format_blob(lines);
+                }
+                Some(Err(e)) => lines.push(Line::from(vec![Span::styled(
+                    format!("  Blob not loaded: {e}"),
+                    gray,
+                )])),
+                None => lines.push(Line::from(vec![Span::styled("  Blob not loaded", gray)])),
+            }
+            lines.push(Line::from(""));
+        }
+    }
+
+    /// Render the three stages as columns, one line of each per row
+    fn format_side_by_side(&self, conflict: &Conflict, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+        let columns: Vec<Vec<String>> = ConflictSide::ALL
+            .iter()
+            .map(|&side| self.side_lines(conflict, side))
+            .collect();
+
+        let mut header = vec![Span::from("  ")];
+        for (i, side) in ConflictSide::ALL.iter().enumerate() {
+            if i > 0 {
+                header.push(Span::styled(" │ ", gray));
+            }
+            header.push(Span::styled(
+                format!("{:<COLUMN_WIDTH$}", format!("{} ({side})", side.stage())),
+                Self::side_style(*side).add_modifier(Modifier::BOLD),
+            ));
+        }
+        lines.push(Line::from(header));
+        lines.push(Line::styled(
+            format!("  {0}─┼─{0}─┼─{0}", "─".repeat(COLUMN_WIDTH)),
+            gray,
+        ));
+
+        let rows = columns
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+            .min(MAX_SIDE_BY_SIDE_LINES);
+        for row in 0..rows {
+            let mut spans = vec![Span::from("  ")];
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::styled(" │ ", gray));
+                }
+                let cell: String = column
+                    .get(row)
+                    .map_or("", String::as_str)
+                    .chars()
+                    .take(COLUMN_WIDTH)
+                    .collect();
+                spans.push(Span::from(format!("{cell:<COLUMN_WIDTH$}")));
+            }
+            lines.push(Line::from(spans));
+        }
+        if columns
+            .iter()
+            .any(|column| column.len() > MAX_SIDE_BY_SIDE_LINES)
+        {
+            lines.push(Line::from(vec![Span::styled(
+                "  ... (more lines truncated)",
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    /// Text lines of one side's blob, or a placeholder explaining why there are none
+    fn side_lines(&self, conflict: &Conflict, side: ConflictSide) -> Vec<String> {
+        let Some(entry) = conflict.side(side) else {
+            return vec!["(absent)".to_string()];
+        };
+        match self.git_index.conflict_blobs.get(&entry.oid_hex()) {
+            Some(Ok(blob)) if blob.is_binary() => {
+                vec![format!("(binary, {} bytes)", blob.content.len())]
+            }
+            Some(Ok(blob)) if blob.content.is_empty() => vec!["(empty file)".to_string()],
+            Some(Ok(blob)) => String::from_utf8_lossy(&blob.content)
+                .lines()
+                .map(|line| line.replace('\t', "    "))
+                .collect(),
+            Some(Err(_)) | None => vec!["(blob not loaded)".to_string()],
+        }
+    }
+
+    fn side_style(side: ConflictSide) -> Style {
+        match side {
+            ConflictSide::Base => Style::default().fg(Color::Gray),
+            ConflictSide::Ours => Style::default().fg(Color::LightGreen),
+            ConflictSide::Theirs => Style::default().fg(Color::LightBlue),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::git::index::{GitIndex, IndexEntry};
+use crate::tui::widget::formatters_utils::format_epoch_utc;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Entries beyond this count are summarized instead of listed
+const MAX_LISTED_ENTRIES: usize = 500;
+
+pub struct EntriesFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> EntriesFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_entries(&self, lines: &mut Vec<Line<'static>>) {
+        self.format_entry_table(lines);
+        if let Some(first) = self.git_index.entries.first() {
+            self.format_entry_layout(first, lines);
+        }
+    }
+
+    fn format_entry_table(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "INDEX ENTRIES",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Sorted by path, then by stage. Stage 0 is a normal entry; stages 1-3",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  (base/ours/theirs) only appear while a merge conflict is unresolved.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        if self.git_index.entries.is_empty() {
+            lines.push(Line::from("No entries in this index."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled(" Mode  ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Object ID   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled("St", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled("Flags", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled("    Size", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled("Path", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "────────┼──────────────┼────┼───────┼──────────┼──────────────────",
+            gray,
+        ));
+
+        for entry in self.git_index.entries.iter().take(MAX_LISTED_ENTRIES) {
+            let oid = entry.oid_hex();
+            let stage_style = if entry.stage() == 0 {
+                gray
+            } else {
+                Style::default().fg(Color::Red)
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {:06o}", entry.mode),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    oid[..12.min(oid.len())].to_string(),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(format!("{:2}", entry.stage()), stage_style),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    format!("{:<5}", Self::short_flags(entry)),
+                    Style::default().fg(Color::Magenta),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    format!("{:8}", entry.size),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                Span::styled(" │ ", gray),
+                if entry.path.is_empty() {
+                    // Split index: a nameless entry replaces a shared index entry
+                    Span::styled("(replaces a shared entry)", gray)
+                } else {
+                    Span::from(entry.path.clone())
+                },
+            ]));
+        }
+
+        if self.git_index.entries.len() > MAX_LISTED_ENTRIES {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more entries not shown",
+                    self.git_index.entries.len() - MAX_LISTED_ENTRIES
+                ),
+                gray,
+            )]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            "  Flags: A = assume-valid, S = skip-worktree, I = intent-to-add",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+    }
+
+    /// Break the first entry down field by field to show the on-disk layout
+    fn format_entry_layout(&self, entry: &IndexEntry, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "ENTRY LAYOUT (FIRST ENTRY)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let border_style = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::LightGreen);
+        let hash_size = self.git_index.hash_size;
+
+        let mut push_row = |byte_range: String, field: &str, value: String| {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {byte_range:<7}"), border_style),
+                Span::styled("│ ", border_style),
+                Span::from(format!("{field:<16}")),
+                Span::styled("│ ", border_style),
+                Span::styled(value, value_style),
+            ]));
+        };
+
+        push_row(
+            "0-7".to_string(),
+            "ctime",
+            Self::format_time(entry.ctime.seconds, entry.ctime.nanoseconds),
+        );
+        push_row(
+            "8-15".to_string(),
+            "mtime",
+            Self::format_time(entry.mtime.seconds, entry.mtime.nanoseconds),
+        );
+        push_row("16-19".to_string(), "dev", format!("{}", entry.dev));
+        push_row("20-23".to_string(), "ino", format!("{}", entry.ino));
+        push_row(
+            "24-27".to_string(),
+            "mode",
+            format!("{:06o} ({})", entry.mode, entry.object_type_name()),
+        );
+        push_row("28-31".to_string(), "uid", format!("{}", entry.uid));
+        push_row("32-35".to_string(), "gid", format!("{}", entry.gid));
+        push_row("36-39".to_string(), "size", format!("{} bytes", entry.size));
+        push_row(
+            format!("40-{}", 39 + hash_size),
+            "object ID",
+            entry.oid_hex(),
+        );
+        push_row(
+            format!("{}-{}", 40 + hash_size, 41 + hash_size),
+            "flags",
+            format!(
+                "0x{:04x} (stage {}, name length {})",
+                entry.flags,
+                entry.stage(),
+                entry.name_length()
+            ),
+        );
+
+        let mut path_start = 42 + hash_size;
+        if let Some(extended_flags) = entry.extended_flags {
+            push_row(
+                format!("{}-{}", path_start, path_start + 1),
+                "extended flags",
+                format!("0x{extended_flags:04x}"),
+            );
+            path_start += 2;
+        }
+
+        match entry.prefix_strip_len {
+            Some(strip_len) => {
+                push_row(
+                    format!("{path_start}-"),
+                    "path",
+                    format!("strip {strip_len} byte(s) of previous path, then suffix"),
+                );
+            }
+            None => {
+                push_row(
+                    format!("{}-{}", path_start, entry.size_on_disk - 1),
+                    "path + padding",
+                    format!("\"{}\" + NUL padding", entry.path),
+                );
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::from("  Entry size on disk: "),
+            Span::styled(
+                format!("{} bytes", entry.size_on_disk),
+                Style::default().fg(Color::LightGreen),
+            ),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    fn short_flags(entry: &IndexEntry) -> String {
+        let mut flags = String::new();
+        if entry.is_assume_valid() {
+            flags.push('A');
+        }
+        if entry.is_skip_worktree() {
+            flags.push('S');
+        }
+        if entry.is_intent_to_add() {
+            flags.push('I');
+        }
+        if flags.is_empty() {
+            flags.push('-');
+        }
+        flags
+    }
+
+    fn format_time(seconds: u32, nanoseconds: u32) -> String {
+        format!("{} (+{nanoseconds} ns)", format_epoch_utc(seconds))
+    }
+}
@@ -0,0 +1,90 @@
+use crate::git::index::GitIndex;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+pub struct ExtensionsFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> ExtensionsFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_extensions(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "EXTENSIONS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Optional blocks after the entries: a 4-byte signature, a 4-byte size,",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  then the payload. Signatures starting with an uppercase letter may be",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  ignored by Git versions that don't know them; others are mandatory.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        if self.git_index.extensions.is_empty() {
+            lines.push(Line::from("No extensions in this index."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled(" Sig  ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled("│ ", gray),
+            Span::styled("Offset ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled("│ ", gray),
+            Span::styled("Size   ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled("│ ", gray),
+            Span::styled("Kind      ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled("│ ", gray),
+            Span::styled("Purpose", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "──────┼────────┼────────┼───────────┼──────────────────────────",
+            gray,
+        ));
+
+        for extension in &self.git_index.extensions {
+            let kind = if extension.is_optional() {
+                "optional "
+            } else {
+                "mandatory"
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {:<5}", extension.signature_str()),
+                    Style::default().fg(Color::LightBlue),
+                ),
+                Span::styled("│ ", gray),
+                Span::styled(
+                    format!("{:6} ", extension.offset),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled("│ ", gray),
+                Span::styled(
+                    format!("{:6} ", extension.size()),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                Span::styled("│ ", gray),
+                Span::from(format!("{kind} ")),
+                Span::styled("│ ", gray),
+                Span::from(extension.description()),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+    }
+}
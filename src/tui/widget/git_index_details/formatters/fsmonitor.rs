@@ -0,0 +1,128 @@
+use crate::git::index::{FsmonitorExtension, FsmonitorToken, GitIndex};
+use crate::tui::widget::formatters_utils::format_epoch_utc;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Dirty entries beyond this count are summarized instead of listed
+const MAX_LISTED_DIRTY: usize = 200;
+
+pub struct FsmonitorFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> FsmonitorFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_fsmonitor(&self, lines: &mut Vec<Line<'static>>) {
+        if self
+            .git_index
+            .extension(&FsmonitorExtension::SIGNATURE)
+            .is_none()
+        {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "FILE SYSTEM MONITOR (FSMN)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Git asks the file system monitor what changed since the token below.",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  Entries not marked dirty are trusted as unchanged: no lstat() needed.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let Some(fsmonitor) = &self.git_index.fsmonitor else {
+            lines.push(Line::from(vec![Span::styled(
+                "  The extension is present but could not be decoded.",
+                Style::default().fg(Color::Red),
+            )]));
+            lines.push(Line::from(""));
+            return;
+        };
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Version: "),
+            Span::styled(format!("{}", fsmonitor.version), value_style),
+        ]));
+        let token = match &fsmonitor.token {
+            FsmonitorToken::Timestamp(nanoseconds) => {
+                let seconds = u32::try_from(nanoseconds / 1_000_000_000).unwrap_or(u32::MAX);
+                format!("{nanoseconds} ns ({})", format_epoch_utc(seconds))
+            }
+            FsmonitorToken::Opaque(token) => format!("\"{token}\""),
+        };
+        lines.push(Line::from(vec![
+            Span::from("  Token: "),
+            Span::styled(token, Style::default().fg(Color::Yellow)),
+        ]));
+
+        let dirty = fsmonitor.dirty_positions();
+        let entry_paths = self.entry_paths();
+        lines.push(Line::from(vec![
+            Span::from("  Dirty entries: "),
+            Span::styled(format!("{}", dirty.len()), value_style),
+            Span::from(" of "),
+            Span::styled(format!("{}", entry_paths.len()), value_style),
+            Span::styled(
+                format!(" (bitmap: {} bytes EWAH)", fsmonitor.bitmap_size),
+                gray,
+            ),
+        ]));
+        lines.push(Line::from(""));
+
+        for &position in dirty.iter().take(MAX_LISTED_DIRTY) {
+            let path = usize::try_from(position)
+                .ok()
+                .and_then(|p| entry_paths.get(p))
+                .map_or("(no such entry)", |path| path.as_str());
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {position:6}"), Style::default().fg(Color::Cyan)),
+                Span::styled(" │ ", gray),
+                Span::styled(path.to_string(), Style::default().fg(Color::Red)),
+            ]));
+        }
+        if dirty.len() > MAX_LISTED_DIRTY {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more dirty entries not shown",
+                    dirty.len() - MAX_LISTED_DIRTY
+                ),
+                gray,
+            )]));
+        }
+        if !dirty.is_empty() {
+            lines.push(Line::from(""));
+        }
+    }
+
+    /// Paths the bitmap positions refer to: the in-memory index, which for a
+    /// split index is the merged one
+    fn entry_paths(&self) -> Vec<String> {
+        match &self.git_index.split_index {
+            Some(Ok(split_index)) => split_index
+                .entries
+                .iter()
+                .map(|merged| merged.entry.path.clone())
+                .collect(),
+            _ => self
+                .git_index
+                .entries
+                .iter()
+                .map(|entry| entry.path.clone())
+                .collect(),
+        }
+    }
+}
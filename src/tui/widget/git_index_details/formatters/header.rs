@@ -0,0 +1,88 @@
+use crate::git::index::GitIndex;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+pub struct HeaderFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> HeaderFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_header(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "INDEX HEADER",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let border_style = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::LightGreen);
+
+        lines.push(Line::from(vec![
+            Span::styled(" Byte ", border_style),
+            Span::styled("│ ", border_style),
+            Span::styled(
+                "Field           ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("│ ", border_style),
+            Span::styled("Value", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "──────┼─────────────────┼─────────────────────",
+            border_style,
+        ));
+
+        let raw = &self.git_index.raw_data;
+        let signature_utf8: String = raw[0..4].iter().map(|&b| b as char).collect();
+
+        let mut push_row = |byte_range: &str, field: &str, value: String| {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {byte_range:<5}"), border_style),
+                Span::styled("│ ", border_style),
+                Span::from(format!("{field:<16}")),
+                Span::styled("│ ", border_style),
+                Span::styled(value, value_style),
+            ]));
+        };
+
+        push_row(
+            "0-3",
+            "Signature",
+            format!("\"{}\" ({})", signature_utf8, hex::encode(&raw[0..4])),
+        );
+        push_row("4-7", "Version", format!("{}", self.git_index.version));
+        push_row(
+            "8-11",
+            "Entry count",
+            format!("{}", self.git_index.entry_count),
+        );
+
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        let version_note = match self.git_index.version {
+            2 => "  Version 2: fixed-size flags, paths padded with NUL bytes to 8-byte boundaries.",
+            3 => "  Version 3: adds optional extended flags (skip-worktree, intent-to-add).",
+            _ => "  Version 4: paths are prefix-compressed against the previous entry, no padding.",
+        };
+        lines.push(Line::from(vec![Span::styled(version_note, gray)]));
+        lines.push(Line::from(vec![
+            Span::styled("  Object IDs: ", gray),
+            Span::styled(
+                format!(
+                    "{} ({} bytes)",
+                    self.git_index.hash_function_name(),
+                    self.git_index.hash_size
+                ),
+                value_style,
+            ),
+        ]));
+        lines.push(Line::from(""));
+    }
+}
@@ -0,0 +1,198 @@
+pub mod cache_tree;
+pub mod conflicts;
+pub mod entries;
+pub mod extensions;
+pub mod fsmonitor;
+pub mod header;
+pub mod resolve_undo;
+pub mod sparse;
+pub mod split;
+pub mod untracked_cache;
+
+use crate::git::index::GitIndex;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+
+pub use cache_tree::CacheTreeFormatter;
+pub use conflicts::ConflictsFormatter;
+pub use entries::EntriesFormatter;
+pub use extensions::ExtensionsFormatter;
+pub use fsmonitor::FsmonitorFormatter;
+pub use header::HeaderFormatter;
+pub use resolve_undo::ResolveUndoFormatter;
+pub use sparse::SparseIndexFormatter;
+pub use split::SplitIndexFormatter;
+pub use untracked_cache::UntrackedCacheFormatter;
+
+pub struct GitIndexFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> GitIndexFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = Vec::new();
+        // Add educational information at the top
+        Self::add_educational_info(&mut lines);
+        // Add detailed sections
+        self.add_header_section(&mut lines);
+        self.add_entries_section(&mut lines);
+        self.add_split_index_section(&mut lines);
+        self.add_conflicts_section(&mut lines);
+        self.add_extensions_section(&mut lines);
+        self.add_cache_tree_section(&mut lines);
+        self.add_untracked_cache_section(&mut lines);
+        self.add_fsmonitor_section(&mut lines);
+        self.add_resolve_undo_section(&mut lines);
+        self.add_sparse_index_section(&mut lines);
+        self.add_checksum_section(&mut lines);
+        self.add_structure_diagram(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_educational_info(lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::from(
+            "The index (staging area) lists every tracked path with the blob the next commit will record.",
+        ));
+        lines.push(Line::from(
+            "It also caches file-system stat data so Git can skip rehashing unchanged files.",
+        ));
+        lines.push(Line::from(""));
+    }
+
+    fn add_header_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = HeaderFormatter::new(self.git_index);
+        formatter.format_header(lines);
+    }
+
+    fn add_entries_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = EntriesFormatter::new(self.git_index);
+        formatter.format_entries(lines);
+    }
+
+    fn add_split_index_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = SplitIndexFormatter::new(self.git_index);
+        formatter.format_split_index(lines);
+    }
+
+    fn add_conflicts_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = ConflictsFormatter::new(self.git_index);
+        formatter.format_conflicts(lines);
+    }
+
+    fn add_extensions_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = ExtensionsFormatter::new(self.git_index);
+        formatter.format_extensions(lines);
+    }
+
+    fn add_cache_tree_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = CacheTreeFormatter::new(self.git_index);
+        formatter.format_cache_tree(lines);
+    }
+
+    fn add_untracked_cache_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = UntrackedCacheFormatter::new(self.git_index);
+        formatter.format_untracked_cache(lines);
+    }
+
+    fn add_fsmonitor_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = FsmonitorFormatter::new(self.git_index);
+        formatter.format_fsmonitor(lines);
+    }
+
+    fn add_resolve_undo_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = ResolveUndoFormatter::new(self.git_index);
+        formatter.format_resolve_undo(lines);
+    }
+
+    fn add_sparse_index_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = SparseIndexFormatter::new(self.git_index);
+        formatter.format_sparse_index(lines);
+    }
+
+    fn add_checksum_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "CHECKSUM",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                "  {} of every preceding byte, verified when the index is read.",
+                self.git_index.hash_function_name()
+            ),
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        lines.push(Line::from(vec![
+            Span::from("  Index checksum: "),
+            Span::styled(
+                hex::encode(&self.git_index.checksum),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]));
+        if self.git_index.is_checksum_skipped() {
+            lines.push(Line::from(vec![Span::styled(
+                "  All zeros: written with index.skipHash=true, so it is not verified.",
+                Style::default().fg(Color::Magenta),
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn add_structure_diagram(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "FILE STRUCTURE DIAGRAM",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let hash_size = self.git_index.hash_size;
+        let path_encoding = if self.git_index.version == 4 {
+            "prefix-compressed"
+        } else {
+            "NUL-padded"
+        };
+
+        lines.push(Line::from("┌─────────────────────────────────┐"));
+        lines.push(Line::from("│ Signature (DIRC)                │ 4 bytes"));
+        lines.push(Line::from("├─────────────────────────────────┤"));
+        lines.push(Line::from(format!(
+            "│ Version ({})                     │ 4 bytes",
+            self.git_index.version
+        )));
+        lines.push(Line::from("├─────────────────────────────────┤"));
+        lines.push(Line::from("│ Number of Entries               │ 4 bytes"));
+        lines.push(Line::from("├─────────────────────────────────┤"));
+        lines.push(Line::from(format!(
+            "│ Entries ({path_encoding:<17})       │ {} × ≥{} bytes",
+            self.git_index.entry_count(),
+            self.git_index.fixed_entry_size() + 1
+        )));
+        for extension in &self.git_index.extensions {
+            lines.push(Line::from("├─────────────────────────────────┤"));
+            lines.push(Line::from(format!(
+                "│ Extension {:<4}                  │ 8 + {} bytes",
+                extension.signature_str(),
+                extension.size()
+            )));
+        }
+        lines.push(Line::from("├─────────────────────────────────┤"));
+        lines.push(Line::from(format!(
+            "│ Index Checksum                  │ {hash_size} bytes"
+        )));
+        lines.push(Line::from("└─────────────────────────────────┘"));
+        lines.push(Line::from(""));
+    }
+}
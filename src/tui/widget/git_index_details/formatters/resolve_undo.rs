@@ -0,0 +1,97 @@
+use crate::git::index::{ConflictSide, GitIndex, ResolveUndo};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Resolved paths beyond this count are summarized instead of listed
+const MAX_LISTED_PATHS: usize = 200;
+
+pub struct ResolveUndoFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> ResolveUndoFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_resolve_undo(&self, lines: &mut Vec<Line<'static>>) {
+        if self.git_index.extension(&ResolveUndo::SIGNATURE).is_none() {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "RESOLVE UNDO (REUC)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Stages dropped when a conflict was resolved with git add. Running",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  git checkout -m <path> recreates the conflict from these entries.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let Some(resolve_undo) = &self.git_index.resolve_undo else {
+            lines.push(Line::from(vec![Span::styled(
+                "  The extension is present but could not be decoded.",
+                Style::default().fg(Color::Red),
+            )]));
+            lines.push(Line::from(""));
+            return;
+        };
+
+        lines.push(Line::from(vec![
+            Span::from("  Restorable paths: "),
+            Span::styled(
+                format!("{}", resolve_undo.entries.len()),
+                Style::default().fg(Color::LightGreen),
+            ),
+        ]));
+        lines.push(Line::from(""));
+
+        for entry in resolve_undo.entries.iter().take(MAX_LISTED_PATHS) {
+            lines.push(Line::styled(
+                entry.path.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            for side in ConflictSide::ALL {
+                let stage = usize::from(side.stage());
+                let mut spans = vec![
+                    Span::styled(format!("  {stage} "), Style::default().fg(Color::Cyan)),
+                    Span::from(format!("{side:<6}")),
+                    Span::styled(" │ ", gray),
+                ];
+                match entry.stage_oid_hex(stage) {
+                    Some(oid) => {
+                        spans.push(Span::styled(
+                            format!("{:06o}", entry.modes[stage - 1]),
+                            Style::default().fg(Color::Cyan),
+                        ));
+                        spans.push(Span::styled(" │ ", gray));
+                        spans.push(Span::styled(oid, Style::default().fg(Color::Yellow)));
+                    }
+                    None => spans.push(Span::styled("(absent)", gray)),
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+
+        if resolve_undo.entries.len() > MAX_LISTED_PATHS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more paths not shown",
+                    resolve_undo.entries.len() - MAX_LISTED_PATHS
+                ),
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+}
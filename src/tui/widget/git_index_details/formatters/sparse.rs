@@ -0,0 +1,244 @@
+use crate::git::index::{ConeMembership, GitIndex, SparseDirectory, TreeNode};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Sparse directories beyond this count are summarized instead of listed
+const MAX_LISTED_DIRECTORIES: usize = 50;
+
+pub struct SparseIndexFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> SparseIndexFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_sparse_index(&self, lines: &mut Vec<Line<'static>>) {
+        if !self.git_index.is_sparse() && self.git_index.sparse_checkout.is_none() {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "SPARSE CHECKOUT",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Only paths matching .git/info/sparse-checkout are checked out; the others",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  keep the skip-worktree flag. A sparse index (sdir extension) goes further",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  and collapses every directory outside the cone into one entry that points",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  at its tree, shown expanded below from the object database.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        self.format_patterns(lines);
+        self.format_membership_summary(lines);
+        if self.git_index.is_sparse() {
+            self.format_directories(lines);
+        }
+    }
+
+    fn format_patterns(&self, lines: &mut Vec<Line<'static>>) {
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Sparse index: "),
+            Span::styled(
+                if self.git_index.is_sparse() {
+                    "yes (sdir)"
+                } else {
+                    "no"
+                },
+                value_style,
+            ),
+        ]));
+
+        let Some(sparse_checkout) = &self.git_index.sparse_checkout else {
+            lines.push(Line::from(vec![Span::styled(
+                "  No .git/info/sparse-checkout file: cone membership can't be determined",
+                Style::default().fg(Color::Yellow),
+            )]));
+            lines.push(Line::from(""));
+            return;
+        };
+
+        lines.push(Line::from(vec![
+            Span::from("  Pattern mode: "),
+            Span::styled(
+                if sparse_checkout.cone {
+                    "cone"
+                } else {
+                    "non-cone (membership not evaluated)"
+                },
+                value_style,
+            ),
+        ]));
+        lines.push(Line::from("  Patterns:"));
+        for pattern in &sparse_checkout.patterns {
+            lines.push(Line::from(vec![
+                Span::from("    "),
+                Span::styled(pattern.clone(), Style::default().fg(Color::Cyan)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_membership_summary(&self, lines: &mut Vec<Line<'static>>) {
+        let Some(sparse_checkout) = &self.git_index.sparse_checkout else {
+            return;
+        };
+        if !sparse_checkout.cone {
+            return;
+        }
+
+        let files = self
+            .git_index
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_sparse_directory());
+        let (mut in_cone, mut out_of_cone) = (0, 0);
+        for entry in files {
+            if sparse_checkout.file_in_cone(&entry.path) == Some(true) {
+                in_cone += 1;
+            } else {
+                out_of_cone += 1;
+            }
+        }
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  File entries in cone: "),
+            Span::styled(format!("{in_cone}"), value_style),
+            Span::from(", out of cone: "),
+            Span::styled(format!("{out_of_cone}"), value_style),
+            Span::from(", sparse directories: "),
+            Span::styled(
+                format!("{}", self.git_index.sparse_directories.len()),
+                value_style,
+            ),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    fn format_directories(&self, lines: &mut Vec<Line<'static>>) {
+        let directories = &self.git_index.sparse_directories;
+        if directories.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                "  No sparse directory entries: every directory is inside the cone",
+                Style::default().fg(Color::Gray),
+            )]));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        for directory in directories.iter().take(MAX_LISTED_DIRECTORIES) {
+            Self::format_directory(directory, lines);
+        }
+        if directories.len() > MAX_LISTED_DIRECTORIES {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more sparse directories not shown",
+                    directories.len() - MAX_LISTED_DIRECTORIES
+                ),
+                Style::default().fg(Color::Gray),
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_directory(directory: &SparseDirectory, lines: &mut Vec<Line<'static>>) {
+        let (label, label_style) = match directory.membership {
+            Some(ConeMembership::OutOfCone) => (
+                ConeMembership::OutOfCone.to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            // A collapsed directory the cone includes means the index is stale
+            Some(membership) => (membership.to_string(), Style::default().fg(Color::Red)),
+            None => ("cone unknown".to_string(), Style::default().fg(Color::Gray)),
+        };
+
+        lines.push(Line::from(vec![
+            Span::from("  ▼ "),
+            Span::styled(
+                directory.path.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    "  tree {}",
+                    &directory.tree_oid[..12.min(directory.tree_oid.len())]
+                ),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::from("  "),
+            Span::styled(format!("[{label}]"), label_style),
+        ]));
+
+        match &directory.tree {
+            Ok(nodes) => {
+                Self::format_tree(nodes, 2, lines);
+                if directory.omitted_entries > 0 {
+                    lines.push(Line::from(vec![Span::styled(
+                        format!("      ... {} more entries", directory.omitted_entries),
+                        Style::default().fg(Color::Gray),
+                    )]));
+                }
+            }
+            Err(e) => lines.push(Line::from(vec![Span::styled(
+                format!("      Could not open tree: {e}"),
+                Style::default().fg(Color::Red),
+            )])),
+        }
+    }
+
+    fn format_tree(nodes: &[TreeNode], depth: usize, lines: &mut Vec<Line<'static>>) {
+        let indent = "  ".repeat(depth);
+        for node in nodes {
+            let marker = match (&node.is_tree, &node.children) {
+                (true, Some(Ok(_))) => "▼ ",
+                (true, _) => "▶ ",
+                (false, _) => "  ",
+            };
+            let name = if node.is_tree {
+                format!("{}/", node.name)
+            } else {
+                node.name.clone()
+            };
+            lines.push(Line::from(vec![
+                Span::from(format!("{indent}{marker}")),
+                Span::styled(
+                    format!("{:>6} ", node.mode),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("{} ", &node.oid[..12.min(node.oid.len())]),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::from(name),
+            ]));
+
+            match &node.children {
+                Some(Ok(children)) => Self::format_tree(children, depth + 1, lines),
+                Some(Err(e)) => lines.push(Line::from(vec![Span::styled(
+                    format!("{indent}    Could not open tree: {e}"),
+                    Style::default().fg(Color::Red),
+                )])),
+                None => {}
+            }
+        }
+    }
+}
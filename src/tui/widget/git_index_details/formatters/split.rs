@@ -0,0 +1,226 @@
+use crate::git::index::{EntrySource, GitIndex, LinkExtension, SplitIndex};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Merged entries beyond this count are summarized instead of listed
+const MAX_LISTED_ENTRIES: usize = 500;
+
+pub struct SplitIndexFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> SplitIndexFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_split_index(&self, lines: &mut Vec<Line<'static>>) {
+        let Some(link) = &self.git_index.link else {
+            return;
+        };
+
+        Self::format_link(link, lines);
+
+        match &self.git_index.split_index {
+            Some(Ok(split_index)) => Self::format_merged_entries(split_index, lines),
+            Some(Err(e)) => {
+                lines.push(Line::from(vec![Span::styled(
+                    format!("  Could not load the shared index: {e}"),
+                    Style::default().fg(Color::Red),
+                )]));
+                lines.push(Line::from(""));
+            }
+            None => {}
+        }
+    }
+
+    fn format_link(link: &LinkExtension, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "SPLIT INDEX (link)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  With core.splitIndex, most entries live in a shared index file that is",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  rarely rewritten. This file only stores changes on top of it: entries",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  to delete, entries to replace (nameless entries at the start of this",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  file, in bitmap order) and new entries.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Shared index: "),
+            Span::styled(
+                link.shared_index_file_name()
+                    .unwrap_or_else(|| "(none: null object ID)".to_string()),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]));
+
+        let mut push_bitmap = |name: &str, positions: &[u64], compressed: Option<usize>| {
+            let mut spans = vec![
+                Span::from(format!("  {name}: ")),
+                Span::styled(format!("{} bit(s) set", positions.len()), value_style),
+            ];
+            if let Some(size) = compressed {
+                spans.push(Span::styled(format!(" (EWAH, {size} bytes)"), gray));
+            }
+            lines.push(Line::from(spans));
+            if !positions.is_empty() {
+                lines.push(Line::from(vec![Span::styled(
+                    format!(
+                        "    shared positions: {}",
+                        Self::format_positions(positions)
+                    ),
+                    Style::default().fg(Color::Cyan),
+                )]));
+            }
+        };
+
+        push_bitmap(
+            "Delete bitmap",
+            &link.deleted_positions(),
+            link.delete_bitmap
+                .as_ref()
+                .map(|bitmap| bitmap.compressed_byte_size()),
+        );
+        push_bitmap(
+            "Replace bitmap",
+            &link.replaced_positions(),
+            link.replace_bitmap
+                .as_ref()
+                .map(|bitmap| bitmap.compressed_byte_size()),
+        );
+
+        lines.push(Line::from(""));
+    }
+
+    fn format_merged_entries(split_index: &SplitIndex, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "MERGED INDEX",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  "),
+            Span::styled(
+                format!("{}", split_index.shared_index.entry_count()),
+                value_style,
+            ),
+            Span::from(" shared entries, "),
+            Span::styled(format!("{}", split_index.deleted_count), value_style),
+            Span::from(" deleted, "),
+            Span::styled(format!("{}", split_index.replaced_count), value_style),
+            Span::from(" replaced, "),
+            Span::styled(format!("{}", split_index.added_count), value_style),
+            Span::from(" added → "),
+            Span::styled(format!("{}", split_index.entries.len()), value_style),
+            Span::from(" entries"),
+        ]));
+        lines.push(Line::from(""));
+
+        lines.push(Line::from(vec![
+            Span::styled(" Mode  ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Object ID   ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled("St", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Source                     ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled("Path", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "────────┼──────────────┼────┼───────────────────────────┼──────────────────",
+            gray,
+        ));
+
+        for merged in split_index.entries.iter().take(MAX_LISTED_ENTRIES) {
+            let entry = &merged.entry;
+            let oid = entry.oid_hex();
+            let source_style = match merged.source {
+                EntrySource::Shared { .. } => gray,
+                EntrySource::Replaced { .. } => Style::default().fg(Color::Magenta),
+                EntrySource::Split { .. } => Style::default().fg(Color::LightBlue),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" {:06o}", entry.mode),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    oid[..12.min(oid.len())].to_string(),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:2}", entry.stage())),
+                Span::styled(" │ ", gray),
+                Span::styled(format!("{:<27}", merged.source.to_string()), source_style),
+                Span::styled(" │ ", gray),
+                Span::from(entry.path.clone()),
+            ]));
+        }
+
+        if split_index.entries.len() > MAX_LISTED_ENTRIES {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more entries not shown",
+                    split_index.entries.len() - MAX_LISTED_ENTRIES
+                ),
+                gray,
+            )]));
+        }
+
+        lines.push(Line::from(""));
+    }
+
+    /// Render a list of positions, collapsing consecutive runs into ranges
+    fn format_positions(positions: &[u64]) -> String {
+        let mut parts = Vec::new();
+        let mut i = 0;
+        while i < positions.len() && parts.len() < 20 {
+            let start = positions[i];
+            let mut end = start;
+            while i + 1 < positions.len() && positions[i + 1] == end + 1 {
+                end += 1;
+                i += 1;
+            }
+            if start == end {
+                parts.push(format!("{start}"));
+            } else {
+                parts.push(format!("{start}-{end}"));
+            }
+            i += 1;
+        }
+        if i < positions.len() {
+            parts.push("...".to_string());
+        }
+        parts.join(", ")
+    }
+}
@@ -0,0 +1,223 @@
+use crate::git::index::untracked_cache::ExcludeFileState;
+use crate::git::index::{GitIndex, UntrackedCache};
+use crate::tui::widget::formatters_utils::format_epoch_utc;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Directories beyond this count are summarized instead of listed
+const MAX_LISTED_DIRECTORIES: usize = 300;
+/// Untracked names shown per directory
+const MAX_UNTRACKED_PER_DIRECTORY: usize = 5;
+
+pub struct UntrackedCacheFormatter<'a> {
+    git_index: &'a GitIndex,
+}
+
+impl<'a> UntrackedCacheFormatter<'a> {
+    #[must_use]
+    pub const fn new(git_index: &'a GitIndex) -> Self {
+        Self { git_index }
+    }
+
+    pub fn format_untracked_cache(&self, lines: &mut Vec<Line<'static>>) {
+        if self
+            .git_index
+            .extension(&UntrackedCache::SIGNATURE)
+            .is_none()
+        {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "UNTRACKED CACHE (UNTR)",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Caches the untracked files of every directory along with the directory's",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  stat data. If a cached directory's mtime is unchanged, git status reuses",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  the list instead of reading the directory again.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let Some(cache) = &self.git_index.untracked_cache else {
+            lines.push(Line::from(vec![Span::styled(
+                "  The extension is present but could not be decoded.",
+                Style::default().fg(Color::Red),
+            )]));
+            lines.push(Line::from(""));
+            return;
+        };
+
+        Self::format_settings(cache, lines);
+        Self::format_directories(cache, lines);
+    }
+
+    fn format_settings(cache: &UntrackedCache, lines: &mut Vec<Line<'static>>) {
+        let value_style = Style::default().fg(Color::LightGreen);
+
+        for identifier in &cache.identifiers {
+            lines.push(Line::from(vec![
+                Span::from("  Valid for: "),
+                Span::styled(identifier.clone(), value_style),
+            ]));
+        }
+        lines.push(Line::from(vec![
+            Span::from("  Directory flags: "),
+            Span::styled(
+                format!(
+                    "0x{:x} ({})",
+                    cache.dir_flags,
+                    cache.dir_flag_names().join(", ")
+                ),
+                value_style,
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Per-directory exclude file: "),
+            Span::styled(cache.exclude_per_dir.clone(), value_style),
+        ]));
+        Self::format_exclude_file("info/exclude", &cache.info_exclude, lines);
+        Self::format_exclude_file("core.excludesFile", &cache.excludes_file, lines);
+
+        lines.push(Line::from(vec![
+            Span::from("  Directories: "),
+            Span::styled(format!("{}", cache.directories.len()), value_style),
+            Span::from(", reusable: "),
+            Span::styled(format!("{}", cache.valid_directory_count()), value_style),
+            Span::from(", untracked names: "),
+            Span::styled(format!("{}", cache.untracked_count()), value_style),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    fn format_exclude_file(name: &str, state: &ExcludeFileState, lines: &mut Vec<Line<'static>>) {
+        let value = if state.exists() {
+            let oid = hex::encode(&state.oid);
+            format!(
+                "{} (mtime {})",
+                &oid[..12.min(oid.len())],
+                format_epoch_utc(state.stat.mtime.seconds)
+            )
+        } else {
+            "not present".to_string()
+        };
+        lines.push(Line::from(vec![
+            Span::from(format!("  {name}: ")),
+            Span::styled(value, Style::default().fg(Color::Yellow)),
+        ]));
+    }
+
+    fn format_directories(cache: &UntrackedCache, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+
+        if cache.directories.is_empty() {
+            lines.push(Line::from("No directory blocks in this untracked cache."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled(" Status ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Ignore hash ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled("Untracked", Style::default().add_modifier(Modifier::BOLD)),
+            Span::styled(" │ ", gray),
+            Span::styled("Directory", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "────────┼──────────────┼───────────┼──────────────────────────",
+            gray,
+        ));
+
+        for directory in cache.directories.iter().take(MAX_LISTED_DIRECTORIES) {
+            let (status, status_style) = if directory.check_only {
+                ("check ", Style::default().fg(Color::Yellow))
+            } else if directory.is_valid() {
+                ("cached", Style::default().fg(Color::LightGreen))
+            } else {
+                ("rescan", Style::default().fg(Color::Red))
+            };
+            let ignore_hash = directory
+                .exclude_oid
+                .as_ref()
+                .map_or_else(|| "-".to_string(), |oid| hex::encode(oid)[..12].to_string());
+            let name = if directory.path.is_empty() {
+                "(root)".to_string()
+            } else {
+                directory.path.clone()
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {status} "), status_style),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    format!("{ignore_hash:<12}"),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    format!("{:9}", directory.untracked.len()),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{}{name}", "  ".repeat(directory.depth))),
+            ]));
+
+            for untracked in directory.untracked.iter().take(MAX_UNTRACKED_PER_DIRECTORY) {
+                lines.push(Line::from(vec![
+                    Span::styled("        │              │           │ ", gray),
+                    Span::styled(
+                        format!("{}  ? {untracked}", "  ".repeat(directory.depth)),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                ]));
+            }
+            if directory.untracked.len() > MAX_UNTRACKED_PER_DIRECTORY {
+                lines.push(Line::from(vec![Span::styled(
+                    format!(
+                        "        │              │           │ {}  ... {} more",
+                        "  ".repeat(directory.depth),
+                        directory.untracked.len() - MAX_UNTRACKED_PER_DIRECTORY
+                    ),
+                    gray,
+                )]));
+            }
+        }
+
+        if cache.directories.len() > MAX_LISTED_DIRECTORIES {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more directories not shown",
+                    cache.directories.len() - MAX_LISTED_DIRECTORIES
+                ),
+                gray,
+            )]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![Span::styled(
+            "  cached = skipped while its mtime matches, rescan = read again,",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  check = only checked for the existence of untracked files",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+    }
+}
@@ -1,15 +1,36 @@
-use crate::git::loose_object::{TreeEntryType, TreeObject};
-use ratatui::style::{Modifier, Style};
-use ratatui::text::Line;
+use crate::git::loose_object::{ParsedContent, TreeEntryType, TreeObject};
+use crate::git::submodule::GitlinkTarget;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
 
 pub struct TreeFormatter<'a> {
     tree: &'a TreeObject,
+    /// Where the tree's gitlinks point, in entry order
+    gitlinks: &'a [GitlinkTarget],
+    /// Index into `gitlinks` of the one Enter opens
+    selected_gitlink: Option<usize>,
 }
 
 impl<'a> TreeFormatter<'a> {
     #[must_use]
     pub const fn new(tree: &'a TreeObject) -> Self {
-        Self { tree }
+        Self {
+            tree,
+            gitlinks: &[],
+            selected_gitlink: None,
+        }
+    }
+
+    /// Follow each gitlink entry with the submodule commit it names
+    #[must_use]
+    pub const fn with_gitlinks(
+        mut self,
+        gitlinks: &'a [GitlinkTarget],
+        selected_gitlink: Option<usize>,
+    ) -> Self {
+        self.gitlinks = gitlinks;
+        self.selected_gitlink = selected_gitlink;
+        self
     }
 
     pub fn format_tree(&self, lines: &mut Vec<Line<'static>>) {
@@ -72,6 +93,7 @@ impl<'a> TreeFormatter<'a> {
         ));
         lines.push(Line::from("─".repeat(20)));
 
+        let mut submodule_index = 0;
         for entry in &self.tree.entries {
             let type_icon = match entry.object_type {
                 TreeEntryType::Blob => "📄",
@@ -97,10 +119,60 @@ impl<'a> TreeFormatter<'a> {
                 &entry.sha1[..8],
                 entry.name
             )));
+
+            if entry.object_type == TreeEntryType::Submodule {
+                self.add_gitlink_line(lines, submodule_index);
+                submodule_index += 1;
+            }
         }
 
         if self.tree.entries.is_empty() {
             lines.push(Line::from("(empty tree)"));
         }
     }
+
+    /// The line under a gitlink naming the commit it records
+    fn add_gitlink_line(&self, lines: &mut Vec<Line<'static>>, index: usize) {
+        let Some(gitlink) = self.gitlinks.get(index) else {
+            return;
+        };
+        let marker = if self.selected_gitlink == Some(index) {
+            Span::styled("  ▶ ", Style::default().fg(Color::Yellow))
+        } else {
+            Span::from("    ")
+        };
+
+        let (Some(submodule), Some(commit)) = (&gitlink.submodule, &gitlink.commit) else {
+            lines.push(Line::from(vec![
+                marker,
+                Span::styled(gitlink_problem(gitlink), Style::default().fg(Color::Red)),
+            ]));
+            return;
+        };
+        let subject = match commit.get_parsed_content() {
+            Some(ParsedContent::Commit(commit)) => commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            _ => format!("not a commit but a {}", commit.object_type),
+        };
+        lines.push(Line::from(vec![
+            marker,
+            Span::from("↳ "),
+            Span::styled(submodule.name.clone(), Style::default().fg(Color::Cyan)),
+            Span::from(": "),
+            Span::styled(subject, Style::default().fg(Color::LightGreen)),
+        ]));
+    }
+}
+
+/// Why a gitlink's commit can't be shown
+fn gitlink_problem(gitlink: &GitlinkTarget) -> String {
+    match (&gitlink.path, &gitlink.submodule) {
+        (None, _) => "↳ tree is not a directory of HEAD holding a submodule".to_string(),
+        (Some(path), None) => format!("↳ no submodule is checked out at {path}"),
+        (Some(_), Some(submodule)) => format!("↳ commit not found in {}", submodule.name),
+    }
 }
@@ -1,4 +1,6 @@
+use crate::git::compat_map::CompatObjectId;
 use crate::git::loose_object::LooseObject;
+use crate::git::submodule::GitlinkTarget;
 use crate::tui::widget::ScrollableTextWidget;
 use ratatui::text::Text;
 
@@ -9,21 +11,81 @@ use formatters::{BlobFormatter, CommitFormatter, TagFormatter, TreeFormatter};
 #[derive(Debug, Clone)]
 pub struct LooseObjectWidget {
     loose_obj: LooseObject,
+    /// Where the gitlinks of a tree point, in entry order
+    gitlinks: Vec<GitlinkTarget>,
+    selected_gitlink: Option<usize>,
+    /// The object's name in the repository's compatibility format
+    compat_id: Option<CompatObjectId>,
     scrollable_widget: ScrollableTextWidget,
 }
 
 impl LooseObjectWidget {
     #[must_use]
     pub fn new(loose_obj: LooseObject) -> Self {
-        let mut scrollable_widget = ScrollableTextWidget::new();
-        // Pre-generate and cache the content
-        let content = LooseObjectFormatter::new(&loose_obj).generate_content();
-        scrollable_widget.set_text(content);
+        Self::with_gitlinks(loose_obj, Vec::new())
+    }
 
-        Self {
+    /// A widget for a tree whose gitlinks were resolved to submodule
+    /// commits
+    #[must_use]
+    pub fn with_gitlinks(loose_obj: LooseObject, gitlinks: Vec<GitlinkTarget>) -> Self {
+        let mut widget = Self {
             loose_obj,
-            scrollable_widget,
+            gitlinks,
+            selected_gitlink: None,
+            compat_id: None,
+            scrollable_widget: ScrollableTextWidget::new(),
+        };
+        // Pre-generate and cache the content
+        widget.refresh_content();
+        widget
+    }
+
+    /// Also show the object's name in the compatibility format
+    #[must_use]
+    pub fn with_compat_id(mut self, compat_id: Option<CompatObjectId>) -> Self {
+        self.compat_id = compat_id;
+        self.refresh_content();
+        self
+    }
+
+    fn refresh_content(&mut self) {
+        let scroll_position = self.scrollable_widget.scroll_position();
+        let content = LooseObjectFormatter::new(
+            &self.loose_obj,
+            &self.gitlinks,
+            self.selected_gitlink,
+            self.compat_id.as_ref(),
+        )
+        .generate_content();
+        self.scrollable_widget.set_text(content);
+        self.scrollable_widget.scroll_to_line(scroll_position);
+    }
+
+    /// Whether the object is a tree with gitlinks
+    #[must_use]
+    pub const fn has_gitlinks(&self) -> bool {
+        !self.gitlinks.is_empty()
+    }
+
+    /// The gitlink Enter opens
+    #[must_use]
+    pub fn selected_gitlink(&self) -> Option<&GitlinkTarget> {
+        self.selected_gitlink
+            .and_then(|index| self.gitlinks.get(index))
+    }
+
+    /// Select the next gitlink, wrapping around, or the first one when
+    /// none is selected
+    pub fn select_next_gitlink(&mut self) {
+        if self.gitlinks.is_empty() {
+            return;
         }
+        self.selected_gitlink = Some(
+            self.selected_gitlink
+                .map_or(0, |index| (index + 1) % self.gitlinks.len()),
+        );
+        self.refresh_content();
     }
 
     #[must_use]
@@ -66,18 +128,31 @@ impl LooseObjectWidget {
 
 struct LooseObjectFormatter<'a> {
     loose_obj: &'a LooseObject,
+    gitlinks: &'a [GitlinkTarget],
+    selected_gitlink: Option<usize>,
+    compat_id: Option<&'a CompatObjectId>,
 }
 
 impl<'a> LooseObjectFormatter<'a> {
-    const fn new(loose_obj: &'a LooseObject) -> Self {
-        Self { loose_obj }
+    const fn new(
+        loose_obj: &'a LooseObject,
+        gitlinks: &'a [GitlinkTarget],
+        selected_gitlink: Option<usize>,
+        compat_id: Option<&'a CompatObjectId>,
+    ) -> Self {
+        Self {
+            loose_obj,
+            gitlinks,
+            selected_gitlink,
+            compat_id,
+        }
     }
 
     fn generate_content(&self) -> Text<'static> {
         let mut lines = Vec::new();
 
         // Add object header information
-        Self::add_object_header(&mut lines, self.loose_obj);
+        Self::add_object_header(&mut lines, self.loose_obj, self.compat_id);
 
         // Add type-specific formatted content
         match self.loose_obj.get_parsed_content() {
@@ -86,7 +161,8 @@ impl<'a> LooseObjectFormatter<'a> {
                 formatter.format_commit(&mut lines);
             }
             Some(crate::git::loose_object::ParsedContent::Tree(tree)) => {
-                let formatter = TreeFormatter::new(tree);
+                let formatter =
+                    TreeFormatter::new(tree).with_gitlinks(self.gitlinks, self.selected_gitlink);
                 formatter.format_tree(&mut lines);
             }
             Some(crate::git::loose_object::ParsedContent::Blob(content)) => {
@@ -105,7 +181,11 @@ impl<'a> LooseObjectFormatter<'a> {
         Text::from(lines)
     }
 
-    fn add_object_header(lines: &mut Vec<ratatui::text::Line<'static>>, loose_obj: &LooseObject) {
+    fn add_object_header(
+        lines: &mut Vec<ratatui::text::Line<'static>>,
+        loose_obj: &LooseObject,
+        compat_id: Option<&CompatObjectId>,
+    ) {
         use ratatui::style::{Modifier, Style};
         use ratatui::text::Line;
 
@@ -116,6 +196,12 @@ impl<'a> LooseObjectFormatter<'a> {
         lines.push(Line::from("─".repeat(40)));
         lines.push(Line::from(""));
         lines.push(Line::from(format!("Object ID: {}", loose_obj.object_id)));
+        if let Some(compat_id) = compat_id {
+            lines.push(Line::from(format!(
+                "{} ID: {}",
+                compat_id.object_format, compat_id.id
+            )));
+        }
         lines.push(Line::from(format!("Type: {}", loose_obj.object_type)));
         lines.push(Line::from(format!("Size: {} bytes", loose_obj.size)));
         lines.push(Line::from(""));
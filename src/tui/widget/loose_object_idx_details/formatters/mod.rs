@@ -0,0 +1,105 @@
+use crate::git::compat_map::LooseObjectIdx;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+pub struct LooseObjectIdxFormatter<'a> {
+    loose_object_idx: &'a LooseObjectIdx,
+}
+
+impl<'a> LooseObjectIdxFormatter<'a> {
+    #[must_use]
+    pub const fn new(loose_object_idx: &'a LooseObjectIdx) -> Self {
+        Self { loose_object_idx }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = vec![
+            Line::from(
+                "The loose object map of a repository with `extensions.compatObjectFormat`.",
+            ),
+            Line::from("Git names every object in both hash functions, but a loose object's path"),
+            Line::from("only gives the name it is stored under; each line of this file pairs that"),
+            Line::from(
+                "name with the other one. Packed objects are mapped by version-3 .idx files.",
+            ),
+            Line::from(""),
+        ];
+
+        self.add_formats_section(&mut lines);
+        self.add_entries_section(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_formats_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "FORMAT");
+
+        let object_format = self.loose_object_idx.object_format;
+        let compat_format = self.loose_object_idx.compat_format;
+        lines.push(Line::from(format!(
+            "Header:        {}",
+            LooseObjectIdx::HEADER
+        )));
+        lines.push(Line::from(format!(
+            "Line:          <{object_format} name> <{compat_format} name>"
+        )));
+        lines.push(Line::from(format!(
+            "Stored as:     {object_format} ({} hex digits)",
+            object_format.hex_len()
+        )));
+        lines.push(Line::from(format!(
+            "Translated to: {compat_format} ({} hex digits)",
+            compat_format.hex_len()
+        )));
+        lines.push(Line::from(format!(
+            "Entries:       {}",
+            self.loose_object_idx.entries.len()
+        )));
+        lines.push(Line::from(""));
+    }
+
+    fn add_entries_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "ENTRIES");
+
+        if self.loose_object_idx.entries.is_empty() {
+            lines.push(Line::from("No objects mapped yet."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let gray = Style::default().fg(Color::Gray);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let stored_width = self.loose_object_idx.object_format.hex_len();
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "  {:<stored_width$}",
+                    self.loose_object_idx.object_format.to_string()
+                ),
+                bold,
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled(self.loose_object_idx.compat_format.to_string(), bold),
+        ]));
+        for (stored, compat) in &self.loose_object_idx.entries {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {stored}"), Style::default().fg(Color::Yellow)),
+                Span::styled(" │ ", gray),
+                Span::styled(compat.clone(), Style::default().fg(Color::Cyan)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn push_title(lines: &mut Vec<Line<'static>>, title: &'static str) {
+        lines.push(Line::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+    }
+}
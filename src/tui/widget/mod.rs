@@ -1,17 +1,37 @@
+pub mod commit_graph_chain_details;
+pub mod commit_graph_details;
 pub mod formatters_utils;
+pub mod git_index_details;
 pub mod loose_obj_details;
+pub mod loose_object_idx_details;
+pub mod multi_pack_index_chain_details;
 pub mod multi_pack_index_details;
 pub mod pack_bitmap_details;
 pub mod pack_idx_details;
 pub mod pack_mtimes_details;
 pub mod pack_obj_details;
 pub mod pack_rev_details;
+pub mod packed_refs_details;
+pub mod reflog_details;
+pub mod reftable_details;
+pub mod reftable_stack_details;
 pub mod scrollable_text;
+pub mod worktree_details;
 
+pub use commit_graph_chain_details::CommitGraphChainWidget;
+pub use commit_graph_details::CommitGraphWidget;
+pub use git_index_details::GitIndexWidget;
+pub use loose_object_idx_details::LooseObjectIdxWidget;
+pub use multi_pack_index_chain_details::MultiPackIndexChainWidget;
 pub use multi_pack_index_details::MultiPackIndexWidget;
 pub use pack_bitmap_details::PackBitmapWidget;
 pub use pack_idx_details::PackIndexWidget;
 pub use pack_mtimes_details::PackMtimesWidget;
 pub use pack_obj_details::PackObjectWidget;
 pub use pack_rev_details::PackReverseIndexWidget;
+pub use packed_refs_details::PackedRefsWidget;
+pub use reflog_details::ReflogWidget;
+pub use reftable_details::ReftableWidget;
+pub use reftable_stack_details::ReftableStackWidget;
 pub use scrollable_text::ScrollableTextWidget;
+pub use worktree_details::WorktreeWidget;
@@ -0,0 +1,166 @@
+use crate::git::commit_graph::BaseCheck;
+use crate::git::pack::{MultiPackIndexChain, MultiPackIndexLayer};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Checksums are abbreviated to this many hex digits in the table
+const SHORT_HASH_LEN: usize = 12;
+
+pub struct MidxLayersFormatter<'a> {
+    chain: &'a MultiPackIndexChain,
+}
+
+impl<'a> MidxLayersFormatter<'a> {
+    #[must_use]
+    pub const fn new(chain: &'a MultiPackIndexChain) -> Self {
+        Self { chain }
+    }
+
+    pub fn format_layers(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "LAYERS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  In chain order, base layer first. Object and pack counts are",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  cumulative: a layer numbers its objects and packs after those of the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  layers below. Its BASE chunk must list exactly those layers, and its",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  trailing checksum must be the hash in its file name.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        if self.chain.layers.is_empty() {
+            lines.push(Line::from("The chain lists no layers."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("Layer", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Checksum    ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Packs", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Objects", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Cumulative   ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Name", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Base list", bold),
+        ]));
+        lines.push(Line::styled(
+            "──────┼──────────────┼───────┼─────────┼───────────────┼──────┼─────────────────",
+            gray,
+        ));
+
+        for (index, layer) in self.chain.layers.iter().enumerate() {
+            Self::format_layer(index, layer, lines);
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_layer(index: usize, layer: &MultiPackIndexLayer, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+        let ok = Style::default().fg(Color::LightGreen);
+        let bad = Style::default().fg(Color::Red);
+
+        let name_check = if layer.checksum_matches_name() {
+            Span::styled("✓   ", ok)
+        } else {
+            Span::styled("✗   ", bad)
+        };
+        let base_check = match &layer.base_check {
+            BaseCheck::Matches if index == 0 => Span::styled("✓ (no bases)", ok),
+            BaseCheck::Matches if index == 1 => Span::styled("✓ layer 0", ok),
+            BaseCheck::Matches => Span::styled(format!("✓ layers 0-{}", index - 1), ok),
+            BaseCheck::CountMismatch { expected, found } => {
+                Span::styled(format!("✗ lists {found} bases, expected {expected}"), bad)
+            }
+            BaseCheck::Mismatch {
+                index: base_index,
+                expected,
+                found,
+            } => Span::styled(
+                format!(
+                    "✗ base {base_index} is {}, expected {}",
+                    &found[..SHORT_HASH_LEN.min(found.len())],
+                    &expected[..SHORT_HASH_LEN.min(expected.len())]
+                ),
+                bad,
+            ),
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{index:5}"), Style::default().fg(Color::Cyan)),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                layer.hash[..SHORT_HASH_LEN.min(layer.hash.len())].to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{:5}", layer.midx.pack_count())),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{:7}", layer.midx.object_count())),
+            Span::styled(" │ ", gray),
+            Span::from(format!(
+                "{:<13}",
+                format!("{}p {}o", layer.packs().end, layer.objects().end)
+            )),
+            Span::styled(" │ ", gray),
+            name_check,
+            Span::styled(" │ ", gray),
+            base_check,
+        ]));
+    }
+
+    pub fn format_packs(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "PACKS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Global pack-int-id: the layer's first pack id plus the pack's index in",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  that layer's PNAM chunk.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        for (index, layer) in self.chain.layers.iter().enumerate() {
+            for (local, name) in layer.midx.pack_names.iter().enumerate() {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:5}", layer.first_pack + local),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(format!(" (L{index}) "), gray),
+                    Span::from(name.clone()),
+                ]));
+            }
+        }
+        lines.push(Line::from(""));
+    }
+}
@@ -0,0 +1,110 @@
+pub mod layers;
+pub mod objects;
+
+use crate::git::pack::MultiPackIndexChain;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+pub use layers::MidxLayersFormatter;
+pub use objects::ChainObjectsFormatter;
+
+pub struct MultiPackIndexChainFormatter<'a> {
+    chain: &'a MultiPackIndexChain,
+}
+
+impl<'a> MultiPackIndexChainFormatter<'a> {
+    #[must_use]
+    pub const fn new(chain: &'a MultiPackIndexChain) -> Self {
+        Self { chain }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = vec![
+            Line::from("An incremental multi-pack-index: instead of rewriting one index over"),
+            Line::from("every pack, `git multi-pack-index write --incremental` indexes only"),
+            Line::from("the new packs in a layer on top. The chain file lists the layers'"),
+            Line::from("checksums, base layer first; each layer names the layers below it in"),
+            Line::from("its BASE chunk. Lookups search the layers from the top down."),
+            Line::from(""),
+        ];
+
+        self.add_chain_section(&mut lines);
+        MidxLayersFormatter::new(self.chain).format_layers(&mut lines);
+        MidxLayersFormatter::new(self.chain).format_packs(&mut lines);
+        ChainObjectsFormatter::new(self.chain).format_objects(&mut lines);
+        self.add_stack_diagram(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_chain_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "MULTI-PACK-INDEX CHAIN",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Chain file: "),
+            Span::styled(self.chain.path.display().to_string(), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Layers: "),
+            Span::styled(format!("{}", self.chain.layers.len()), value_style),
+            Span::from(", total packs: "),
+            Span::styled(format!("{}", self.chain.pack_count()), value_style),
+            Span::from(", total objects: "),
+            Span::styled(format!("{}", self.chain.object_count()), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Consistency: "),
+            if self.chain.is_consistent() {
+                Span::styled("✓ every layer matches the chain", value_style)
+            } else {
+                Span::styled(
+                    "✗ some layers disagree with the chain (see below)",
+                    Style::default().fg(Color::Red),
+                )
+            },
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    fn add_stack_diagram(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "LAYER STACK",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        lines.push(Line::from("┌─────────────────────────────────┐"));
+        for (index, layer) in self.chain.layers.iter().enumerate().rev() {
+            let objects = layer.objects();
+            let packs = layer.packs();
+            let label = if index == 0 {
+                format!("Layer {index} (base)")
+            } else {
+                format!("Layer {index}")
+            };
+            lines.push(Line::from(format!(
+                "│ {label:<18} {:<12} │ objects {}..{}, packs {}..{}",
+                &layer.hash[..12.min(layer.hash.len())],
+                objects.start,
+                objects.end,
+                packs.start,
+                packs.end
+            )));
+            if index > 0 {
+                lines.push(Line::from("├─────────────────────────────────┤"));
+            }
+        }
+        lines.push(Line::from("└─────────────────────────────────┘"));
+        lines.push(Line::from(""));
+    }
+}
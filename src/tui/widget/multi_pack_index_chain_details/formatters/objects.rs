@@ -0,0 +1,111 @@
+use crate::git::pack::MultiPackIndexChain;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Objects beyond this count are summarized instead of listed
+const MAX_LISTED_OBJECTS: usize = 1000;
+/// Object IDs are abbreviated to this many hex digits in the table
+const SHORT_OID_LEN: usize = 12;
+
+pub struct ChainObjectsFormatter<'a> {
+    chain: &'a MultiPackIndexChain,
+}
+
+impl<'a> ChainObjectsFormatter<'a> {
+    #[must_use]
+    pub const fn new(chain: &'a MultiPackIndexChain) -> Self {
+        Self { chain }
+    }
+
+    pub fn format_objects(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "OBJECTS ACROSS LAYERS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  An object's global position is its layer's first object plus its row",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  in that layer's OIDL; object IDs are sorted within a layer only.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let object_count = self.chain.object_count();
+        if object_count == 0 {
+            lines.push(Line::from("No objects in this chain."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("Global", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Layer", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Object      ", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Pack", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Offset", bold),
+        ]));
+        lines.push(Line::styled(
+            "───────┼───────┼──────────────┼──────┼──────────────",
+            gray,
+        ));
+
+        for position in 0..object_count.min(MAX_LISTED_OBJECTS) {
+            self.format_object(position, lines);
+        }
+
+        if object_count > MAX_LISTED_OBJECTS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more objects not shown",
+                    object_count - MAX_LISTED_OBJECTS
+                ),
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_object(&self, position: usize, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+        let Some((layer_index, local)) = self.chain.locate(position) else {
+            return;
+        };
+        let layer = &self.chain.layers[layer_index];
+        let oid = layer.midx.oid_hex_at(local).unwrap_or_default();
+        let (pack, offset) = layer.midx.offset_at(local).map_or_else(
+            || ("?".to_string(), "?".to_string()),
+            |(pack_id, offset)| {
+                (
+                    format!("{}", layer.first_pack + pack_id as usize),
+                    format!("{offset}"),
+                )
+            },
+        );
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{position:6}"), Style::default().fg(Color::Cyan)),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{:>5}", format!("L{layer_index}"))),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                oid[..SHORT_OID_LEN.min(oid.len())].to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(" │ ", gray),
+            Span::from(format!("{pack:>4}")),
+            Span::styled(" │ ", gray),
+            Span::from(offset),
+        ]));
+    }
+}
@@ -26,7 +26,7 @@ impl<'a> ChecksumsFormatter<'a> {
 
     fn add_sha1_checksums_section(&self, lines: &mut Vec<Line<'static>>) {
         lines.push(Line::styled(
-            "SHA-1 Checksums:",
+            format!("{} Checksums:", self.pack_index.object_format),
             Style::default().add_modifier(Modifier::BOLD),
         ));
         lines.push(Line::from(""));
@@ -37,7 +37,7 @@ impl<'a> ChecksumsFormatter<'a> {
             Style::default().add_modifier(Modifier::UNDERLINED),
         ));
         lines.push(Line::styled(
-            format!("  {}", hex::encode(self.pack_index.pack_checksum)),
+            format!("  {}", hex::encode(&self.pack_index.pack_checksum)),
             Style::default().fg(Color::Green),
         ));
         lines.push(Line::from(
@@ -57,7 +57,7 @@ impl<'a> ChecksumsFormatter<'a> {
             Style::default().add_modifier(Modifier::UNDERLINED),
         ));
         lines.push(Line::styled(
-            format!("  {}", hex::encode(self.pack_index.index_checksum)),
+            format!("  {}", hex::encode(&self.pack_index.index_checksum)),
             Style::default().fg(Color::Green),
         ));
         lines.push(Line::from(
@@ -0,0 +1,100 @@
+use crate::git::pack::PackIndex;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Compat names listed before the rest are summarized
+const MAX_COMPAT_NAMES: usize = 20;
+
+/// The per-format tables of a version-3 index
+pub struct CompatFormatter<'a> {
+    pack_index: &'a PackIndex,
+}
+
+impl<'a> CompatFormatter<'a> {
+    #[must_use]
+    pub const fn new(pack_index: &'a PackIndex) -> Self {
+        Self { pack_index }
+    }
+
+    pub fn format_hash_tables(&self, lines: &mut Vec<Line<'static>>) {
+        if self.pack_index.hash_tables.is_empty() {
+            return;
+        }
+
+        lines.push(Line::styled(
+            "OBJECT FORMAT TABLES",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(25)));
+        lines.push(Line::from(""));
+        lines.push(Line::from(
+            "Version 3 names every object in each object format listed in the header.",
+        ));
+        lines.push(Line::from(
+            "Each format has sorted shortened names, full names in pack order and a",
+        ));
+        lines.push(Line::from(
+            "map from one order to the other; the first format also has the CRC32",
+        ));
+        lines.push(Line::from("values and offsets."));
+        lines.push(Line::from(""));
+
+        for (i, table) in self.pack_index.hash_tables.iter().enumerate() {
+            let role = if i == 0 { "stored" } else { "compat" };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<8}", table.object_format.to_string()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::from(format!(
+                    " ({role}, id '{}'): {}-byte short names, tables at byte {}",
+                    String::from_utf8_lossy(&table.object_format.format_id().to_be_bytes()),
+                    table.short_name_len,
+                    u64::from(table.table_offset) + 1
+                )),
+            ]));
+        }
+        lines.push(Line::from(""));
+
+        self.format_compat_names(lines);
+    }
+
+    fn format_compat_names(&self, lines: &mut Vec<Line<'static>>) {
+        let Some(compat) = &self.pack_index.compat else {
+            return;
+        };
+
+        lines.push(Line::styled(
+            format!(
+                "{} names, in {} name order:",
+                compat.object_format, self.pack_index.object_format
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from(""));
+        for (name, compat_name) in self
+            .pack_index
+            .object_names
+            .iter()
+            .zip(&compat.object_names)
+            .take(MAX_COMPAT_NAMES)
+        {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}", hex::encode(name)),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(" → ", Style::default().fg(Color::Gray)),
+                Span::styled(hex::encode(compat_name), Style::default().fg(Color::Cyan)),
+            ]));
+        }
+        let remaining = compat.object_names.len().saturating_sub(MAX_COMPAT_NAMES);
+        if remaining > 0 {
+            lines.push(Line::styled(
+                format!("  ... and {remaining} more"),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+        lines.push(Line::from(""));
+    }
+}
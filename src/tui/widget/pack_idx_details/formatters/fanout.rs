@@ -14,9 +14,14 @@ impl<'a> FanoutFormatter<'a> {
     }
 
     /// Calculate the 1-based byte position in the .idx file for a fanout entry
-    const fn calculate_fanout_byte_position(fanout_index: usize) -> u64 {
-        let header_size = 8u64; // magic (4) + version (4)
-        let fanout_start = header_size; // Fan-out table starts at byte 8 (0-based)
+    const fn calculate_fanout_byte_position(&self, fanout_index: usize) -> u64 {
+        // After magic (4) + version (4); version 1 has no header and
+        // starts with the fan-out table
+        let fanout_start = if self.pack_index.version == 1 {
+            0
+        } else {
+            8u64
+        };
 
         // Each fanout entry is 4 bytes
         // Add 1 to convert from 0-based to 1-based indexing
@@ -46,8 +51,21 @@ impl<'a> FanoutFormatter<'a> {
         // Show distribution statistics
         self.add_distribution_stats(lines);
 
-        // Show some sample entries
-        self.add_sample_entries(lines);
+        if self.pack_index.version == 3 {
+            // Nothing to show byte by byte
+            lines.push(Line::styled(
+                "Version 3 indexes don't store a fan-out table: readers compute it",
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::styled(
+                "from the sorted shortened names, as done here.",
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::from(""));
+        } else {
+            // Show some sample entries
+            self.add_sample_entries(lines);
+        }
 
         // Show search optimization explanation
         self.add_search_explanation(lines);
@@ -134,7 +152,7 @@ impl<'a> FanoutFormatter<'a> {
                 }
 
                 let bucket_size = count.saturating_sub(prev_count);
-                let byte_pos = Self::calculate_fanout_byte_position(i);
+                let byte_pos = self.calculate_fanout_byte_position(i);
                 let hex_value = format_u32_as_hex_bytes(count);
 
                 if bucket_size > 0 {
@@ -1,4 +1,5 @@
 use crate::git::pack::PackIndex;
+use crate::tui::widget::formatters_utils::format_u32_as_hex_bytes;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 
@@ -20,6 +21,11 @@ impl<'a> HeaderFormatter<'a> {
         lines.push(Line::from("─".repeat(20)));
         lines.push(Line::from(""));
 
+        if self.pack_index.version == 1 {
+            self.format_v1_header(lines);
+            return;
+        }
+
         let border_style = Style::default().fg(Color::Gray);
         let left_bit_style = Style::default().fg(Color::LightBlue);
         let right_bit_style = Style::default().fg(Color::LightGreen);
@@ -264,4 +270,41 @@ impl<'a> HeaderFormatter<'a> {
         )]));
         lines.push(Line::from(""));
     }
+
+    /// Version 1 has nothing to show byte by byte: explain how it is told
+    /// apart from later versions instead
+    fn format_v1_header(&self, lines: &mut Vec<Line<'static>>) {
+        let note_style = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![
+            Span::styled(
+                "                Version: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::from("1 (no header)"),
+        ]));
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            "Version 1 has no signature and no version: the file starts directly",
+            note_style,
+        ));
+        lines.push(Line::styled(
+            "with the fan-out table. Git reads the first 4 bytes and only takes",
+            note_style,
+        ));
+        lines.push(Line::styled(
+            "the file for version 2 or later if they are \\377tOc, which as a",
+            note_style,
+        ));
+        lines.push(Line::styled(
+            "fan-out entry would claim over 4 billion objects starting with 0x00.",
+            note_style,
+        ));
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "First 4 bytes: {} (fan-out entry 0x00 = {})",
+            format_u32_as_hex_bytes(self.pack_index.fan_out[0]),
+            self.pack_index.fan_out[0]
+        )));
+        lines.push(Line::from(""));
+    }
 }
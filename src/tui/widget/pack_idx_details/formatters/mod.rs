@@ -1,13 +1,15 @@
 pub mod checksums;
+pub mod compat;
 pub mod fanout;
 pub mod header;
 pub mod objects;
 
 use crate::git::pack::PackIndex;
-use ratatui::style::{Modifier, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Text};
 
 pub use checksums::ChecksumsFormatter;
+pub use compat::CompatFormatter;
 pub use fanout::FanoutFormatter;
 pub use header::HeaderFormatter;
 pub use objects::ObjectsFormatter;
@@ -31,6 +33,7 @@ impl<'a> PackIndexFormatter<'a> {
         self.add_header_section(&mut lines);
         self.add_fanout_section(&mut lines);
         self.add_objects_section(&mut lines);
+        self.add_hash_tables_section(&mut lines);
         self.add_checksums_section(&mut lines);
         self.add_structure_diagram(&mut lines);
 
@@ -62,6 +65,11 @@ impl<'a> PackIndexFormatter<'a> {
         formatter.format_objects_overview(lines);
     }
 
+    fn add_hash_tables_section(&self, lines: &mut Vec<Line<'static>>) {
+        let formatter = CompatFormatter::new(self.pack_index);
+        formatter.format_hash_tables(lines);
+    }
+
     fn add_checksums_section(&self, lines: &mut Vec<Line<'static>>) {
         let formatter = ChecksumsFormatter::new(self.pack_index);
         formatter.format_checksums(lines);
@@ -75,6 +83,12 @@ impl<'a> PackIndexFormatter<'a> {
         lines.push(Line::from("─".repeat(30)));
         lines.push(Line::from(""));
 
+        match self.pack_index.version {
+            1 => return self.add_v1_structure_diagram(lines),
+            3 => return self.add_v3_structure_diagram(lines),
+            _ => {}
+        }
+
         lines.push(Line::from("┌─────────────────────────────────┐"));
         lines.push(Line::from("│ Magic Number (\\377tOc)          │ 4 bytes"));
         lines.push(Line::from("├─────────────────────────────────┤"));
@@ -84,8 +98,11 @@ impl<'a> PackIndexFormatter<'a> {
             "│ Fan-out Table                   │ 256 × 4 bytes",
         ));
         lines.push(Line::from("├─────────────────────────────────┤"));
+        let object_format = self.pack_index.object_format;
+        let hash_len = object_format.raw_len();
         lines.push(Line::from(format!(
-            "│ Object Names (SHA-1)            │ {} × 20 bytes",
+            "│ {:<32}│ {} × {hash_len} bytes",
+            format!("Object Names ({object_format})"),
             self.pack_index.object_count()
         )));
         lines.push(Line::from("├─────────────────────────────────┤"));
@@ -108,9 +125,121 @@ impl<'a> PackIndexFormatter<'a> {
         }
 
         lines.push(Line::from("├─────────────────────────────────┤"));
-        lines.push(Line::from("│ Pack File Checksum              │ 20 bytes"));
+        lines.push(Line::from(format!(
+            "│ Pack File Checksum              │ {hash_len} bytes"
+        )));
         lines.push(Line::from("├─────────────────────────────────┤"));
-        lines.push(Line::from("│ Index File Checksum             │ 20 bytes"));
+        lines.push(Line::from(format!(
+            "│ Index File Checksum             │ {hash_len} bytes"
+        )));
+        lines.push(Line::from("└─────────────────────────────────┘"));
+        lines.push(Line::from(""));
+    }
+
+    /// Version 1: no header, and one entry per object with its offset in
+    /// front of its name
+    fn add_v1_structure_diagram(&self, lines: &mut Vec<Line<'static>>) {
+        let objects = self.pack_index.object_count();
+        let hash_len = self.pack_index.object_format.raw_len();
+        let row = |label: &str, size: String| Line::from(format!("│ {label:<32}│ {size}"));
+        let rule = || Line::from("├─────────────────────────────────┤");
+
+        lines.push(Line::from("┌─────────────────────────────────┐"));
+        lines.push(row("Fan-out Table", "256 × 4 bytes".to_string()));
+        lines.push(rule());
+        lines.push(row(
+            &format!("Offset + {} Name", self.pack_index.object_format),
+            format!("{objects} × {} bytes", 4 + hash_len),
+        ));
+        lines.push(rule());
+        lines.push(row("Pack File Checksum", format!("{hash_len} bytes")));
+        lines.push(rule());
+        lines.push(row("Index File Checksum", format!("{hash_len} bytes")));
+        lines.push(Line::from("└─────────────────────────────────┘"));
+        lines.push(Line::from(""));
+        lines.push(Line::styled(
+            "Version 2 added the magic number and version in front, split the",
+            Style::default().fg(Color::Gray),
+        ));
+        lines.push(Line::styled(
+            "entries into separate name and offset tables, and added CRC32",
+            Style::default().fg(Color::Gray),
+        ));
+        lines.push(Line::styled(
+            "values and 8-byte offsets for objects beyond 2 GiB.",
+            Style::default().fg(Color::Gray),
+        ));
+        lines.push(Line::from(""));
+    }
+
+    /// Version 3: a header naming each object format, then each format's
+    /// tables at the offsets it gives
+    fn add_v3_structure_diagram(&self, lines: &mut Vec<Line<'static>>) {
+        let objects = self.pack_index.object_count();
+        let row = |label: &str, size: String| Line::from(format!("│ {label:<32}│ {size}"));
+        let rule = || Line::from("├─────────────────────────────────┤");
+
+        lines.push(Line::from("┌─────────────────────────────────┐"));
+        lines.push(row("Magic Number (\\377tOc)", "4 bytes".to_string()));
+        lines.push(rule());
+        lines.push(row("Version (3)", "4 bytes".to_string()));
+        lines.push(rule());
+        lines.push(row(
+            "Header Length, Objects, Formats",
+            "3 × 4 bytes".to_string(),
+        ));
+        lines.push(rule());
+        lines.push(row(
+            "Format ID, Short Length, Offset",
+            format!("{} × 12 bytes", self.pack_index.hash_tables.len()),
+        ));
+        lines.push(rule());
+        lines.push(row("Trailer Offset", "4 bytes".to_string()));
+
+        for (i, table) in self.pack_index.hash_tables.iter().enumerate() {
+            let hash_len = table.object_format.raw_len();
+            lines.push(Line::from(format!(
+                "├─ {} tables {}┤",
+                table.object_format,
+                "─".repeat(23 - table.object_format.to_string().len())
+            )));
+            lines.push(row(
+                "Shortened Names (sorted)",
+                format!("{objects} × {} bytes", table.short_name_len),
+            ));
+            lines.push(rule());
+            lines.push(row(
+                "Full Names (pack order)",
+                format!("{objects} × {hash_len} bytes"),
+            ));
+            lines.push(rule());
+            lines.push(row(
+                "Sorted → Pack Order Map",
+                format!("{objects} × 4 bytes"),
+            ));
+            if i == 0 {
+                lines.push(rule());
+                lines.push(row(
+                    "CRC32 Checksums (pack order)",
+                    format!("{objects} × 4 bytes"),
+                ));
+                lines.push(rule());
+                lines.push(row("Pack File Offsets", format!("{objects} × 4 bytes")));
+                if let Some(ref large_offsets) = self.pack_index.large_offsets {
+                    lines.push(rule());
+                    lines.push(row(
+                        "Large Offsets (optional)",
+                        format!("{} × 8 bytes", large_offsets.len()),
+                    ));
+                }
+            }
+        }
+
+        let hash_len = self.pack_index.object_format.raw_len();
+        lines.push(Line::from("├─ Trailer ───────────────────────┤"));
+        lines.push(row("Pack File Checksum", format!("{hash_len} bytes")));
+        lines.push(rule());
+        lines.push(row("Index File Checksum", format!("{hash_len} bytes")));
         lines.push(Line::from("└─────────────────────────────────┘"));
         lines.push(Line::from(""));
     }
@@ -14,48 +14,36 @@ impl<'a> ObjectsFormatter<'a> {
 
     /// Calculate the 1-based byte position in the .idx file for a given object entry
     ///
-    /// Pack index file structure:
+    /// Version 2 file structure:
     /// - Header: 8 bytes (magic + version)
     /// - Fan-out table: 256 * 4 = 1024 bytes
-    /// - Object names: N * 20 bytes (where N = object count)
+    /// - Object names: N * H bytes (N = object count, H = 20 for SHA-1 or
+    ///   32 for SHA-256)
     /// - CRC32 checksums: N * 4 bytes
     /// - Offsets: N * 4 bytes
     /// - Large offsets: variable (if any)
-    /// - Pack checksum: 20 bytes
-    /// - Index checksum: 20 bytes
-    const fn calculate_object_byte_position(object_index: usize) -> u64 {
-        let header_size = 8u64; // magic (4) + version (4)
-        let fanout_size = 256 * 4u64; // 256 entries * 4 bytes each
-        let object_names_start = header_size + fanout_size;
-
-        // Each object name is 20 bytes
+    /// - Pack checksum: H bytes
+    /// - Index checksum: H bytes
+    ///
+    /// Version 3 keeps full names and CRC32 values in pack order instead,
+    /// so a sorted entry's bytes are wherever its object is in the pack.
+    /// Version 1 has no header and no CRC32 table: after the fan-out table,
+    /// each object has one entry of its offset followed by its name.
+    fn calculate_object_byte_position(&self, object_index: usize) -> u64 {
         // Add 1 to convert from 0-based to 1-based indexing
-        object_names_start + (object_index as u64 * 20) + 1
+        self.pack_index.object_name_position(object_index) + 1
     }
 
     /// Calculate the 1-based byte position in the .idx file for a CRC32 entry
-    const fn calculate_crc32_byte_position(&self, object_index: usize) -> u64 {
-        let header_size = 8u64; // magic (4) + version (4)
-        let fanout_size = 256 * 4u64; // 256 entries * 4 bytes each
-        let object_names_size = self.pack_index.object_count() as u64 * 20; // N * 20 bytes
-        let crc32_start = header_size + fanout_size + object_names_size;
-
-        // Each CRC32 is 4 bytes
-        // Add 1 to convert from 0-based to 1-based indexing
-        crc32_start + (object_index as u64 * 4) + 1
+    fn calculate_crc32_byte_position(&self, object_index: usize) -> Option<u64> {
+        self.pack_index
+            .crc32_position(object_index)
+            .map(|position| position + 1)
     }
 
     /// Calculate the 1-based byte position in the .idx file for an offset entry
-    const fn calculate_offset_byte_position(&self, object_index: usize) -> u64 {
-        let header_size = 8u64; // magic (4) + version (4)
-        let fanout_size = 256 * 4u64; // 256 entries * 4 bytes each
-        let object_names_size = self.pack_index.object_count() as u64 * 20; // N * 20 bytes
-        let crc32_size = self.pack_index.object_count() as u64 * 4; // N * 4 bytes
-        let offsets_start = header_size + fanout_size + object_names_size + crc32_size;
-
-        // Each offset is 4 bytes
-        // Add 1 to convert from 0-based to 1-based indexing
-        offsets_start + (object_index as u64 * 4) + 1
+    fn calculate_offset_byte_position(&self, object_index: usize) -> u64 {
+        self.pack_index.offset_position(object_index) + 1
     }
 
     pub fn format_objects_overview(&self, lines: &mut Vec<Line<'static>>) {
@@ -72,9 +60,11 @@ impl<'a> ObjectsFormatter<'a> {
         lines.push(Line::from("─".repeat(25)));
         lines.push(Line::from(""));
 
-        lines.push(Line::from(
-            "SHA-1 hashes sorted lexicographically (20 bytes each)",
-        ));
+        let object_format = self.pack_index.object_format;
+        lines.push(Line::from(format!(
+            "{object_format} hashes sorted lexicographically ({} bytes each)",
+            object_format.raw_len()
+        )));
         lines.push(Line::from(format!(
             "Total objects: {}",
             self.pack_index.object_count()
@@ -87,24 +77,22 @@ impl<'a> ObjectsFormatter<'a> {
             return;
         }
 
-        // Table header
-        lines.push(Line::from(
-            "┌──────┬──────────────────────────────────────────┐",
-        ));
+        // Table header, with the hash column as wide as a hex object ID
+        let hex_len = object_format.hex_len();
+        let rule = "─".repeat(hex_len + 2);
+        lines.push(Line::from(format!("┌──────┬{rule}┐")));
         lines.push(Line::styled(
-            "│ Byte │ SHA-1 Hash                               │",
+            format!("│ Byte │ {:<hex_len$} │", format!("{object_format} Hash")),
             Style::default().add_modifier(Modifier::BOLD),
         ));
-        lines.push(Line::from(
-            "├──────┼──────────────────────────────────────────┤",
-        ));
+        lines.push(Line::from(format!("├──────┼{rule}┤")));
 
         let entries_to_show = self.determine_sample_indices();
 
         for (i, show_entry) in entries_to_show.iter().enumerate() {
             if *show_entry {
-                let hash = hex::encode(self.pack_index.object_names[i]);
-                let byte_pos = Self::calculate_object_byte_position(i);
+                let hash = hex::encode(&self.pack_index.object_names[i]);
+                let byte_pos = self.calculate_object_byte_position(i);
 
                 let line_parts = vec![
                     ("│ ".to_string(), Style::default()),
@@ -126,7 +114,7 @@ impl<'a> ObjectsFormatter<'a> {
                     ("...".to_string(), Style::default().fg(Color::Gray)),
                     (" │ ".to_string(), Style::default()),
                     (
-                        "...                                     ".to_string(),
+                        format!("{:<hex_len$}", "..."),
                         Style::default().fg(Color::Gray),
                     ),
                     (" │".to_string(), Style::default()),
@@ -140,9 +128,7 @@ impl<'a> ObjectsFormatter<'a> {
             }
         }
 
-        lines.push(Line::from(
-            "└──────┴──────────────────────────────────────────┘",
-        ));
+        lines.push(Line::from(format!("└──────┴{rule}┘")));
         lines.push(Line::from(""));
     }
 
@@ -163,6 +149,23 @@ impl<'a> ObjectsFormatter<'a> {
         )));
         lines.push(Line::from(""));
 
+        if self.pack_index.version == 1 {
+            lines.push(Line::styled(
+                "Version 1 indexes have no CRC32 table: they were introduced with",
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::styled(
+                "version 2, so that packed data can be copied into a new pack",
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::styled(
+                "without being inflated to check it.",
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::from(""));
+            return;
+        }
+
         if self.pack_index.crc32_checksums.is_empty() {
             lines.push(Line::from("No CRC32 checksums available."));
             lines.push(Line::from(""));
@@ -182,7 +185,7 @@ impl<'a> ObjectsFormatter<'a> {
         for (i, show_entry) in entries_to_show.iter().enumerate() {
             if *show_entry {
                 let crc32 = self.pack_index.crc32_checksums[i];
-                let byte_pos = self.calculate_crc32_byte_position(i);
+                let byte_pos = self.calculate_crc32_byte_position(i).unwrap_or_default();
                 let hex_bytes = format!(
                     "{:02x} {:02x} {:02x} {:02x}",
                     (crc32 >> 24) & 0xff,
@@ -239,13 +242,23 @@ impl<'a> ObjectsFormatter<'a> {
             "Total offsets: {}",
             self.pack_index.offsets.len()
         )));
+        if self.pack_index.version == 1 {
+            lines.push(Line::styled(
+                "Version 1 stores each offset just before its object's name, as a",
+                Style::default().fg(Color::Gray),
+            ));
+            lines.push(Line::styled(
+                "plain 32-bit value: packs are limited to 4 GiB.",
+                Style::default().fg(Color::Gray),
+            ));
+        }
 
         // Show large offset info if present
         let large_offset_count = self
             .pack_index
             .offsets
             .iter()
-            .filter(|&&offset| offset & 0x8000_0000 != 0)
+            .filter(|&&offset| self.is_large_offset(offset))
             .count();
 
         if large_offset_count > 0 {
@@ -285,7 +298,7 @@ impl<'a> ObjectsFormatter<'a> {
                     raw_offset & 0xff
                 );
 
-                let offset_display = if raw_offset & 0x8000_0000 != 0 {
+                let offset_display = if self.is_large_offset(raw_offset) {
                     format!("{actual_offset:>8} L") // L for Large offset
                 } else {
                     format!("{actual_offset:>9}")
@@ -299,7 +312,7 @@ impl<'a> ObjectsFormatter<'a> {
                     (" │ ".to_string(), Style::default()),
                     (
                         offset_display,
-                        if raw_offset & 0x8000_0000 != 0 {
+                        if self.is_large_offset(raw_offset) {
                             Style::default().fg(Color::Yellow)
                         } else {
                             Style::default().fg(Color::Magenta)
@@ -342,6 +355,12 @@ impl<'a> ObjectsFormatter<'a> {
         }
     }
 
+    /// Whether a 4-byte offset points into the large offset table, which
+    /// version 1 doesn't have
+    const fn is_large_offset(&self, raw_offset: u32) -> bool {
+        self.pack_index.version != 1 && raw_offset & 0x8000_0000 != 0
+    }
+
     fn add_large_offsets_info(lines: &mut Vec<Line<'static>>, large_offsets: &[u64]) {
         lines.push(Line::styled(
             "Large Offset Table (8-byte offsets for pack files > 4GB):",
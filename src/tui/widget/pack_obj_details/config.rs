@@ -28,13 +28,11 @@ impl HeaderSection {
     pub fn from_byte_position(
         byte_index: usize,
         obj_type: crate::git::pack::ObjectType,
-        raw_data_len: usize,
         size_byte_count: usize,
     ) -> Self {
-        const HASH_BYTES: usize = 20;
-        let is_ref_delta_hash = obj_type == crate::git::pack::ObjectType::RefDelta
-            && raw_data_len >= HASH_BYTES
-            && byte_index >= raw_data_len - HASH_BYTES;
+        // The base object ID (20 or 32 bytes) follows the size bytes
+        let is_ref_delta_hash =
+            obj_type == crate::git::pack::ObjectType::RefDelta && byte_index >= size_byte_count;
 
         let is_ofs_delta_offset =
             obj_type == crate::git::pack::ObjectType::OfsDelta && byte_index >= size_byte_count;
@@ -52,14 +50,10 @@ impl HeaderSection {
 // Helper function to calculate the number of bytes used for size encoding
 #[must_use]
 pub fn calculate_size_byte_count(obj_type: crate::git::pack::ObjectType, raw_data: &[u8]) -> usize {
-    const HASH_BYTES: usize = 20;
     match obj_type {
-        crate::git::pack::ObjectType::RefDelta => {
-            // RefDelta: size bytes + 20-byte hash
-            raw_data.len() - HASH_BYTES
-        }
-        crate::git::pack::ObjectType::OfsDelta => {
-            // OfsDelta: find where size encoding ends
+        crate::git::pack::ObjectType::RefDelta | crate::git::pack::ObjectType::OfsDelta => {
+            // Deltas: find where size encoding ends; the base hash or
+            // offset follows
             let mut size_bytes = 0;
             for (i, &byte) in raw_data.iter().enumerate() {
                 size_bytes = i + 1;
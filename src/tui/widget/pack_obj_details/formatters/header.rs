@@ -137,10 +137,8 @@ impl<'a> HeaderFormatter<'a> {
         let obj_type = self.header.obj_type();
         let size_byte_count = calculate_size_byte_count(obj_type, raw_data);
 
-        let current_section =
-            HeaderSection::from_byte_position(i, obj_type, raw_data.len(), size_byte_count);
-        let prev_section =
-            HeaderSection::from_byte_position(i - 1, obj_type, raw_data.len(), size_byte_count);
+        let current_section = HeaderSection::from_byte_position(i, obj_type, size_byte_count);
+        let prev_section = HeaderSection::from_byte_position(i - 1, obj_type, size_byte_count);
         let is_section_transition = current_section != prev_section;
 
         if current_section == HeaderSection::Hash {
@@ -347,8 +345,11 @@ impl<'a> HeaderFormatter<'a> {
         colored_hash: &[Span<'static>],
     ) {
         match self.header {
-            crate::git::pack::ObjectHeader::RefDelta { .. } => {
-                lines.push(Line::from("  - Base object hash (20 bytes):"));
+            crate::git::pack::ObjectHeader::RefDelta { base_ref, .. } => {
+                lines.push(Line::from(format!(
+                    "  - Base object hash ({} bytes):",
+                    base_ref.len()
+                )));
                 let mut hash_line = vec![Span::from("      ")];
                 hash_line.extend(colored_hash.iter().cloned());
                 lines.push(Line::from(hash_line));
@@ -449,9 +450,15 @@ fn push_grouped_bits(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::object_format::ObjectFormat;
 
     fn render(header_bytes: &[u8]) -> Vec<String> {
-        let (_, header) = crate::git::pack::ObjectHeader::parse(header_bytes).unwrap();
+        render_with_format(header_bytes, ObjectFormat::Sha1)
+    }
+
+    fn render_with_format(header_bytes: &[u8], object_format: ObjectFormat) -> Vec<String> {
+        let (_, header) =
+            crate::git::pack::ObjectHeader::parse_with_format(header_bytes, object_format).unwrap();
         let mut lines = Vec::new();
         HeaderFormatter::new(&header).format_header(&mut lines);
         lines
@@ -508,4 +515,19 @@ mod tests {
         let expected: String = (1..=20u8).map(|b| format!("{b:02X}")).collect();
         assert_eq!(lines[idx + 1].trim(), expected);
     }
+
+    #[test]
+    fn ref_delta_summary_shows_sha256_base_hash() {
+        // Type 7 (ref-delta), size 0x15 over two bytes, then a 32-byte base
+        let mut data = vec![0xF5, 0x01];
+        data.extend(1..=32u8);
+        let lines = render_with_format(&data, ObjectFormat::Sha256);
+
+        let idx = lines
+            .iter()
+            .position(|l| l.contains("Base object hash (32 bytes)"))
+            .expect("base hash label");
+        let expected: String = (1..=32u8).map(|b| format!("{b:02X}")).collect();
+        assert_eq!(lines[idx + 1].trim(), expected);
+    }
 }
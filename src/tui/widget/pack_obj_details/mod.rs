@@ -139,6 +139,7 @@ impl<'a> PackObjectFormatter<'a> {
     fn generate_content(&self) -> Text<'static> {
         let mut lines = Vec::new();
 
+        self.add_object_ids_section(&mut lines);
         if let Some(ref object_data) = self.pack_obj.object_data {
             Self::add_header_section(&mut lines, object_data);
             Self::add_content_section(&mut lines, object_data);
@@ -149,6 +150,29 @@ impl<'a> PackObjectFormatter<'a> {
         Text::from(lines)
     }
 
+    /// Both names of an object in a repository with a compatibility
+    /// format
+    fn add_object_ids_section(&self, lines: &mut Vec<ratatui::text::Line<'static>>) {
+        use ratatui::style::{Modifier, Style};
+        use ratatui::text::Line;
+
+        let (Some(sha1), Some(compat_id)) = (&self.pack_obj.sha1, &self.pack_obj.compat_id) else {
+            return;
+        };
+        lines.push(Line::styled(
+            "OBJECT IDS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Object ID: {sha1}")));
+        lines.push(Line::from(format!(
+            "{} ID: {}",
+            compat_id.object_format, compat_id.id
+        )));
+        lines.push(Line::from(""));
+    }
+
     fn add_header_section(
         lines: &mut Vec<ratatui::text::Line<'static>>,
         object_data: &crate::git::pack::Object,
@@ -0,0 +1,110 @@
+pub mod refs;
+
+use crate::git::refs::PackedRefs;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+
+pub use refs::RefsFormatter;
+
+pub struct PackedRefsFormatter<'a> {
+    packed_refs: &'a PackedRefs,
+}
+
+impl<'a> PackedRefsFormatter<'a> {
+    #[must_use]
+    pub const fn new(packed_refs: &'a PackedRefs) -> Self {
+        Self { packed_refs }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = Vec::new();
+        Self::add_educational_info(&mut lines);
+        self.add_header_section(&mut lines);
+        RefsFormatter::new(self.packed_refs).format_refs(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_educational_info(lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::from(
+            "git pack-refs (run by git gc) moves refs from files under refs/ into this single file.",
+        ));
+        lines.push(Line::from(
+            "Each line is \"<oid> <ref name>\"; a \"^<oid>\" line after an annotated tag records the object it peels to.",
+        ));
+        lines.push(Line::from(
+            "A loose file under refs/ with the same name takes precedence over the packed entry.",
+        ));
+        lines.push(Line::from(""));
+    }
+
+    fn add_header_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "HEADER",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::LightGreen);
+
+        if self.packed_refs.traits.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  No \"{}\" line: nothing is promised about peeling or order",
+                    PackedRefs::HEADER_PREFIX
+                ),
+                gray,
+            )]));
+        } else {
+            lines.push(Line::from(vec![
+                Span::from("  Traits: "),
+                Span::styled(self.packed_refs.traits.join(" "), value_style),
+            ]));
+            for name in &self.packed_refs.traits {
+                let meaning = match name.as_str() {
+                    "peeled" => "every tag under refs/tags/ that can be peeled has a ^ line",
+                    "fully-peeled" => "every ref that can be peeled has a ^ line",
+                    "sorted" => "refs are sorted by name, so lookups can binary search",
+                    _ => "unknown trait, ignored by git",
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("    {name:<13}"), Style::default().fg(Color::Cyan)),
+                    Span::styled(meaning, gray),
+                ]));
+            }
+        }
+        lines.push(Line::from(""));
+
+        lines.push(Line::from(vec![
+            Span::from("  Refs: "),
+            Span::styled(format!("{}", self.packed_refs.refs.len()), value_style),
+            Span::from(", peeled: "),
+            Span::styled(
+                format!(
+                    "{}",
+                    self.packed_refs
+                        .refs
+                        .iter()
+                        .filter(|packed_ref| packed_ref.peeled.is_some())
+                        .count()
+                ),
+                value_style,
+            ),
+            Span::from(", shadowed by loose files: "),
+            Span::styled(
+                format!("{}", self.packed_refs.shadowed_count()),
+                value_style,
+            ),
+        ]));
+        if self.packed_refs.has_trait("sorted") && !self.packed_refs.is_sorted() {
+            lines.push(Line::from(vec![Span::styled(
+                "  The file declares \"sorted\" but its refs are out of order",
+                Style::default().fg(Color::Red),
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::git::refs::{PackedRef, PackedRefs, PeelState};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Refs beyond this count are summarized instead of listed
+const MAX_LISTED_REFS: usize = 2000;
+/// Object IDs are abbreviated to this many hex digits in the table
+const SHORT_OID_LEN: usize = 12;
+
+pub struct RefsFormatter<'a> {
+    packed_refs: &'a PackedRefs,
+}
+
+impl<'a> RefsFormatter<'a> {
+    #[must_use]
+    pub const fn new(packed_refs: &'a PackedRefs) -> Self {
+        Self { packed_refs }
+    }
+
+    pub fn format_refs(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "REFS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![
+            Span::styled("  • Peeled: ", gray),
+            Span::styled(
+                "target of an annotated tag; - = not a tag, ? = not recorded",
+                gray,
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  • Loose: ", gray),
+            Span::styled(
+                "a file under refs/ overrides the packed entry (stale if it differs)",
+                gray,
+            ),
+        ]));
+        lines.push(Line::from(""));
+
+        if self.packed_refs.refs.is_empty() {
+            lines.push(Line::from(vec![Span::styled("  No packed refs", gray)]));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled(" Line", gray),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Target      ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Peeled      ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                "Loose       ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" │ ", gray),
+            Span::styled("Ref", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "──────┼──────────────┼──────────────┼──────────────┼──────────────────────",
+            gray,
+        ));
+
+        for packed_ref in self.packed_refs.refs.iter().take(MAX_LISTED_REFS) {
+            self.format_ref(packed_ref, lines);
+        }
+
+        if self.packed_refs.refs.len() > MAX_LISTED_REFS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more refs not shown",
+                    self.packed_refs.refs.len() - MAX_LISTED_REFS
+                ),
+                gray,
+            )]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn format_ref(&self, packed_ref: &PackedRef, lines: &mut Vec<Line<'static>>) {
+        let gray = Style::default().fg(Color::Gray);
+
+        let peeled = match self.packed_refs.peel_state(packed_ref) {
+            PeelState::Peeled(oid) => Span::styled(
+                Self::short_oid(&oid),
+                Style::default().fg(Color::LightMagenta),
+            ),
+            state => Span::styled(format!("{state:<SHORT_OID_LEN$}"), gray),
+        };
+        let loose = match &packed_ref.shadowed_by {
+            None => Span::styled(format!("{:<SHORT_OID_LEN$}", ""), gray),
+            Some(_) if !packed_ref.is_stale() => {
+                Span::styled(format!("{:<SHORT_OID_LEN$}", "same"), gray)
+            }
+            Some(loose) => Span::styled(Self::short_oid(loose), Style::default().fg(Color::Red)),
+        };
+        let name_style = if packed_ref.shadowed_by.is_some() {
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT)
+        } else {
+            Style::default()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:5}", packed_ref.line), gray),
+            Span::styled(" │ ", gray),
+            Span::styled(
+                Self::short_oid(&packed_ref.oid),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(" │ ", gray),
+            peeled,
+            Span::styled(" │ ", gray),
+            loose,
+            Span::styled(" │ ", gray),
+            Span::styled(packed_ref.name.clone(), name_style),
+        ]));
+    }
+
+    /// First hex digits of an object ID, or of a symref's target
+    fn short_oid(value: &str) -> String {
+        let short: String = value.chars().take(SHORT_OID_LEN).collect();
+        format!("{short:<SHORT_OID_LEN$}")
+    }
+}
@@ -0,0 +1,104 @@
+pub mod timeline;
+
+use crate::git::refs::Reflog;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+
+pub use timeline::TimelineFormatter;
+
+pub struct ReflogFormatter<'a> {
+    reflog: &'a Reflog,
+}
+
+impl<'a> ReflogFormatter<'a> {
+    #[must_use]
+    pub const fn new(reflog: &'a Reflog) -> Self {
+        Self { reflog }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        self.generate_content_with_timeline_start().0
+    }
+
+    /// The content, plus the index of the line where the first (newest)
+    /// timeline entry starts
+    #[must_use]
+    pub fn generate_content_with_timeline_start(&self) -> (Text<'static>, usize) {
+        let mut lines = Vec::new();
+        Self::add_educational_info(&mut lines);
+        self.add_summary_section(&mut lines);
+        let timeline_start = TimelineFormatter::new(self.reflog).format_timeline(&mut lines);
+
+        (Text::from(lines), timeline_start)
+    }
+
+    fn add_educational_info(lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::from(
+            "Git appends a line to a ref's reflog every time the ref moves: commit, checkout, reset, rebase, merge...",
+        ));
+        lines.push(Line::from(
+            "Each line is \"<old oid> <new oid> <name> <email> <timestamp> <timezone>\" and a tab-separated message.",
+        ));
+        lines.push(Line::from(
+            "Commits a reset or rebase left behind stay reachable from here as <ref>@{n} until git reflog expire prunes them.",
+        ));
+        lines.push(Line::from(""));
+    }
+
+    fn add_summary_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "SUMMARY",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Ref: "),
+            Span::styled(self.reflog.ref_name.clone(), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Entries: "),
+            Span::styled(format!("{}", self.reflog.entries.len()), value_style),
+        ]));
+
+        let count = |action: &str| {
+            self.reflog
+                .entries
+                .iter()
+                .filter(|entry| entry.action().starts_with(action))
+                .count()
+        };
+        lines.push(Line::from(vec![
+            Span::from("  Commits: "),
+            Span::styled(format!("{}", count("commit")), value_style),
+            Span::from(", resets: "),
+            Span::styled(format!("{}", count("reset")), value_style),
+            Span::from(", rebases: "),
+            Span::styled(format!("{}", count("rebase")), value_style),
+            Span::from(", checkouts: "),
+            Span::styled(format!("{}", count("checkout")), value_style),
+        ]));
+        lines.push(Line::from(""));
+
+        if !self.reflog.malformed_lines.is_empty() {
+            let warning_style = Style::default().fg(Color::Yellow);
+            lines.push(Line::styled(
+                format!(
+                    "  Skipped {} malformed line(s), as git does:",
+                    self.reflog.malformed_lines.len()
+                ),
+                warning_style,
+            ));
+            for malformed in &self.reflog.malformed_lines {
+                lines.push(Line::styled(
+                    format!("    line {}: {}", malformed.line_number, malformed.content),
+                    warning_style,
+                ));
+            }
+            lines.push(Line::from(""));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::git::refs::{Reflog, ReflogEntry};
+use crate::tui::widget::formatters_utils::format_epoch_with_offset;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Object IDs are abbreviated to this many hex digits in the timeline
+const SHORT_OID_LEN: usize = 12;
+
+pub struct TimelineFormatter<'a> {
+    reflog: &'a Reflog,
+}
+
+impl<'a> TimelineFormatter<'a> {
+    /// Every entry takes this many lines of the timeline
+    pub const LINES_PER_ENTRY: usize = 2;
+
+    #[must_use]
+    pub const fn new(reflog: &'a Reflog) -> Self {
+        Self { reflog }
+    }
+
+    /// Append the timeline, newest entry first, and return the index of the
+    /// line where the first entry starts
+    pub fn format_timeline(&self, lines: &mut Vec<Line<'static>>) -> usize {
+        lines.push(Line::styled(
+            "TIMELINE",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        if self.reflog.entries.is_empty() {
+            lines.push(Line::from(vec![Span::styled("  No entries", gray)]));
+            return lines.len();
+        }
+        lines.push(Line::from(vec![Span::styled(
+            "  Newest first, numbered as <ref>@{n}; in the TUI, Enter opens the commit an entry moved the ref to",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let timeline_start = lines.len();
+        let label_width = self
+            .reflog
+            .entries
+            .len()
+            .saturating_sub(1)
+            .to_string()
+            .len()
+            + self.reflog.ref_name.len()
+            + 3;
+        for (index, entry) in self.reflog.newest_first() {
+            self.format_entry(index, entry, label_width, lines);
+        }
+        lines.push(Line::from(""));
+        timeline_start
+    }
+
+    fn format_entry(
+        &self,
+        index: usize,
+        entry: &ReflogEntry,
+        label_width: usize,
+        lines: &mut Vec<Line<'static>>,
+    ) {
+        let gray = Style::default().fg(Color::Gray);
+        let label = format!("{}@{{{index}}}", self.reflog.ref_name);
+
+        let new_oid = if entry.is_deletion() {
+            Span::styled(format!("{:<SHORT_OID_LEN$}", "(deleted)"), gray)
+        } else {
+            Span::styled(
+                Self::short_oid(&entry.new_oid),
+                Style::default().fg(Color::Yellow),
+            )
+        };
+        let (action, detail) = entry
+            .message
+            .split_at(entry.action().len().min(entry.message.len()));
+        lines.push(Line::from(vec![
+            Span::from("  "),
+            Span::from(format!("{label:<label_width$}")),
+            Span::from(" "),
+            new_oid,
+            Span::from("  "),
+            Span::styled(
+                format_epoch_with_offset(entry.timestamp, entry.offset_minutes()),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::from("  "),
+            Span::styled(action.to_string(), Self::action_style(entry.action())),
+            Span::from(detail.to_string()),
+        ]));
+
+        let old_oid = if entry.is_creation() {
+            Span::styled(format!("{:<SHORT_OID_LEN$}", "(created)"), gray)
+        } else if matches!(entry.action(), "reset" | "rebase (start)") {
+            // Where the ref was before being moved away: the commit to
+            // recover if the reset was a mistake
+            Span::styled(
+                Self::short_oid(&entry.old_oid),
+                Style::default().fg(Color::LightRed),
+            )
+        } else {
+            Span::styled(Self::short_oid(&entry.old_oid), gray)
+        };
+        lines.push(Line::from(vec![
+            Span::from(format!("  {:label_width$} ", "")),
+            old_oid,
+            Span::styled(" → ", gray),
+            Span::styled(Self::short_oid(&entry.new_oid), gray),
+            Span::styled(format!("  {} <{}>", entry.name, entry.email), gray),
+        ]));
+    }
+
+    /// Mark the first line of a timeline entry as selected or not
+    pub fn set_selected(line: &mut Line<'static>, selected: bool) {
+        if let Some(marker) = line.spans.first_mut() {
+            *marker = if selected {
+                Span::styled("▶ ", Style::default().fg(Color::Yellow))
+            } else {
+                Span::from("  ")
+            };
+        }
+        if let Some(label) = line.spans.get_mut(1) {
+            label.style = if selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+        }
+    }
+
+    fn action_style(action: &str) -> Style {
+        let color = match action.split_whitespace().next().unwrap_or_default() {
+            "commit" => Color::LightGreen,
+            "reset" => Color::LightRed,
+            "checkout" | "switch" => Color::LightBlue,
+            "rebase" | "cherry-pick" => Color::LightMagenta,
+            "merge" | "pull" | "fetch" => Color::LightCyan,
+            _ => Color::White,
+        };
+        Style::default().fg(color)
+    }
+
+    fn short_oid(oid: &str) -> String {
+        let short: String = oid.chars().take(SHORT_OID_LEN).collect();
+        format!("{short:<SHORT_OID_LEN$}")
+    }
+}
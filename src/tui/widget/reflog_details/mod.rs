@@ -0,0 +1,126 @@
+pub mod formatters;
+
+use crate::git::refs::{Reflog, ReflogEntry};
+use crate::tui::helpers::render_styled_paragraph_with_scrollbar;
+use ratatui::text::Text;
+use std::path::{Path, PathBuf};
+
+use formatters::{ReflogFormatter, TimelineFormatter};
+
+/// Timeline of a reflog with one selected entry
+///
+/// Scrolling moves the selection, which the view follows, so the selected
+/// entry is always on screen and can be opened.
+#[derive(Debug, Clone)]
+pub struct ReflogWidget {
+    reflog: Reflog,
+    git_dir: PathBuf,
+    text: Text<'static>,
+    timeline_start: usize,
+    selected: usize,
+    scroll_position: usize,
+}
+
+impl ReflogWidget {
+    #[must_use]
+    pub fn new(reflog: Reflog, git_dir: PathBuf) -> Self {
+        // Pre-generate and cache the content
+        let (text, timeline_start) =
+            ReflogFormatter::new(&reflog).generate_content_with_timeline_start();
+        let mut widget = Self {
+            reflog,
+            git_dir,
+            text,
+            timeline_start,
+            selected: 0,
+            scroll_position: 0,
+        };
+        widget.mark_selected(true);
+        widget
+    }
+
+    /// Git directory the reflog belongs to, where its objects live
+    #[must_use]
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// The selected entry, counting from the newest
+    #[must_use]
+    pub fn selected_entry(&self) -> Option<&ReflogEntry> {
+        self.reflog
+            .newest_first()
+            .nth(self.selected)
+            .map(|(_, entry)| entry)
+    }
+
+    #[must_use]
+    pub fn text(&self) -> Text<'static> {
+        self.text.clone()
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.select(self.selected.saturating_sub(1));
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.select(self.selected + 1);
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.select(0);
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.select(usize::MAX);
+    }
+
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.reflog.entries.len().saturating_sub(1));
+        self.mark_selected(false);
+        self.selected = index;
+        self.mark_selected(true);
+    }
+
+    fn mark_selected(&mut self, selected: bool) {
+        if self.reflog.entries.is_empty() {
+            return;
+        }
+        let line = self.timeline_start + self.selected * TimelineFormatter::LINES_PER_ENTRY;
+        if let Some(line) = self.text.lines.get_mut(line) {
+            TimelineFormatter::set_selected(line, selected);
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        f: &mut ratatui::Frame,
+        area: ratatui::layout::Rect,
+        is_focused: bool,
+    ) {
+        let visible_height = area.height.saturating_sub(2) as usize; // Account for borders
+        let entry_top = self.timeline_start + self.selected * TimelineFormatter::LINES_PER_ENTRY;
+        let entry_bottom = entry_top + TimelineFormatter::LINES_PER_ENTRY;
+
+        // Keep the selected entry visible, showing the header above the newest one
+        if self.selected == 0 {
+            self.scroll_position = 0;
+        }
+        if entry_bottom > self.scroll_position + visible_height {
+            self.scroll_position = entry_bottom.saturating_sub(visible_height);
+        }
+        if entry_top < self.scroll_position {
+            self.scroll_position = entry_top;
+        }
+
+        let title = format!("Reflog: {}", self.reflog.ref_name);
+        render_styled_paragraph_with_scrollbar(
+            f,
+            area,
+            &self.text,
+            self.scroll_position,
+            &title,
+            is_focused,
+        );
+    }
+}
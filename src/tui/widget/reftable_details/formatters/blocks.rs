@@ -0,0 +1,90 @@
+use crate::git::refs::Reftable;
+use crate::git::refs::reftable::BlockType;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+pub struct BlocksFormatter<'a> {
+    reftable: &'a Reftable,
+}
+
+impl<'a> BlocksFormatter<'a> {
+    #[must_use]
+    pub const fn new(reftable: &'a Reftable) -> Self {
+        Self { reftable }
+    }
+
+    pub fn format_blocks(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "BLOCKS",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Sections come in order: ref blocks, obj blocks, log blocks, each",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  optionally followed by its index. Ref and obj blocks are padded to the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  block size; log blocks are deflated, so Length is their inflated size.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        if self.reftable.blocks.is_empty() {
+            lines.push(Line::from("The table has no blocks."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled(" Type", bold),
+            Span::styled(" │ ", gray),
+            Span::styled(" Offset", bold),
+            Span::styled(" │ ", gray),
+            Span::styled(" Length", bold),
+            Span::styled(" │ ", gray),
+            Span::styled(" Stored", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Records", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Restarts", bold),
+        ]));
+        lines.push(Line::styled(
+            "──────┼─────────┼─────────┼─────────┼─────────┼──────────",
+            gray,
+        ));
+
+        for block in &self.reftable.blocks {
+            let type_color = match block.block_type {
+                BlockType::Ref => Color::Yellow,
+                BlockType::Obj => Color::Magenta,
+                BlockType::Log => Color::LightBlue,
+                BlockType::Index => Color::Cyan,
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:>5}", block.block_type.to_string()),
+                    Style::default().fg(type_color),
+                ),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:7}", block.offset)),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:7}", block.block_len)),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:7}", block.stored_size)),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:7}", block.record_count)),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{}", block.restart_offsets.len())),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+}
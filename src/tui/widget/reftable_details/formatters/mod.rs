@@ -0,0 +1,159 @@
+pub mod blocks;
+pub mod records;
+
+use crate::git::refs::Reftable;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+pub use blocks::BlocksFormatter;
+pub use records::RecordsFormatter;
+
+pub struct ReftableFormatter<'a> {
+    reftable: &'a Reftable,
+}
+
+impl<'a> ReftableFormatter<'a> {
+    #[must_use]
+    pub const fn new(reftable: &'a Reftable) -> Self {
+        Self { reftable }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = vec![
+            Line::from("One table of a reftable ref store. Each transaction writes a new"),
+            Line::from("immutable table with the refs and reflog entries it changed; tables"),
+            Line::from("are stacked in tables.list and compacted over time. Records are"),
+            Line::from("sorted and prefix-compressed: each stores only the bytes that differ"),
+            Line::from("from the previous key, except at restart points, whose offsets end"),
+            Line::from("every block so readers can binary search it."),
+            Line::from(""),
+        ];
+
+        self.add_header_section(&mut lines);
+        BlocksFormatter::new(self.reftable).format_blocks(&mut lines);
+        let records = RecordsFormatter::new(self.reftable);
+        records.format_refs(&mut lines);
+        records.format_objs(&mut lines);
+        records.format_logs(&mut lines);
+        records.format_indexes(&mut lines);
+        self.add_footer_section(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_header_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "REFTABLE HEADER",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let border_style = Style::default().fg(Color::Gray);
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::styled(" Byte  ", border_style),
+            Span::styled("│ ", border_style),
+            Span::styled(
+                "Field            ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("│ ", border_style),
+            Span::styled("Value", Style::default().add_modifier(Modifier::BOLD)),
+        ]));
+        lines.push(Line::styled(
+            "───────┼──────────────────┼─────────────────────",
+            border_style,
+        ));
+
+        let header = &self.reftable.header;
+        let mut push_row = |byte_range: &str, field: &str, value: String| {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {byte_range:<6}"), border_style),
+                Span::styled("│ ", border_style),
+                Span::from(format!("{field:<17}")),
+                Span::styled("│ ", border_style),
+                Span::styled(value, value_style),
+            ]));
+        };
+
+        push_row("0-3", "Signature", "\"REFT\"".to_string());
+        push_row("4", "Version", format!("{}", header.version));
+        push_row(
+            "5-7",
+            "Block size",
+            match header.block_size {
+                0 => "0 (unaligned)".to_string(),
+                size => format!("{size} bytes"),
+            },
+        );
+        push_row(
+            "8-15",
+            "Min update index",
+            format!("{}", header.min_update_index),
+        );
+        push_row(
+            "16-23",
+            "Max update index",
+            format!("{}", header.max_update_index),
+        );
+        if let Some(hash_id) = header.hash_id {
+            let name: String = hash_id.to_be_bytes().iter().map(|&b| b as char).collect();
+            push_row("24-27", "Hash ID", format!("\"{name}\""));
+        }
+
+        lines.push(Line::from(""));
+    }
+
+    fn add_footer_section(&self, lines: &mut Vec<Line<'static>>) {
+        lines.push(Line::styled(
+            "FOOTER",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  A copy of the header, then where each section starts (0 when absent),",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  then a CRC-32 of the footer. Readers start here to find the indexes.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let footer = &self.reftable.footer;
+        let value_style = Style::default().fg(Color::LightGreen);
+        for (field, value) in [
+            ("Ref index position", footer.ref_index_position),
+            ("Obj position", footer.obj_position),
+            ("Obj ID length", u64::from(footer.obj_id_len)),
+            ("Obj index position", footer.obj_index_position),
+            ("Log position", footer.log_position),
+            ("Log index position", footer.log_index_position),
+        ] {
+            lines.push(Line::from(vec![
+                Span::from(format!("  {field:<19}")),
+                Span::styled(format!("{value}"), value_style),
+            ]));
+        }
+        lines.push(Line::from(vec![
+            Span::from(format!("  {:<19}", "CRC-32")),
+            Span::styled(format!("{:08x} ", footer.crc32), value_style),
+            if footer.crc_matches {
+                Span::styled("✓", value_style)
+            } else {
+                Span::styled(
+                    "✗ does not match the footer",
+                    Style::default().fg(Color::Red),
+                )
+            },
+        ]));
+        lines.push(Line::from(""));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::git::refs::Reftable;
+use crate::git::refs::reftable::{LogValue, RefValue};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Records past this many per section are summarized instead of listed
+const MAX_LISTED_RECORDS: usize = 500;
+/// Object IDs are abbreviated to this many hex digits in the tables
+const SHORT_OID_LEN: usize = 12;
+
+pub struct RecordsFormatter<'a> {
+    reftable: &'a Reftable,
+}
+
+impl<'a> RecordsFormatter<'a> {
+    #[must_use]
+    pub const fn new(reftable: &'a Reftable) -> Self {
+        Self { reftable }
+    }
+
+    pub fn format_refs(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "REF RECORDS");
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  The first column is the update index of the transaction that wrote the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  ref, the second how many name bytes it shares with the previous record",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  (always 0 at restart points).",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        if self.reftable.refs.is_empty() {
+            lines.push(Line::from("No ref records."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        for record in self.reftable.refs.iter().take(MAX_LISTED_RECORDS) {
+            let value = match &record.value {
+                RefValue::Deletion => Span::styled("deleted", Style::default().fg(Color::Red)),
+                RefValue::Object(oid) => {
+                    Span::styled(short_oid(oid), Style::default().fg(Color::LightGreen))
+                }
+                RefValue::Peeled { oid, peeled } => Span::styled(
+                    format!("{} ^{}", short_oid(oid), short_oid(peeled)),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                RefValue::Symbolic(target) => {
+                    Span::styled(format!("-> {target}"), Style::default().fg(Color::Magenta))
+                }
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:6}", record.update_index),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(format!("{:3}", record.prefix_length), gray),
+                Span::styled(" │ ", gray),
+                Span::styled(record.name.clone(), Style::default().fg(Color::Yellow)),
+                Span::from(" "),
+                value,
+            ]));
+        }
+        Self::push_more(lines, self.reftable.refs.len());
+        lines.push(Line::from(""));
+    }
+
+    pub fn format_objs(&self, lines: &mut Vec<Line<'static>>) {
+        if self.reftable.objs.is_empty() {
+            return;
+        }
+        Self::push_title(lines, "OBJ RECORDS");
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            format!(
+                "  Reverse index: the first {} bytes of an object ID, and the ref blocks",
+                self.reftable.footer.obj_id_len
+            ),
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  holding refs that point at it. Used by `git for-each-ref --points-at`.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        for record in self.reftable.objs.iter().take(MAX_LISTED_RECORDS) {
+            let positions = record
+                .block_positions
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {}", record.oid_prefix),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                Span::styled(" → blocks at ", gray),
+                Span::from(positions),
+            ]));
+        }
+        Self::push_more(lines, self.reftable.objs.len());
+        lines.push(Line::from(""));
+    }
+
+    pub fn format_logs(&self, lines: &mut Vec<Line<'static>>) {
+        if self.reftable.logs.is_empty() {
+            return;
+        }
+        Self::push_title(lines, "LOG RECORDS");
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  Reflog entries, keyed by ref name and reversed update index so the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  newest entry of each ref sorts first.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        for record in self.reftable.logs.iter().take(MAX_LISTED_RECORDS) {
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:6}", record.update_index),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(record.ref_name.clone(), Style::default().fg(Color::Yellow)),
+                Span::from(" "),
+            ];
+            match &record.value {
+                LogValue::Deletion => {
+                    spans.push(Span::styled("deleted", Style::default().fg(Color::Red)));
+                }
+                LogValue::Update {
+                    old_oid,
+                    new_oid,
+                    name,
+                    message,
+                    ..
+                } => {
+                    spans.push(Span::styled(
+                        format!("{} → {}", short_oid(old_oid), short_oid(new_oid)),
+                        Style::default().fg(Color::LightGreen),
+                    ));
+                    spans.push(Span::styled(format!(" {name}: "), gray));
+                    spans.push(Span::from(message.trim_end().to_string()));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        Self::push_more(lines, self.reftable.logs.len());
+        lines.push(Line::from(""));
+    }
+
+    pub fn format_indexes(&self, lines: &mut Vec<Line<'static>>) {
+        if self.reftable.indexes.is_empty() {
+            return;
+        }
+        Self::push_title(lines, "INDEX RECORDS");
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  The last key of each block and where the block starts, so a lookup",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  reads one block per section instead of scanning them all.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        for record in self.reftable.indexes.iter().take(MAX_LISTED_RECORDS) {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:>5}", record.section.to_string()), gray),
+                Span::styled(" │ ", gray),
+                Span::styled(
+                    format!("{:7}", record.block_position),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(" │ ", gray),
+                Span::styled(record.key_label(), Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        Self::push_more(lines, self.reftable.indexes.len());
+        lines.push(Line::from(""));
+    }
+
+    fn push_title(lines: &mut Vec<Line<'static>>, title: &'static str) {
+        lines.push(Line::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+    }
+
+    fn push_more(lines: &mut Vec<Line<'static>>, count: usize) {
+        if count > MAX_LISTED_RECORDS {
+            lines.push(Line::from(vec![Span::styled(
+                format!(
+                    "  ... {} more records not shown",
+                    count - MAX_LISTED_RECORDS
+                ),
+                Style::default().fg(Color::Gray),
+            )]));
+        }
+    }
+}
+
+fn short_oid(oid: &str) -> String {
+    oid[..SHORT_OID_LEN.min(oid.len())].to_string()
+}
@@ -0,0 +1,171 @@
+use crate::git::refs::ReftableStack;
+use crate::git::refs::reftable::RefValue;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+/// Object IDs are abbreviated to this many hex digits in the tables
+const SHORT_OID_LEN: usize = 12;
+
+pub struct ReftableStackFormatter<'a> {
+    stack: &'a ReftableStack,
+}
+
+impl<'a> ReftableStackFormatter<'a> {
+    #[must_use]
+    pub const fn new(stack: &'a ReftableStack) -> Self {
+        Self { stack }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = vec![
+            Line::from("The reftable stack: the refs and reflogs of a repository created with"),
+            Line::from("`extensions.refStorage=reftable`, which has no loose refs or"),
+            Line::from("packed-refs. tables.list names the tables oldest first; each update"),
+            Line::from("appends a table and `git pack-refs` compacts them into one."),
+            Line::from(""),
+        ];
+
+        self.add_tables_section(&mut lines);
+        self.add_refs_section(&mut lines);
+        self.add_reflogs_section(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_tables_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "TABLES");
+
+        if self.stack.tables.is_empty() {
+            lines.push(Line::from("The stack is empty."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        let gray = Style::default().fg(Color::Gray);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("  #", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Update indexes", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Refs", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("Logs", bold),
+            Span::styled(" │ ", gray),
+            Span::styled("File", bold),
+        ]));
+        lines.push(Line::styled(
+            "────┼────────────────┼──────┼──────┼────────────────────",
+            gray,
+        ));
+
+        for (index, (name, table)) in self.stack.tables.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{index:3}"), Style::default().fg(Color::Cyan)),
+                Span::styled(" │ ", gray),
+                Span::from(format!(
+                    "{:>14}",
+                    format!(
+                        "{}-{}",
+                        table.header.min_update_index, table.header.max_update_index
+                    )
+                )),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:4}", table.refs.len())),
+                Span::styled(" │ ", gray),
+                Span::from(format!("{:4}", table.logs.len())),
+                Span::styled(" │ ", gray),
+                Span::styled(name.clone(), Style::default().fg(Color::Yellow)),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn add_refs_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "MERGED REFS");
+
+        let gray = Style::default().fg(Color::Gray);
+        lines.push(Line::from(vec![Span::styled(
+            "  The newest table mentioning a ref wins; deletion records hide the",
+            gray,
+        )]));
+        lines.push(Line::from(vec![Span::styled(
+            "  values of older tables. # is the table the value comes from.",
+            gray,
+        )]));
+        lines.push(Line::from(""));
+
+        let refs = self.stack.refs();
+        if refs.is_empty() {
+            lines.push(Line::from("No refs."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        for (name, (index, record)) in refs {
+            let value = match &record.value {
+                RefValue::Deletion => continue,
+                RefValue::Object(oid) => {
+                    Span::styled(short_oid(oid), Style::default().fg(Color::LightGreen))
+                }
+                RefValue::Peeled { oid, peeled } => Span::styled(
+                    format!("{} ^{}", short_oid(oid), short_oid(peeled)),
+                    Style::default().fg(Color::LightGreen),
+                ),
+                RefValue::Symbolic(target) => {
+                    Span::styled(format!("-> {target}"), Style::default().fg(Color::Magenta))
+                }
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{index:3}"), Style::default().fg(Color::Cyan)),
+                Span::styled(" │ ", gray),
+                Span::styled(name, Style::default().fg(Color::Yellow)),
+                Span::from(" "),
+                value,
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn add_reflogs_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "REFLOGS");
+
+        let names = self.stack.log_ref_names();
+        if names.is_empty() {
+            lines.push(Line::from("No reflog entries."));
+            lines.push(Line::from(""));
+            return;
+        }
+
+        for name in names {
+            let count = self.stack.log_records(&name).len();
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {name}"), Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    format!(
+                        " ({count} {})",
+                        if count == 1 { "entry" } else { "entries" }
+                    ),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn push_title(lines: &mut Vec<Line<'static>>, title: &'static str) {
+        lines.push(Line::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+    }
+}
+
+fn short_oid(oid: &str) -> String {
+    oid[..SHORT_OID_LEN.min(oid.len())].to_string()
+}
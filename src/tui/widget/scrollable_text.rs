@@ -57,6 +57,11 @@ impl ScrollableTextWidget {
         self.scroll_position = self.max_scroll;
     }
 
+    /// Scroll so that `line` is at the top, as far as the content allows
+    pub const fn scroll_to_line(&mut self, line: usize) {
+        self.scroll_position = line;
+    }
+
     /// Get the current scroll position
     #[must_use]
     pub const fn scroll_position(&self) -> usize {
@@ -77,6 +82,7 @@ impl ScrollableTextWidget {
         let total_lines = content.lines.len();
         let visible_height = area.height.saturating_sub(2) as usize; // Account for borders
         self.max_scroll = total_lines.saturating_sub(visible_height);
+        self.scroll_position = self.scroll_position.min(self.max_scroll);
 
         render_styled_paragraph_with_scrollbar(
             f,
@@ -0,0 +1,163 @@
+use crate::git::refs::{RefTarget, ResolvedTarget};
+use crate::git::worktree::WorktreeDetails;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+};
+
+pub struct WorktreeFormatter<'a> {
+    details: &'a WorktreeDetails,
+}
+
+impl<'a> WorktreeFormatter<'a> {
+    #[must_use]
+    pub const fn new(details: &'a WorktreeDetails) -> Self {
+        Self { details }
+    }
+
+    #[must_use]
+    pub fn generate_content(&self) -> Text<'static> {
+        let mut lines = vec![
+            Line::from("A linked worktree: a second checkout made with `git worktree add`."),
+            Line::from("Its `.git` is a file pointing here; this directory keeps what the"),
+            Line::from("worktree has for itself (HEAD, index, per-worktree refs and logs)"),
+            Line::from("while refs and objects are shared through the `commondir` file."),
+            Line::from(""),
+        ];
+
+        self.add_worktree_section(&mut lines);
+        self.add_head_section(&mut lines);
+        self.add_index_section(&mut lines);
+
+        Text::from(lines)
+    }
+
+    fn add_worktree_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "WORKTREE");
+
+        let worktree = &self.details.worktree;
+        let value_style = Style::default().fg(Color::LightGreen);
+        lines.push(Line::from(vec![
+            Span::from("  Name: "),
+            Span::styled(worktree.name.clone(), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Git directory: "),
+            Span::styled(worktree.admin_dir.display().to_string(), value_style),
+        ]));
+        lines.push(Line::from(vec![
+            Span::from("  Checked out at: "),
+            worktree.checkout.as_ref().map_or_else(
+                || Span::styled("unknown (no gitdir file)", Style::default().fg(Color::Red)),
+                |checkout| Span::styled(checkout.display().to_string(), value_style),
+            ),
+        ]));
+
+        let state = match (&worktree.locked, &worktree.prunable) {
+            (Some(reason), _) if reason.is_empty() => {
+                Span::styled("locked", Style::default().fg(Color::Yellow))
+            }
+            (Some(reason), _) => Span::styled(
+                format!("locked: {reason}"),
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, Some(reason)) => Span::styled(
+                format!("prunable: {reason}"),
+                Style::default().fg(Color::Red),
+            ),
+            (None, None) => Span::styled("active", value_style),
+        };
+        lines.push(Line::from(vec![Span::from("  State: "), state]));
+        lines.push(Line::from(""));
+    }
+
+    fn add_head_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "HEAD");
+
+        let gray = Style::default().fg(Color::Gray);
+        let Some(head) = &self.details.worktree.head else {
+            lines.push(Line::from("No HEAD file."));
+            lines.push(Line::from(""));
+            return;
+        };
+        lines.push(Line::from(vec![
+            Span::from("  Content: "),
+            Span::styled(head.to_string(), Style::default().fg(Color::Yellow)),
+        ]));
+        if matches!(head, RefTarget::Object(_)) {
+            lines.push(Line::from(vec![Span::styled(
+                "  Detached: HEAD names a commit rather than a branch",
+                gray,
+            )]));
+        }
+
+        if let Some(resolution) = &self.details.head {
+            let end_style = match &resolution.end {
+                ResolvedTarget::Object(_) => Style::default().fg(Color::LightGreen),
+                _ => Style::default().fg(Color::Red),
+            };
+            let mut spans = vec![Span::from("  Resolves to: ")];
+            for name in resolution.chain.iter().skip(1) {
+                spans.push(Span::styled(name.clone(), Style::default().fg(Color::Cyan)));
+                spans.push(Span::from(" → "));
+            }
+            spans.push(Span::styled(resolution.end.to_string(), end_style));
+            lines.push(Line::from(spans));
+            if let ResolvedTarget::Dangling(_) = resolution.end {
+                lines.push(Line::from(vec![Span::styled(
+                    "  An unborn branch: nothing has been committed on it yet",
+                    gray,
+                )]));
+            }
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn add_index_section(&self, lines: &mut Vec<Line<'static>>) {
+        Self::push_title(lines, "INDEX");
+
+        match &self.details.index {
+            None => lines.push(Line::from("No index yet.")),
+            Some(Err(e)) => lines.push(Line::styled(e.clone(), Style::default().fg(Color::Red))),
+            Some(Ok(index)) => {
+                let value_style = Style::default().fg(Color::LightGreen);
+                lines.push(Line::from(vec![
+                    Span::from("  Version: "),
+                    Span::styled(format!("{}", index.version), value_style),
+                    Span::from(", entries: "),
+                    Span::styled(format!("{}", index.entries.len()), value_style),
+                ]));
+                let extensions: Vec<String> = index
+                    .extensions
+                    .iter()
+                    .map(|extension| String::from_utf8_lossy(&extension.signature).to_string())
+                    .collect();
+                lines.push(Line::from(vec![
+                    Span::from("  Extensions: "),
+                    Span::styled(
+                        if extensions.is_empty() {
+                            "none".to_string()
+                        } else {
+                            extensions.join(", ")
+                        },
+                        value_style,
+                    ),
+                ]));
+                lines.push(Line::from(vec![Span::styled(
+                    "  Open the index entry below this worktree for every entry",
+                    Style::default().fg(Color::Gray),
+                )]));
+            }
+        }
+        lines.push(Line::from(""));
+    }
+
+    fn push_title(lines: &mut Vec<Line<'static>>, title: &'static str) {
+        lines.push(Line::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::from("─".repeat(30)));
+        lines.push(Line::from(""));
+    }
+}